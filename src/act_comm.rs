@@ -9,39 +9,38 @@
 *  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
 ************************************************************************ */
 
-use crate::config::{HOLLER_MOVE_COST, LEVEL_CAN_SHOUT, NOPERSON, OK};
+use crate::channels::{channel_talk, find_channel};
+use crate::clan::{find_player, member_name};
 use crate::depot::{Depot, DepotId, HasId};
 use crate::handler::{get_char_vis, get_obj_in_list_vis, get_player_vis, FindFlags};
 use crate::interpreter::{
-    delete_doubledollar, half_chop, two_arguments, CMD_INFO, SCMD_ASK, SCMD_HOLLER, SCMD_QSAY,
-    SCMD_SHOUT, SCMD_WHISPER,
+    delete_doubledollar, half_chop, two_arguments, CMD_INFO, SCMD_ASK, SCMD_QSAY, SCMD_WHISPER,
 };
-use crate::screen::{C_CMP, C_NRM, KGRN, KMAG, KNRM, KNUL, KRED, KYEL};
+use crate::screen::{close_markup, MsgCategory, MARKUP_NRM};
+use crate::scripts::speech_trigger;
 use crate::structs::ConState::ConPlaying;
 use crate::structs::{
     AffectFlags, ItemType, PrefFlags, RoomFlags, LVL_GOD, LVL_IMMORT, MAX_NOTE_LENGTH, NOBODY,
-    PLR_NOSHOUT, PLR_WRITING, WEAR_HOLD,
+    PLR_WRITING, WEAR_HOLD,
 };
+use crate::tells::{is_ignoring, tell_store, TellRefusal};
 use crate::util::can_see_obj;
-use crate::{
-    _clrlevel, an, clr, Game, CCNRM, CCRED, COLOR_LEV, TO_CHAR, TO_NOTVICT, TO_ROOM, TO_SLEEP,
-    TO_VICT,
-};
 use crate::{act, send_to_char, CharData, DescriptorData, ObjData, TextData, VictimRef, DB};
+use crate::{an, Game, TO_CHAR, TO_NOTVICT, TO_ROOM, TO_SLEEP, TO_VICT};
 
 #[allow(clippy::too_many_arguments)]
 pub fn do_say(
     game: &mut Game,
     db: &mut DB,
     chars: &mut Depot<CharData>,
-    _texts: &mut Depot<TextData>,
-    _objs: &mut Depot<ObjData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
     chid: DepotId,
     argument: &str,
     _cmd: usize,
     _subcmd: i32,
 ) {
-    let argument = argument.trim_start();
+    let argument = close_markup(argument.trim_start());
     let ch = chars.get(chid);
 
     if argument.is_empty() {
@@ -55,7 +54,12 @@ pub fn do_say(
             &mut game.descriptors,
             chars,
             db,
-            &format!("$n says, '{}'", argument),
+            &format!(
+                "{}$n says, '{}'{}",
+                MsgCategory::Say.tag(),
+                argument,
+                MARKUP_NRM
+            ),
             false,
             Some(ch),
             None,
@@ -63,16 +67,22 @@ pub fn do_say(
             TO_ROOM,
         );
         if !ch.is_npc() && ch.prf_flagged(PrefFlags::NOREPEAT) {
-            send_to_char(&mut game.descriptors, ch, OK);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         } else {
             let mut argument = argument.to_string();
             delete_doubledollar(&mut argument);
             send_to_char(
                 &mut game.descriptors,
                 ch,
-                &format!("You say, '{}'\r\n", argument),
+                &format!(
+                    "{}You say, '{}'{}\r\n",
+                    MsgCategory::Say.tag(),
+                    argument,
+                    MARKUP_NRM
+                ),
             );
         }
+        speech_trigger(game, db, chars, texts, objs, chid, &argument);
     }
 }
 
@@ -89,7 +99,7 @@ pub fn do_gsay(
     _subcmd: i32,
 ) {
     let ch = chars.get(chid);
-    let argument = argument.trim_start();
+    let argument = close_markup(argument.trim_start());
 
     if !ch.aff_flagged(AffectFlags::GROUP) {
         send_to_char(
@@ -141,7 +151,7 @@ pub fn do_gsay(
             }
         }
         if ch.prf_flagged(PrefFlags::NOREPEAT) {
-            send_to_char(&mut game.descriptors, ch, OK);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         } else {
             send_to_char(
                 &mut game.descriptors,
@@ -160,9 +170,10 @@ fn perform_tell(
     vict_id: DepotId,
     arg: &str,
 ) {
+    let arg = close_markup(arg);
     let ch = chars.get(chid);
     let vict = chars.get(vict_id);
-    send_to_char(descs, vict, CCRED!(vict, C_NRM));
+    send_to_char(descs, vict, MsgCategory::Tell.tag());
     act(
         descs,
         chars,
@@ -174,12 +185,12 @@ fn perform_tell(
         Some(VictimRef::Char(vict)),
         TO_VICT | TO_SLEEP,
     );
-    send_to_char(descs, vict, CCNRM!(vict, C_NRM));
+    send_to_char(descs, vict, MARKUP_NRM);
 
     if !ch.is_npc() && ch.prf_flagged(PrefFlags::NOREPEAT) {
-        send_to_char(descs, ch, OK);
+        send_to_char(descs, ch, db.msg(ch, "ok"));
     } else {
-        send_to_char(descs, ch, CCRED!(ch, C_NRM));
+        send_to_char(descs, ch, MsgCategory::Tell.tag());
         act(
             descs,
             chars,
@@ -191,7 +202,7 @@ fn perform_tell(
             Some(VictimRef::Char(vict)),
             TO_CHAR | TO_SLEEP,
         );
-        send_to_char(descs, ch, CCNRM!(ch, C_NRM));
+        send_to_char(descs, ch, MARKUP_NRM);
     }
     if !vict.is_npc() && !ch.is_npc() {
         let ch_idnum = ch.get_idnum();
@@ -217,6 +228,18 @@ fn is_tell_ok(
         );
     } else if db.room_flagged(ch.in_room(), RoomFlags::SOUNDPROOF) {
         send_to_char(descs, ch, "The walls seem to absorb your words.\r\n");
+    } else if is_ignoring(ch, vict) {
+        act(
+            descs,
+            chars,
+            db,
+            "You are ignoring $M.",
+            false,
+            Some(ch),
+            None,
+            Some(VictimRef::Char(vict)),
+            TO_CHAR | TO_SLEEP,
+        );
     } else if !vict.is_npc() && vict.desc.is_none() {
         /* linkless */
         act(
@@ -242,6 +265,18 @@ fn is_tell_ok(
             Some(VictimRef::Char(vict)),
             TO_CHAR | TO_SLEEP,
         );
+    } else if is_ignoring(vict, ch) {
+        act(
+            descs,
+            chars,
+            db,
+            "$E is ignoring you.",
+            false,
+            Some(ch),
+            None,
+            Some(VictimRef::Char(vict)),
+            TO_CHAR | TO_SLEEP,
+        );
     } else if (!vict.is_npc() && vict.prf_flagged(PrefFlags::NOTELL))
         || db.room_flagged(vict.in_room(), RoomFlags::SOUNDPROOF)
     {
@@ -263,6 +298,60 @@ fn is_tell_ok(
     false
 }
 
+/* A player who isn't playing is given the tell when they come in */
+fn tell_offline(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &Depot<CharData>,
+    chid: DepotId,
+    name: &str,
+    arg: &str,
+) {
+    let ch = chars.get(chid);
+    let idnum = db.get_id_by_name(&name.to_lowercase());
+    if ch.is_npc() || idnum < 0 || find_player(db, chars, idnum).is_some() {
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
+    } else if ch.prf_flagged(PrefFlags::NOTELL) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You can't tell other people while you have notell on.\r\n",
+        );
+    } else if db.room_flagged(ch.in_room(), RoomFlags::SOUNDPROOF) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The walls seem to absorb your words.\r\n",
+        );
+    } else if ch.player_specials.ignored.contains(&idnum) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!("You are ignoring {}.\r\n", member_name(db, idnum)),
+        );
+    } else if let Err(refusal) = tell_store(db, ch.get_idnum(), idnum, &close_markup(arg)) {
+        let msg = match refusal {
+            TellRefusal::RecipientFull => format!(
+                "{} has too many tells waiting already.\r\n",
+                member_name(db, idnum)
+            ),
+            TellRefusal::SenderFull => {
+                "You have left too many tells for players who aren't playing.\r\n".to_string()
+            }
+        };
+        send_to_char(&mut game.descriptors, ch, &msg);
+    } else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!(
+                "{} isn't playing; your tell will be given when they come in.\r\n",
+                member_name(db, idnum)
+            ),
+        );
+    }
+}
+
 /*
  * Yes, do_tell probably could be combined with whisper and ask, but
  * called frequently, and should IMHO be kept as tight as possible.
@@ -306,7 +395,7 @@ pub fn do_tell(
         ) {
             vict = player;
         } else {
-            send_to_char(&mut game.descriptors, ch, NOPERSON);
+            tell_offline(game, db, chars, chid, &buf, &buf2);
             return;
         }
     } else if let Some(char) = get_char_vis(
@@ -320,7 +409,7 @@ pub fn do_tell(
     ) {
         vict = char;
     } else {
-        send_to_char(&mut game.descriptors, ch, NOPERSON);
+        tell_offline(game, db, chars, chid, &buf, &buf2);
         return;
     }
     if is_tell_ok(&mut game.descriptors, chars, db, ch, vict) {
@@ -385,6 +474,9 @@ pub fn do_reply(
                     argument,
                 );
             }
+        } else if let Some(name) = db.get_name_by_id(ch.get_last_tell()) {
+            let name = name.to_string();
+            tell_offline(game, db, chars, chid, &name, argument);
         } else {
             send_to_char(&mut game.descriptors, ch, "They are no longer playing.\r\n");
         }
@@ -434,6 +526,7 @@ pub fn do_spec_comm(
     let mut buf2 = String::new();
 
     half_chop(&mut argument, &mut buf, &mut buf2);
+    let buf2 = close_markup(&buf2);
     if buf.is_empty() || buf2.is_empty() {
         send_to_char(
             &mut game.descriptors,
@@ -473,7 +566,7 @@ pub fn do_spec_comm(
             );
 
             if ch.prf_flagged(PrefFlags::NOREPEAT) {
-                send_to_char(&mut game.descriptors, ch, OK);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
             } else {
                 send_to_char(
                     &mut game.descriptors,
@@ -494,7 +587,7 @@ pub fn do_spec_comm(
             );
         }
     } else {
-        send_to_char(&mut game.descriptors, ch, NOPERSON);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
     }
 }
 
@@ -721,7 +814,7 @@ pub fn do_page(
     } else if arg.is_empty() {
         send_to_char(&mut game.descriptors, ch, "Whom do you wish to page?\r\n");
     } else {
-        let buf = format!("\x07\x07*$n* {}", buf2);
+        let buf = format!("\x07\x07*$n* {}", close_markup(&buf2));
         if arg == "all" && ch.get_level() > LVL_GOD {
             for d_id in game.descriptor_list.clone() {
                 let d = game.desc(d_id);
@@ -770,7 +863,7 @@ pub fn do_page(
                 TO_VICT,
             );
             if ch.prf_flagged(PrefFlags::NOREPEAT) {
-                send_to_char(&mut game.descriptors, ch, OK);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
             } else {
                 act(
                     &mut game.descriptors,
@@ -798,6 +891,10 @@ pub fn do_page(
  * generalized communication func, originally by Fred C. Merkel (Torg) *
  *********************************************************************/
 
+/*
+ * Holler, shout, gossip and the other channels are defined in the channel
+ * file; the command names the channel (see channels.rs).
+ */
 #[allow(clippy::too_many_arguments)]
 pub fn do_gen_comm(
     game: &mut Game,
@@ -807,181 +904,19 @@ pub fn do_gen_comm(
     _objs: &mut Depot<ObjData>,
     chid: DepotId,
     argument: &str,
-    _cmd: usize,
-    subcmd: i32,
+    cmd: usize,
+    _subcmd: i32,
 ) {
-    let ch = chars.get(chid);
-    // char color_on[24];
-
-    /* Array of flags which must _not_ be set in order for comm to be heard */
-    const CHANNELS: [PrefFlags; 6] = [
-        PrefFlags::empty(),
-        PrefFlags::DEAF,
-        PrefFlags::NOGOSS,
-        PrefFlags::NOAUCT,
-        PrefFlags::NOGRATZ,
-        PrefFlags::empty(),
-    ];
-
-    /*
-     * COM_MSGS: [0] Message if you can't perform the action because of noshout
-     *           [1] name of the action
-     *           [2] message if you're not on the channel
-     *           [3] a color string.
-     */
-    const COM_MSGS: [[&str; 4]; 5] = [
-        ["You cannot holler!!\r\n", "holler", "", KYEL],
-        [
-            "You cannot shout!!\r\n",
-            "shout",
-            "Turn off your noshout flag first!\r\n",
-            KYEL,
-        ],
-        [
-            "You cannot gossip!!\r\n",
-            "gossip",
-            "You aren't even on the channel!\r\n",
-            KYEL,
-        ],
-        [
-            "You cannot auction!!\r\n",
-            "auction",
-            "You aren't even on the channel!\r\n",
-            KMAG,
-        ],
-        [
-            "You cannot congratulate!\r\n",
-            "congrat",
-            "You aren't even on the channel!\r\n",
-            KGRN,
-        ],
-    ];
-
-    /* to keep pets, etc from being ordered to shout */
-    if let Some(ch_desc) = ch.desc {
-        if ch.plr_flagged(PLR_NOSHOUT) {
-            send_to_char(&mut game.descriptors, ch, COM_MSGS[subcmd as usize][0]);
-            return;
-        }
-        if db.room_flagged(ch.in_room(), RoomFlags::SOUNDPROOF) {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "The walls seem to absorb your words.\r\n",
-            );
-            return;
-        }
-        /* level_can_shout defined in config.c */
-        if ch.get_level() < LEVEL_CAN_SHOUT as u8 {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                format!(
-                    "You must be at least level {} before you can {}.\r\n",
-                    LEVEL_CAN_SHOUT, COM_MSGS[subcmd as usize][1]
-                )
-                .as_str(),
-            );
-            return;
-        }
-        /* make sure the char is on the channel */
-        if ch.prf_flagged(CHANNELS[subcmd as usize]) {
-            send_to_char(&mut game.descriptors, ch, COM_MSGS[subcmd as usize][2]);
-            return;
-        }
-        /* skip leading spaces */
-        let argument = argument.trim_start();
-
-        /* make sure that there is something there to say! */
-        if argument.is_empty() {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                format!(
-                    "Yes, {}, fine, {} we must, but WHAT???\r\n",
-                    COM_MSGS[subcmd as usize][1], COM_MSGS[subcmd as usize][1]
-                )
-                .as_str(),
-            );
-            return;
-        }
-        if subcmd == SCMD_HOLLER {
-            if ch.get_move() < HOLLER_MOVE_COST as i16 {
-                send_to_char(
-                    &mut game.descriptors,
-                    ch,
-                    "You're too exhausted to holler.\r\n",
-                );
-                return;
-            } else {
-                let ch = chars.get_mut(chid);
-                ch.set_move(ch.get_move() - HOLLER_MOVE_COST as i16);
-            }
-        }
-        /* set up the color on code */
-        let color_on = COM_MSGS[subcmd as usize][3];
-
-        /* first, set up strings to be given to the communicator */
+    let Some(c) = find_channel(db, CMD_INFO[cmd].command) else {
         let ch = chars.get(chid);
-        if ch.prf_flagged(PrefFlags::NOREPEAT) {
-            send_to_char(&mut game.descriptors, ch, OK);
-        } else {
-            let messg = format!(
-                "{}You {}, '{}'{}\r\n",
-                if COLOR_LEV!(ch) >= C_CMP {
-                    color_on
-                } else {
-                    ""
-                },
-                COM_MSGS[subcmd as usize][1],
-                argument,
-                CCNRM!(ch, C_CMP)
-            );
-            send_to_char(&mut game.descriptors, ch, messg.as_str());
-        }
-
-        let buf1 = format!("$n {}s, '{}'", COM_MSGS[subcmd as usize][1], argument);
-
-        /* now send all the strings out */
-        for d_id in game.descriptor_list.clone() {
-            let d = game.desc(d_id);
-            if let Some(ic_id) = d.character {
-                let ic = chars.get(ic_id);
-                if d.state() == ConPlaying
-                    && d_id != ch_desc
-                    && !ic.prf_flagged(CHANNELS[subcmd as usize])
-                    && !ic.plr_flagged(PLR_WRITING)
-                    && !db.room_flagged(ic.in_room(), RoomFlags::SOUNDPROOF)
-                {
-                    if subcmd == SCMD_SHOUT
-                        && (db.world[ch.in_room() as usize].zone
-                            != db.world[ic.in_room() as usize].zone
-                            || !ic.awake())
-                    {
-                        continue;
-                    }
-
-                    if COLOR_LEV!(ic) >= C_NRM {
-                        send_to_char(&mut game.descriptors, ic, color_on);
-                    }
-                    act(
-                        &mut game.descriptors,
-                        chars,
-                        db,
-                        &buf1,
-                        false,
-                        Some(ch),
-                        None,
-                        Some(VictimRef::Char(ic)),
-                        TO_VICT | TO_SLEEP,
-                    );
-                    if COLOR_LEV!(ic) >= C_NRM {
-                        send_to_char(&mut game.descriptors, ic, KNRM);
-                    }
-                }
-            }
-        }
-    }
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "That channel has been closed.\r\n",
+        );
+        return;
+    };
+    channel_talk(game, db, chars, chid, c, argument);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1005,7 +940,8 @@ pub fn do_qcomm(
         );
         return;
     }
-    let argument = argument.trim_start();
+    let argument = close_markup(argument.trim_start());
+    let argument = argument.as_str();
 
     if argument.is_empty() {
         send_to_char(
@@ -1023,7 +959,7 @@ pub fn do_qcomm(
         let mut buf;
 
         if ch.prf_flagged(PrefFlags::NOREPEAT) {
-            send_to_char(&mut game.descriptors, ch, OK);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         } else if subcmd == SCMD_QSAY {
             buf = format!("You quest-say, '{}'", argument);
             act(
@@ -12,11 +12,11 @@
 use std::rc::Rc;
 
 use crate::act_social::{do_action, do_insult};
-use crate::class::{find_class_bitvector, level_exp, title_female, title_male};
-use crate::config::NOPERSON;
+use crate::clan::clan_tag;
+use crate::class::{find_class_bitvector, level_exp, title_female, title_male, PC_CLASS_TYPES};
 use crate::constants::{
-    CIRCLEMUD_VERSION, COLOR_LIQUID, CONNECTED_TYPES, DIRS, FULLNESS, MONTH_NAME, ROOM_BITS,
-    WEAR_WHERE, WEEKDAYS,
+    CIRCLEMUD_VERSION, CLIMATE_TYPES, COLOR_LIQUID, CONNECTED_TYPES, DIRS, FULLNESS, MONTH_NAME,
+    ROOM_BITS, SEASON_NAME, WEAR_WHERE, WEEKDAYS,
 };
 use crate::db::DB;
 use crate::depot::{Depot, DepotId, HasId};
@@ -30,20 +30,24 @@ use crate::interpreter::{
     SCMD_READ, SCMD_SOCIALS, SCMD_VERSION, SCMD_WHOAMI, SCMD_WIZHELP, SCMD_WIZLIST,
 };
 use crate::modify::page_string;
-use crate::screen::{C_NRM, C_OFF, C_SPR, KCYN, KGRN, KNRM, KNUL, KRED, KYEL};
+use crate::screen::{
+    screen_reader, valid_color_code, ColorDepth, MsgCategory, COLOR_THEMES, C_OFF, C_SPR, KGRN,
+    KNRM, KNUL, KRED, KYEL, MARKUP_NRM, MSG_CATEGORIES,
+};
 use crate::spells::SPELL_ARMOR;
 use crate::structs::ConState::ConPlaying;
-use crate::structs::RoomFlags;
 use crate::structs::{
     AffectFlags, ExitFlags, ExtraDescrData, ExtraFlags, ItemType, Position, PrefFlags, Sex,
     CONT_CLOSED, LVL_GOD, LVL_IMPL, NOWHERE, PLR_KILLER, PLR_MAILING, PLR_THIEF, PLR_WRITING,
 };
+use crate::structs::{Climate, RoomAffectFlags, RoomFlags, NUM_OF_DIRS};
 use crate::structs::{DRUNK, FULL, LVL_IMMORT, NUM_WEARS, THIRST};
 use crate::util::{
     age, can_see, can_see_obj, pers, rand_number, real_time_passed, sprintbit, sprinttype,
     time_now, SECS_PER_MUD_HOUR, SECS_PER_REAL_MIN,
 };
-use crate::{_clrlevel, an, clr, Game, CCCYN, CCGRN, CCRED, CCYEL, COLOR_LEV, TO_NOTVICT};
+use crate::weather::{season, snows};
+use crate::{_clrlevel, an, clr, Game, CCGRN, CCRED, CCYEL, COLOR_LEV, TO_NOTVICT};
 use crate::{act, send_to_char, CharData, DescriptorData, ObjData, TextData, VictimRef};
 use crate::{CCNRM, TO_VICT};
 use log::error;
@@ -484,7 +488,7 @@ fn do_auto_exits(descs: &mut Depot<DescriptorData>, db: &DB, ch: &CharData) {
     send_to_char(
         descs,
         ch,
-        format!("{}[ Exits: ", CCCYN!(ch, C_NRM)).as_str(),
+        format!("{}[ Exits: ", MsgCategory::Exits.tag()).as_str(),
     );
     for (door, dir) in DIRS.iter().enumerate() {
         if let Some(exit) = db.exit(ch, door) {
@@ -503,7 +507,7 @@ fn do_auto_exits(descs: &mut Depot<DescriptorData>, db: &DB, ch: &CharData) {
         format!(
             "{}]{}\r\n",
             if slen != 0 { "" } else { "None!" },
-            CCNRM!(ch, C_NRM)
+            MARKUP_NRM
         )
         .as_str(),
     );
@@ -596,7 +600,7 @@ pub fn look_at_room(
         send_to_char(descs, ch, "You see nothing but infinite darkness...\r\n");
         return;
     }
-    send_to_char(descs, ch, CCCYN!(ch, C_NRM));
+    send_to_char(descs, ch, MsgCategory::RoomTitle.tag());
 
     if !ch.is_npc() && ch.prf_flagged(PrefFlags::ROOMFLAGS) {
         let mut buf = String::new();
@@ -616,13 +620,13 @@ pub fn look_at_room(
         send_to_char(descs, ch, &db.world[ch.in_room() as usize].name);
     }
 
-    send_to_char(descs, ch, format!("{}\r\n", CCNRM!(ch, C_NRM)).as_str());
+    send_to_char(descs, ch, format!("{}\r\n", MARKUP_NRM).as_str());
 
     if (!ch.is_npc() && !ch.prf_flagged(PrefFlags::BRIEF))
         || ignore_brief
         || db.room_flagged(ch.in_room(), RoomFlags::DEATH)
     {
-        send_to_char(descs, ch, &db.world[ch.in_room() as usize].description);
+        send_to_char(descs, ch, db.room_description(ch.in_room()));
     }
 
     /* autoexits */
@@ -630,8 +634,31 @@ pub fn look_at_room(
         do_auto_exits(descs, db, ch);
     }
 
+    /* lingering room spells */
+    let room = ch.in_room();
+    if db.room_affected(room, RoomAffectFlags::CONSECRATED) {
+        send_to_char(descs, ch, "A soft golden glow suffuses this place.\r\n");
+    }
+    for (door, dir_name) in DIRS.iter().enumerate().take(NUM_OF_DIRS) {
+        if db.exit_walled(room, door) {
+            send_to_char(
+                descs,
+                ch,
+                format!(
+                    "A shimmering wall of force blocks the way {}.\r\n",
+                    dir_name
+                )
+                .as_str(),
+            );
+        }
+    }
+    if db.room_affected(room, RoomAffectFlags::FOG) && !ch.prf_flagged(PrefFlags::HOLYLIGHT) {
+        send_to_char(descs, ch, "A thick fog hides everything around you.\r\n");
+        return;
+    }
+
     /* now list characters & objects */
-    send_to_char(descs, ch, CCGRN!(ch, C_NRM));
+    send_to_char(descs, ch, MsgCategory::ObjList.tag());
     list_obj_to_char(
         descs,
         db,
@@ -643,7 +670,7 @@ pub fn look_at_room(
         SHOW_OBJ_LONG,
         false,
     );
-    send_to_char(descs, ch, CCYEL!(ch, C_NRM));
+    send_to_char(descs, ch, MsgCategory::CharList.tag());
     list_char_to_char(
         descs,
         db,
@@ -651,7 +678,7 @@ pub fn look_at_room(
         &db.world[ch.in_room() as usize].peoples,
         ch,
     );
-    send_to_char(descs, ch, CCNRM!(ch, C_NRM));
+    send_to_char(descs, ch, MARKUP_NRM);
 }
 
 fn look_in_direction(
@@ -1142,15 +1169,33 @@ pub fn do_score(
         format!("You are {} years old.\r\n", ch.get_age()).as_str(),
     );
     let ch = chars.get(chid);
+    /* screen readers get the same in plain sentences, without layout */
+    let linear = screen_reader(ch);
     if age(ch).month == 0 && age(ch).day == 0 {
-        send_to_char(&mut game.descriptors, ch, "  It's your birthday today.\r\n");
-    } else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            if linear {
+                "It's your birthday today.\r\n"
+            } else {
+                "  It's your birthday today.\r\n"
+            },
+        );
+    } else if !linear {
         send_to_char(&mut game.descriptors, ch, "\r\n");
     }
     let ch = chars.get(chid);
-    send_to_char(
-        &mut game.descriptors,
-        ch,
+    let points = if linear {
+        format!(
+            "You have {} of {} hit points, {} of {} mana points and {} of {} movement points.\r\n",
+            ch.get_hit(),
+            ch.get_max_hit(),
+            ch.get_mana(),
+            ch.get_max_mana(),
+            ch.get_move(),
+            ch.get_max_move()
+        )
+    } else {
         format!(
             "You have {}({}) hit, {}({}) mana and {}({}) movement points.\r\n",
             ch.get_hit(),
@@ -1160,14 +1205,19 @@ pub fn do_score(
             ch.get_move(),
             ch.get_max_move()
         )
-        .as_str(),
-    );
+    };
+    send_to_char(&mut game.descriptors, ch, &points);
+    let armor = if linear {
+        format!("{:.1}", compute_armor_class(ch) as f32 / 10.0)
+    } else {
+        format!("{}/10", compute_armor_class(ch))
+    };
     send_to_char(
         &mut game.descriptors,
         ch,
         format!(
-            "Your armor class is {}/10, and your alignment is {}.\r\n",
-            compute_armor_class(ch),
+            "Your armor class is {}, and your alignment is {}.\r\n",
+            armor,
             ch.get_alignment()
         )
         .as_str(),
@@ -1182,6 +1232,18 @@ pub fn do_score(
         )
         .as_str(),
     );
+    if ch.get_quest_points() > 0 {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "You have earned {} quest point{}.\r\n",
+                ch.get_quest_points(),
+                if ch.get_quest_points() == 1 { "" } else { "s" }
+            )
+            .as_str(),
+        );
+    }
     if ch.get_level() < LVL_IMMORT {
         send_to_char(
             &mut game.descriptors,
@@ -1361,9 +1423,19 @@ pub fn do_equipment(
 ) {
     let ch = chars.get(chid);
     let mut found = false;
+    let linear = screen_reader(ch);
     send_to_char(&mut game.descriptors, ch, "You are using:\r\n");
     for (i, wear_where) in WEAR_WHERE.iter().enumerate() {
         if let Some(oid) = ch.get_eq(i) {
+            /* "<worn on head>      " is read as "Worn on head: " */
+            let where_linear;
+            let wear_where = if linear {
+                let place = wear_where.trim_end().trim_matches(|c| c == '<' || c == '>');
+                where_linear = format!("{}{}: ", place[..1].to_uppercase(), &place[1..]);
+                where_linear.as_str()
+            } else {
+                wear_where
+            };
             let obj = objs.get(oid);
             if can_see_obj(&game.descriptors, chars, db, ch, obj) {
                 send_to_char(&mut game.descriptors, ch, wear_where);
@@ -1471,14 +1543,31 @@ pub fn do_weather(
         "rainy",
         "lit by flashes of lightning",
     ];
-    if db.outside(ch) {
+    const SNOW_LOOK: [&str; 4] = [
+        "cloudless",
+        "cloudy",
+        "snowy",
+        "hidden by a howling blizzard",
+    ];
+    if db.under_sky(ch) {
+        let zone = &db.zone_table[db.world[ch.in_room() as usize].zone as usize];
+        let season = season(db.time_info.month);
+        let look = if snows(zone.climate, season) {
+            &SNOW_LOOK
+        } else {
+            &SKY_LOOK
+        };
         let messg = format!(
             "The sky is {} and {}.\r\n",
-            SKY_LOOK[db.weather_info.sky as usize],
-            if db.weather_info.change >= 0 {
-                "you feel a warm wind from south"
-            } else {
+            look[zone.weather.sky as usize],
+            if zone.weather.change < 0 {
                 "your foot tells you bad weather is due"
+            } else {
+                match zone.climate {
+                    Climate::Desert => "you feel a hot, dry wind from the south",
+                    Climate::Arctic => "you feel a bitter wind from the north",
+                    _ => "you feel a warm wind from south",
+                }
             }
         );
         send_to_char(&mut game.descriptors, ch, messg.as_str());
@@ -1488,11 +1577,13 @@ pub fn do_weather(
                 &mut game.descriptors,
                 ch,
                 format!(
-                    "Pressure: {} (change: {}), Sky: {} ({})\r\n",
-                    db.weather_info.pressure,
-                    db.weather_info.change,
-                    db.weather_info.sky as usize,
-                    SKY_LOOK[db.weather_info.sky as usize],
+                    "Pressure: {} (change: {}), Sky: {} ({}), Climate: {}, Season: {}\r\n",
+                    zone.weather.pressure,
+                    zone.weather.change,
+                    zone.weather.sky as usize,
+                    look[zone.weather.sky as usize],
+                    CLIMATE_TYPES[zone.climate as usize],
+                    SEASON_NAME[season as usize],
                 )
                 .as_str(),
             );
@@ -1680,7 +1771,12 @@ pub fn do_who(
         }
     } /* end while (parser) */
 
-    send_to_char(&mut game.descriptors, ch, "Players\r\n-------\r\n");
+    let linear = screen_reader(ch);
+    if linear {
+        send_to_char(&mut game.descriptors, ch, "Players online:\r\n");
+    } else {
+        send_to_char(&mut game.descriptors, ch, "Players\r\n-------\r\n");
+    }
     let mut num_can_see = 0;
 
     for d_id in game.descriptor_list.clone() {
@@ -1728,6 +1824,53 @@ pub fn do_who(
         if showclass != 0 && (showclass & (1 << tch.get_class() as i8)) == 0 {
             continue;
         }
+        if linear {
+            num_can_see += 1;
+            let mut notes = vec![format!(
+                "level {} {}",
+                tch.get_level(),
+                PC_CLASS_TYPES[tch.get_class() as usize].to_lowercase()
+            )];
+            if let Some(tag) = clan_tag(db, tch) {
+                notes.push(format!("of clan {}", tag));
+            }
+            if tch.get_invis_lev() != 0 {
+                notes.push(format!("invisible to level {}", tch.get_invis_lev()));
+            } else if tch.aff_flagged(AffectFlags::INVISIBLE) {
+                notes.push("invisible".to_string());
+            }
+            if tch.plr_flagged(PLR_MAILING) {
+                notes.push("writing mail".to_string());
+            } else if tch.plr_flagged(PLR_WRITING) {
+                notes.push("writing".to_string());
+            }
+            if tch.prf_flagged(PrefFlags::DEAF) {
+                notes.push("deaf to shouts".to_string());
+            }
+            if tch.prf_flagged(PrefFlags::NOTELL) {
+                notes.push("not taking tells".to_string());
+            }
+            if tch.prf_flagged(PrefFlags::QUEST) {
+                notes.push("on a quest".to_string());
+            }
+            if tch.plr_flagged(PLR_THIEF) {
+                notes.push("a thief".to_string());
+            }
+            if tch.plr_flagged(PLR_KILLER) {
+                notes.push("a killer".to_string());
+            }
+            let who = if short_list || tch.get_title().is_empty() {
+                tch.get_name().to_string()
+            } else {
+                format!("{} {}", tch.get_name(), tch.get_title())
+            };
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                format!("{}, {}.\r\n", who, notes.join(", ")).as_str(),
+            );
+            continue;
+        }
         if short_list {
             #[allow(clippy::blocks_in_conditions)]
             let messg = format!(
@@ -1758,7 +1901,7 @@ pub fn do_who(
         } else {
             num_can_see += 1;
             let messg = format!(
-                "{}[{:2} {}] {} {}",
+                "{}[{:2} {}] {}{} {}",
                 if tch.get_level() >= LVL_IMMORT {
                     CCYEL!(ch, C_SPR)
                 } else {
@@ -1766,6 +1909,7 @@ pub fn do_who(
                 },
                 tch.get_level(),
                 tch.class_abbr(),
+                clan_tag(db, tch).map_or(String::new(), |tag| format!("[{}] ", tag)),
                 tch.get_name(),
                 tch.get_title()
             );
@@ -1807,7 +1951,7 @@ pub fn do_who(
             send_to_char(&mut game.descriptors, ch, "\r\n");
         } /* endif shortlist */
     } /* end of for */
-    if short_list && (num_can_see % 4) != 0 {
+    if short_list && !linear && (num_can_see % 4) != 0 {
         send_to_char(&mut game.descriptors, ch, "\r\n");
     }
     if num_can_see == 0 {
@@ -2569,7 +2713,7 @@ pub fn do_diagnose(
         ) {
             diag_char_to_char(&mut game.descriptors, db, chars, vict, ch);
         } else {
-            send_to_char(&mut game.descriptors, ch, NOPERSON);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
         }
     } else if let Some(fighting_id) = ch.fighting_id() {
         let fighting = chars.get(fighting_id);
@@ -2599,15 +2743,51 @@ pub fn do_color(
         return;
     }
 
-    one_argument(argument, &mut arg);
+    let rest = one_argument(argument, &mut arg);
 
     if arg.is_empty() {
+        let depth = match ch.player_specials.color_depth {
+            Some(depth) => depth.name().to_string(),
+            None => match ch.desc {
+                Some(d_id) => format!("auto, {}", game.desc(d_id).color_depth.name()),
+                None => "auto".to_string(),
+            },
+        };
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "Your current color level is {} (color depth {}).\r\n",
+                CTYPES[COLOR_LEV!(ch) as usize],
+                depth
+            )
+            .as_str(),
+        );
+        return;
+    }
+    if is_abbrev(&arg, "depth") {
+        one_argument(rest, &mut arg);
+        let depth = if arg == "auto" {
+            None
+        } else if let Some(depth) = ColorDepth::from_name(&arg) {
+            Some(depth)
+        } else {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "Usage: color depth { Auto | None | 16 | 256 | Truecolor }\r\n",
+            );
+            return;
+        };
+        let ch = chars.get_mut(chid);
+        ch.player_specials.color_depth = depth;
+        let ch = chars.get(chid);
         send_to_char(
             &mut game.descriptors,
             ch,
             format!(
-                "Your current color level is {}.\r\n",
-                CTYPES[COLOR_LEV!(ch) as usize]
+                "Your color depth is now {}.\r\n",
+                depth.map_or("auto", ColorDepth::name)
             )
             .as_str(),
         );
@@ -2634,13 +2814,137 @@ pub fn do_color(
             )
             .as_str(),
         );
+        if screen_reader(ch) {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "It takes effect once the screen reader mode is off.\r\n",
+            );
+        }
     } else {
         send_to_char(
             &mut game.descriptors,
             ch,
-            "Usage: color { Off | Sparse | Normal | Complete }\r\n",
+            "Usage: color { Off | Sparse | Normal | Complete | Depth <depth> }\r\n",
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_colorset(
+    game: &mut Game,
+    _db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    let mut arg1 = String::new();
+    if ch.is_npc() {
+        return;
+    }
+    let themes = COLOR_THEMES
+        .iter()
+        .map(|t| t.name)
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    /* colors are case sensitive, &r is not &R */
+    let arg2 = one_argument(argument, &mut arg1).trim();
+
+    if arg1.is_empty() {
+        let ps = &ch.player_specials;
+        let mut buf = format!(
+            "Message colors (theme {}):\r\n",
+            COLOR_THEMES[ps.color_theme].name
+        );
+        for (cat, (name, _)) in MSG_CATEGORIES.iter().enumerate() {
+            let color = ps.colors[cat]
+                .as_deref()
+                .unwrap_or(COLOR_THEMES[ps.color_theme].colors[cat]);
+            buf.push_str(&format!(
+                "  {:<10} &({})This is how it looks.{}  {}{}\r\n",
+                name,
+                name,
+                MARKUP_NRM,
+                if color.is_empty() {
+                    "none".to_string()
+                } else {
+                    color.replace('&', "&&")
+                },
+                if ps.colors[cat].is_some() {
+                    " (changed)"
+                } else {
+                    ""
+                }
+            ));
+        }
+        send_to_char(&mut game.descriptors, ch, &buf);
+        return;
+    }
+    if is_abbrev(&arg1, "theme") {
+        let arg2 = arg2.to_ascii_lowercase();
+        let Some(theme) = COLOR_THEMES.iter().position(|t| t.name == arg2) else {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                format!("Usage: colorset theme {{ {} }}\r\n", themes).as_str(),
+            );
+            return;
+        };
+        let ch = chars.get_mut(chid);
+        ch.player_specials.color_theme = theme;
+        ch.player_specials.colors = Default::default();
+        let ch = chars.get(chid);
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!("Your messages now use the {} colors.\r\n", arg2).as_str(),
         );
+        return;
     }
+    let Some(cat) = MSG_CATEGORIES.iter().position(|(n, _)| *n == arg1) else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "Usage: colorset <category> {{ <color> | none | default }}\r\n       colorset theme {{ {} }}\r\nCategories: {}\r\n",
+                themes,
+                MSG_CATEGORIES.map(|(n, _)| n).join(" ")
+            )
+            .as_str(),
+        );
+        return;
+    };
+    let color = match arg2 {
+        "default" => None,
+        "none" => Some(Rc::from("")),
+        code if valid_color_code(code) => Some(Rc::from(code)),
+        _ => {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "Colors are written &&r, &&R (bright) or &&#rrggbb; see HELP COLOR.\r\n",
+            );
+            return;
+        }
+    };
+    let ch = chars.get_mut(chid);
+    ch.player_specials.colors[cat] = color;
+    let ch = chars.get(chid);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        format!(
+            "&({})Your {} messages now look like this.{}\r\n",
+            arg1, arg1, MARKUP_NRM
+        )
+        .as_str(),
+    );
 }
 
 #[macro_export]
@@ -2712,7 +3016,8 @@ pub fn do_toggle(
  Mana Display: {:3}    NoTell: {:3}    Repeat Comm.: {:3}\r\n\
  Auto Show Exit: {:3}    Deaf: {:3}    Wimp Level: {:3}\r\n\
  Gossip Channel: {:3}    Auction Channel: {:3}    Grats Channel: {:3}\r\n\
- Color Level: {}\r\n",
+ Color Level: {}    Clan Channel: {:3}    Screen Reader: {:3}\r\n\
+        Privacy: {:3}\r\n",
             onoff!(ch.prf_flagged(PrefFlags::DISPHP)),
             onoff!(ch.prf_flagged(PrefFlags::BRIEF)),
             onoff!(!ch.prf_flagged(PrefFlags::SUMMONABLE)),
@@ -2728,7 +3033,10 @@ pub fn do_toggle(
             onoff!(!ch.prf_flagged(PrefFlags::NOGOSS)),
             onoff!(!ch.prf_flagged(PrefFlags::NOAUCT)),
             onoff!(!ch.prf_flagged(PrefFlags::NOGRATZ)),
-            CTYPES[COLOR_LEV!(ch) as usize]
+            CTYPES[COLOR_LEV!(ch) as usize],
+            onoff!(!ch.prf_flagged(PrefFlags::NOCLAN)),
+            onoff!(ch.prf_flagged(PrefFlags::SCREENREADER)),
+            onoff!(ch.prf_flagged(PrefFlags::PRIVACY))
         )
         .as_str(),
     );
@@ -14,10 +14,12 @@ use std::rc::Rc;
 
 use crate::depot::{Depot, DepotId, HasId};
 use crate::limits::gain_condition;
+use crate::quest::quest_deliver;
+use crate::scripts::{drop_trigger, give_trigger};
 use crate::{act, send_to_char, CharData, DescriptorData, ObjData, TextData, VictimRef};
 use log::error;
 
-use crate::config::{DONATION_ROOM_1, NOPERSON, OK};
+use crate::config::DONATION_ROOM_1;
 use crate::constants::{DRINKNAMES, DRINKS, DRINK_AFF, STR_APP};
 use crate::db::DB;
 use crate::handler::{
@@ -993,9 +995,10 @@ macro_rules! vanish {
 
 #[allow(clippy::too_many_arguments)]
 fn perform_drop(
-    descs: &mut Depot<DescriptorData>,
+    game: &mut Game,
     db: &mut DB,
     chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
     objs: &mut Depot<ObjData>,
     chid: DepotId,
     oid: DepotId,
@@ -1008,7 +1011,7 @@ fn perform_drop(
     if obj.obj_flagged(ExtraFlags::NODROP) {
         let buf = format!("You can't {} $p, it must be CURSED!", sname);
         act(
-            descs,
+            &mut game.descriptors,
             chars,
             db,
             &buf,
@@ -1023,7 +1026,7 @@ fn perform_drop(
 
     let buf = format!("You {} $p.{}", sname, vanish!(mode));
     act(
-        descs,
+        &mut game.descriptors,
         chars,
         db,
         &buf,
@@ -1036,7 +1039,7 @@ fn perform_drop(
 
     let buf = format!("$n {}s $p.{}", sname, vanish!(mode));
     act(
-        descs,
+        &mut game.descriptors,
         chars,
         db,
         &buf,
@@ -1056,12 +1059,13 @@ fn perform_drop(
     match mode {
         SCMD_DROP => {
             db.obj_to_room(obj, ch.in_room());
+            drop_trigger(game, db, chars, texts, objs, chid, oid);
         }
 
         SCMD_DONATE => {
             db.obj_to_room(obj, rdr);
             act(
-                descs,
+                &mut game.descriptors,
                 chars,
                 db,
                 "$p suddenly appears in a puff a smoke!",
@@ -1093,7 +1097,7 @@ pub fn do_drop(
     game: &mut Game,
     db: &mut DB,
     chars: &mut Depot<CharData>,
-    _texts: &mut Depot<TextData>,
+    texts: &mut Depot<TextData>,
     objs: &mut Depot<ObjData>,
     chid: DepotId,
     argument: &str,
@@ -1193,9 +1197,10 @@ pub fn do_drop(
             }
             while let Some(the_obj) = obj {
                 amount += perform_drop(
-                    &mut game.descriptors,
+                    game,
                     db,
                     chars,
+                    texts,
                     objs,
                     chid,
                     the_obj.id(),
@@ -1253,17 +1258,8 @@ pub fn do_drop(
             } else {
                 let list = ch.carrying.clone();
                 for oid in list {
-                    amount += perform_drop(
-                        &mut game.descriptors,
-                        db,
-                        chars,
-                        objs,
-                        chid,
-                        oid,
-                        mode,
-                        sname,
-                        rdr,
-                    );
+                    amount +=
+                        perform_drop(game, db, chars, texts, objs, chid, oid, mode, sname, rdr);
                 }
             }
         } else if dotmode == FindAllType::AllDot {
@@ -1298,9 +1294,10 @@ pub fn do_drop(
 
             while let Some(the_obj) = obj {
                 amount += perform_drop(
-                    &mut game.descriptors,
+                    game,
                     db,
                     chars,
+                    texts,
                     objs,
                     chid,
                     the_obj.id(),
@@ -1333,9 +1330,10 @@ pub fn do_drop(
                 &ch.carrying,
             ) {
                 amount += perform_drop(
-                    &mut game.descriptors,
+                    game,
                     db,
                     chars,
+                    texts,
                     objs,
                     chid,
                     obj.id(),
@@ -1376,10 +1374,12 @@ pub fn do_drop(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn perform_give(
-    descs: &mut Depot<DescriptorData>,
+    game: &mut Game,
     db: &mut DB,
     chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
     objs: &mut Depot<ObjData>,
     chid: DepotId,
     vict_id: DepotId,
@@ -1390,7 +1390,7 @@ fn perform_give(
     let obj = objs.get_mut(oid);
     if obj.obj_flagged(ExtraFlags::NODROP) {
         act(
-            descs,
+            &mut game.descriptors,
             chars,
             db,
             "You can't let go of $p!!  Yeech!",
@@ -1404,7 +1404,7 @@ fn perform_give(
     }
     if vict.is_carrying_n() >= vict.can_carry_n() as u8 {
         act(
-            descs,
+            &mut game.descriptors,
             chars,
             db,
             "$N seems to have $S hands full.",
@@ -1418,7 +1418,7 @@ fn perform_give(
     }
     if obj.get_obj_weight() + vict.is_carrying_w() > vict.can_carry_w() as i32 {
         act(
-            descs,
+            &mut game.descriptors,
             chars,
             db,
             "$E can't carry that much weight.",
@@ -1437,7 +1437,7 @@ fn perform_give(
     let obj = objs.get(oid);
     let vict = chars.get(vict_id);
     act(
-        descs,
+        &mut game.descriptors,
         chars,
         db,
         "You give $p to $N.",
@@ -1448,7 +1448,7 @@ fn perform_give(
         TO_CHAR,
     );
     act(
-        descs,
+        &mut game.descriptors,
         chars,
         db,
         "$n gives you $p.",
@@ -1459,7 +1459,7 @@ fn perform_give(
         TO_VICT,
     );
     act(
-        descs,
+        &mut game.descriptors,
         chars,
         db,
         "$n gives $p to $N.",
@@ -1469,6 +1469,8 @@ fn perform_give(
         Some(VictimRef::Char(vict)),
         TO_NOTVICT,
     );
+    quest_deliver(game, chars, db, objs, chid, vict_id, oid);
+    give_trigger(game, db, chars, texts, objs, chid, oid, vict_id);
 }
 
 /* utility function for give */
@@ -1486,7 +1488,7 @@ fn give_find_vict<'a>(
     } else {
         match get_char_vis(descs, chars, db, ch, &mut arg, None, FindFlags::CHAR_ROOM) {
             None => {
-                send_to_char(descs, ch, NOPERSON);
+                send_to_char(descs, ch, db.msg(ch, "noperson"));
             }
             Some(vict) if vict.id() == ch.id() => {
                 send_to_char(descs, ch, "What's the point of that?\r\n");
@@ -1524,7 +1526,7 @@ fn perform_give_gold(
         send_to_char(descs, ch, "You don't have that many coins!\r\n");
         return;
     }
-    send_to_char(descs, ch, OK);
+    send_to_char(descs, ch, db.msg(ch, "ok"));
 
     buf = format!(
         "$n gives you {} gold coin{}.",
@@ -1570,7 +1572,7 @@ pub fn do_give(
     game: &mut Game,
     db: &mut DB,
     chars: &mut Depot<CharData>,
-    _texts: &mut Depot<TextData>,
+    texts: &mut Depot<TextData>,
     objs: &mut Depot<ObjData>,
     chid: DepotId,
     argument: &str,
@@ -1615,15 +1617,7 @@ pub fn do_give(
                     let vict_id = vict.id();
                     while amount != 0 {
                         amount -= 1;
-                        perform_give(
-                            &mut game.descriptors,
-                            db,
-                            chars,
-                            objs,
-                            chid,
-                            vict_id,
-                            obj.id(),
-                        );
+                        perform_give(game, db, chars, texts, objs, chid, vict_id, obj.id());
                         let ch = chars.get(chid);
                         if let Some(found_obj) = get_obj_in_list_vis(
                             &game.descriptors,
@@ -1668,15 +1662,7 @@ pub fn do_give(
                     None,
                     &ch.carrying,
                 ) {
-                    perform_give(
-                        &mut game.descriptors,
-                        db,
-                        chars,
-                        objs,
-                        chid,
-                        vict.id(),
-                        obj.id(),
-                    );
+                    perform_give(game, db, chars, texts, objs, chid, vict.id(), obj.id());
                 } else {
                     //       let ch = chars.get(chid);
                     send_to_char(
@@ -1707,15 +1693,7 @@ pub fn do_give(
                         if can_see_obj(&game.descriptors, chars, db, ch, obj)
                             && (dotmode == FindAllType::All || isname(&arg, &obj.name))
                         {
-                            perform_give(
-                                &mut game.descriptors,
-                                db,
-                                chars,
-                                objs,
-                                chid,
-                                vict_id,
-                                oid,
-                            );
+                            perform_give(game, db, chars, texts, objs, chid, vict_id, oid);
                         }
                     }
                 }
@@ -11,14 +11,16 @@
 
 use log::error;
 
+use crate::clan::clan_can_enter;
 use crate::depot::{Depot, DepotId, HasId};
 use crate::fight::death_cry;
-use crate::{act, send_to_char, send_to_room, DescriptorData, TextData, VictimRef};
+use crate::scripts::{entry_trigger, greet_mtrigger};
+use crate::{act, act_msg, send_to_char, send_to_room, DescriptorData, TextData, VictimRef};
 use std::borrow::Borrow;
 
 use crate::act_informative::look_at_room;
 use crate::act_item::find_eq_pos;
-use crate::config::{NOPERSON, OK, TUNNEL_SIZE};
+use crate::config::TUNNEL_SIZE;
 use crate::constants::{DEX_APP_SKILL, DIRS, MOVEMENT_LOSS, REV_DIR};
 use crate::db::DB;
 use crate::handler::{fname, generic_find, get_char_vis, isname, FindFlags};
@@ -27,6 +29,7 @@ use crate::interpreter::{
     one_argument, search_block, special, two_arguments, SCMD_CLOSE, SCMD_LOCK, SCMD_OPEN,
     SCMD_PICK, SCMD_UNLOCK,
 };
+use crate::quest::quest_visit;
 use crate::spells::SKILL_PICK_LOCK;
 use crate::structs::{
     AffectFlags, CharData, ExitFlags, ItemType, ObjData, ObjVnum, Position, RoomFlags, RoomRnum,
@@ -94,32 +97,20 @@ pub fn perform_move(
     }
     match db.exit(ch, dir as usize) {
         None => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Alas, you cannot go that way...\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.no_way"));
         }
         Some(exit) if exit.to_room == NOWHERE => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Alas, you cannot go that way...\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.no_way"));
         }
         Some(exit) if exit.exit_flagged(ExitFlags::CLOSED) => {
             if !exit.keyword.is_empty() {
                 send_to_char(
                     &mut game.descriptors,
                     ch,
-                    format!(
-                        "The {} seems to be closed.\r\n",
-                        fname(exit.keyword.as_ref())
-                    )
-                    .as_str(),
+                    &db.msgf(ch, "move.door_closed", &[&fname(exit.keyword.as_ref())]),
                 );
             } else {
-                send_to_char(&mut game.descriptors, ch, "It seems to be closed.\r\n");
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.closed"));
             }
         }
         Some(_) => {
@@ -146,11 +137,12 @@ pub fn perform_move(
                 let follower = chars.get(f.follower);
                 if follower.in_room() == was_in && follower.get_pos() >= Position::Standing {
                     let ch = chars.get(chid);
-                    act(
+                    act_msg(
                         &mut game.descriptors,
                         chars,
                         db,
-                        "You follow $N.\r\n",
+                        "move.follow",
+                        &[],
                         false,
                         Some(follower),
                         None,
@@ -190,16 +182,13 @@ pub fn do_simple_move(
 
     if let Some(master) = ch.master {
         if ch.aff_flagged(AffectFlags::CHARM) && ch.in_room() == chars.get(master).in_room() {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "The thought of leaving your master makes you weep.\r\n",
-            );
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.charmed"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n bursts into tears.",
+                "move.charmed_room",
+                &[],
                 false,
                 Some(ch),
                 None,
@@ -210,17 +199,18 @@ pub fn do_simple_move(
         }
     }
 
+    if db.exit_walled(ch.in_room(), dir as usize) {
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.wall"));
+        return false;
+    }
+
     if let Some(exit) = db.exit(ch, dir as usize) {
         /* if this room or the one we're going to needs a boat, check for one */
         if ((db.sect(ch.in_room()) == SectorType::WaterNoSwim)
             || (db.sect(exit.to_room) == SectorType::WaterNoSwim))
             && !has_boat(&mut game.descriptors, objs, ch)
         {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You need a boat to go there.\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.need_boat"));
             return false;
         }
 
@@ -235,10 +225,10 @@ pub fn do_simple_move(
                 send_to_char(
                     &mut game.descriptors,
                     ch,
-                    "You are too exhausted to follow.\r\n",
+                    db.msg(ch, "move.too_tired_follow"),
                 );
             } else {
-                send_to_char(&mut game.descriptors, ch, "You are too exhausted.\r\n");
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.too_tired"));
             }
 
             return false;
@@ -247,38 +237,28 @@ pub fn do_simple_move(
         if db.room_flagged(ch.in_room(), RoomFlags::ATRIUM)
             && !house_can_enter(db, ch, db.get_room_vnum(exit.to_room))
         {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "That's private property -- no trespassing!\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.private"));
+            return false;
+        }
+        if db.room_flagged(exit.to_room, RoomFlags::CLANHALL)
+            && !clan_can_enter(db, ch, db.get_room_vnum(exit.to_room))
+        {
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.clan_hall"));
             return false;
         }
         if db.room_flagged(exit.to_room, RoomFlags::TUNNEL)
             && num_pc_in_room(db.world[exit.to_room as usize].borrow()) >= TUNNEL_SIZE
         {
             if TUNNEL_SIZE > 1 {
-                send_to_char(
-                    &mut game.descriptors,
-                    ch,
-                    "There isn't enough room for you to go there!\r\n",
-                );
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.tunnel_full"));
             } else {
-                send_to_char(
-                    &mut game.descriptors,
-                    ch,
-                    "There isn't enough room there for more than one person!\r\n",
-                );
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.tunnel"));
             }
             return false;
         }
         /* Mortals and low level gods cannot enter greater god rooms. */
         if db.room_flagged(exit.to_room, RoomFlags::GODROOM) && ch.get_level() < LVL_GRGOD {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You aren't godly enough to use that room!\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "move.godroom"));
             return false;
         }
 
@@ -289,12 +269,13 @@ pub fn do_simple_move(
         }
         let ch = chars.get(chid);
         if !ch.aff_flagged(AffectFlags::SNEAK) {
-            let buf2 = format!("$n leaves {}.", DIRS[dir as usize]);
-            act(
+            let dir_name = format!("dir.{}", DIRS[dir as usize]);
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                buf2.as_str(),
+                "move.leave",
+                &[&dir_name],
                 true,
                 Some(ch),
                 None,
@@ -321,11 +302,12 @@ pub fn do_simple_move(
 
         let ch = chars.get(chid);
         if !ch.aff_flagged(AffectFlags::SNEAK) {
-            act(
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n has arrived.",
+                "move.arrive",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -346,6 +328,10 @@ pub fn do_simple_move(
             db.extract_char(chars, chid);
             return false;
         }
+
+        quest_visit(game, chars, db, chid);
+        entry_trigger(game, db, chars, texts, objs, chid);
+        greet_mtrigger(game, db, chars, texts, objs, chid);
         true
     } else {
         false
@@ -633,7 +619,7 @@ fn do_doorcmd(
                 } // else should not happen
             }
             let ch = chars.get(chid);
-            send_to_char(descs, ch, OK);
+            send_to_char(descs, ch, db.msg(ch, "ok"));
         }
         SCMD_CLOSE => {
             let ch_in_room = ch.in_room();
@@ -644,7 +630,7 @@ fn do_doorcmd(
                 } // else should not happen
             }
             let ch = chars.get(chid);
-            send_to_char(descs, ch, OK);
+            send_to_char(descs, ch, db.msg(ch, "ok"));
         }
         SCMD_LOCK => {
             let ch_in_room = ch.in_room();
@@ -655,7 +641,7 @@ fn do_doorcmd(
                 } // else should not happen
             }
             let ch = chars.get(chid);
-            send_to_char(descs, ch, OK);
+            send_to_char(descs, ch, db.msg(ch, "ok"));
         }
         SCMD_UNLOCK => {
             let ch_in_room = ch.in_room();
@@ -666,7 +652,7 @@ fn do_doorcmd(
                 } // else should not happen
             }
             let ch = chars.get(chid);
-            send_to_char(descs, ch, OK);
+            send_to_char(descs, ch, db.msg(ch, "ok"));
         }
 
         SCMD_PICK => {
@@ -1134,15 +1120,20 @@ pub fn do_stand(
     let ch = chars.get(chid);
     match ch.get_pos() {
         Position::Standing => {
-            send_to_char(&mut game.descriptors, ch, "You are already standing.\r\n");
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                db.msg(ch, "pos.already_standing"),
+            );
         }
         Position::Sitting => {
-            send_to_char(&mut game.descriptors, ch, "You stand up.\r\n");
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.stand"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n clambers to $s feet.",
+                "pos.stand_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1158,16 +1149,13 @@ pub fn do_stand(
             });
         }
         Position::Resting => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You stop resting, and stand up.\r\n",
-            );
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.stand_from_rest"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n stops resting, and clambers on $s feet.",
+                "pos.stand_from_rest_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1178,26 +1166,27 @@ pub fn do_stand(
             ch.set_pos(Position::Standing);
         }
         Position::Sleeping => {
-            send_to_char(&mut game.descriptors, ch, "You have to wake up first!\r\n");
-        }
-        Position::Fighting => {
             send_to_char(
                 &mut game.descriptors,
                 ch,
-                "Do you not consider fighting as standing?\r\n",
+                db.msg(ch, "pos.wake_first_stand"),
             );
         }
+        Position::Fighting => {
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.stand_fighting"));
+        }
         _ => {
             send_to_char(
                 &mut game.descriptors,
                 ch,
-                "You stop floating around, and put your feet on the ground.\r\n",
+                db.msg(ch, "pos.stand_from_float"),
             );
-            act(
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n stops floating around, and puts $s feet on the ground.",
+                "pos.stand_from_float_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1225,12 +1214,13 @@ pub fn do_sit(
     let ch = chars.get(chid);
     match ch.get_pos() {
         Position::Standing => {
-            send_to_char(&mut game.descriptors, ch, "You sit down.\r\n");
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.sit"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n sits down.",
+                "pos.sit_room",
+                &[],
                 false,
                 Some(ch),
                 None,
@@ -1241,19 +1231,16 @@ pub fn do_sit(
             ch.set_pos(Position::Sitting);
         }
         Position::Sitting => {
-            send_to_char(&mut game.descriptors, ch, "You're sitting already.\r\n");
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.already_sitting"));
         }
         Position::Resting => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You stop resting, and sit up.\r\n",
-            );
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.sit_from_rest"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n stops resting.",
+                "pos.sit_from_rest_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1264,26 +1251,19 @@ pub fn do_sit(
             ch.set_pos(Position::Sitting);
         }
         Position::Sleeping => {
-            send_to_char(&mut game.descriptors, ch, "You have to wake up first.\r\n");
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.wake_first"));
         }
         Position::Fighting => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Sit down while fighting? Are you MAD?\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.sit_fighting"));
         }
         _ => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You stop floating around, and sit down.\r\n",
-            );
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.sit_from_float"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n stops floating around, and sits down.",
+                "pos.sit_from_float_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1311,16 +1291,13 @@ pub fn do_rest(
     let ch = chars.get(chid);
     match ch.get_pos() {
         Position::Standing => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You sit down and rest your tired bones.\r\n",
-            );
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.rest_from_stand"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n sits down and rests.",
+                "pos.rest_from_stand_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1331,12 +1308,13 @@ pub fn do_rest(
             ch.set_pos(Position::Resting);
         }
         Position::Sitting => {
-            send_to_char(&mut game.descriptors, ch, "You rest your tired bones.\r\n");
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.rest"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n rests.",
+                "pos.rest_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1347,29 +1325,22 @@ pub fn do_rest(
             ch.set_pos(Position::Resting);
         }
         Position::Resting => {
-            send_to_char(&mut game.descriptors, ch, "You are already resting.\r\n");
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.already_resting"));
         }
         Position::Sleeping => {
-            send_to_char(&mut game.descriptors, ch, "You have to wake up first.\r\n");
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.wake_first"));
         }
         Position::Fighting => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Rest while fighting?  Are you MAD?\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.rest_fighting"));
         }
         _ => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You stop floating around, and stop to rest your tired bones.\r\n",
-            );
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.rest_from_float"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n stops floating around, and rests.",
+                "pos.rest_from_float_room",
+                &[],
                 false,
                 Some(ch),
                 None,
@@ -1397,12 +1368,13 @@ pub fn do_sleep(
     let ch = chars.get(chid);
     match ch.get_pos() {
         Position::Standing | Position::Sitting | Position::Resting => {
-            send_to_char(&mut game.descriptors, ch, "You go to sleep.\r\n");
-            act(
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.sleep"));
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n lies down and falls asleep.",
+                "pos.sleep_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1413,30 +1385,23 @@ pub fn do_sleep(
             ch.set_pos(Position::Sleeping);
         }
         Position::Sleeping => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You are already sound asleep.\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.already_asleep"));
         }
         Position::Fighting => {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Sleep while fighting?  Are you MAD?\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.sleep_fighting"));
         }
         _ => {
             send_to_char(
                 &mut game.descriptors,
                 ch,
-                "You stop floating around, and lie down to sleep.\r\n",
+                db.msg(ch, "pos.sleep_from_float"),
             );
-            act(
+            act_msg(
                 &mut game.descriptors,
                 chars,
                 db,
-                "$n stops floating around, and lie down to sleep.",
+                "pos.sleep_from_float_room",
+                &[],
                 true,
                 Some(ch),
                 None,
@@ -1469,11 +1434,7 @@ pub fn do_wake(
     if !arg.is_empty() {
         #[allow(clippy::blocks_in_conditions)]
         if ch.get_pos() == Position::Sleeping {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Maybe you should wake yourself up first.\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.wake_self_first"));
         }
         match get_char_vis(
             &game.descriptors,
@@ -1485,17 +1446,18 @@ pub fn do_wake(
             FindFlags::CHAR_ROOM,
         ) {
             None => {
-                send_to_char(&mut game.descriptors, ch, NOPERSON);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
             }
             Some(vict) if vict.id() == chid => {
                 self_ = true;
             }
             Some(vict) if vict.awake() => {
-                act(
+                act_msg(
                     &mut game.descriptors,
                     chars,
                     db,
-                    "$E is already awake.",
+                    "pos.already_awake_vict",
+                    &[],
                     false,
                     Some(ch),
                     None,
@@ -1504,11 +1466,12 @@ pub fn do_wake(
                 );
             }
             Some(vict) if vict.aff_flagged(AffectFlags::SLEEP) => {
-                act(
+                act_msg(
                     &mut game.descriptors,
                     chars,
                     db,
-                    "You can't wake $M up!",
+                    "pos.cant_wake_vict",
+                    &[],
                     false,
                     Some(ch),
                     None,
@@ -1517,11 +1480,12 @@ pub fn do_wake(
                 );
             }
             Some(vict) if vict.get_pos() < Position::Sleeping => {
-                act(
+                act_msg(
                     &mut game.descriptors,
                     chars,
                     db,
-                    "$E's in pretty bad shape!",
+                    "pos.bad_shape_vict",
+                    &[],
                     false,
                     Some(ch),
                     None,
@@ -1530,22 +1494,24 @@ pub fn do_wake(
                 );
             }
             Some(vict) => {
-                act(
+                act_msg(
                     &mut game.descriptors,
                     chars,
                     db,
-                    "You wake $M up.",
+                    "pos.wake_vict",
+                    &[],
                     false,
                     Some(ch),
                     None,
                     Some(VictimRef::Char(vict)),
                     TO_CHAR,
                 );
-                act(
+                act_msg(
                     &mut game.descriptors,
                     chars,
                     db,
-                    "You are awakened by $n.",
+                    "pos.awakened_by",
+                    &[],
                     false,
                     Some(ch),
                     None,
@@ -1562,16 +1528,17 @@ pub fn do_wake(
     }
     let ch = chars.get(chid);
     if ch.aff_flagged(AffectFlags::SLEEP) {
-        send_to_char(&mut game.descriptors, ch, "You can't wake up!\r\n");
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.cant_wake"));
     } else if ch.get_pos() > Position::Sleeping {
-        send_to_char(&mut game.descriptors, ch, "You are already awake...\r\n");
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.already_awake"));
     } else {
-        send_to_char(&mut game.descriptors, ch, "You awaken, and sit up.\r\n");
-        act(
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "pos.wake"));
+        act_msg(
             &mut game.descriptors,
             chars,
             db,
-            "$n awakens.",
+            "pos.wake_room",
+            &[],
             true,
             Some(ch),
             None,
@@ -1612,7 +1579,7 @@ pub fn do_follow(
         ) {
             leader = char;
         } else {
-            send_to_char(&mut game.descriptors, ch, NOPERSON);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
             return;
         }
     } else {
@@ -12,7 +12,7 @@
 use log::error;
 
 use crate::act_movement::do_simple_move;
-use crate::config::{NOPERSON, OK, PK_ALLOWED};
+use crate::config::PK_ALLOWED;
 use crate::depot::{Depot, DepotId, HasId};
 use crate::fight::{check_killer, compute_armor_class, raw_kill};
 use crate::handler::{get_char_vis, FindFlags};
@@ -67,7 +67,7 @@ pub fn do_assist(
             FindFlags::CHAR_ROOM,
         ) {
             None => {
-                send_to_char(&mut game.descriptors, ch, NOPERSON);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
             }
             Some(helpee) if helpee.id() == chid => {
                 send_to_char(
@@ -267,16 +267,12 @@ pub fn do_hit(
                     }
                 }
                 let ch = chars.get(chid);
-                if ch.get_pos() == Position::Standing {
-                    if let Some(fighting_id) = ch.fighting_id() {
-                        if vict_id == fighting_id {
-                            send_to_char(&mut game.descriptors, ch, "You do the best you can!\r\n");
-                        } else {
-                            game.hit(chars, db, texts, objs, chid, vict_id, TYPE_UNDEFINED);
-                            let ch = chars.get_mut(chid);
-                            ch.set_wait_state((PULSE_VIOLENCE + 2) as i32);
-                        }
-                    }
+                if ch.get_pos() == Position::Standing && ch.fighting_id() != Some(vict_id) {
+                    game.hit(chars, db, texts, objs, chid, vict_id, TYPE_UNDEFINED);
+                    let ch = chars.get_mut(chid);
+                    ch.set_wait_state((PULSE_VIOLENCE + 2) as i32);
+                } else {
+                    send_to_char(&mut game.descriptors, ch, "You do the best you can!\r\n");
                 }
             }
         }
@@ -582,7 +578,7 @@ pub fn do_order(
                             TO_ROOM,
                         );
                     } else {
-                        send_to_char(&mut game.descriptors, ch, OK);
+                        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
                         command_interpreter(game, db, chars, texts, objs, vict.id(), &message);
                     }
                 } else {
@@ -621,7 +617,7 @@ pub fn do_order(
                     }
                     let ch = chars.get(chid);
                     if found {
-                        send_to_char(&mut game.descriptors, ch, OK);
+                        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
                     } else {
                         send_to_char(
                             &mut game.descriptors,
@@ -14,6 +14,7 @@ use std::cmp::{max, min};
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::rc::Rc;
 
 use crate::depot::{Depot, DepotId, HasId};
 use crate::{
@@ -23,8 +24,9 @@ use log::error;
 
 use crate::act_wizard::perform_immort_vis;
 use crate::alias::write_aliases;
-use crate::config::{AUTO_SAVE, FREE_RENT, MAX_FILESIZE, NOPERSON, OK, PT_ALLOWED};
-use crate::constants::DEX_APP_SKILL;
+use crate::class::level_exp;
+use crate::config::{AUTO_SAVE, FREE_RENT, MAX_FILESIZE, PT_ALLOWED};
+use crate::constants::{DEX_APP_SKILL, DIRS};
 use crate::db::{BUG_FILE, IDEA_FILE, TYPO_FILE};
 use crate::fight::{appear, die};
 use crate::handler::{
@@ -35,18 +37,21 @@ use crate::house::house_crashsave;
 use crate::interpreter::{
     delete_doubledollar, half_chop, one_argument, two_arguments, CMD_INFO, SCMD_AUTOEXIT,
     SCMD_BRIEF, SCMD_BUG, SCMD_COMPACT, SCMD_DEAF, SCMD_HOLYLIGHT, SCMD_IDEA, SCMD_NOAUCTION,
-    SCMD_NOGOSSIP, SCMD_NOGRATZ, SCMD_NOHASSLE, SCMD_NOREPEAT, SCMD_NOSUMMON, SCMD_NOTELL,
-    SCMD_NOWIZ, SCMD_QUAFF, SCMD_QUEST, SCMD_QUIT, SCMD_RECITE, SCMD_ROOMFLAGS, SCMD_SLOWNS,
-    SCMD_TRACK, SCMD_TYPO, SCMD_USE,
+    SCMD_NOCLAN, SCMD_NOGOSSIP, SCMD_NOGRATZ, SCMD_NOHASSLE, SCMD_NOREPEAT, SCMD_NOSUMMON,
+    SCMD_NOTELL, SCMD_NOWIZ, SCMD_PRIVACY, SCMD_QUAFF, SCMD_QUEST, SCMD_QUIT, SCMD_RECITE,
+    SCMD_ROOMFLAGS, SCMD_SCREENREADER, SCMD_SLOWNS, SCMD_TRACK, SCMD_TYPO, SCMD_USE,
 };
 use crate::objsave::{crash_crashsave, crash_rentsave};
+use crate::screen::close_markup;
 use crate::shops::shop_keeper;
 use crate::spec_procs::list_skills;
 use crate::spell_parser::mag_objectmagic;
 use crate::spells::{SKILL_HIDE, SKILL_SNEAK, SKILL_STEAL, TYPE_UNDEFINED};
 use crate::structs::{
-    AffectFlags, AffectedType, ApplyType, ItemType, Position, PrefFlags, RoomFlags, LVL_IMMORT,
-    MAX_TITLE_LENGTH, NUM_WEARS, PLR_LOADROOM, PLR_NOTITLE, WEAR_HOLD,
+    AffectFlags, AffectedType, ApplyType, ExitFlags, ItemType, Position, PrefFlags, RoomFlags,
+    LVL_IMMORT, MAX_PROMPT_LENGTH, MAX_SCREEN_WIDTH, MAX_TITLE_LENGTH, MIN_SCREEN_WIDTH,
+    MOB_NOTDEADYET, NOWHERE, NUM_OF_DIRS, NUM_WEARS, PLR_LOADROOM, PLR_NOTDEADYET, PLR_NOTITLE,
+    WEAR_HOLD,
 };
 use crate::util::{can_see, can_see_obj, rand_number, stop_follower, DisplayMode};
 use crate::{an, Game, TO_CHAR, TO_NOTVICT, TO_ROOM, TO_VICT};
@@ -82,7 +87,7 @@ pub fn do_quit(
         );
     } else if ch.get_pos() < Position::Stunned {
         send_to_char(&mut game.descriptors, ch, "You die before your time...\r\n");
-        die(chid, game, chars, db, texts, objs);
+        die(chid, None, game, chars, db, texts, objs);
     } else {
         act(
             &mut game.descriptors,
@@ -639,7 +644,7 @@ pub fn do_title(
     _subcmd: i32,
 ) {
     let ch = chars.get(chid);
-    let mut argument = argument.trim_start().to_string();
+    let mut argument = close_markup(argument.trim_start());
     delete_doubledollar(&mut argument);
 
     if ch.is_npc() {
@@ -869,7 +874,7 @@ pub fn do_group(
     ) {
         vict = char;
     } else {
-        send_to_char(&mut game.descriptors, ch, NOPERSON);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
         return;
     }
     let is_not_groupable = if let Some(master_id) = vict.master {
@@ -1487,7 +1492,7 @@ pub fn do_wimpy(
 #[allow(clippy::too_many_arguments)]
 pub fn do_display(
     game: &mut Game,
-    _db: &mut DB,
+    db: &mut DB,
     chars: &mut Depot<CharData>,
     _texts: &mut Depot<TextData>,
     _objs: &mut Depot<ObjData>,
@@ -1511,7 +1516,7 @@ pub fn do_display(
         send_to_char(
             &mut game.descriptors,
             ch,
-            "Usage: prompt { { H | M | V } | all | auto | none }\r\n",
+            "Usage: display { { H | M | V } | all | auto | none }\r\n",
         );
         return;
     }
@@ -1559,7 +1564,7 @@ pub fn do_display(
                     send_to_char(
                         &mut game.descriptors,
                         ch,
-                        "Usage: prompt { { H | M | V } | all | auto | none }\r\n",
+                        "Usage: display { { H | M | V } | all | auto | none }\r\n",
                     );
                     return;
                 }
@@ -1567,7 +1572,290 @@ pub fn do_display(
         }
     }
 
-    send_to_char(&mut game.descriptors, ch, OK);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
+}
+
+/* One word for how hurt someone is, for the %t and %o prompt tokens */
+fn prompt_condition(ch: &CharData) -> &'static str {
+    /* killed this pulse, not extracted yet */
+    if ch.mob_flagged(MOB_NOTDEADYET) || ch.plr_flagged(PLR_NOTDEADYET) {
+        return "";
+    }
+    let percent = if ch.get_max_hit() > 0 {
+        (100 * ch.get_hit() as i32) / ch.get_max_hit() as i32
+    } else {
+        -1
+    };
+    match percent {
+        100.. => "excellent",
+        90..=99 => "scratched",
+        75..=89 => "bruised",
+        50..=74 => "wounded",
+        30..=49 => "nasty",
+        15..=29 => "hurt",
+        0..=14 => "awful",
+        _ => "bleeding",
+    }
+}
+
+/*
+ * Expands a prompt template.  Tokens start with '%':
+ *
+ *   %h %H  hit points, current and maximum
+ *   %m %M  mana, current and maximum
+ *   %v %V  movement, current and maximum
+ *   %x     experience needed for the next level
+ *   %g     gold carried
+ *   %t %o  condition of the tank and of your opponent, in combat
+ *   %r     room vnum (immortals only)
+ *   %e     obvious exits
+ *   %T     time of day
+ *   %Cx    color x: r, g, y, b, m, c, w, or n for normal
+ *   %%     a percent sign
+ */
+pub fn expand_prompt(chars: &Depot<CharData>, db: &DB, ch: &CharData, template: &str) -> String {
+    let mut prompt = String::new();
+    let mut it = template.chars();
+
+    while let Some(c) = it.next() {
+        if c != '%' {
+            prompt.push(c);
+            continue;
+        }
+        match it.next() {
+            Some('h') => prompt.push_str(&ch.get_hit().to_string()),
+            Some('H') => prompt.push_str(&ch.get_max_hit().to_string()),
+            Some('m') => prompt.push_str(&ch.get_mana().to_string()),
+            Some('M') => prompt.push_str(&ch.get_max_mana().to_string()),
+            Some('v') => prompt.push_str(&ch.get_move().to_string()),
+            Some('V') => prompt.push_str(&ch.get_max_move().to_string()),
+            Some('x') => {
+                let tnl = if ch.get_level() >= LVL_IMMORT {
+                    0
+                } else {
+                    max(
+                        0,
+                        level_exp(ch.get_class(), ch.get_level() + 1) - ch.get_exp(),
+                    )
+                };
+                prompt.push_str(&tnl.to_string());
+            }
+            Some('g') => prompt.push_str(&ch.get_gold().to_string()),
+            Some('t') => {
+                if let Some(tank_id) = ch
+                    .fighting_id()
+                    .and_then(|vict_id| chars.get(vict_id).fighting_id())
+                {
+                    prompt.push_str(prompt_condition(chars.get(tank_id)));
+                }
+            }
+            Some('o') => {
+                if let Some(vict_id) = ch.fighting_id() {
+                    prompt.push_str(prompt_condition(chars.get(vict_id)));
+                }
+            }
+            Some('r') => {
+                if ch.get_level() >= LVL_IMMORT {
+                    prompt.push_str(&db.get_room_vnum(ch.in_room()).to_string());
+                }
+            }
+            /* as the exits command, nothing to the blind or in the dark */
+            Some('e') => {
+                if ch.aff_flagged(AffectFlags::BLIND)
+                    || (db.is_dark(ch.in_room()) && !ch.can_see_in_dark())
+                {
+                    continue;
+                }
+                let exits = DIRS
+                    .iter()
+                    .take(NUM_OF_DIRS)
+                    .enumerate()
+                    .filter(|&(door, _)| {
+                        db.exit(ch, door).is_some_and(|exit| {
+                            exit.to_room != NOWHERE && !exit.exit_flagged(ExitFlags::CLOSED)
+                        })
+                    })
+                    .map(|(_, dir)| dir[..1].to_uppercase())
+                    .collect::<String>();
+                prompt.push_str(if exits.is_empty() { "none" } else { &exits });
+            }
+            Some('T') => {
+                let hours = db.time_info.hours;
+                prompt.push_str(&format!(
+                    "{}{}",
+                    if hours % 12 == 0 { 12 } else { hours % 12 },
+                    if hours >= 12 { "pm" } else { "am" }
+                ));
+            }
+            /* color markup, shown as the player's color settings allow */
+            Some('C') => {
+                if let Some(color) = it.next().filter(|c| "rgybmcwn".contains(*c)) {
+                    prompt.push('&');
+                    prompt.push(color);
+                }
+            }
+            Some('%') => prompt.push('%'),
+            Some(other) => {
+                prompt.push('%');
+                prompt.push(other);
+            }
+            None => prompt.push('%'),
+        }
+    }
+    close_markup(&prompt)
+}
+
+const PROMPT_HELP: &str = concat!(
+    "Usage: prompt <template> | default\r\n",
+    "  %h %H  hit points, current and maximum\r\n",
+    "  %m %M  mana, current and maximum\r\n",
+    "  %v %V  movement, current and maximum\r\n",
+    "  %x     experience to the next level\r\n",
+    "  %g     gold carried\r\n",
+    "  %t %o  condition of the tank and of your opponent, in combat\r\n",
+    "  %e     obvious exits\r\n",
+    "  %T     time of day\r\n",
+);
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_prompt(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Monsters don't need prompts.  Go away.\r\n",
+        );
+        return;
+    }
+    let argument = argument.trim();
+
+    if argument.is_empty() {
+        let current = if ch.player_specials.prompt.is_empty() {
+            "You are using the default prompt (see DISPLAY).\r\n".to_string()
+        } else {
+            format!("Your prompt is: {}\r\n", ch.player_specials.prompt)
+        };
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "{}{}{}{}",
+                current,
+                PROMPT_HELP,
+                if ch.get_level() >= LVL_IMMORT {
+                    "  %r     room vnum\r\n"
+                } else {
+                    ""
+                },
+                concat!(
+                    "  %Cx    color x: r, g, y, b, m, c, w, or n for normal\r\n",
+                    "  %%     a percent sign\r\n"
+                )
+            )
+            .as_str(),
+        );
+        return;
+    }
+
+    if argument.eq_ignore_ascii_case("default") {
+        let ch = chars.get_mut(chid);
+        ch.player_specials.prompt = Rc::from("");
+        let ch = chars.get(chid);
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Your prompt is back to the default.\r\n",
+        );
+        return;
+    }
+
+    if argument.len() > MAX_PROMPT_LENGTH {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "Prompts can't be longer than {} characters.\r\n",
+                MAX_PROMPT_LENGTH
+            )
+            .as_str(),
+        );
+        return;
+    }
+
+    /* the input line loses its trailing blanks; give one back */
+    let ch = chars.get_mut(chid);
+    ch.player_specials.prompt = Rc::from(format!("{} ", close_markup(argument)).as_str());
+    let ch = chars.get(chid);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_width(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg = String::new();
+    one_argument(argument, &mut arg);
+
+    if arg.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "Your screen is {} columns wide.\r\nUsage: width <{}-{}> | default\r\n",
+                ch.get_screen_width(),
+                MIN_SCREEN_WIDTH,
+                MAX_SCREEN_WIDTH
+            )
+            .as_str(),
+        );
+        return;
+    }
+    let width = if arg == "default" {
+        0
+    } else {
+        match arg.parse::<u16>() {
+            Ok(width) if (MIN_SCREEN_WIDTH..=MAX_SCREEN_WIDTH).contains(&width) => width,
+            _ => {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    format!(
+                        "The width must be from {} to {} columns.\r\n",
+                        MIN_SCREEN_WIDTH, MAX_SCREEN_WIDTH
+                    )
+                    .as_str(),
+                );
+                return;
+            }
+        }
+    };
+    let ch = chars.get_mut(chid);
+    ch.player_specials.screen_width = width;
+    let ch = chars.get(chid);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1690,7 +1978,7 @@ pub fn do_gen_tog(
     subcmd: i32,
 ) {
     let ch = chars.get(chid);
-    const TOG_MESSAGES: [[&str; 2]; 17] = [
+    const TOG_MESSAGES: [[&str; 2]; 20] = [
         [
             "You are now safe from summoning by other players.\r\n",
             "You may now be summoned by other players.\r\n",
@@ -1744,6 +2032,18 @@ pub fn do_gen_tog(
             "Will no longer track through doors.\r\n",
             "Will now track through doors.\r\n",
         ],
+        [
+            "You can now hear your clan.\r\n",
+            "You are now deaf to your clan.\r\n",
+        ],
+        [
+            "Screen reader mode off.\r\n",
+            "Screen reader mode on.  Colors, borders and tables are left out.\r\n",
+        ],
+        [
+            "Your friends will now be told when you come and go.\r\n",
+            "Your friends will no longer be told when you come and go.\r\n",
+        ],
     ];
 
     if ch.is_npc() {
@@ -1778,6 +2078,15 @@ pub fn do_gen_tog(
         SCMD_NOGRATZ => {
             prf_tog_chk!(ch, PrefFlags::NOGRATZ)
         }
+        SCMD_NOCLAN => {
+            prf_tog_chk!(ch, PrefFlags::NOCLAN)
+        }
+        SCMD_SCREENREADER => {
+            prf_tog_chk!(ch, PrefFlags::SCREENREADER)
+        }
+        SCMD_PRIVACY => {
+            prf_tog_chk!(ch, PrefFlags::PRIVACY)
+        }
         SCMD_NOWIZ => {
             prf_tog_chk!(ch, PrefFlags::NOWIZ)
         }
@@ -1824,3 +2133,62 @@ pub fn do_gen_tog(
         );
     }
 }
+
+#[cfg(test)]
+mod act_other_tests {
+    use super::*;
+    use crate::db::TestWorld;
+
+    fn prompt(w: &TestWorld, chid: DepotId, template: &str) -> String {
+        expand_prompt(&w.chars, &w.db, w.chars.get(chid), template)
+    }
+
+    #[test]
+    fn test_expand_prompt() {
+        let mut w = TestWorld::boot("prompt");
+        let alice = w.add_player("alice", 1, 3001);
+        let orc = w.add_player("orc", 2, 3001);
+        let ch = w.chars.get_mut(alice);
+        ch.set_hit(12);
+        ch.set_max_hit(20);
+        ch.set_mana(3);
+        ch.set_max_mana(10);
+        ch.set_move(50);
+        ch.set_max_move(80);
+        ch.set_gold(77);
+        ch.set_exp(level_exp(ch.get_class(), 11) - 100);
+        w.db.time_info.hours = 13;
+
+        assert_eq!(prompt(&w, alice, "%h/%H %m/%M %v/%V"), "12/20 3/10 50/80");
+        assert_eq!(prompt(&w, alice, "%x %g %T"), "100 77 1pm");
+        assert_eq!(prompt(&w, alice, "%e"), "NESWD");
+        assert_eq!(prompt(&w, alice, "100%% %q %"), "100% %q %");
+        /* no room vnum but to the gods, no condition out of a fight */
+        assert_eq!(prompt(&w, alice, "[%r%t%o]"), "[]");
+
+        /* the orc fights alice, who is then the tank */
+        w.chars.get_mut(orc).set_max_hit(10);
+        w.chars.get_mut(orc).set_hit(10);
+        w.chars.get_mut(alice).set_fighting(Some(orc));
+        w.chars.get_mut(orc).set_fighting(Some(alice));
+        assert_eq!(prompt(&w, alice, "%t %o"), "wounded excellent");
+
+        /* colors are markup, closed at the end if left open */
+        assert_eq!(prompt(&w, alice, "%Cr%h%Cn>"), "&r12&n>");
+        assert_eq!(prompt(&w, alice, "%Cg%h%Cz>"), "&g12>&n");
+
+        /* as the exits command, the blind and those in the dark see none */
+        w.chars
+            .get_mut(alice)
+            .set_aff_flags_bits(AffectFlags::BLIND);
+        assert_eq!(prompt(&w, alice, "[%e]"), "[]");
+        w.chars.get_mut(alice).remove_aff_flags(AffectFlags::BLIND);
+        let temple = w.db.real_room(3001);
+        w.db.set_room_flags_bit(temple, RoomFlags::DARK);
+        assert_eq!(prompt(&w, alice, "[%e]"), "[]");
+        w.chars
+            .get_mut(alice)
+            .set_aff_flags_bits(AffectFlags::INFRAVISION);
+        assert_eq!(prompt(&w, alice, "[%e]"), "[NESWD]");
+    }
+}
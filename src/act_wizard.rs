@@ -16,14 +16,16 @@ use std::rc::Rc;
 use std::{mem, slice};
 
 use crate::act_informative::look_at_room;
+use crate::clan::clan_can_enter;
 use crate::class::{
     do_start, level_exp, parse_class, roll_real_abils, CLASS_ABBREVS, PC_CLASS_TYPES,
 };
-use crate::config::{LOAD_INTO_INVENTORY, NOPERSON, OK};
+use crate::config::LOAD_INTO_INVENTORY;
 use crate::constants::{
-    ACTION_BITS, AFFECTED_BITS, APPLY_TYPES, CONNECTED_TYPES, CONTAINER_BITS, DEX_APP, DIRS,
-    DRINKS, EXIT_BITS, EXTRA_BITS, GENDERS, INT_APP, ITEM_TYPES, NPC_CLASS_TYPES, PLAYER_BITS,
-    POSITION_TYPES, PREFERENCE_BITS, ROOM_BITS, SECTOR_TYPES, WEAR_BITS, WIS_APP,
+    ACTION_BITS, AFFECTED_BITS, APPLY_TYPES, CLIMATE_TYPES, CONNECTED_TYPES, CONTAINER_BITS,
+    DESC_CONDS, DEX_APP, DIRS, DRINKS, EXIT_BITS, EXTRA_BITS, GENDERS, INT_APP, ITEM_TYPES,
+    NPC_CLASS_TYPES, PLAYER_BITS, POSITION_TYPES, PREFERENCE_BITS, ROOM_AFFECT_BITS, ROOM_BITS,
+    SECTOR_TYPES, WEAR_BITS, WIS_APP,
 };
 use crate::db::{
     clear_char, parse_c_string, store_to_char, LoadType, DB, FASTBOOT_FILE, KILLSCRIPT_FILE,
@@ -44,16 +46,16 @@ use crate::interpreter::{
 use crate::limits::{gain_exp_regardless, hit_gain, mana_gain, move_gain, set_title};
 use crate::modify::page_string;
 use crate::objsave::crash_listrent;
-use crate::screen::{C_NRM, KCYN, KGRN, KNRM, KNUL, KYEL};
+use crate::screen::{close_markup, C_NRM, KCYN, KGRN, KNRM, KNUL, KYEL};
 use crate::shops::show_shops;
 use crate::spell_parser::skill_name;
 use crate::structs::ConState::{ConClose, ConDisconnect, ConPlaying};
 use crate::structs::{
-    AffectFlags, CharData, CharFileU, Class, ItemType, PrefFlags, RoomFlags, RoomRnum, RoomVnum,
-    ZoneRnum, DRUNK, FULL, LVL_FREEZE, LVL_GOD, LVL_GRGOD, LVL_IMMORT, LVL_IMPL, MAX_OBJ_AFFECT,
-    MAX_SKILLS, NOBODY, NOTHING, NOWHERE, NUM_OF_DIRS, NUM_WEARS, PLR_DELETED, PLR_FROZEN,
-    PLR_INVSTART, PLR_KILLER, PLR_LOADROOM, PLR_MAILING, PLR_NODELETE, PLR_NOSHOUT, PLR_NOTITLE,
-    PLR_NOWIZLIST, PLR_SITEOK, PLR_THIEF, PLR_WRITING, THIRST,
+    AffectFlags, CharData, CharFileU, Class, ItemType, PrefFlags, RoomAffectFlags, RoomFlags,
+    RoomRnum, RoomVnum, ZoneRnum, DRUNK, FULL, LVL_FREEZE, LVL_GOD, LVL_GRGOD, LVL_IMMORT,
+    LVL_IMPL, MAX_OBJ_AFFECT, MAX_SKILLS, NOBODY, NOTHING, NOWHERE, NUM_OF_DIRS, NUM_WEARS,
+    PLR_DELETED, PLR_FROZEN, PLR_INVSTART, PLR_KILLER, PLR_LOADROOM, PLR_MAILING, PLR_NODELETE,
+    PLR_NOSHOUT, PLR_NOTITLE, PLR_NOWIZLIST, PLR_SITEOK, PLR_THIEF, PLR_WRITING, THIRST,
 };
 use crate::util::{
     age, can_see, can_see_obj, ctime, hmhr, pers, sprintbit, sprinttype, time_now, touch,
@@ -86,7 +88,7 @@ pub fn do_echo(
     subcmd: i32,
 ) {
     let ch = chars.get(chid);
-    let argument = argument.trim_start();
+    let argument = close_markup(argument.trim_start());
 
     if argument.is_empty() {
         send_to_char(&mut game.descriptors, ch, "Yes.. but what?\r\n");
@@ -110,7 +112,7 @@ pub fn do_echo(
         );
         let ch = chars.get(chid);
         if ch.prf_flagged(PrefFlags::NOREPEAT) {
-            send_to_char(&mut game.descriptors, ch, OK);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         } else {
             act(
                 &mut game.descriptors,
@@ -162,7 +164,7 @@ pub fn do_send(
     ) {
         vict = char;
     } else {
-        send_to_char(&mut game.descriptors, ch, NOPERSON);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
         return;
     }
     send_to_char(&mut game.descriptors, vict, format!("{}\r\n", buf).as_str());
@@ -278,6 +280,14 @@ fn find_target_room(
         && !house_can_enter(db, ch, db.get_room_vnum(location))
     {
         send_to_char(descs, ch, "That's private property -- no trespassing!\r\n");
+    } else if db.room_flagged(location, RoomFlags::CLANHALL)
+        && !clan_can_enter(db, ch, db.get_room_vnum(location))
+    {
+        send_to_char(
+            descs,
+            ch,
+            "Only members of the clan may enter that hall.\r\n",
+        );
     } else {
         return location;
     }
@@ -448,7 +458,7 @@ pub fn do_trans(
             FindFlags::CHAR_WORLD,
         ) {
             None => {
-                send_to_char(&mut game.descriptors, ch, NOPERSON);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
             }
             Some(victim) if victim.id() == chid => {
                 send_to_char(
@@ -579,7 +589,7 @@ pub fn do_trans(
         }
     }
     let ch = chars.get(chid);
-    send_to_char(&mut game.descriptors, ch, OK);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -618,7 +628,7 @@ pub fn do_teleport(
             FindFlags::CHAR_WORLD,
         ) {
             None => {
-                send_to_char(&mut game.descriptors, ch, NOPERSON);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
             }
             Some(victim) if victim.id() == chid => {
                 send_to_char(
@@ -647,7 +657,7 @@ pub fn do_teleport(
                     target != NOWHERE
                 } =>
             {
-                send_to_char(&mut game.descriptors, ch, OK);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
                 act(
                     &mut game.descriptors,
                     chars,
@@ -821,6 +831,52 @@ fn do_stat_room(
         }
     }
 
+    for alt in &db.world[chars.get(chid).in_room() as usize].alt_descriptions {
+        let ch = chars.get(chid);
+        let mut buf = String::new();
+        sprintbit(alt.conditions.bits() as i64, &DESC_CONDS, &mut buf);
+        send_to_char(
+            descs,
+            ch,
+            format!(
+                "Description when {}{}{}:\r\n{}",
+                CCCYN!(ch, C_NRM),
+                buf.trim_end(),
+                CCNRM!(ch, C_NRM),
+                alt.description
+            )
+            .as_str(),
+        );
+    }
+
+    /* Routine to show what spells a room is affected by */
+    for af in &db.world[chars.get(chid).in_room() as usize].affected {
+        let ch = chars.get(chid);
+        let mut buf = String::new();
+        sprintbit(af.bitvector.bits() as i64, &ROOM_AFFECT_BITS, &mut buf);
+        let extra = if af.bitvector.intersects(RoomAffectFlags::WALL) {
+            format!(" {}", DIRS[af.modifier as usize])
+        } else if af.bitvector.intersects(RoomAffectFlags::CONSECRATED) {
+            format!(" +{}% regen", af.modifier)
+        } else {
+            String::new()
+        };
+        send_to_char(
+            descs,
+            ch,
+            format!(
+                "SPL: ({:3}hr) {}{:21}{} sets {}{}\r\n",
+                af.duration + 1,
+                CCCYN!(ch, C_NRM),
+                skill_name(db, af._type as i32),
+                CCNRM!(ch, C_NRM),
+                buf.trim_end(),
+                extra
+            )
+            .as_str(),
+        );
+    }
+
     if !rm_peoples.is_empty() {
         let ch = chars.get(chid);
         send_to_char(
@@ -2054,7 +2110,7 @@ pub fn do_snoop(
         send_to_char(&mut game.descriptors, ch, "You can't.\r\n");
         return;
     }
-    send_to_char(&mut game.descriptors, ch, OK);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
     if let Some(snooping) = game.desc(ch_desc).snooping {
         game.desc_mut(snooping).snoop_by = None;
     }
@@ -2141,8 +2197,16 @@ pub fn do_switch(
             ch,
             "That's private property -- no trespassing!\r\n",
         );
+    } else if db.room_flagged(victim.in_room(), RoomFlags::CLANHALL)
+        && !clan_can_enter(db, ch, db.get_room_vnum(victim.in_room()))
+    {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Only members of the clan may enter that hall.\r\n",
+        );
     } else {
-        send_to_char(&mut game.descriptors, ch, OK);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         game.desc_mut(ch_desc).character = Some(victim.id());
         game.desc_mut(ch_desc).original = Some(chid);
         let ch = chars.get(chid);
@@ -2563,7 +2627,7 @@ pub fn do_purge(
             return;
         }
         let ch = chars.get(chid);
-        send_to_char(&mut game.descriptors, ch, OK);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
     } else {
         /* no argument. clean out the room */
 
@@ -2815,7 +2879,7 @@ You feel slightly different.",
         );
     }
     let ch = chars.get(chid);
-    send_to_char(&mut game.descriptors, ch, OK);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
     let ch = chars.get(chid);
     if newlevel < oldlevel {
         let victim = chars.get(victim_id);
@@ -2894,7 +2958,7 @@ pub fn do_restore(
         FindFlags::CHAR_WORLD,
     ) {
         None => {
-            send_to_char(&mut game.descriptors, ch, NOPERSON);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
         }
         Some(vict) if !vict.is_npc() && chid != vict.id() && vict.get_level() >= ch.get_level() => {
             send_to_char(&mut game.descriptors, ch, "They don't need your help.\r\n");
@@ -2932,7 +2996,7 @@ pub fn do_restore(
             affect_total(objs, vict);
             let vict = chars.get(vict_id);
             let ch = chars.get(chid);
-            send_to_char(&mut game.descriptors, ch, OK);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
             act(
                 &mut game.descriptors,
                 chars,
@@ -3074,7 +3138,7 @@ pub fn do_invis(
 #[allow(clippy::too_many_arguments)]
 pub fn do_gecho(
     game: &mut Game,
-    _db: &mut DB,
+    db: &mut DB,
     chars: &mut Depot<CharData>,
     _texts: &mut Depot<TextData>,
     _objs: &mut Depot<ObjData>,
@@ -3105,7 +3169,7 @@ pub fn do_gecho(
         }
         let ch = chars.get(chid);
         if ch.prf_flagged(PrefFlags::NOREPEAT) {
-            send_to_char(&mut game.descriptors, ch, OK);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         } else {
             send_to_char(
                 &mut game.descriptors,
@@ -3119,7 +3183,7 @@ pub fn do_gecho(
 #[allow(clippy::too_many_arguments)]
 pub fn do_poofset(
     game: &mut Game,
-    _db: &mut DB,
+    db: &mut DB,
     chars: &mut Depot<CharData>,
     _texts: &mut Depot<TextData>,
     _objs: &mut Depot<ObjData>,
@@ -3143,7 +3207,7 @@ pub fn do_poofset(
 
         *msg = Rc::from(argument);
     }
-    send_to_char(&mut game.descriptors, ch, OK);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -3460,13 +3524,13 @@ pub fn do_force(
             FindFlags::CHAR_WORLD,
         ) {
             None => {
-                send_to_char(&mut game.descriptors, ch, NOPERSON);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
             }
             Some(vict) if !vict.is_npc() && ch.get_level() <= vict.get_level() => {
                 send_to_char(&mut game.descriptors, ch, "No, no, no!\r\n");
             }
             Some(vict) => {
-                send_to_char(&mut game.descriptors, ch, OK);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
                 act(
                     &mut game.descriptors,
                     chars,
@@ -3496,7 +3560,7 @@ pub fn do_force(
             }
         }
     } else if arg == "room" {
-        send_to_char(&mut game.descriptors, ch, OK);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         let ch = chars.get(chid);
         game.mudlog(
             chars,
@@ -3533,7 +3597,7 @@ pub fn do_force(
         }
     } else {
         /* force all */
-        send_to_char(&mut game.descriptors, ch, OK);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
         let ch = chars.get(chid);
         game.mudlog(
             chars,
@@ -3748,7 +3812,7 @@ pub fn do_wiznet(
     }
     let ch = chars.get(chid);
     if ch.prf_flagged(PrefFlags::NOREPEAT) {
-        send_to_char(&mut game.descriptors, ch, OK);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
     }
 }
 
@@ -4131,13 +4195,120 @@ fn print_zone_to_buf(db: &DB, buf: &mut String, zone: ZoneRnum) {
     let zone = &db.zone_table[zone as usize];
     buf.push_str(
         format!(
-            "{:3} {:30} Age: {:3}; Reset: {:3} ({:1}); Range: {:5}-{:5}\r\n",
-            zone.number, zone.name, zone.age, zone.lifespan, zone.reset_mode, zone.bot, zone.top
+            "{:3} {:30} Age: {:3}; Reset: {:3} ({:1}); Range: {:5}-{:5}; Climate: {}\r\n",
+            zone.number,
+            zone.name,
+            zone.age,
+            zone.lifespan,
+            zone.reset_mode,
+            zone.bot,
+            zone.top,
+            CLIMATE_TYPES[zone.climate as usize]
         )
         .as_str(),
     );
 }
 
+/* The reset table of a zone, in words. */
+fn print_zone_cmds_to_buf(db: &DB, buf: &mut String, zone: ZoneRnum) {
+    const SKIES: [&str; 4] = ["cloudless", "cloudy", "raining", "lightning"];
+    const DOORS: [&str; 3] = ["open", "closed", "locked"];
+    let mob = |rnum: i32| {
+        format!(
+            "mobile #{} ({})",
+            db.mob_vnum(rnum),
+            db.mob_protos
+                .get(rnum as usize)
+                .map_or("?", |m| m.player.short_descr.as_ref())
+        )
+    };
+    let obj = |rnum: i32| {
+        format!(
+            "object #{} ({})",
+            db.obj_vnum(rnum),
+            db.obj_proto
+                .get(rnum as usize)
+                .map_or("?", |o| o.short_description.as_ref())
+        )
+    };
+    let sky = |s: i32| SKIES.get(s as usize).copied().unwrap_or("?");
+
+    buf.push_str("Reset commands:\r\n");
+    for zcmd in db.zone_table[zone as usize]
+        .cmd
+        .iter()
+        .take_while(|c| c.command != 'S')
+    {
+        let what = match zcmd.command {
+            'M' => format!(
+                "Load {} in room #{}, max {}",
+                mob(zcmd.arg1),
+                db.room_vnum(zcmd.arg3),
+                zcmd.arg2
+            ),
+            'Z' => format!(
+                "Load {} in a random room, max {}",
+                mob(zcmd.arg1),
+                zcmd.arg2
+            ),
+            'O' if zcmd.arg3 == NOWHERE as i32 => {
+                format!("Load {} nowhere, max {}", obj(zcmd.arg1), zcmd.arg2)
+            }
+            'O' => format!(
+                "Load {} in room #{}, max {}",
+                obj(zcmd.arg1),
+                db.room_vnum(zcmd.arg3),
+                zcmd.arg2
+            ),
+            'G' => format!("Give {}, max {}", obj(zcmd.arg1), zcmd.arg2),
+            'E' => format!(
+                "Equip {} on position {}, max {}",
+                obj(zcmd.arg1),
+                zcmd.arg3,
+                zcmd.arg2
+            ),
+            'P' => format!(
+                "Put {} in {}, max {}",
+                obj(zcmd.arg1),
+                obj(zcmd.arg3),
+                zcmd.arg2
+            ),
+            'D' => format!(
+                "Make door {} of room #{} {}",
+                DIRS.get(zcmd.arg2 as usize).copied().unwrap_or("?"),
+                db.room_vnum(zcmd.arg1),
+                DOORS.get(zcmd.arg3 as usize).copied().unwrap_or("?")
+            ),
+            'R' => format!(
+                "Remove {} from room #{}",
+                obj(zcmd.arg2),
+                db.room_vnum(zcmd.arg1)
+            ),
+            '%' => format!("Go on {}% of the time", zcmd.arg1),
+            'H' => format!("Go on from {}:00 to {}:59", zcmd.arg1, zcmd.arg2),
+            'W' if zcmd.arg1 == zcmd.arg2 => format!("Go on if the sky is {}", sky(zcmd.arg1)),
+            'W' => format!(
+                "Go on if the sky is {} to {}",
+                sky(zcmd.arg1),
+                sky(zcmd.arg2)
+            ),
+            'L' => format!("Run one of the next {} commands", zcmd.arg1),
+            '*' => "Disabled".to_string(),
+            _ => "Unknown".to_string(),
+        };
+        buf.push_str(
+            format!(
+                "[{:5}] {}{} {}\r\n",
+                zcmd.line,
+                if zcmd.if_flag { "  " } else { "" },
+                zcmd.command,
+                what
+            )
+            .as_str(),
+        );
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn do_show(
     game: &mut Game,
@@ -4267,11 +4438,14 @@ pub fn do_show(
         1 => {
             /* tightened up by JE 4/6/93 */
             if self_ {
-                print_zone_to_buf(db, &mut buf, db.world[ch.in_room() as usize].zone);
+                let zone = db.world[ch.in_room() as usize].zone;
+                print_zone_to_buf(db, &mut buf, zone);
+                print_zone_cmds_to_buf(db, &mut buf, zone);
             } else if let Ok(value) = value.parse::<i32>() {
                 let zrn = db.zone_table.iter().position(|z| z.number == value as i16);
                 if let Some(zrn) = zrn {
                     print_zone_to_buf(db, &mut buf, zrn as ZoneRnum);
+                    print_zone_cmds_to_buf(db, &mut buf, zrn as ZoneRnum);
                 } else {
                     send_to_char(&mut game.descriptors, ch, "That is not a valid zone.\r\n");
                     return;
@@ -4975,7 +5149,7 @@ fn perform_set(
             .as_str(),
         );
     } else {
-        send_to_char(descs, ch, OK);
+        send_to_char(descs, ch, db.msg(ch, "ok"));
     }
     let rnum;
     let vict = chars.get_mut(vict_id);
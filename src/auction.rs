@@ -0,0 +1,1178 @@
+/* ************************************************************************
+*   File: auction.rs                                    Part of CircleMUD *
+*  Usage: The auction house: listings, bids, buyouts and settlement       *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+use std::cmp::max;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+use std::{mem, slice};
+
+use log::{error, info};
+
+use crate::bank::{bank_credit, ledger_record, LedgerKind};
+use crate::clan::find_player;
+use crate::db::{save_char, AUCTION_FILE, DB, LIB_AUCTION};
+use crate::depot::{Depot, DepotId, HasId};
+use crate::handler::{get_obj_in_list_vis, obj_to_char};
+use crate::interpreter::{half_chop, is_abbrev, is_number, one_argument};
+use crate::objsave::{crash_crashsave, crash_is_unrentable, obj_from_store, obj_to_store};
+use crate::screen::{C_NRM, KMAG, KNRM, KNUL};
+use crate::sidefile::{open_side_file, read_records, save_side_file, write_record, SideRecord};
+use crate::structs::ConState::ConPlaying;
+use crate::structs::{
+    CharData, ExtraFlags, ItemType, ObjFileElem, ObjVnum, PrefFlags, RoomFlags, NOTHING,
+    PLR_WRITING,
+};
+use crate::util::time_now;
+use crate::{
+    _clrlevel, act, clr, send_to_char, Game, ObjData, TextData, CCMAG, CCNRM, TO_CHAR, TO_ROOM,
+};
+
+pub const MAX_LOTS_PER_SELLER: usize = 10;
+pub const MAX_AUCTION_HOURS: u64 = 72;
+const DEFAULT_AUCTION_HOURS: u64 = 24;
+const AUCTION_CUT: i64 = 5; /* percent of every sale kept by the house */
+const AUCTION_EXTEND: u64 = 5 * 60; /* a late bid keeps the lot open this long */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuctionLot {
+    pub number: i32,
+    pub vnum: ObjVnum,
+    /* prototype of the escrowed object, for the listings */
+    pub seller: i64,
+    pub min_bid: i32,
+    pub buyout: i32,
+    /* 0 if the lot can't be bought out */
+    pub bid: i32,
+    pub bidder: i64,
+    /* idnum of the highest bidder, -1 if none */
+    pub expires: u64,
+    pub owner: i64,
+    /* -1 while running, then whoever may collect the object */
+}
+
+impl AuctionLot {
+    fn running(&self) -> bool {
+        self.owner < 0
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AuctionHouse {
+    pub lots: Vec<AuctionLot>,
+    pub credits: Vec<(i64, i32)>,
+    /* gold that could not be put in a bank account: (idnum, amount) */
+}
+
+/* The escrowed object of a lot is kept in its own file */
+fn lot_filename(number: i32) -> String {
+    format!("{}{}.obj", LIB_AUCTION, number)
+}
+
+fn lot_store_obj(db: &DB, obj: &ObjData, number: i32) -> bool {
+    let filename = lot_filename(number);
+    match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&filename)
+    {
+        Err(err) => {
+            error!("SYSERR: Unable to open '{}' for writing: {}", filename, err);
+            false
+        }
+        Ok(mut fl) => obj_to_store(db, obj, &mut fl, 0),
+    }
+}
+
+/* Take the object of a lot out of escrow */
+fn lot_load_obj(db: &mut DB, objs: &mut Depot<ObjData>, number: i32) -> Option<DepotId> {
+    let filename = lot_filename(number);
+    let mut fl = match OpenOptions::new().read(true).open(&filename) {
+        Err(err) => {
+            error!("SYSERR: Unable to open auction lot '{}': {}", filename, err);
+            return None;
+        }
+        Ok(fl) => fl,
+    };
+    let mut object = ObjFileElem::new();
+    unsafe {
+        let obj_elem_slice = slice::from_raw_parts_mut(
+            &mut object as *mut _ as *mut u8,
+            mem::size_of::<ObjFileElem>(),
+        );
+        if let Err(err) = fl.read_exact(obj_elem_slice) {
+            error!("SYSERR: Error reading auction lot '{}': {}", filename, err);
+            return None;
+        }
+    }
+    let mut location = 0;
+    let oid = obj_from_store(db, objs, &object, &mut location)?;
+    if let Err(err) = fs::remove_file(&filename) {
+        error!("SYSERR: Unable to delete '{}': {}", filename, err);
+    }
+    Some(oid)
+}
+
+/* Read the listings from the auction file at boot time */
+pub fn auction_boot(db: &mut DB) {
+    let Some(reader) = open_side_file(AUCTION_FILE) else {
+        info!("   Auction file '{}' does not exist.", AUCTION_FILE);
+        return;
+    };
+    let mut house = parse_auctions(reader);
+    house.lots.retain(|lot| {
+        if !Path::new(&lot_filename(lot.number)).exists() {
+            error!(
+                "SYSERR: Auction lot #{} has lost its object, removed",
+                lot.number
+            );
+            return false;
+        }
+        true
+    });
+    info!("   {} lots.", house.lots.len());
+    db.auctions = house;
+}
+
+enum AuctionRecord {
+    Lot(AuctionLot),
+    Credit(i64, i32),
+}
+
+fn parse_auction(rec: &SideRecord) -> Result<AuctionRecord, String> {
+    match rec.key.as_str() {
+        "lot" => Ok(AuctionRecord::Lot(AuctionLot {
+            number: rec.number("Number")?,
+            vnum: rec.number("Vnum")?,
+            seller: rec.number("Seller")?,
+            min_bid: rec.number("MinBid")?,
+            buyout: rec.number("Buyout")?,
+            bid: rec.number("Bid")?,
+            bidder: rec.number("Bidder")?,
+            expires: rec.number("Expires")?,
+            owner: rec.number("Owner")?,
+        })),
+        "credit" => Ok(AuctionRecord::Credit(
+            rec.number("Player")?,
+            rec.number("Gold")?,
+        )),
+        _ => Err("unknown record".into()),
+    }
+}
+
+/*
+ * The auction file is a side file with a record per lot and per pending
+ * credit:
+ *
+ *   #lot                          #credit
+ *   Number: <lot number>          Player: <idnum>
+ *   Vnum: <obj vnum>              Gold: <coins>
+ *   Seller: <idnum>               End
+ *   MinBid: <coins>
+ *   Buyout: <coins>
+ *   Bid: <coins>
+ *   Bidder: <idnum>
+ *   Expires: <time>
+ *   Owner: <idnum>
+ *   End
+ */
+pub(crate) fn parse_auctions(reader: impl BufRead) -> AuctionHouse {
+    let mut house = AuctionHouse::default();
+    let (records, _) = read_records(reader, AUCTION_FILE, parse_auction);
+    for record in records {
+        match record {
+            AuctionRecord::Lot(lot) if house.lots.iter().any(|l| l.number == lot.number) => {
+                error!(
+                    "SYSERR: Duplicate auction lot #{} in '{}', skipped",
+                    lot.number, AUCTION_FILE
+                );
+            }
+            AuctionRecord::Lot(lot) => house.lots.push(lot),
+            AuctionRecord::Credit(idnum, gold) => house.credits.push((idnum, gold)),
+        }
+    }
+    house
+}
+
+pub(crate) fn write_auctions(w: &mut impl Write, house: &AuctionHouse) -> io::Result<()> {
+    for lot in &house.lots {
+        write_record(
+            w,
+            "lot",
+            &[
+                ("Number", lot.number.to_string()),
+                ("Vnum", lot.vnum.to_string()),
+                ("Seller", lot.seller.to_string()),
+                ("MinBid", lot.min_bid.to_string()),
+                ("Buyout", lot.buyout.to_string()),
+                ("Bid", lot.bid.to_string()),
+                ("Bidder", lot.bidder.to_string()),
+                ("Expires", lot.expires.to_string()),
+                ("Owner", lot.owner.to_string()),
+            ],
+        )?;
+    }
+    for (idnum, gold) in &house.credits {
+        write_record(
+            w,
+            "credit",
+            &[("Player", idnum.to_string()), ("Gold", gold.to_string())],
+        )?;
+    }
+    Ok(())
+}
+
+/* Save the listings to the auction file after every change */
+pub fn auction_save(db: &DB) {
+    save_side_file(AUCTION_FILE, |w| write_auctions(w, &db.auctions));
+}
+
+fn lot_name(db: &DB, lot: &AuctionLot) -> String {
+    let rnum = db.real_object(lot.vnum);
+    if rnum == NOTHING {
+        "something".to_string()
+    } else {
+        db.obj_proto[rnum as usize].short_description.to_string()
+    }
+}
+
+fn player_name(db: &DB, idnum: i64) -> String {
+    db.get_name_by_id(idnum).map_or_else(
+        || "someone".to_string(),
+        |name| {
+            let mut name = name.to_string();
+            if let Some(first) = name.get_mut(0..1) {
+                first.make_ascii_uppercase();
+            }
+            name
+        },
+    )
+}
+
+fn time_left(lot: &AuctionLot) -> String {
+    let left = lot.expires.saturating_sub(time_now());
+    if left == 0 {
+        "closing".to_string()
+    } else {
+        format!("{}h {:02}m", left / 3600, left % 3600 / 60)
+    }
+}
+
+fn coins(amount: i32) -> String {
+    format!("{} gold coin{}", amount, if amount == 1 { "" } else { "s" })
+}
+
+fn find_lot(db: &DB, arg: &str) -> Option<usize> {
+    let number = arg.trim_start_matches('#').parse::<i32>().ok()?;
+    db.auctions.lots.iter().position(|l| l.number == number)
+}
+
+/* Tell everybody on the auction channel */
+fn auction_announce(game: &mut Game, chars: &Depot<CharData>, msg: &str) {
+    for d_id in game.descriptor_list.clone() {
+        let d = game.desc(d_id);
+        let Some(ic_id) = d.character else {
+            continue;
+        };
+        let ic = chars.get(ic_id);
+        if d.state() == ConPlaying
+            && !ic.prf_flagged(PrefFlags::NOAUCT)
+            && !ic.plr_flagged(PLR_WRITING)
+        {
+            let msg = format!(
+                "{}[Auction] {}{}\r\n",
+                CCMAG!(ic, C_NRM),
+                msg,
+                CCNRM!(ic, C_NRM)
+            );
+            send_to_char(&mut game.descriptors, ic, &msg);
+        }
+    }
+}
+
+/*
+ * Save a player whose gold or objects the auction house just took or gave,
+ * together with the auction file, so that no crash can lose or double them.
+ */
+fn auction_save_char(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+) {
+    save_char(&mut game.descriptors, db, chars, texts, objs, chid);
+    crash_crashsave(chars, db, objs, chid);
+}
+
+/* Tell a player about their lots, or mail them if they are away */
+fn auction_notify(
+    game: &mut Game,
+    chars: &Depot<CharData>,
+    db: &mut DB,
+    to: i64,
+    from: i64,
+    msg: &str,
+) {
+    if let Some(id) = find_player(db, chars, to) {
+        send_to_char(
+            &mut game.descriptors,
+            chars.get(id),
+            &format!("{}\r\n", msg),
+        );
+    } else if !db.no_mail {
+        db.store_mail(to, from, &format!("{}\r\n", msg));
+    }
+}
+
+/* Put gold in a bank account, or keep it for its owner if that fails */
+#[allow(clippy::too_many_arguments)]
+fn auction_pay(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    idnum: i64,
+    amount: i32,
+    other: i64,
+) {
+    if amount <= 0
+        || bank_credit(
+            game,
+            chars,
+            db,
+            texts,
+            objs,
+            idnum,
+            amount,
+            LedgerKind::Auction,
+            other,
+        )
+    {
+        return;
+    }
+    if let Some(credit) = db.auctions.credits.iter_mut().find(|c| c.0 == idnum) {
+        credit.1 += amount;
+    } else {
+        db.auctions.credits.push((idnum, amount));
+    }
+}
+
+/* The seller gets the price of a sold lot, less the cut of the house */
+#[allow(clippy::too_many_arguments)]
+fn pay_seller(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    lot: &AuctionLot,
+    price: i32,
+    buyer: i64,
+) -> i32 {
+    let proceeds = price - (price as i64 * AUCTION_CUT / 100) as i32;
+    auction_pay(game, chars, db, texts, objs, lot.seller, proceeds, buyer);
+    proceeds
+}
+
+/* Give the bid of the highest bidder back */
+fn refund_bidder(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    l: usize,
+    why: &str,
+) {
+    let lot = db.auctions.lots[l];
+    if lot.bidder < 0 {
+        return;
+    }
+    auction_pay(
+        game, chars, db, texts, objs, lot.bidder, lot.bid, lot.seller,
+    );
+    let msg = format!(
+        "{} on lot #{}, {}.  Your {} went back to your bank account.",
+        why,
+        lot.number,
+        lot_name(db, &lot),
+        coins(lot.bid)
+    );
+    auction_notify(game, chars, db, lot.bidder, lot.seller, &msg);
+}
+
+/* Close the lots whose time has run out */
+pub fn auction_update(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+) {
+    let now = time_now();
+    let mut changed = false;
+    for l in 0..db.auctions.lots.len() {
+        let lot = db.auctions.lots[l];
+        if !lot.running() || lot.expires > now {
+            continue;
+        }
+        changed = true;
+        let name = lot_name(db, &lot);
+        if lot.bidder >= 0 {
+            db.auctions.lots[l].owner = lot.bidder;
+            let proceeds = pay_seller(game, chars, db, texts, objs, &lot, lot.bid, lot.bidder);
+            let msg = format!(
+                "You won lot #{}, {}, for {}.  Collect it at the auction house.",
+                lot.number,
+                name,
+                coins(lot.bid)
+            );
+            auction_notify(game, chars, db, lot.bidder, lot.seller, &msg);
+            let msg = format!(
+                "Lot #{}, {}, sold for {}.  {} went to your bank account.",
+                lot.number,
+                name,
+                coins(lot.bid),
+                coins(proceeds)
+            );
+            auction_notify(game, chars, db, lot.seller, lot.bidder, &msg);
+            auction_announce(
+                game,
+                chars,
+                &format!(
+                    "Lot #{}, {}, sold for {}.",
+                    lot.number,
+                    name,
+                    coins(lot.bid)
+                ),
+            );
+        } else {
+            db.auctions.lots[l].owner = lot.seller;
+            let msg = format!(
+                "Lot #{}, {}, did not sell.  Collect it at the auction house.",
+                lot.number, name
+            );
+            auction_notify(game, chars, db, lot.seller, lot.seller, &msg);
+        }
+    }
+    if changed {
+        auction_save(db);
+    }
+}
+
+/* Pay out what the auction house owes a player who just came in */
+pub fn auction_login(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+) {
+    let idnum = chars.get(chid).get_idnum();
+    if let Some(c) = db.auctions.credits.iter().position(|c| c.0 == idnum) {
+        let (_, gold) = db.auctions.credits.remove(c);
+        let ch = chars.get_mut(chid);
+        ch.set_bank_gold(ch.get_bank_gold() + gold);
+        ledger_record(
+            ch.get_name(),
+            LedgerKind::Auction,
+            gold as i64,
+            ch.get_bank_gold() as i64,
+            -1,
+        );
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!(
+                "The auction house has put {} in your bank account.\r\n",
+                coins(gold)
+            ),
+        );
+        auction_save(db);
+        save_char(&mut game.descriptors, db, chars, texts, objs, chid);
+    }
+    let waiting = db.auctions.lots.iter().filter(|l| l.owner == idnum).count();
+    if waiting > 0 {
+        send_to_char(
+            &mut game.descriptors,
+            chars.get(chid),
+            &format!(
+                "You have {} lot{} waiting at the auction house.\r\n",
+                waiting,
+                if waiting == 1 { "" } else { "s" }
+            ),
+        );
+    }
+}
+
+const MARKET_FORMAT: &str = "Usage: market [list]\r\n\
+       market info <lot>\r\n\
+       market sell <item> <minimum bid> [buyout] [hours]\r\n\
+       market bid <lot> <amount>\r\n\
+       market buy <lot>\r\n\
+       market cancel <lot>\r\n\
+       market collect\r\n";
+
+fn market_list(game: &mut Game, chars: &Depot<CharData>, db: &DB, chid: DepotId) {
+    let ch = chars.get(chid);
+    let mut buf = String::new();
+    for lot in db.auctions.lots.iter().filter(|l| l.running()) {
+        buf.push_str(&format!(
+            "{:4}  {:30.30} {:7} {:>7}  {:>8}  {}\r\n",
+            lot.number,
+            lot_name(db, lot),
+            max(lot.bid, lot.min_bid),
+            if lot.buyout > 0 {
+                lot.buyout.to_string()
+            } else {
+                "-".to_string()
+            },
+            time_left(lot),
+            player_name(db, lot.seller)
+        ));
+    }
+    if buf.is_empty() {
+        buf.push_str("Nothing is up for auction right now.\r\n");
+    } else {
+        buf.insert_str(
+            0,
+            "Lot   Item                               Bid  Buyout  Time left Seller\r\n\
+             ----  ------------------------------ ------- -------  --------- ------\r\n",
+        );
+    }
+    let waiting = db
+        .auctions
+        .lots
+        .iter()
+        .filter(|l| l.owner == ch.get_idnum())
+        .count();
+    if waiting > 0 {
+        buf.push_str(&format!(
+            "You have {} lot{} to collect.\r\n",
+            waiting,
+            if waiting == 1 { "" } else { "s" }
+        ));
+    }
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+fn market_info(game: &mut Game, chars: &Depot<CharData>, db: &DB, chid: DepotId, arg: &str) {
+    let ch = chars.get(chid);
+    let Some(l) = find_lot(db, arg).filter(|&l| db.auctions.lots[l].running()) else {
+        send_to_char(&mut game.descriptors, ch, "There is no such lot.\r\n");
+        return;
+    };
+    let lot = &db.auctions.lots[l];
+    let mut buf = format!(
+        "Lot #{}: {}\r\nSeller: {}\r\nMinimum bid: {}\r\n",
+        lot.number,
+        lot_name(db, lot),
+        player_name(db, lot.seller),
+        coins(lot.min_bid)
+    );
+    if lot.bidder < 0 {
+        buf.push_str("No bids yet.\r\n");
+    } else {
+        buf.push_str(&format!(
+            "Highest bid: {} by {}\r\n",
+            coins(lot.bid),
+            if lot.bidder == ch.get_idnum() {
+                "you".to_string()
+            } else {
+                player_name(db, lot.bidder)
+            }
+        ));
+    }
+    if lot.buyout > 0 {
+        buf.push_str(&format!("Buyout: {}\r\n", coins(lot.buyout)));
+    }
+    buf.push_str(&format!("Time left: {}\r\n", time_left(lot)));
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+fn market_sell(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    arg: &str,
+) {
+    let ch = chars.get(chid);
+    let mut name = String::new();
+    let rest = one_argument(arg, &mut name);
+    let args: Vec<&str> = rest.split_whitespace().collect();
+    if name.is_empty() || args.is_empty() || args.len() > 3 || !args.iter().all(|a| is_number(a)) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Usage: market sell <item> <minimum bid> [buyout] [hours]\r\n",
+        );
+        return;
+    }
+    let min_bid = args[0].parse::<i32>().unwrap_or(0);
+    let buyout = args.get(1).map_or(0, |a| a.parse::<i32>().unwrap_or(-1));
+    let hours = args
+        .get(2)
+        .map_or(DEFAULT_AUCTION_HOURS, |a| a.parse::<u64>().unwrap_or(0));
+    if min_bid <= 0 {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The minimum bid must be at least one gold coin.\r\n",
+        );
+        return;
+    }
+    if buyout != 0 && buyout < min_bid {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The buyout can't be lower than the minimum bid.\r\n",
+        );
+        return;
+    }
+    if !(1..=MAX_AUCTION_HOURS).contains(&hours) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!("A lot can run from 1 to {} hours.\r\n", MAX_AUCTION_HOURS),
+        );
+        return;
+    }
+    let Some(obj) = get_obj_in_list_vis(
+        &game.descriptors,
+        chars,
+        db,
+        objs,
+        ch,
+        &name,
+        None,
+        &ch.carrying,
+    ) else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!("You don't seem to have any {}s.\r\n", name),
+        );
+        return;
+    };
+    let oid = obj.id();
+    let refusal = if obj.obj_flagged(ExtraFlags::NODROP) {
+        Some("You can't let go of $p!!  Yeech!")
+    } else if obj.get_obj_type() == ItemType::Container && !obj.contains.is_empty() {
+        Some("You'll have to empty $p first.")
+    } else if crash_is_unrentable(obj) {
+        Some("The auction house won't take $p.")
+    } else {
+        None
+    };
+    if let Some(msg) = refusal {
+        act(
+            &mut game.descriptors,
+            chars,
+            db,
+            msg,
+            false,
+            Some(ch),
+            Some(obj),
+            None,
+            TO_CHAR,
+        );
+        return;
+    }
+    let seller = ch.get_idnum();
+    if db
+        .auctions
+        .lots
+        .iter()
+        .filter(|l| l.seller == seller && l.running())
+        .count()
+        >= MAX_LOTS_PER_SELLER
+    {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You already have as many lots up for auction as you may.\r\n",
+        );
+        return;
+    }
+
+    let number = db.auctions.lots.iter().map(|l| l.number).max().unwrap_or(0) + 1;
+    if !lot_store_obj(db, obj, number) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The auction house can't take anything right now.\r\n",
+        );
+        return;
+    }
+    let lot = AuctionLot {
+        number,
+        vnum: db.get_obj_vnum(obj),
+        seller,
+        min_bid,
+        buyout,
+        bid: 0,
+        bidder: -1,
+        expires: time_now() + hours * 3600,
+        owner: -1,
+    };
+    let msg = format!("You put $p up for auction as lot #{}.", number);
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        &msg,
+        false,
+        Some(ch),
+        Some(obj),
+        None,
+        TO_CHAR,
+    );
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        "$n puts $p up for auction.",
+        true,
+        Some(ch),
+        Some(obj),
+        None,
+        TO_ROOM,
+    );
+    db.extract_obj(chars, objs, oid);
+    db.auctions.lots.push(lot);
+    auction_save(db);
+    auction_save_char(game, chars, db, texts, objs, chid);
+
+    let mut msg = format!(
+        "Lot #{}, {}, is up for auction.  Minimum bid {}",
+        number,
+        lot_name(db, &lot),
+        coins(min_bid)
+    );
+    if buyout > 0 {
+        msg.push_str(&format!(", buyout {}", coins(buyout)));
+    }
+    msg.push('.');
+    auction_announce(game, chars, &msg);
+}
+
+/* Find a running lot somebody else put up */
+fn lot_for_buyer(game: &mut Game, ch: &CharData, db: &DB, arg: &str) -> Option<usize> {
+    let Some(l) = find_lot(db, arg).filter(|&l| db.auctions.lots[l].running()) else {
+        send_to_char(&mut game.descriptors, ch, "There is no such lot.\r\n");
+        return None;
+    };
+    if db.auctions.lots[l].seller == ch.get_idnum() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You can't bid on your own lot.\r\n",
+        );
+        return None;
+    }
+    Some(l)
+}
+
+fn market_bid(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    arg: &str,
+) {
+    let ch = chars.get(chid);
+    let mut arg1 = String::new();
+    let mut arg2 = String::new();
+    let mut arg = arg.to_string();
+    half_chop(&mut arg, &mut arg1, &mut arg2);
+    if arg1.is_empty() || !is_number(&arg2) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Usage: market bid <lot> <amount>\r\n",
+        );
+        return;
+    }
+    let Some(l) = lot_for_buyer(game, ch, db, &arg1) else {
+        return;
+    };
+    let lot = db.auctions.lots[l];
+    let amount = arg2.parse::<i32>().unwrap_or(0);
+    let lowest = if lot.bidder < 0 {
+        lot.min_bid
+    } else {
+        lot.bid + max(1, lot.bid / 20)
+    };
+    let refusal = if lot.bidder == ch.get_idnum() {
+        Some("You already have the highest bid on that lot.\r\n".to_string())
+    } else if amount < lowest {
+        Some(format!("You must bid at least {}.\r\n", coins(lowest)))
+    } else if lot.buyout > 0 && amount >= lot.buyout {
+        Some("For that much you might as well buy it out.\r\n".to_string())
+    } else if ch.get_gold() < amount {
+        Some("You don't have that much gold!\r\n".to_string())
+    } else {
+        None
+    };
+    if let Some(msg) = refusal {
+        send_to_char(&mut game.descriptors, ch, &msg);
+        return;
+    }
+
+    refund_bidder(game, chars, db, texts, objs, l, "You have been outbid");
+    let ch = chars.get_mut(chid);
+    ch.set_gold(ch.get_gold() - amount);
+    let lot = &mut db.auctions.lots[l];
+    lot.bid = amount;
+    lot.bidder = ch.get_idnum();
+    /* no sniping: a late bid gives the others time to answer */
+    lot.expires = max(lot.expires, time_now() + AUCTION_EXTEND);
+    let lot = *lot;
+    auction_save(db);
+    auction_save_char(game, chars, db, texts, objs, chid);
+
+    let ch = chars.get(chid);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!("You bid {} on lot #{}.\r\n", coins(amount), lot.number),
+    );
+    let name = lot_name(db, &lot);
+    auction_announce(
+        game,
+        chars,
+        &format!(
+            "Lot #{}, {}: the bid is now {}.",
+            lot.number,
+            name,
+            coins(amount)
+        ),
+    );
+}
+
+fn market_buy(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    arg: &str,
+) {
+    let ch = chars.get(chid);
+    let Some(l) = lot_for_buyer(game, ch, db, arg) else {
+        return;
+    };
+    let lot = db.auctions.lots[l];
+    /* the highest bidder only pays what their bid leaves */
+    let price = if lot.bidder == ch.get_idnum() {
+        lot.buyout - lot.bid
+    } else {
+        lot.buyout
+    };
+    if lot.buyout <= 0 {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "That lot can't be bought out, you'll have to bid.\r\n",
+        );
+        return;
+    }
+    if ch.get_gold() < price {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You don't have that much gold!\r\n",
+        );
+        return;
+    }
+    let Some(oid) = lot_load_obj(db, objs, lot.number) else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The auction house can't find that lot right now.\r\n",
+        );
+        return;
+    };
+
+    let buyer = chars.get(chid).get_idnum();
+    if lot.bidder != buyer {
+        refund_bidder(game, chars, db, texts, objs, l, "Somebody bought out");
+    }
+    db.auctions.lots.remove(l);
+    let ch = chars.get_mut(chid);
+    ch.set_gold(ch.get_gold() - price);
+    obj_to_char(objs.get_mut(oid), ch);
+    let proceeds = pay_seller(game, chars, db, texts, objs, &lot, lot.buyout, buyer);
+    auction_save(db);
+    auction_save_char(game, chars, db, texts, objs, chid);
+
+    let ch = chars.get(chid);
+    let msg = format!("You buy $p for {}.", coins(lot.buyout));
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        &msg,
+        false,
+        Some(ch),
+        Some(objs.get(oid)),
+        None,
+        TO_CHAR,
+    );
+    let name = lot_name(db, &lot);
+    let msg = format!(
+        "Lot #{}, {}, was bought out by {} for {}.  {} went to your bank account.",
+        lot.number,
+        name,
+        player_name(db, buyer),
+        coins(lot.buyout),
+        coins(proceeds)
+    );
+    auction_notify(game, chars, db, lot.seller, buyer, &msg);
+    auction_announce(
+        game,
+        chars,
+        &format!(
+            "Lot #{}, {}, was bought out for {}.",
+            lot.number,
+            name,
+            coins(lot.buyout)
+        ),
+    );
+}
+
+/* Hand the object of a lot to a character and close the lot */
+fn lot_give(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    l: usize,
+) -> bool {
+    let number = db.auctions.lots[l].number;
+    let Some(oid) = lot_load_obj(db, objs, number) else {
+        send_to_char(
+            &mut game.descriptors,
+            chars.get(chid),
+            &format!(
+                "The auction house can't find lot #{} right now.\r\n",
+                number
+            ),
+        );
+        return false;
+    };
+    db.auctions.lots.remove(l);
+    obj_to_char(objs.get_mut(oid), chars.get_mut(chid));
+    auction_save(db);
+    auction_save_char(game, chars, db, texts, objs, chid);
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        "You collect $p.",
+        false,
+        Some(chars.get(chid)),
+        Some(objs.get(oid)),
+        None,
+        TO_CHAR,
+    );
+    true
+}
+
+fn market_cancel(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    arg: &str,
+) {
+    let ch = chars.get(chid);
+    let Some(l) = find_lot(db, arg)
+        .filter(|&l| db.auctions.lots[l].running() && db.auctions.lots[l].seller == ch.get_idnum())
+    else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You have no such lot up for auction.\r\n",
+        );
+        return;
+    };
+    if db.auctions.lots[l].bidder >= 0 {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Somebody has bid on that lot already, it's too late.\r\n",
+        );
+        return;
+    }
+    lot_give(game, chars, db, texts, objs, chid, l);
+}
+
+fn market_collect(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+) {
+    let idnum = chars.get(chid).get_idnum();
+    let mut collected = false;
+    let mut l = 0;
+    while l < db.auctions.lots.len() {
+        if db.auctions.lots[l].owner == idnum && lot_give(game, chars, db, texts, objs, chid, l) {
+            collected = true;
+        } else {
+            l += 1;
+        }
+    }
+    if !collected {
+        send_to_char(
+            &mut game.descriptors,
+            chars.get(chid),
+            "The auction house has nothing for you.\r\n",
+        );
+    }
+}
+
+/* The market command: everything done at the auction house */
+#[allow(clippy::too_many_arguments)]
+pub fn do_market(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg1 = String::new();
+    let mut arg2 = String::new();
+    let mut argument = argument.to_string();
+    half_chop(&mut argument, &mut arg1, &mut arg2);
+
+    if arg1.is_empty() || is_abbrev(&arg1, "list") {
+        market_list(game, chars, db, chid);
+        return;
+    }
+    if is_abbrev(&arg1, "info") {
+        market_info(game, chars, db, chid, &arg2);
+        return;
+    }
+    if !db.room_flagged(ch.in_room(), RoomFlags::AUCTION) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You can only do that at an auction house.\r\n",
+        );
+        return;
+    }
+    if is_abbrev(&arg1, "sell") {
+        market_sell(game, chars, db, texts, objs, chid, &arg2);
+    } else if is_abbrev(&arg1, "bid") {
+        market_bid(game, chars, db, texts, objs, chid, &arg2);
+    } else if is_abbrev(&arg1, "buy") {
+        market_buy(game, chars, db, texts, objs, chid, &arg2);
+    } else if is_abbrev(&arg1, "cancel") {
+        market_cancel(game, chars, db, texts, objs, chid, &arg2);
+    } else if is_abbrev(&arg1, "collect") {
+        market_collect(game, chars, db, texts, objs, chid);
+    } else {
+        send_to_char(&mut game.descriptors, ch, MARKET_FORMAT);
+    }
+}
+
+#[cfg(test)]
+mod auction_tests {
+    use super::*;
+    use crate::db::TestWorld;
+
+    #[test]
+    fn test_lot_escrow() {
+        let mut w = TestWorld::boot("auction");
+        let temple = w.db.real_room(3001);
+        w.db.set_room_flags_bit(temple, RoomFlags::AUCTION);
+        let seller = w.add_player("seller", 1, 3001);
+        let alice = w.add_player("alice", 2, 3001);
+        let bob = w.add_player("bob", 3, 3001);
+        w.chars.get_mut(alice).set_gold(100);
+        w.chars.get_mut(bob).set_gold(100);
+        w.give_obj(seller, 3001);
+        w.give_obj(seller, 3001);
+
+        /* the objects leave the game for escrow */
+        w.command(seller, "market sell beer 10");
+        w.command(seller, "market sell beer 10 50");
+        assert!(w.chars.get(seller).carrying.is_empty());
+        assert!(Path::new(&lot_filename(1)).exists());
+        assert!(Path::new(&lot_filename(2)).exists());
+        let saved = parse_auctions(open_side_file(AUCTION_FILE).unwrap());
+        assert_eq!(saved, w.db.auctions);
+
+        /* a bid is held; the outbid one goes back to the bank */
+        w.command(alice, "market bid 1 20");
+        w.command(bob, "market bid 1 30");
+        assert_eq!(w.chars.get(alice).get_gold(), 80);
+        assert_eq!(w.chars.get(alice).get_bank_gold(), 20);
+        assert_eq!(w.chars.get(bob).get_gold(), 70);
+        assert_eq!(w.db.auctions.lots[0].bidder, 3);
+
+        /* a buyout hands the object over at once and refunds the bidder */
+        w.command(alice, "market bid 2 15");
+        w.command(bob, "market buy 2");
+        assert_eq!(w.chars.get(bob).get_gold(), 20);
+        assert_eq!(w.chars.get(bob).carrying.len(), 1);
+        assert_eq!(w.chars.get(alice).get_bank_gold(), 35);
+        assert!(!Path::new(&lot_filename(2)).exists());
+        assert_eq!(w.chars.get(seller).get_bank_gold(), 48);
+
+        /* at closing the seller is paid, less the cut of the house */
+        w.db.auctions.lots[0].expires = 0;
+        auction_update(
+            &mut w.game,
+            &mut w.chars,
+            &mut w.db,
+            &mut w.texts,
+            &mut w.objs,
+        );
+        assert_eq!(w.chars.get(seller).get_bank_gold(), 48 + 29);
+        assert_eq!(w.db.auctions.lots[0].owner, 3);
+
+        /* and only the winner may collect */
+        w.command(alice, "market collect");
+        assert!(w.chars.get(alice).carrying.is_empty());
+        w.command(bob, "market collect");
+        assert_eq!(w.chars.get(bob).carrying.len(), 2);
+        assert!(w.db.auctions.lots.is_empty());
+        assert!(!Path::new(&lot_filename(1)).exists());
+    }
+}
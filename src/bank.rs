@@ -0,0 +1,734 @@
+/* ************************************************************************
+*   File: bank.rs                                       Part of CircleMUD *
+*  Usage: Bank accounts, transfers and the ledger kept for every player   *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+use std::fs;
+use std::io::ErrorKind;
+
+use chrono::{TimeZone, Utc};
+use log::error;
+
+use crate::clan::{clan_save_control, find_clan_by_member, ClanPerms};
+use crate::db::{save_char, DB};
+use crate::depot::{Depot, DepotId};
+use crate::house::{find_house_account, house_save_control};
+use crate::interpreter::{cmd_is, is_abbrev, is_number, one_argument, CMD_INFO};
+use crate::sidefile::{append_record, open_side_file, read_records, SideRecord};
+use crate::structs::{CharData, CharFileU, MeRef, LVL_IMMORT};
+use crate::util::{get_filename, time_now, FileType};
+use crate::{act, send_to_char, Game, ObjData, TextData, TO_ROOM};
+
+const STATEMENT_LENGTH: usize = 10; /* entries shown by the statement command */
+const AUDIT_LENGTH: usize = 20; /* entries shown by audit, unless told otherwise */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerKind {
+    Deposit,
+    Withdraw,
+    TransferIn,
+    TransferOut,
+    Auction,
+    ClanDeposit,
+    ClanWithdraw,
+    HouseDeposit,
+    HouseWithdraw,
+}
+
+/* How each kind of entry is written in the ledger files */
+const LEDGER_KINDS: [(&str, LedgerKind); 9] = [
+    ("deposit", LedgerKind::Deposit),
+    ("withdraw", LedgerKind::Withdraw),
+    ("xfer-in", LedgerKind::TransferIn),
+    ("xfer-out", LedgerKind::TransferOut),
+    ("auction", LedgerKind::Auction),
+    ("clan-in", LedgerKind::ClanDeposit),
+    ("clan-out", LedgerKind::ClanWithdraw),
+    ("house-in", LedgerKind::HouseDeposit),
+    ("house-out", LedgerKind::HouseWithdraw),
+];
+
+impl LedgerKind {
+    fn name(self) -> &'static str {
+        LEDGER_KINDS.iter().find(|(_, k)| *k == self).unwrap().0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LedgerEntry {
+    pub time: u64,
+    pub kind: LedgerKind,
+    pub amount: i64,
+    /* change to the account, negative when coins left it */
+    pub balance: i64,
+    /* what the account held afterwards */
+    pub other: i64,
+    /* idnum of the other party, clan number or house vnum; -1 if none */
+}
+
+fn parse_entry(rec: &SideRecord) -> Result<LedgerEntry, String> {
+    let &(_, kind) = LEDGER_KINDS
+        .iter()
+        .find(|(n, _)| *n == rec.key)
+        .ok_or("unknown kind")?;
+    Ok(LedgerEntry {
+        time: rec.number("Time")?,
+        kind,
+        amount: rec.number("Amount")?,
+        balance: rec.number("Balance")?,
+        other: rec.number("Other")?,
+    })
+}
+
+/*
+ * A ledger file is a side file with one record per entry, oldest first,
+ * added as they come:
+ *
+ *   #<kind>
+ *   Time: <time>
+ *   Amount: <change>
+ *   Balance: <balance>
+ *   Other: <other party>
+ *   End
+ */
+pub fn read_ledger(name: &str) -> Vec<LedgerEntry> {
+    let mut fname = String::new();
+    if !get_filename(&mut fname, FileType::Bank, name) {
+        return vec![];
+    }
+    let Some(reader) = open_side_file(&fname) else {
+        return vec![];
+    };
+    read_records(reader, &fname, parse_entry).0
+}
+
+/* Add an entry at the end of the ledger of a player */
+pub fn ledger_record(name: &str, kind: LedgerKind, amount: i64, balance: i64, other: i64) {
+    let mut fname = String::new();
+    if !get_filename(&mut fname, FileType::Bank, name) {
+        return;
+    }
+    append_record(
+        &fname,
+        kind.name(),
+        &[
+            ("Time", time_now().to_string()),
+            ("Amount", amount.to_string()),
+            ("Balance", balance.to_string()),
+            ("Other", other.to_string()),
+        ],
+    );
+}
+
+pub fn delete_ledger(charname: &str) {
+    let mut filename = String::new();
+
+    if !get_filename(&mut filename, FileType::Bank, charname) {
+        return;
+    }
+
+    if let Err(err) = fs::remove_file(&filename) {
+        if err.kind() != ErrorKind::NotFound {
+            error!("SYSERR: deleting ledger file {}: {}", filename, err);
+        }
+    }
+}
+
+/*
+ * The character of a player, if loaded: playing, linkless or still in the
+ * menu.  A player's bank account must then be changed there, as it is
+ * saved over the player file.
+ */
+fn find_account_holder(
+    game: &Game,
+    chars: &Depot<CharData>,
+    db: &DB,
+    idnum: i64,
+) -> Option<DepotId> {
+    game.descriptor_list
+        .iter()
+        .filter_map(|&d_id| game.desc(d_id).character)
+        .chain(db.character_list.iter().copied())
+        .find(|&id| !chars.get(id).is_npc() && chars.get(id).get_idnum() == idnum)
+}
+
+/*
+ * Put gold in the bank account of a player, whether they are playing or not.
+ * A loaded player is saved at once, as the player file would be otherwise.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn bank_credit(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    idnum: i64,
+    amount: i32,
+    kind: LedgerKind,
+    other: i64,
+) -> bool {
+    if let Some(id) = find_account_holder(game, chars, db, idnum) {
+        let ch = chars.get_mut(id);
+        ch.set_bank_gold(ch.get_bank_gold() + amount);
+        ledger_record(
+            ch.get_name(),
+            kind,
+            amount as i64,
+            ch.get_bank_gold() as i64,
+            other,
+        );
+        save_char(&mut game.descriptors, db, chars, texts, objs, id);
+        return true;
+    }
+    let Some(name) = db.get_name_by_id(idnum).map(str::to_string) else {
+        error!("SYSERR: bank_credit: no player with idnum {}", idnum);
+        return false;
+    };
+    let mut st = CharFileU::default();
+    let Some(player_i) = db.load_char(&name, &mut st) else {
+        error!("SYSERR: bank_credit: couldn't load {}", name);
+        return false;
+    };
+    st.points.bank_gold += amount;
+    db.write_char(player_i, &st);
+    ledger_record(
+        &name,
+        kind,
+        amount as i64,
+        st.points.bank_gold as i64,
+        other,
+    );
+    true
+}
+
+fn capitalized(name: &str) -> String {
+    let mut name = name.to_string();
+    if let Some(first) = name.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    name
+}
+
+/* Who or what the other party of an entry is */
+fn entry_other(db: &DB, entry: &LedgerEntry) -> String {
+    match entry.kind {
+        LedgerKind::Deposit | LedgerKind::Withdraw => String::new(),
+        LedgerKind::ClanDeposit | LedgerKind::ClanWithdraw => db
+            .clans
+            .iter()
+            .find(|c| c.number as i64 == entry.other)
+            .map_or_else(
+                || format!("clan #{}", entry.other),
+                |c| format!("clan {}", c.tag),
+            ),
+        LedgerKind::HouseDeposit | LedgerKind::HouseWithdraw => {
+            format!("house #{}", entry.other)
+        }
+        LedgerKind::TransferIn | LedgerKind::TransferOut | LedgerKind::Auction => {
+            if entry.other < 0 {
+                String::new()
+            } else {
+                db.get_name_by_id(entry.other)
+                    .map_or_else(|| format!("#{}", entry.other), capitalized)
+            }
+        }
+    }
+}
+
+fn format_ledger(db: &DB, entries: &[LedgerEntry]) -> String {
+    let mut buf = String::from(
+        "Date              Kind         Amount    Balance  With\r\n\
+         ----------------  ---------  --------  ---------  ----\r\n",
+    );
+    for entry in entries {
+        let date = Utc
+            .timestamp_opt(entry.time as i64, 0)
+            .single()
+            .map_or_else(
+                || "?".to_string(),
+                |d| d.format("%Y-%m-%d %H:%M").to_string(),
+            );
+        let line = format!(
+            "{:16}  {:9}  {:>8}  {:>9}  {}",
+            date,
+            entry.kind.name(),
+            entry.amount,
+            entry.balance,
+            entry_other(db, entry)
+        );
+        buf.push_str(line.trim_end());
+        buf.push_str("\r\n");
+    }
+    buf
+}
+
+/* Which account a banking command is about: the player's own, or a shared one */
+enum Account {
+    Own,
+    Clan(usize),
+    House(usize),
+}
+
+fn find_account(game: &mut Game, db: &DB, ch: &CharData, arg: &str) -> Option<Account> {
+    if arg.is_empty() {
+        Some(Account::Own)
+    } else if is_abbrev(arg, "clan") {
+        let account = find_clan_by_member(db, ch.get_idnum()).map(Account::Clan);
+        if account.is_none() {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "You don't belong to any clan.\r\n",
+            );
+        }
+        account
+    } else if is_abbrev(arg, "house") {
+        let account = find_house_account(db, ch.get_idnum()).map(Account::House);
+        if account.is_none() {
+            send_to_char(&mut game.descriptors, ch, "You have no house account.\r\n");
+        }
+        account
+    } else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "That account is either yours, your clan's or your house's.\r\n",
+        );
+        None
+    }
+}
+
+fn bank_balance(game: &mut Game, db: &DB, ch: &CharData, account: Account) {
+    let msg = match account {
+        Account::Own if ch.get_bank_gold() > 0 => {
+            format!("Your current balance is {} coins.\r\n", ch.get_bank_gold())
+        }
+        Account::Own => "You currently have no money deposited.\r\n".to_string(),
+        Account::Clan(c) => format!(
+            "The treasury of {} holds {} coins.\r\n",
+            db.clans[c].name, db.clans[c].treasury
+        ),
+        Account::House(h) => format!(
+            "The account of house #{} holds {} coins.\r\n",
+            db.house_control[h].vnum, db.house_control[h].gold
+        ),
+    };
+    send_to_char(&mut game.descriptors, ch, &msg);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bank_move(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    account: Account,
+    amount: i32,
+    deposit: bool,
+) -> bool {
+    let ch = chars.get(chid);
+    let held = match account {
+        Account::Own => ch.get_bank_gold() as i64,
+        Account::Clan(c) => db.clans[c].treasury,
+        Account::House(h) => db.house_control[h].gold,
+    };
+    if deposit && ch.get_gold() < amount {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You don't have that many coins!\r\n",
+        );
+        return false;
+    }
+    if !deposit {
+        if let Account::Clan(c) = account {
+            if !db.clans[c].can(ch.get_idnum(), ClanPerms::WITHDRAW) {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    "You may not withdraw from the clan treasury.\r\n",
+                );
+                return false;
+            }
+        }
+        if held < amount as i64 {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "You don't have that many coins deposited!\r\n",
+            );
+            return false;
+        }
+    }
+    let change = if deposit { amount } else { -amount };
+    let ch = chars.get_mut(chid);
+    ch.set_gold(ch.get_gold() - change);
+    let (kind, balance, other) = match account {
+        Account::Own => {
+            ch.set_bank_gold(ch.get_bank_gold() + change);
+            let kind = if deposit {
+                LedgerKind::Deposit
+            } else {
+                LedgerKind::Withdraw
+            };
+            (kind, ch.get_bank_gold() as i64, -1)
+        }
+        Account::Clan(c) => {
+            db.clans[c].treasury += change as i64;
+            clan_save_control(db);
+            let kind = if deposit {
+                LedgerKind::ClanDeposit
+            } else {
+                LedgerKind::ClanWithdraw
+            };
+            (kind, db.clans[c].treasury, db.clans[c].number as i64)
+        }
+        Account::House(h) => {
+            db.house_control[h].gold += change as i64;
+            house_save_control(db);
+            let kind = if deposit {
+                LedgerKind::HouseDeposit
+            } else {
+                LedgerKind::HouseWithdraw
+            };
+            (
+                kind,
+                db.house_control[h].gold,
+                db.house_control[h].vnum as i64,
+            )
+        }
+    };
+    ledger_record(ch.get_name(), kind, change as i64, balance, other);
+    /* the coins on hand must not outlive a crash on top of the account */
+    save_char(&mut game.descriptors, db, chars, texts, objs, chid);
+    let ch = chars.get(chid);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!(
+            "You {} {} coins.\r\n",
+            if deposit { "deposit" } else { "withdraw" },
+            amount
+        ),
+    );
+    true
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bank_transfer(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    amount: &str,
+    name: &str,
+) -> bool {
+    let ch = chars.get(chid);
+    if !is_number(amount) || name.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Usage: transfer <amount> <player>\r\n",
+        );
+        return false;
+    }
+    let amount = amount.parse::<i32>().unwrap_or(0);
+    let to = db.get_id_by_name(name);
+    let refusal = if amount <= 0 {
+        Some("How much do you want to transfer?\r\n")
+    } else if to < 0 {
+        Some("There is no such player.\r\n")
+    } else if to == ch.get_idnum() {
+        Some("Moving coins around your own account won't make you any richer.\r\n")
+    } else if ch.get_bank_gold() < amount {
+        Some("You don't have that many coins deposited!\r\n")
+    } else {
+        None
+    };
+    if let Some(msg) = refusal {
+        send_to_char(&mut game.descriptors, ch, msg);
+        return false;
+    }
+    let from = ch.get_idnum();
+    if !bank_credit(
+        game,
+        chars,
+        db,
+        texts,
+        objs,
+        to,
+        amount,
+        LedgerKind::TransferIn,
+        from,
+    ) {
+        send_to_char(
+            &mut game.descriptors,
+            chars.get(chid),
+            "The bank couldn't make that transfer.\r\n",
+        );
+        return false;
+    }
+    let ch = chars.get_mut(chid);
+    ch.set_bank_gold(ch.get_bank_gold() - amount);
+    ledger_record(
+        ch.get_name(),
+        LedgerKind::TransferOut,
+        -amount as i64,
+        ch.get_bank_gold() as i64,
+        to,
+    );
+    /* the other side was saved with the credit: no crash can leave the coins in two places */
+    save_char(&mut game.descriptors, db, chars, texts, objs, chid);
+    let msg = format!(
+        "{} transferred {} coins to your bank account.\r\n",
+        chars.get(chid).get_name(),
+        amount
+    );
+    if let Some(vict_id) = find_account_holder(game, chars, db, to) {
+        send_to_char(&mut game.descriptors, chars.get(vict_id), &msg);
+    } else if !db.no_mail {
+        db.store_mail(to, from, &msg);
+    }
+    send_to_char(
+        &mut game.descriptors,
+        chars.get(chid),
+        &format!(
+            "You transfer {} coins to {}.\r\n",
+            amount,
+            capitalized(name)
+        ),
+    );
+    true
+}
+
+fn transaction_act(game: &mut Game, chars: &Depot<CharData>, db: &DB, chid: DepotId) {
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        "$n makes a bank transaction.",
+        true,
+        Some(chars.get(chid)),
+        None,
+        None,
+        TO_ROOM,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn bank(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    _me: MeRef,
+    cmd: usize,
+    argument: &str,
+) -> bool {
+    let ch = chars.get(chid);
+    let mut arg1 = String::new();
+    let mut arg2 = String::new();
+    one_argument(one_argument(argument, &mut arg1), &mut arg2);
+
+    if cmd_is(cmd, "balance") {
+        if let Some(account) = find_account(game, db, ch, &arg1) {
+            bank_balance(game, db, ch, account);
+        }
+        true
+    } else if cmd_is(cmd, "deposit") || cmd_is(cmd, "withdraw") {
+        let deposit = cmd_is(cmd, "deposit");
+        let amount = arg1.parse::<i32>().unwrap_or(-1);
+        if amount <= 0 {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                &format!(
+                    "How much do you want to {}?\r\n",
+                    if deposit { "deposit" } else { "withdraw" }
+                ),
+            );
+            return true;
+        }
+        if let Some(account) = find_account(game, db, ch, &arg2) {
+            if bank_move(game, chars, db, texts, objs, chid, account, amount, deposit) {
+                transaction_act(game, chars, db, chid);
+            }
+        }
+        true
+    } else if cmd_is(cmd, "transfer")
+        && (CMD_INFO[cmd].minimum_level < LVL_IMMORT || is_number(&arg1))
+    {
+        /* immortals transferring players rather than gold go on to do_trans */
+        if bank_transfer(game, chars, db, texts, objs, chid, &arg1, &arg2) {
+            transaction_act(game, chars, db, chid);
+        }
+        true
+    } else if cmd_is(cmd, "statement") {
+        let ledger = read_ledger(ch.get_name());
+        if ledger.is_empty() {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "Your account has seen no business yet.\r\n",
+            );
+        } else {
+            let start = ledger.len().saturating_sub(STATEMENT_LENGTH);
+            let buf = format_ledger(db, &ledger[start..]);
+            send_to_char(&mut game.descriptors, ch, &buf);
+        }
+        true
+    } else {
+        false
+    }
+}
+
+/* The audit command, for immortals tracing where the gold went */
+#[allow(clippy::too_many_arguments)]
+pub fn do_audit(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    let mut name = String::new();
+    let mut count = String::new();
+    one_argument(one_argument(argument, &mut name), &mut count);
+    let count = if count == "all" {
+        usize::MAX
+    } else if count.is_empty() {
+        AUDIT_LENGTH
+    } else if let Ok(count) = count.parse::<usize>() {
+        count
+    } else {
+        name.clear();
+        0
+    };
+    if name.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Usage: audit <player> [<number of entries> | all]\r\n",
+        );
+        return;
+    }
+    let idnum = db.get_id_by_name(&name);
+    if idnum < 0 {
+        send_to_char(&mut game.descriptors, ch, "There is no such player.\r\n");
+        return;
+    }
+    let (gold, bank_gold) = if let Some(id) = find_account_holder(game, chars, db, idnum) {
+        (chars.get(id).get_gold(), chars.get(id).get_bank_gold())
+    } else {
+        let mut st = CharFileU::default();
+        if db.load_char(&name, &mut st).is_none() {
+            send_to_char(&mut game.descriptors, ch, "There is no such player.\r\n");
+            return;
+        }
+        (st.points.gold, st.points.bank_gold)
+    };
+    let ledger = read_ledger(&name);
+    let mut buf = format!(
+        "{} has {} coins on hand and {} in the bank.\r\n",
+        capitalized(&name),
+        gold,
+        bank_gold
+    );
+    if ledger.is_empty() {
+        buf.push_str("The ledger is empty.\r\n");
+        send_to_char(&mut game.descriptors, ch, &buf);
+        return;
+    }
+    let start = ledger.len().saturating_sub(count);
+    buf.push_str(&format!(
+        "Showing {} of {} ledger entries.\r\n",
+        ledger.len() - start,
+        ledger.len()
+    ));
+    buf.push_str(&format_ledger(db, &ledger[start..]));
+
+    /* and the totals of the whole ledger, by kind */
+    buf.push_str("Totals:");
+    for (kind_name, kind) in LEDGER_KINDS {
+        let (n, total) = ledger
+            .iter()
+            .filter(|e| e.kind == kind)
+            .fold((0, 0), |(n, total), e| (n + 1, total + e.amount));
+        if n > 0 {
+            buf.push_str(&format!(" {} {} ({}),", kind_name, total, n));
+        }
+    }
+    buf.pop();
+    buf.push_str("\r\n");
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+#[cfg(test)]
+mod bank_tests {
+    use super::*;
+    use crate::db::TestWorld;
+    use crate::spec_assign::assignobj;
+
+    fn balances(w: &TestWorld, ids: &[DepotId]) -> Vec<i32> {
+        ids.iter()
+            .map(|&id| w.chars.get(id).get_bank_gold())
+            .collect()
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut w = TestWorld::boot("bank");
+        assignobj(&mut w.db, 3034, bank);
+        w.put_obj(3034, 3001);
+        let alice = w.add_player("alice", 1, 3001);
+        let bob = w.add_player("bob", 2, 3001);
+        w.chars.get_mut(alice).set_bank_gold(100);
+
+        w.command(alice, "transfer 40 bob");
+        assert_eq!(balances(&w, &[alice, bob]), [60, 40]);
+        let out = read_ledger("alice");
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].kind, LedgerKind::TransferOut);
+        assert_eq!((out[0].amount, out[0].balance, out[0].other), (-40, 60, 2));
+        let into = read_ledger("bob");
+        assert_eq!(into.len(), 1);
+        assert_eq!(into[0].kind, LedgerKind::TransferIn);
+        assert_eq!(
+            (into[0].amount, into[0].balance, into[0].other),
+            (40, 40, 1)
+        );
+
+        /* refused transfers move nothing and leave no trace */
+        w.command(alice, "transfer 61 bob");
+        w.command(alice, "transfer 0 bob");
+        w.command(alice, "transfer 10 alice");
+        w.command(alice, "transfer 10 nobody");
+        assert_eq!(balances(&w, &[alice, bob]), [60, 40]);
+        assert_eq!(read_ledger("alice").len(), 1);
+
+        /* nor does one the other side could not be credited with */
+        w.db.character_list.retain(|&id| id != bob);
+        w.command(alice, "transfer 10 bob");
+        assert_eq!(balances(&w, &[alice]), [60]);
+        assert_eq!(read_ledger("alice").len(), 1);
+        assert_eq!(read_ledger("bob").len(), 1);
+    }
+}
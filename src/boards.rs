@@ -223,6 +223,20 @@ fn find_board(
     None
 }
 
+/* Look the board objects up again after the object table changed. */
+pub fn renum_boards(db: &mut DB) {
+    for i in 0..NUM_OF_BOARDS {
+        let rnum = db.real_object(db.boards.boardinfo[i].vnum);
+        if rnum == NOTHING {
+            error!(
+                "SYSERR: board vnum {} does not exist any more!",
+                db.boards.boardinfo[i].vnum
+            );
+        }
+        db.boards.boardinfo[i].rnum = rnum;
+    }
+}
+
 fn init_boards(db: &mut DB, texts: &mut Depot<TextData>) {
     let mut fatal_error = 0;
     for i in 0..INDEX_SIZE {
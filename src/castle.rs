@@ -20,7 +20,6 @@ use std::iter::Iterator;
 use crate::depot::{Depot, DepotId};
 use crate::handler::obj_to_char;
 use crate::{act, send_to_char, DescriptorData, TextData, VictimRef};
-use log::error;
 
 use crate::act_movement::{do_follow, do_gen_door, perform_move};
 use crate::db::DB;
@@ -28,8 +27,8 @@ use crate::interpreter::{SCMD_CLOSE, SCMD_LOCK, SCMD_OPEN, SCMD_UNLOCK};
 use crate::spell_parser::cast_spell;
 use crate::spells::{SPELL_COLOR_SPRAY, SPELL_FIREBALL, SPELL_HARM, SPELL_HEAL, TYPE_UNDEFINED};
 use crate::structs::{
-    CharData, ItemType, MeRef, MobVnum, ObjData, Position, RoomRnum, RoomVnum, Special, WearFlags,
-    NOBODY, NOWHERE,
+    CharData, ItemType, MeRef, MobVnum, ObjData, Position, RoomRnum, RoomVnum, WearFlags, NOBODY,
+    NOWHERE,
 };
 use crate::util::rand_number;
 use crate::{Game, TO_CHAR, TO_NOTVICT, TO_ROOM, TO_VICT};
@@ -42,28 +41,11 @@ const Z_KINGS_C: i32 = 150;
 \**********************************************************************/
 
 /*
- * Assign castle special procedures.
+ * The castle special procedures are assigned in lib/world/spec.lst.
  *
- * NOTE: The mobile number isn't fully specified. It's only an offset
- *	from the zone's base.
+ * NOTE: The mobile numbers the procedures look for aren't fully
+ * specified.  They are only offsets from the zone's base.
  */
-fn castle_mob_spec(db: &mut DB, mobnum: MobVnum, specproc: Special) {
-    let vmv = castle_virtual(db, mobnum);
-    let mut rmr = NOBODY;
-
-    if vmv != NOBODY {
-        rmr = db.real_mobile(vmv);
-    }
-
-    if rmr == NOBODY {
-        if !db.mini_mud {
-            error!("SYSERR: assign_kings_castle(): can't find mob #{}.", vmv);
-        }
-    } else {
-        db.mob_index[rmr as usize].func = Some(specproc);
-    }
-}
-
 fn castle_virtual(db: &DB, offset: MobVnum) -> MobVnum {
     if let Some(num) = db.real_zone(Z_KINGS_C as RoomVnum) {
         db.zone_table[num].bot + offset
@@ -80,40 +62,6 @@ fn castle_real_room(db: &DB, roomoffset: RoomVnum) -> RoomRnum {
     }
 }
 
-/*
- * Routine: assign_kings_castle
- *
- * Used to assign function pointers to all mobiles in the Kings Castle.
- * Called from spec_assign.c.
- */
-pub fn assign_kings_castle(db: &mut DB) {
-    castle_mob_spec(db, 0, castle_guard); /* Gwydion */
-    /* Added the previous line -- Furry */
-    castle_mob_spec(db, 1, king_welmar); /* Our dear friend, the King */
-    castle_mob_spec(db, 3, castle_guard); /* Jim */
-    castle_mob_spec(db, 4, castle_guard); /* Brian */
-    castle_mob_spec(db, 5, castle_guard); /* Mick */
-    castle_mob_spec(db, 6, castle_guard); /* Matt */
-    castle_mob_spec(db, 7, castle_guard); /* Jochem */
-    castle_mob_spec(db, 8, castle_guard); /* Anne */
-    castle_mob_spec(db, 9, castle_guard); /* Andrew */
-    castle_mob_spec(db, 10, castle_guard); /* Bertram */
-    castle_mob_spec(db, 11, castle_guard); /* Jeanette */
-    castle_mob_spec(db, 12, peter); /* Peter */
-    castle_mob_spec(db, 13, training_master); /* The training master */
-    castle_mob_spec(db, 16, james); /* James the Butler */
-    castle_mob_spec(db, 17, cleaning); /* Ze Cleaning Fomen */
-    castle_mob_spec(db, 20, tim); /* Tim, Tom's twin */
-    castle_mob_spec(db, 21, tom); /* Tom, Tim's twin */
-    castle_mob_spec(db, 24, dick_n_david); /* Dick, guard of the
-                                            * Treasury */
-    castle_mob_spec(db, 25, dick_n_david); /* David, Dicks brother */
-    castle_mob_spec(db, 26, jerry); /* Jerry, the Gambler */
-    castle_mob_spec(db, 27, castle_guard); /* Michael */
-    castle_mob_spec(db, 28, castle_guard); /* Hans */
-    castle_mob_spec(db, 29, castle_guard); /* Boris */
-}
-
 /*
  * Routine: member_of_staff
  *
@@ -491,6 +439,7 @@ fn fry_victim(
                 chid,
                 Some(tchid),
                 None,
+                None,
                 SPELL_COLOR_SPRAY,
             );
         }
@@ -520,6 +469,7 @@ fn fry_victim(
                 chid,
                 Some(tchid),
                 None,
+                None,
                 SPELL_HARM,
             );
         }
@@ -566,6 +516,7 @@ fn fry_victim(
                 chid,
                 Some(tchid),
                 None,
+                None,
                 SPELL_FIREBALL,
             );
         }
@@ -580,6 +531,7 @@ fn fry_victim(
                     chid,
                     Some(chid),
                     None,
+                    None,
                     SPELL_HEAL,
                 );
             }
@@ -1290,7 +1242,7 @@ fn castle_twin_proc(
  * This doesn't make sure he _can_ carry it...
  */
 #[allow(clippy::too_many_arguments)]
-fn james(
+pub fn james(
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
@@ -1366,7 +1318,7 @@ fn castle_cleaner(
  * Picks up any trash she finds...
  */
 #[allow(clippy::too_many_arguments)]
-fn cleaning(
+pub fn cleaning(
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
@@ -1386,7 +1338,7 @@ fn cleaning(
  * Standard routine for ordinary castle guards.
  */
 #[allow(clippy::too_many_arguments)]
-fn castle_guard(
+pub fn castle_guard(
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
@@ -1412,7 +1364,7 @@ fn castle_guard(
  * Routine for the guards Dick and David.
  */
 #[allow(clippy::too_many_arguments)]
-fn dick_n_david(
+pub fn dick_n_david(
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
@@ -1450,7 +1402,7 @@ fn dick_n_david(
  * Routine for Captain of the Guards.
  */
 #[allow(clippy::too_many_arguments)]
-fn peter(
+pub fn peter(
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
@@ -1732,7 +1684,7 @@ fn peter(
  * Code by Sapowox modified by Pjotr.(Original code from Master)
  */
 #[allow(clippy::too_many_arguments)]
-fn jerry(
+pub fn jerry(
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
@@ -0,0 +1,961 @@
+/* ************************************************************************
+*   File: channels.rs                                   Part of CircleMUD *
+*  Usage: Communication channels: gossip, auction and friends, plus the   *
+*         private channels players make for themselves                    *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::mem;
+use std::rc::Rc;
+
+use chrono::{TimeZone, Utc};
+use log::{error, info};
+
+use crate::clan::{clan_tag, find_clan_by_member, find_player, member_name, same_clan};
+use crate::db::{CHANNEL_FILE, DB, PCHANNEL_FILE};
+use crate::depot::{Depot, DepotId};
+use crate::interpreter::{half_chop, is_abbrev, one_argument, CMD_INFO};
+use crate::screen::{close_markup, C_CMP, C_NRM, MARKUP_NRM};
+use crate::sidefile::{open_side_file, read_records, save_side_file, write_record, SideRecord};
+use crate::structs::ConState::ConPlaying;
+use crate::structs::{
+    CharData, PrefFlags, RoomFlags, LVL_GRGOD, LVL_IMMORT, PLR_NOSHOUT, PLR_WRITING,
+};
+use crate::tells::is_ignoring;
+use crate::util::time_now;
+use crate::{
+    _clrlevel, act, send_to_char, Game, ObjData, TextData, VictimRef, COLOR_LEV, TO_SLEEP, TO_VICT,
+};
+
+pub const MAX_PRIVATE_CHANNELS: usize = 3; /* per owner */
+const PRIVATE_HISTORY: usize = 20;
+const MAX_CHANNEL_NAME: usize = 12;
+
+/* who hears what is said on a channel */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Audience {
+    Global,
+    Zone,
+    Clan,
+    Immortal,
+    Private,
+}
+
+const AUDIENCES: [&str; 5] = ["global", "zone", "clan", "immortal", "private"];
+
+/* the old toggle commands still turn their channel off */
+const TOGGLES: [(&str, PrefFlags); 5] = [
+    ("deaf", PrefFlags::DEAF),
+    ("nogossip", PrefFlags::NOGOSS),
+    ("noauction", PrefFlags::NOAUCT),
+    ("nograts", PrefFlags::NOGRATZ),
+    ("noclan", PrefFlags::NOCLAN),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelLine {
+    pub when: u64,
+    pub scope: i64,
+    /* zone or clan number it was said in, for zone and clan channels */
+    pub invis: i16,
+    /* invisibility level of the speaker */
+    pub idnum: i64,
+    /* of the speaker, for ignore lists */
+    pub who: Rc<str>,
+    pub text: Rc<str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelData {
+    pub name: Rc<str>,
+    /* what players type to talk on it */
+    pub level: u8,
+    /* minimum level to talk */
+    pub move_cost: i16,
+    pub color: Rc<str>,
+    /* color markup of the messages */
+    pub audience: Audience,
+    pub toggle: Option<PrefFlags>,
+    /* preference turning the channel off, if not the channel list */
+    pub awake: bool,
+    /* only heard by those awake */
+    pub verb: Rc<str>,
+    pub self_msg: Rc<str>,
+    /* "You gossip" */
+    pub others_msg: Rc<str>,
+    /* "$n gossips" */
+    pub history_size: usize,
+    pub owner: i64,
+    /* idnum of the player who made a private channel */
+    pub invited: Vec<i64>,
+    /* idnums of the other members of a private channel */
+    pub history: VecDeque<ChannelLine>,
+}
+
+impl ChannelData {
+    pub fn new(name: &str) -> ChannelData {
+        ChannelData {
+            name: Rc::from(name),
+            level: 0,
+            move_cost: 0,
+            color: Rc::from(""),
+            audience: Audience::Global,
+            toggle: None,
+            awake: false,
+            verb: Rc::from(name),
+            self_msg: Rc::from(""),
+            others_msg: Rc::from(""),
+            history_size: 0,
+            owner: 0,
+            invited: vec![],
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn is_member(&self, idnum: i64) -> bool {
+        self.owner == idnum || self.invited.contains(&idnum)
+    }
+}
+
+fn valid_channel_name(name: &str) -> bool {
+    (3..=MAX_CHANNEL_NAME).contains(&name.len()) && name.chars().all(|c| c.is_ascii_lowercase())
+}
+
+fn parse_channel_field(chan: &mut ChannelData, tag: &str, value: &str) -> Result<(), String> {
+    let number = |value: &str| {
+        value
+            .parse::<i64>()
+            .ok()
+            .filter(|&n| n >= 0)
+            .ok_or_else(|| format!("bad number '{}'", value))
+    };
+    match tag {
+        "Level" => chan.level = number(value)?.min(u8::MAX as i64) as u8,
+        "Move" => chan.move_cost = number(value)?.min(i16::MAX as i64) as i16,
+        "Color" => chan.color = Rc::from(value),
+        "Audience" => {
+            let Some(audience) = AUDIENCES.iter().position(|&a| a == value) else {
+                return Err(format!("unknown audience '{}'", value));
+            };
+            chan.audience = [
+                Audience::Global,
+                Audience::Zone,
+                Audience::Clan,
+                Audience::Immortal,
+                Audience::Private,
+            ][audience];
+        }
+        "Toggle" => {
+            let Some((_, flag)) = TOGGLES.iter().find(|(name, _)| *name == value) else {
+                return Err(format!("unknown toggle '{}'", value));
+            };
+            chan.toggle = Some(*flag);
+        }
+        "Awake" => chan.awake = value == "yes",
+        "Verb" => chan.verb = Rc::from(value),
+        "Self" => chan.self_msg = Rc::from(value),
+        "Others" => chan.others_msg = Rc::from(value),
+        "History" => chan.history_size = number(value)? as usize,
+        "Owner" => chan.owner = number(value)?,
+        "Invite" => chan.invited.push(number(value)?),
+        _ => return Err(format!("unknown field '{}'", tag)),
+    }
+    Ok(())
+}
+
+/* fill in what the record left out */
+fn finish_channel(mut chan: ChannelData) -> Result<ChannelData, &'static str> {
+    if chan.audience == Audience::Private {
+        if chan.owner == 0 {
+            return Err("private channel without an owner");
+        }
+        chan.history_size = PRIVATE_HISTORY;
+        chan.self_msg = Rc::from(format!("[{}] You say", chan.name));
+        chan.others_msg = Rc::from(format!("[{}] $n says", chan.name));
+    }
+    if chan.self_msg.is_empty() {
+        chan.self_msg = Rc::from(format!("You {}", chan.verb));
+    }
+    if chan.others_msg.is_empty() {
+        chan.others_msg = Rc::from(format!("$n {}s", chan.verb));
+    }
+    Ok(chan)
+}
+
+fn parse_channel(rec: &SideRecord) -> Result<ChannelData, String> {
+    if !valid_channel_name(&rec.key) {
+        return Err("illegal channel name".into());
+    }
+    let mut chan = ChannelData::new(&rec.key);
+    for (tag, value) in &rec.fields {
+        parse_channel_field(&mut chan, tag, value.trim())?;
+    }
+    Ok(finish_channel(chan)?)
+}
+
+/*
+ * A channel file is a side file with one record per channel, keyed by
+ * its name.  See lib/misc/channels for the fields.  Private channels are
+ * saved in the same format with their owner and invited players.
+ */
+pub(crate) fn parse_channels<R: BufRead>(reader: R, file: &str) -> Vec<ChannelData> {
+    let (parsed, _) = read_records(reader, file, parse_channel);
+    let mut channels: Vec<ChannelData> = vec![];
+    for chan in parsed {
+        if channels.iter().any(|c| c.name == chan.name) {
+            error!(
+                "SYSERR: Channel file {}: duplicate channel {}, skipped",
+                file, chan.name
+            );
+            continue;
+        }
+        channels.push(chan);
+    }
+    channels
+}
+
+pub(crate) fn write_channels(w: &mut impl Write, channels: &[ChannelData]) -> io::Result<()> {
+    for chan in channels.iter().filter(|c| c.audience == Audience::Private) {
+        let mut fields = vec![
+            ("Audience", "private".to_string()),
+            ("Owner", chan.owner.to_string()),
+        ];
+        for idnum in &chan.invited {
+            fields.push(("Invite", idnum.to_string()));
+        }
+        write_record(w, &chan.name, &fields)?;
+    }
+    Ok(())
+}
+
+/* Save the private channels after every change */
+pub fn channel_save_private(db: &DB) {
+    save_side_file(PCHANNEL_FILE, |w| write_channels(w, &db.channels));
+}
+
+/*
+ * Read the channel definitions at boot time and on 'reload channels'.
+ * What was said on a channel is kept across a reload, and private
+ * channels are left alone.
+ */
+pub fn boot_channels(db: &mut DB) -> bool {
+    let fl = match File::open(CHANNEL_FILE) {
+        Ok(fl) => fl,
+        Err(err) => {
+            error!("SYSERR: Cannot read channel file {}: {}", CHANNEL_FILE, err);
+            return false;
+        }
+    };
+    let mut channels = parse_channels(BufReader::new(fl), CHANNEL_FILE);
+    channels.retain(|chan| {
+        if chan.audience == Audience::Private {
+            error!(
+                "SYSERR: Channel {} in {} may not be private, skipped",
+                chan.name, CHANNEL_FILE
+            );
+        }
+        chan.audience != Audience::Private
+    });
+    let mut old = mem::take(&mut db.channels);
+    for chan in channels.iter_mut() {
+        if let Some(prev) = old.iter_mut().find(|c| c.name == chan.name) {
+            chan.history = mem::take(&mut prev.history);
+        }
+        chan.history.truncate(chan.history_size);
+    }
+    info!("   {} channels.", channels.len());
+    let mut dropped = false;
+    for chan in old.into_iter().filter(|c| c.audience == Audience::Private) {
+        if channels.iter().any(|c| c.name == chan.name) {
+            error!(
+                "SYSERR: Private channel {} has the name of a channel, removed",
+                chan.name
+            );
+            dropped = true;
+            continue;
+        }
+        channels.push(chan);
+    }
+    db.channels = channels;
+    if dropped {
+        channel_save_private(db);
+    }
+    true
+}
+
+/* Read the private channels at boot time, once the players are known */
+pub fn boot_private_channels(db: &mut DB) {
+    let Some(reader) = open_side_file(PCHANNEL_FILE) else {
+        info!(
+            "   Private channel file '{}' does not exist.",
+            PCHANNEL_FILE
+        );
+        return;
+    };
+    for mut chan in parse_channels(reader, PCHANNEL_FILE) {
+        if chan.audience != Audience::Private {
+            error!(
+                "SYSERR: Channel {} in {} is not private, skipped",
+                chan.name, PCHANNEL_FILE
+            );
+            continue;
+        }
+        /* players who no longer exist have left their channels */
+        if db.get_name_by_id(chan.owner).is_none() {
+            continue;
+        }
+        chan.invited.retain(|&id| db.get_name_by_id(id).is_some());
+        if db.channels.iter().any(|c| c.name == chan.name) {
+            error!(
+                "SYSERR: Private channel {} has the name of a channel, skipped",
+                chan.name
+            );
+            continue;
+        }
+        db.channels.push(chan);
+    }
+}
+
+pub fn find_channel(db: &DB, name: &str) -> Option<usize> {
+    db.channels.iter().position(|c| *c.name == *name)
+}
+
+/* may ch talk on (and list) the channel at all? */
+pub fn channel_allowed(db: &DB, ch: &CharData, c: usize) -> bool {
+    let chan = &db.channels[c];
+    match chan.audience {
+        Audience::Immortal => ch.get_level() >= LVL_IMMORT,
+        Audience::Private => !ch.is_npc() && chan.is_member(ch.get_idnum()),
+        _ => true,
+    }
+}
+
+fn channel_on(ch: &CharData, chan: &ChannelData) -> bool {
+    match chan.toggle {
+        Some(flag) => !ch.prf_flagged(flag),
+        None => !ch.player_specials.channels_off.contains(&chan.name),
+    }
+}
+
+/* the zone or clan what ch says belongs to */
+fn channel_scope(db: &DB, ch: &CharData, chan: &ChannelData) -> i64 {
+    match chan.audience {
+        Audience::Zone => db.world[ch.in_room() as usize].zone as i64,
+        Audience::Clan => {
+            find_clan_by_member(db, ch.get_idnum()).map_or(-1, |c| db.clans[c].number as i64)
+        }
+        _ => 0,
+    }
+}
+
+fn channel_history(game: &mut Game, db: &DB, ch: &CharData, c: usize) {
+    let chan = &db.channels[c];
+    let scope = channel_scope(db, ch, chan);
+    let mut buf = String::new();
+    for line in chan
+        .history
+        .iter()
+        .rev()
+        .filter(|l| l.scope == scope && !ch.player_specials.ignored.contains(&l.idnum))
+    {
+        let when = Utc
+            .timestamp_opt(line.when as i64, 0)
+            .single()
+            .map_or_else(String::new, |t| t.format("%H:%M").to_string());
+        let who = if line.invis > ch.get_level() as i16 {
+            "Someone"
+        } else {
+            &line.who
+        };
+        buf.push_str(&format!(
+            "[{}] {}, '{}'\r\n",
+            when,
+            chan.others_msg.replace("$n", who),
+            line.text
+        ));
+    }
+    if buf.is_empty() {
+        buf.push_str("Nothing has been said on that channel lately.\r\n");
+    }
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+/*
+ * Talk on a channel: what do_gen_comm did for holler, shout, gossip,
+ * auction, grats and clantalk, now for any channel.
+ */
+pub fn channel_talk(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    chid: DepotId,
+    c: usize,
+    argument: &str,
+) {
+    let ch = chars.get(chid);
+
+    /* to keep pets, etc from being ordered to shout */
+    let Some(ch_desc) = ch.desc else {
+        return;
+    };
+    let chan = &db.channels[c];
+    if ch.plr_flagged(PLR_NOSHOUT) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!("You cannot {}!!\r\n", chan.verb),
+        );
+        return;
+    }
+    if db.room_flagged(ch.in_room(), RoomFlags::SOUNDPROOF) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The walls seem to absorb your words.\r\n",
+        );
+        return;
+    }
+    if !channel_allowed(db, ch, c) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You are not on that channel.\r\n",
+        );
+        return;
+    }
+    if chan.audience == Audience::Clan && clan_tag(db, ch).is_none() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You don't belong to any clan.\r\n",
+        );
+        return;
+    }
+    if ch.get_level() < chan.level {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!(
+                "You must be at least level {} before you can {}.\r\n",
+                chan.level, chan.verb
+            ),
+        );
+        return;
+    }
+    /* make sure the char is on the channel */
+    if !channel_on(ch, chan) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You aren't even on the channel!\r\n",
+        );
+        return;
+    }
+    /* skip leading spaces */
+    let argument = close_markup(argument.trim_start());
+    let argument = argument.as_str();
+
+    if argument == "history" {
+        channel_history(game, db, ch, c);
+        return;
+    }
+    /* make sure that there is something there to say! */
+    if argument.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!(
+                "Yes, {}, fine, {} we must, but WHAT???\r\n",
+                chan.verb, chan.verb
+            ),
+        );
+        return;
+    }
+    if chan.move_cost > 0 {
+        if ch.get_move() < chan.move_cost {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                &format!("You're too exhausted to {}.\r\n", chan.verb),
+            );
+            return;
+        }
+        let cost = chan.move_cost;
+        let ch = chars.get_mut(chid);
+        ch.set_move(ch.get_move() - cost);
+    }
+    let ch = chars.get(chid);
+    let chan = &db.channels[c];
+
+    /* first, set up strings to be given to the communicator */
+    if ch.prf_flagged(PrefFlags::NOREPEAT) {
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
+    } else {
+        let colored = COLOR_LEV!(ch) >= C_CMP && !chan.color.is_empty();
+        let messg = format!(
+            "{}{}, '{}'{}\r\n",
+            if colored { &chan.color } else { "" },
+            chan.self_msg,
+            argument,
+            if colored { MARKUP_NRM } else { "" }
+        );
+        send_to_char(&mut game.descriptors, ch, &messg);
+    }
+
+    let buf1 = if chan.audience == Audience::Clan {
+        format!(
+            "[{}] {}, '{}'",
+            clan_tag(db, ch).unwrap_or_default(),
+            chan.others_msg,
+            argument
+        )
+    } else {
+        format!("{}, '{}'", chan.others_msg, argument)
+    };
+
+    /* keep it for '<channel> history' */
+    let line = ChannelLine {
+        when: time_now(),
+        scope: channel_scope(db, ch, chan),
+        invis: ch.get_invis_lev(),
+        idnum: ch.get_idnum(),
+        who: Rc::from(ch.get_name().as_ref()),
+        text: Rc::from(argument),
+    };
+    let zone = db.world[ch.in_room() as usize].zone;
+    let chan = &mut db.channels[c];
+    if chan.history_size > 0 {
+        chan.history.push_front(line);
+        chan.history.truncate(chan.history_size);
+    }
+    let chan = &db.channels[c];
+
+    /* now send all the strings out */
+    for d_id in game.descriptor_list.clone() {
+        let d = game.desc(d_id);
+        let Some(ic_id) = d.character else {
+            continue;
+        };
+        let ic = chars.get(ic_id);
+        if d.state() != ConPlaying
+            || d_id == ch_desc
+            || !channel_on(ic, chan)
+            || is_ignoring(ic, ch)
+            || ic.plr_flagged(PLR_WRITING)
+            || db.room_flagged(ic.in_room(), RoomFlags::SOUNDPROOF)
+            || (chan.awake && !ic.awake())
+        {
+            continue;
+        }
+        let hears = match chan.audience {
+            Audience::Global => true,
+            Audience::Zone => db.world[ic.in_room() as usize].zone == zone,
+            Audience::Clan => same_clan(db, ch, ic),
+            Audience::Immortal | Audience::Private => channel_allowed(db, ic, c),
+        };
+        if !hears {
+            continue;
+        }
+        let colored = COLOR_LEV!(ic) >= C_NRM && !chan.color.is_empty();
+        if colored {
+            send_to_char(&mut game.descriptors, ic, &chan.color);
+        }
+        act(
+            &mut game.descriptors,
+            chars,
+            db,
+            &buf1,
+            false,
+            Some(ch),
+            None,
+            Some(VictimRef::Char(ic)),
+            TO_VICT | TO_SLEEP,
+        );
+        if colored {
+            send_to_char(&mut game.descriptors, ic, MARKUP_NRM);
+        }
+    }
+}
+
+fn channel_list(game: &mut Game, db: &DB, ch: &CharData) {
+    let mut buf = String::from("Channel      Status  Audience\r\n");
+    for (c, chan) in db.channels.iter().enumerate() {
+        if !channel_allowed(db, ch, c) {
+            continue;
+        }
+        buf.push_str(&format!(
+            "{:<12} {:<7} {}",
+            chan.name,
+            if channel_on(ch, chan) { "on" } else { "off" },
+            AUDIENCES[chan.audience as usize]
+        ));
+        if chan.audience == Audience::Private {
+            buf.push_str(&format!(" (owned by {})", member_name(db, chan.owner)));
+        }
+        buf.push_str("\r\n");
+    }
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+fn channel_toggle(
+    game: &mut Game,
+    db: &DB,
+    chars: &mut Depot<CharData>,
+    chid: DepotId,
+    c: usize,
+    on: bool,
+) {
+    let chan = &db.channels[c];
+    let ch = chars.get_mut(chid);
+    match chan.toggle {
+        Some(flag) if on => ch.remove_prf_flags_bits(flag),
+        Some(flag) => ch.set_prf_flags_bits(flag),
+        None => {
+            let off = &mut ch.player_specials.channels_off;
+            off.retain(|name| *name != chan.name);
+            if !on {
+                off.push(chan.name.clone());
+            }
+        }
+    }
+    let ch = chars.get(chid);
+    let msg = if on {
+        format!("You will now hear the {} channel.\r\n", chan.name)
+    } else {
+        format!("You will no longer hear the {} channel.\r\n", chan.name)
+    };
+    send_to_char(&mut game.descriptors, ch, &msg);
+}
+
+fn channel_create(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &Depot<CharData>,
+    chid: DepotId,
+    name: &str,
+) {
+    let ch = chars.get(chid);
+    if !valid_channel_name(name) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!(
+                "Channel names are 3 to {} lowercase letters.\r\n",
+                MAX_CHANNEL_NAME
+            ),
+        );
+        return;
+    }
+    if find_channel(db, name).is_some() || CMD_INFO.iter().any(|cmd| cmd.command == name) {
+        send_to_char(&mut game.descriptors, ch, "That name is already taken.\r\n");
+        return;
+    }
+    let owned = db
+        .channels
+        .iter()
+        .filter(|c| c.audience == Audience::Private && c.owner == ch.get_idnum())
+        .count();
+    if owned >= MAX_PRIVATE_CHANNELS {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!(
+                "You may not own more than {} channels.\r\n",
+                MAX_PRIVATE_CHANNELS
+            ),
+        );
+        return;
+    }
+    let mut chan = ChannelData::new(name);
+    chan.audience = Audience::Private;
+    chan.owner = ch.get_idnum();
+    let Ok(chan) = finish_channel(chan) else {
+        return;
+    };
+    db.channels.push(chan);
+    channel_save_private(db);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!(
+            "Channel {} created.  Type 'channel invite {} <player>' to let others in.\r\n",
+            name, name
+        ),
+    );
+}
+
+/* the private channel ch may manage, or None after telling why not */
+fn owned_channel(game: &mut Game, db: &DB, ch: &CharData, name: &str) -> Option<usize> {
+    let Some(c) = find_channel(db, name)
+        .filter(|&c| channel_allowed(db, ch, c) || ch.get_level() >= LVL_GRGOD)
+    else {
+        send_to_char(&mut game.descriptors, ch, "There is no such channel.\r\n");
+        return None;
+    };
+    let chan = &db.channels[c];
+    if chan.audience != Audience::Private
+        || (chan.owner != ch.get_idnum() && ch.get_level() < LVL_GRGOD)
+    {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "That channel isn't yours to change.\r\n",
+        );
+        return None;
+    }
+    Some(c)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn channel_invite(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &Depot<CharData>,
+    chid: DepotId,
+    name: &str,
+    who: &str,
+    invite: bool,
+) {
+    let ch = chars.get(chid);
+    let Some(c) = owned_channel(game, db, ch, name) else {
+        return;
+    };
+    let idnum = db.get_id_by_name(&who.to_lowercase());
+    if idnum < 0 {
+        send_to_char(&mut game.descriptors, ch, "There is no such player.\r\n");
+        return;
+    }
+    let chan = &mut db.channels[c];
+    if invite == chan.is_member(idnum) {
+        let msg = if invite {
+            "They are already on that channel.\r\n"
+        } else {
+            "They aren't on that channel.\r\n"
+        };
+        send_to_char(&mut game.descriptors, ch, msg);
+        return;
+    }
+    if idnum == chan.owner {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "The owner of a channel cannot leave it.\r\n",
+        );
+        return;
+    }
+    if invite {
+        chan.invited.push(idnum);
+    } else {
+        chan.invited.retain(|&id| id != idnum);
+    }
+    let chan_name = chan.name.clone();
+    channel_save_private(db);
+    let vict_name = member_name(db, idnum);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!(
+            "{} is {} on the {} channel.\r\n",
+            vict_name,
+            if invite { "now" } else { "no longer" },
+            chan_name
+        ),
+    );
+    if let Some(vict_id) = find_player(db, chars, idnum) {
+        let msg = if invite {
+            format!(
+                "$n lets you in the {} channel.  Type '{} <message>' to talk there.",
+                chan_name, chan_name
+            )
+        } else {
+            format!("$n removes you from the {} channel.", chan_name)
+        };
+        act(
+            &mut game.descriptors,
+            chars,
+            db,
+            &msg,
+            false,
+            Some(ch),
+            None,
+            Some(VictimRef::Char(chars.get(vict_id))),
+            TO_VICT | TO_SLEEP,
+        );
+    }
+}
+
+fn channel_leave(game: &mut Game, db: &mut DB, chars: &Depot<CharData>, chid: DepotId, name: &str) {
+    let ch = chars.get(chid);
+    let Some(c) = find_channel(db, name)
+        .filter(|&c| db.channels[c].audience == Audience::Private && channel_allowed(db, ch, c))
+    else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You are not on such a channel.\r\n",
+        );
+        return;
+    };
+    if db.channels[c].owner == ch.get_idnum() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You own that channel; destroy it instead.\r\n",
+        );
+        return;
+    }
+    let idnum = ch.get_idnum();
+    db.channels[c].invited.retain(|&id| id != idnum);
+    channel_save_private(db);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!("You leave the {} channel.\r\n", name),
+    );
+}
+
+fn channel_destroy(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &Depot<CharData>,
+    chid: DepotId,
+    name: &str,
+) {
+    let ch = chars.get(chid);
+    let Some(c) = owned_channel(game, db, ch, name) else {
+        return;
+    };
+    db.channels.remove(c);
+    channel_save_private(db);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!("The {} channel is no more.\r\n", name),
+    );
+}
+
+const CHANNEL_FORMAT: &str = "Usage: channel [on | off] <channel>\r\n\
+       channel create | destroy | leave <channel>\r\n\
+       channel invite | uninvite <channel> <player>\r\n";
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_channel(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg1 = String::new();
+    let mut arg2 = String::new();
+    let mut argument = argument.to_string();
+    half_chop(&mut argument, &mut arg1, &mut arg2);
+    let mut name = String::new();
+    let rest = one_argument(&arg2, &mut name);
+    let mut who = String::new();
+    one_argument(rest, &mut who);
+
+    if arg1.is_empty() {
+        channel_list(game, db, ch);
+    } else if (arg1 == "on" || arg1 == "off") && !name.is_empty() {
+        match find_channel(db, &name).filter(|&c| channel_allowed(db, ch, c)) {
+            Some(c) => channel_toggle(game, db, chars, chid, c, arg1 == "on"),
+            None => {
+                send_to_char(&mut game.descriptors, ch, "There is no such channel.\r\n");
+            }
+        }
+    } else if is_abbrev(&arg1, "create") && !name.is_empty() {
+        channel_create(game, db, chars, chid, &name);
+    } else if is_abbrev(&arg1, "destroy") && !name.is_empty() {
+        channel_destroy(game, db, chars, chid, &name);
+    } else if is_abbrev(&arg1, "leave") && !name.is_empty() {
+        channel_leave(game, db, chars, chid, &name);
+    } else if is_abbrev(&arg1, "invite") && !who.is_empty() {
+        channel_invite(game, db, chars, chid, &name, &who, true);
+    } else if is_abbrev(&arg1, "uninvite") && !who.is_empty() {
+        channel_invite(game, db, chars, chid, &name, &who, false);
+    } else {
+        send_to_char(&mut game.descriptors, ch, CHANNEL_FORMAT);
+    }
+}
+
+#[cfg(test)]
+mod channels_tests {
+    use super::*;
+    use crate::db::TestWorld;
+
+    fn allowed(w: &TestWorld, chid: DepotId, name: &str) -> bool {
+        find_channel(&w.db, name).is_some_and(|c| channel_allowed(&w.db, w.chars.get(chid), c))
+    }
+
+    #[test]
+    fn test_channel_permissions() {
+        let mut w = TestWorld::boot("channels");
+        assert!(boot_channels(&mut w.db));
+        let alice = w.add_player("alice", 1, 3001);
+        let bob = w.add_player("bob", 2, 3001);
+        let carol = w.add_player("carol", 3, 3001);
+        let god = w.add_player("god", 4, 3001);
+        w.chars.get_mut(god).set_level(LVL_GRGOD);
+
+        assert!(allowed(&w, alice, "gossip"));
+        assert!(!allowed(&w, alice, "immtalk"));
+        assert!(allowed(&w, god, "immtalk"));
+
+        /* a private channel is for its owner and those invited */
+        w.command(alice, "channel create ravens");
+        assert!(allowed(&w, alice, "ravens"));
+        assert!(!allowed(&w, bob, "ravens"));
+        w.command(bob, "channel invite ravens carol");
+        assert!(!allowed(&w, carol, "ravens"));
+        w.command(alice, "channel invite ravens bob");
+        assert!(allowed(&w, bob, "ravens"));
+
+        /* only the owner, or a greater god, may change it */
+        w.command(bob, "channel invite ravens carol");
+        w.command(bob, "channel destroy ravens");
+        assert!(!allowed(&w, carol, "ravens"));
+        assert!(find_channel(&w.db, "ravens").is_some());
+        w.command(alice, "channel leave ravens");
+        assert!(allowed(&w, alice, "ravens"));
+
+        /* the members outlast a reboot */
+        let saved = parse_channels(open_side_file(PCHANNEL_FILE).unwrap(), "test");
+        assert_eq!(saved.len(), 1);
+        assert_eq!((saved[0].owner, &saved[0].invited), (1, &vec![2]));
+
+        w.command(bob, "channel leave ravens");
+        assert!(!allowed(&w, bob, "ravens"));
+        w.command(god, "channel destroy ravens");
+        assert_eq!(find_channel(&w.db, "ravens"), None);
+    }
+
+    #[test]
+    fn test_shipped_channel_file_parses() {
+        let channels = parse_channels(include_str!("../lib/misc/channels").as_bytes(), "channels");
+        for name in ["holler", "shout", "gossip", "auction", "grats", "clantalk"] {
+            assert!(
+                channels.iter().any(|c| *c.name == *name),
+                "channel {} is missing",
+                name
+            );
+        }
+        assert!(channels.iter().all(|c| c.audience != Audience::Private));
+    }
+}
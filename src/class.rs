@@ -28,20 +28,9 @@ use crate::constants::{CON_APP, WIS_APP};
 use crate::db::DB;
 use crate::depot::{Depot, DepotId};
 use crate::interpreter::{SCMD_EAST, SCMD_NORTH, SCMD_SOUTH, SCMD_WEST};
-use crate::spell_parser::spell_level;
 use crate::spells::{
-    SAVING_BREATH, SAVING_PARA, SAVING_PETRI, SAVING_ROD, SAVING_SPELL, SKILL_BACKSTAB, SKILL_BASH,
-    SKILL_HIDE, SKILL_KICK, SKILL_PICK_LOCK, SKILL_RESCUE, SKILL_SNEAK, SKILL_STEAL, SKILL_TRACK,
-    SPELL_ARMOR, SPELL_BLESS, SPELL_BLINDNESS, SPELL_BURNING_HANDS, SPELL_CALL_LIGHTNING,
-    SPELL_CHARM, SPELL_CHILL_TOUCH, SPELL_CLONE, SPELL_COLOR_SPRAY, SPELL_CONTROL_WEATHER,
-    SPELL_CREATE_FOOD, SPELL_CREATE_WATER, SPELL_CURE_BLIND, SPELL_CURE_CRITIC, SPELL_CURE_LIGHT,
-    SPELL_CURSE, SPELL_DETECT_ALIGN, SPELL_DETECT_INVIS, SPELL_DETECT_MAGIC, SPELL_DETECT_POISON,
-    SPELL_DISPEL_EVIL, SPELL_DISPEL_GOOD, SPELL_EARTHQUAKE, SPELL_ENCHANT_WEAPON,
-    SPELL_ENERGY_DRAIN, SPELL_FIREBALL, SPELL_GROUP_ARMOR, SPELL_GROUP_HEAL, SPELL_HARM,
-    SPELL_HEAL, SPELL_INFRAVISION, SPELL_INVISIBLE, SPELL_LIGHTNING_BOLT, SPELL_LOCATE_OBJECT,
-    SPELL_MAGIC_MISSILE, SPELL_POISON, SPELL_PROT_FROM_EVIL, SPELL_REMOVE_CURSE,
-    SPELL_REMOVE_POISON, SPELL_SANCTUARY, SPELL_SENSE_LIFE, SPELL_SHOCKING_GRASP, SPELL_SLEEP,
-    SPELL_STRENGTH, SPELL_SUMMON, SPELL_WORD_OF_RECALL,
+    SAVING_BREATH, SAVING_PARA, SAVING_PETRI, SAVING_ROD, SAVING_SPELL, SKILL_BACKSTAB, SKILL_HIDE,
+    SKILL_PICK_LOCK, SKILL_SNEAK, SKILL_STEAL, SKILL_TRACK,
 };
 use crate::structs::{
     CharData, Class, ExtraFlags, GuildInfoType, ObjData, PrefFlags, DRUNK, FULL, LVL_GOD,
@@ -54,13 +43,7 @@ pub const CLASS_ABBREVS: [&str; 4] = ["Mu", "Cl", "Th", "Wa"];
 
 pub const PC_CLASS_TYPES: [&str; 4] = ["Magic User", "Cleric", "Thief", "Warrior"];
 
-/* The menu for choosing a class in interpreter.c: */
-pub const CLASS_MENU: &str = "\r\n\
-     Select a class:\r\n\
-        [C]leric\r\n\
-        [T]hief\r\n\
-        [W]arrior\r\n\
-        [M]agic-user\r\n";
+/* The menu for choosing a class is "nanny.class" in the message catalogs. */
 
 /*
  * The code to interpret a class letter -- used in interpreter.c when a
@@ -3690,83 +3673,6 @@ pub fn invalid_class(ch: &CharData, obj: &ObjData) -> bool {
     false
 }
 
-/*
- * SPELLS AND SKILLS.  This area defines which spells are assigned to
- * which classes, and the minimum level the character must be to use
- * the spell or skill.
- */
-pub fn init_spell_levels(db: &mut DB) {
-    /* MAGES */
-    spell_level(db, SPELL_MAGIC_MISSILE, Class::MagicUser, 1);
-    spell_level(db, SPELL_DETECT_INVIS, Class::MagicUser, 2);
-    spell_level(db, SPELL_DETECT_MAGIC, Class::MagicUser, 2);
-    spell_level(db, SPELL_CHILL_TOUCH, Class::MagicUser, 3);
-    spell_level(db, SPELL_INFRAVISION, Class::MagicUser, 3);
-    spell_level(db, SPELL_INVISIBLE, Class::MagicUser, 4);
-    spell_level(db, SPELL_ARMOR, Class::MagicUser, 4);
-    spell_level(db, SPELL_BURNING_HANDS, Class::MagicUser, 5);
-    spell_level(db, SPELL_LOCATE_OBJECT, Class::MagicUser, 6);
-    spell_level(db, SPELL_STRENGTH, Class::MagicUser, 6);
-    spell_level(db, SPELL_SHOCKING_GRASP, Class::MagicUser, 7);
-    spell_level(db, SPELL_SLEEP, Class::MagicUser, 8);
-    spell_level(db, SPELL_LIGHTNING_BOLT, Class::MagicUser, 9);
-    spell_level(db, SPELL_BLINDNESS, Class::MagicUser, 9);
-    spell_level(db, SPELL_DETECT_POISON, Class::MagicUser, 10);
-    spell_level(db, SPELL_COLOR_SPRAY, Class::MagicUser, 11);
-    spell_level(db, SPELL_ENERGY_DRAIN, Class::MagicUser, 13);
-    spell_level(db, SPELL_CURSE, Class::MagicUser, 14);
-    spell_level(db, SPELL_POISON, Class::MagicUser, 14);
-    spell_level(db, SPELL_FIREBALL, Class::MagicUser, 15);
-    spell_level(db, SPELL_CHARM, Class::MagicUser, 16);
-    spell_level(db, SPELL_ENCHANT_WEAPON, Class::MagicUser, 26);
-    spell_level(db, SPELL_CLONE, Class::MagicUser, 30);
-
-    /* CLERICS */
-    spell_level(db, SPELL_CURE_LIGHT, Class::Cleric, 1);
-    spell_level(db, SPELL_ARMOR, Class::Cleric, 1);
-    spell_level(db, SPELL_CREATE_FOOD, Class::Cleric, 2);
-    spell_level(db, SPELL_CREATE_WATER, Class::Cleric, 2);
-    spell_level(db, SPELL_DETECT_POISON, Class::Cleric, 3);
-    spell_level(db, SPELL_DETECT_ALIGN, Class::Cleric, 4);
-    spell_level(db, SPELL_CURE_BLIND, Class::Cleric, 4);
-    spell_level(db, SPELL_BLESS, Class::Cleric, 5);
-    spell_level(db, SPELL_DETECT_INVIS, Class::Cleric, 6);
-    spell_level(db, SPELL_BLINDNESS, Class::Cleric, 6);
-    spell_level(db, SPELL_INFRAVISION, Class::Cleric, 7);
-    spell_level(db, SPELL_PROT_FROM_EVIL, Class::Cleric, 8);
-    spell_level(db, SPELL_POISON, Class::Cleric, 8);
-    spell_level(db, SPELL_GROUP_ARMOR, Class::Cleric, 9);
-    spell_level(db, SPELL_CURE_CRITIC, Class::Cleric, 9);
-    spell_level(db, SPELL_SUMMON, Class::Cleric, 10);
-    spell_level(db, SPELL_REMOVE_POISON, Class::Cleric, 10);
-    spell_level(db, SPELL_WORD_OF_RECALL, Class::Cleric, 12);
-    spell_level(db, SPELL_EARTHQUAKE, Class::Cleric, 12);
-    spell_level(db, SPELL_DISPEL_EVIL, Class::Cleric, 14);
-    spell_level(db, SPELL_DISPEL_GOOD, Class::Cleric, 14);
-    spell_level(db, SPELL_SANCTUARY, Class::Cleric, 15);
-    spell_level(db, SPELL_CALL_LIGHTNING, Class::Cleric, 15);
-    spell_level(db, SPELL_HEAL, Class::Cleric, 16);
-    spell_level(db, SPELL_CONTROL_WEATHER, Class::Cleric, 17);
-    spell_level(db, SPELL_SENSE_LIFE, Class::Cleric, 18);
-    spell_level(db, SPELL_HARM, Class::Cleric, 19);
-    spell_level(db, SPELL_GROUP_HEAL, Class::Cleric, 22);
-    spell_level(db, SPELL_REMOVE_CURSE, Class::Cleric, 26);
-
-    /* THIEVES */
-    spell_level(db, SKILL_SNEAK, Class::Thief, 1);
-    spell_level(db, SKILL_PICK_LOCK, Class::Thief, 2);
-    spell_level(db, SKILL_BACKSTAB, Class::Thief, 3);
-    spell_level(db, SKILL_STEAL, Class::Thief, 4);
-    spell_level(db, SKILL_HIDE, Class::Thief, 5);
-    spell_level(db, SKILL_TRACK, Class::Thief, 6);
-
-    /* WARRIORS */
-    spell_level(db, SKILL_KICK, Class::Warrior, 1);
-    spell_level(db, SKILL_RESCUE, Class::Warrior, 3);
-    spell_level(db, SKILL_TRACK, Class::Warrior, 9);
-    spell_level(db, SKILL_BASH, Class::Warrior, 12);
-}
-
 /*
  * This is the exp given to implementors -- it must always be greater
  * than the exp required for immortality, plus at least 20,000 or so.
@@ -45,11 +45,10 @@ pub const PK_ALLOWED: bool = false;
 /* is playerthieving allowed? */
 pub const PT_ALLOWED: bool = false;
 
-/* minimum level a player must be to shout/holler/gossip/auction */
-pub const LEVEL_CAN_SHOUT: i32 = 1;
-
-/* number of movement points it costs to holler */
-pub const HOLLER_MOVE_COST: i32 = 20;
+/*
+ * The minimum level to shout, holler, gossip or auction and the movement
+ * points it costs to holler are set for each channel in misc/channels.
+ */
 
 /*  how many people can get into a tunnel?  The default is two, but there
 *  is also an alternate message in the case of one person being allowed.
@@ -82,10 +81,11 @@ pub const DTS_ARE_DUMPS: bool = true;
 */
 pub const LOAD_INTO_INVENTORY: bool = false;
 
-/* "okay" etc. */
-pub const OK: &str = "Okay.\r\n";
-pub const NOPERSON: &str = "No-one by that name here.\r\n";
-pub const NOEFFECT: &str = "Nothing seems to happen.\r\n";
+/*
+ * "okay" etc., the login menu and the welcome messages are in the message
+ * catalogs (misc/lang/), as "ok", "noperson", "noeffect", "nanny.menu",
+ * "nanny.welcome" and "nanny.start".
+ */
 
 /*
 * If you want mortals to level up to immortal once they have enough
@@ -247,26 +247,6 @@ pub struct Config {
     pub track_through_doors: bool,
 }
 
-pub const MENU: &str = "
-Welcome to CircleMUD!
-0) Exit from CircleMUD.
-1) Enter the game.
-2) Enter description.
-3) Read the background story.
-4) Change password.
-5) Delete this character.
-
-   Make your choice: ";
-
-pub const WELC_MESSG: &str = "
-Welcome to the land of CircleMUD!  May your visit here be... Interesting.
-\r\n";
-
-pub const START_MESSG: &str =
-    "Welcome.  This is your new CircleMUD character!  You can now earn gold,
-gain experience, find weapons and equipment, and much more -- while
-meeting people from around the world!\r\n";
-
 /****************************************************************************/
 /****************************************************************************/
 
@@ -21,7 +21,7 @@ pub const CIRCLEMUD_VERSION: &str = "mud-r version 1.0, based on CircleMUD, vers
 pub const DIRS: [&str; 7] = ["north", "east", "south", "west", "up", "down", "\n"];
 
 /* ROOM_x */
-pub const ROOM_BITS: [&str; 17] = [
+pub const ROOM_BITS: [&str; 19] = [
     "DARK",
     "DEATH",
     "NO_MOB",
@@ -38,9 +38,20 @@ pub const ROOM_BITS: [&str; 17] = [
     "ATRIUM",
     "OLC",
     "*", /* BFS MARK */
+    "CLANHALL",
+    "AUCTION",
     "\n",
 ];
 
+/* DESC_x */
+pub const DESC_CONDS: [&str; 14] = [
+    "day", "night", "dawn", "dusk", "clear", "cloudy", "rain", "snow", "storm", "winter", "spring",
+    "summer", "autumn", "\n",
+];
+
+/* RAFF_x */
+pub const ROOM_AFFECT_BITS: [&str; 6] = ["DARKNESS", "FOG", "SILENCE", "WALL", "CONSECRATED", "\n"];
+
 /* EX_x */
 pub const EXIT_BITS: [&str; 5] = ["DOOR", "CLOSED", "LOCKED", "PICKPROOF", "\n"];
 
@@ -112,12 +123,15 @@ pub const ACTION_BITS: [&str; 20] = [
 ];
 
 /* PRF_x */
-pub const PREFERENCE_BITS: [&str; 23] = [
+pub const PREFERENCE_BITS: [&str; 25] = [
     "BRIEF", "COMPACT", "DEAF", "NO_TELL", "D_HP", "D_MANA", "D_MOVE", "AUTOEX", "NO_HASS",
     "QUEST", "SUMN", "NO_REP", "LIGHT", "C1", "C2", "NO_WIZ", "L1", "L2", "NO_AUC", "NO_GOS",
-    "NO_GTZ", "RMFLG", "\n",
+    "NO_GTZ", "RMFLG", "D_AUTO", "NO_CLAN", "\n",
 ];
 
+/* CLAN_x */
+pub const CLAN_PERMS: [&str; 5] = ["recruit", "boot", "promote", "withdraw", "\n"];
+
 /* AFF_x */
 pub const AFFECTED_BITS: [&str; 23] = [
     "BLIND",
@@ -146,7 +160,7 @@ pub const AFFECTED_BITS: [&str; 23] = [
 ];
 
 /* CON_x */
-pub const CONNECTED_TYPES: [&str; 19] = [
+pub const CONNECTED_TYPES: [&str; 21] = [
     "Playing",
     "Disconnecting",
     "Get name",
@@ -165,6 +179,8 @@ pub const CONNECTED_TYPES: [&str; 19] = [
     "Self-Delete 1",
     "Self-Delete 2",
     "Disconnecting",
+    "Select language",
+    "Change language",
     "\n",
 ];
 
@@ -1084,6 +1100,12 @@ pub const MONTH_NAME: [&str; 17] = [
     "Month of the Great Evil",
 ];
 
+/* CLIMATE_ */
+pub const CLIMATE_TYPES: [&str; 5] = ["temperate", "desert", "arctic", "underground", "\n"];
+
+/* Not used in sprinttype(). */
+pub const SEASON_NAME: [&str; 4] = ["winter", "spring", "summer", "autumn"];
+
 /* --- End of constants arrays. --- */
 
 /*
@@ -9,12 +9,14 @@
 *  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
 ************************************************************************ */
 use std::cmp::{max, min};
+use std::fmt::Display;
 use std::fs::{File, OpenOptions};
 use std::io::Read;
 use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Seek, SeekFrom, Write};
 use std::os::unix::fs::FileExt;
 use std::path::Path;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::{fs, io, mem, process, slice};
 
 use log::{error, info, warn};
@@ -22,14 +24,16 @@ use regex::Regex;
 
 use crate::act_informative::sort_commands;
 use crate::act_social::{boot_social_messages, SocialMessg};
+use crate::auction::{auction_boot, AuctionHouse};
 use crate::ban::{load_banned, read_invalid_list};
 use crate::boards::BoardSystem;
 use crate::castle::KingWelmar;
-use crate::class::init_spell_levels;
-use crate::config::{FROZEN_START_ROOM, IMMORT_START_ROOM, MORTAL_START_ROOM, OK};
+use crate::channels::{boot_channels, boot_private_channels, ChannelData};
+use crate::clan::{clan_boot, ClanData};
+use crate::config::{FROZEN_START_ROOM, IMMORT_START_ROOM, MORTAL_START_ROOM};
 use crate::constants::{
-    ACTION_BITS_COUNT, AFFECTED_BITS_COUNT, DRINKNAMES, EXTRA_BITS_COUNT, ROOM_BITS_COUNT,
-    WEAR_BITS_COUNT,
+    ACTION_BITS_COUNT, AFFECTED_BITS_COUNT, DESC_CONDS, DRINKNAMES, EXTRA_BITS_COUNT,
+    ROOM_BITS_COUNT, WEAR_BITS_COUNT,
 };
 use crate::depot::{Depot, DepotId, HasId};
 use crate::handler::{
@@ -37,31 +41,38 @@ use crate::handler::{
 };
 use crate::house::{house_boot, HouseControlRec, MAX_HOUSES};
 use crate::interpreter::{one_argument, one_word, search_block};
+use crate::lang::{boot_languages, MsgCatalog};
 use crate::mail::MailSystem;
 use crate::modify::paginate_string;
 use crate::objsave::update_obj_file;
+use crate::prefs::write_prefs;
+use crate::quest::{boot_quests, forget_removed_quests, write_quests, QuestData};
+use crate::scripts::{load_triggers, ScriptOwner, Scripts};
 use crate::shops::{assign_the_shopkeepers, boot_the_shops, destroy_shops, ShopData};
-use crate::spec_assign::{assign_mobiles, assign_objects, assign_rooms};
+use crate::spec_assign::assign_spec_procs;
 use crate::spec_procs::{sort_spells, Mayor};
+use crate::specscript::SpecScripts;
 use crate::spell_parser::{mag_assign_spells, skill_name, UNUSED_SPELLNAME};
 use crate::spells::{SpellInfoType, MAX_SPELLS, TOP_SPELL_DEFINE};
 use crate::structs::ConState::ConPlaying;
 use crate::structs::{
-    AffectFlags, AffectedType, ApplyType, CharAbilityData, CharData, CharFileU, CharPlayerData,
-    CharPointData, CharSpecialData, CharSpecialDataSaved, Class, ExitFlags, ExtraDescrData,
-    ExtraFlags, IndexData, ItemType, MessageList, MobRnum, MobSpecialData, MobVnum,
-    ObjAffectedType, ObjData, ObjFlagData, ObjRnum, ObjVnum, PlayerSpecialData,
-    PlayerSpecialDataSaved, Position, PrefFlags, RoomData, RoomDirectionData, RoomFlags, RoomRnum,
-    RoomVnum, SectorType, Sex, SkyCondition, SunState, TimeData, TimeInfoData, WearFlags,
-    WeatherData, ZoneRnum, ZoneVnum, HOST_LENGTH, LVL_GOD, LVL_IMMORT, LVL_IMPL, MAX_AFFECT,
-    MAX_NAME_LENGTH, MAX_OBJ_AFFECT, MAX_PWD_LENGTH, MAX_SKILLS, MAX_TITLE_LENGTH, MAX_TONGUE,
-    MOB_AGGRESSIVE, MOB_AGGR_EVIL, MOB_AGGR_GOOD, MOB_AGGR_NEUTRAL, MOB_ISNPC, MOB_NOTDEADYET,
-    NOBODY, NOTHING, NOWHERE, NUM_OF_DIRS, NUM_WEARS, PASSES_PER_SEC, PULSE_ZONE,
+    AffectFlags, AffectedType, AltDescrData, ApplyType, CharAbilityData, CharData, CharFileU,
+    CharPlayerData, CharPointData, CharSpecialData, CharSpecialDataSaved, Class, Climate,
+    DescConds, ExitFlags, ExtraDescrData, ExtraFlags, IndexData, ItemType, MessageList, MobRnum,
+    MobSpecialData, MobVnum, ObjAffectedType, ObjData, ObjFlagData, ObjRnum, ObjVnum,
+    PlayerSpecialData, PlayerSpecialDataSaved, Position, PrefFlags, RoomData, RoomDirectionData,
+    RoomFlags, RoomRnum, RoomVnum, SectorType, Sex, SkyCondition, SunState, TimeData, TimeInfoData,
+    WearFlags, WeatherData, ZoneRnum, ZoneVnum, HOST_LENGTH, LVL_GOD, LVL_IMMORT, LVL_IMPL,
+    MAX_AFFECT, MAX_NAME_LENGTH, MAX_OBJ_AFFECT, MAX_PWD_LENGTH, MAX_SKILLS, MAX_TITLE_LENGTH,
+    MAX_TONGUE, MOB_AGGRESSIVE, MOB_AGGR_EVIL, MOB_AGGR_GOOD, MOB_AGGR_NEUTRAL, MOB_ISNPC,
+    MOB_NOTDEADYET, NOBODY, NOTHING, NOWHERE, NUM_OF_DIRS, NUM_WEARS, PASSES_PER_SEC, PULSE_ZONE,
 };
+use crate::tells::{tells_boot, OfflineTell};
 use crate::util::{
     dice, get_line, mud_time_passed, mud_time_to_secs, prune_crlf, rand_number, time_now, touch,
     DisplayMode, SECS_PER_REAL_HOUR,
 };
+use crate::weather::init_weather;
 use crate::{
     check_player_special, get_last_tell_mut, send_to_char, DescriptorData, Game, TextData,
 };
@@ -85,9 +96,15 @@ pub const BUG_FILE: &str = "./misc/bugs"; /*         'bug'		*/
 pub const MESS_FILE: &str = "./misc/messages"; /* damage messages		*/
 pub const SOCMESS_FILE: &str = "./misc/socials"; /* messages for social acts	*/
 pub const XNAME_FILE: &str = "./misc/xnames"; /* invalid name substrings	*/
+pub const QUEST_FILE: &str = "./misc/quests"; /* quest definitions		*/
+pub const SPELL_FILE: &str = "./misc/spells"; /* spell and skill definitions	*/
+pub const CHANNEL_FILE: &str = "./misc/channels"; /* communication channels	*/
+pub const LANG_DIR: &str = "./misc/lang"; /* message catalogs, one per language */
 
 //pub const LIB_PLRTEXT: &str = "plrtext/";
 pub const LIB_PLROBJS: &str = "plrobjs/";
+pub const LIB_AUCTION: &str = "auction/";
+pub const MAP_DIR: &str = "maps"; /* for the 'mapexport'-command */
 
 pub const KILLSCRIPT_FILE: &str = "../.killscript";
 pub const FASTBOOT_FILE: &str = "../.fastboot";
@@ -97,17 +114,28 @@ pub const PLAYER_FILE: &str = "etc/players";
 pub const MAIL_FILE: &str = "etc/plrmail"; /* for the mudmail system	*/
 pub const BAN_FILE: &str = "etc/badsites"; /* for the siteban system	*/
 pub const HCONTROL_FILE: &str = "etc/hcontrol"; /* for the house system	*/
+pub const CLAN_FILE: &str = "etc/clans"; /* for the clan system	*/
+pub const AUCTION_FILE: &str = "etc/auctions"; /* for the auction house	*/
+pub const PCHANNEL_FILE: &str = "etc/channels"; /* private channels	*/
+pub const TELL_FILE: &str = "etc/tells"; /* tells to players away	*/
 pub const TIME_FILE: &str = "etc/time";
 
 pub const LIB_PLRALIAS: &str = "plralias/";
+pub const LIB_PLRQUEST: &str = "plrquest/";
+pub const LIB_PLRBANK: &str = "plrbank/";
+pub const LIB_PLRPREFS: &str = "plrprefs/";
 
 pub const SUF_OBJS: &str = "objs";
 //pub const SUF_TEXT: &str = "text";
 pub const SUF_ALIAS: &str = "alias";
+pub const SUF_QUEST: &str = "quest";
+pub const SUF_BANK: &str = "bank";
+pub const SUF_PREFS: &str = "prefs";
 
 pub struct PlayerIndexElement {
     pub(crate) name: Rc<str>,
     id: i64,
+    level: u8,
 }
 
 pub struct HelpIndexElement {
@@ -157,6 +185,8 @@ pub struct DB {
     /* rnum of immort start room	 */
     pub r_frozen_start_room: RoomRnum,
     /* rnum of frozen start room	 */
+    pub(crate) dangling_exits: Vec<(RoomRnum, usize, RoomVnum)>,
+    /* exits whose target room vnum does not exist */
     pub credits: Rc<str>,
     /* game credits			 */
     pub news: Rc<str>,
@@ -185,8 +215,8 @@ pub struct DB {
     /* the help table	 */
     pub time_info: TimeInfoData,
     /* the infomation about the time    */
-    pub weather_info: WeatherData,
-    /* the infomation about the weather */
+    pub sunlight: SunState,
+    /* how much sun, weather is kept per zone */
     // struct player_special_data dummy_mob;	/* dummy spec area for mobs	*/
     pub reset_q: Vec<ZoneRnum>,
     pub extractions_pending: i32,
@@ -202,10 +232,19 @@ pub struct DB {
     pub boards: BoardSystem,
     pub house_control: [HouseControlRec; MAX_HOUSES],
     pub num_of_houses: usize,
+    pub clans: Vec<ClanData>,
+    pub channels: Vec<ChannelData>,
+    pub auctions: AuctionHouse,
+    pub offline_tells: Vec<OfflineTell>,
+    pub quests: Vec<QuestData>,
+    pub catalog: MsgCatalog,
     pub mails: MailSystem,
     pub(crate) mayor: Mayor,
     pub(crate) king_welmar: KingWelmar,
     pub scheck: bool,
+    /* triggers and the variables of running scripts */
+    pub scripts: Scripts,
+    pub spec_scripts: SpecScripts,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -216,18 +255,18 @@ pub enum LoadType {
 }
 
 /* structure for the reset commands */
-struct ResetCom {
-    command: char,
+pub(crate) struct ResetCom {
+    pub(crate) command: char,
     /* current command                      */
-    if_flag: bool,
+    pub(crate) if_flag: bool,
     /* if TRUE: exe only if preceding exe'd */
-    arg1: i32,
+    pub(crate) arg1: i32,
     /*                                      */
-    arg2: i32,
+    pub(crate) arg2: i32,
     /* Arguments to the command             */
-    arg3: i32,
+    pub(crate) arg3: i32,
     /*                                      */
-    line: i32,
+    pub(crate) line: i32,
     /* line number this command appears on  */
 
     /*
@@ -239,6 +278,12 @@ struct ResetCom {
      *  'G': Obj to char       *
      *  'E': Obj to char equip *
      *  'D': Set state of door *
+     *  'R': Rem obj from room *
+     *  'Z': Mob to random room*
+     *  '%': Go on by chance   *
+     *  'H': Go on at hours    *
+     *  'W': Go on in weather  *
+     *  'L': Pick one of next  *
     */
 }
 
@@ -258,8 +303,12 @@ pub struct ZoneData {
     /* conditions for reset (see below)   */
     pub number: ZoneVnum,
     /* virtual number of this zone	  */
-    cmd: Vec<ResetCom>,
+    pub(crate) cmd: Vec<ResetCom>,
     /* command table for reset	          */
+    pub climate: Climate,
+    /* how the weather behaves here       */
+    pub weather: WeatherData,
+    /* the weather of this zone           */
 
     /*
      * Reset mode:
@@ -294,6 +343,13 @@ impl DB {
             .map(|p| p.name.as_ref())
     }
 
+    pub fn get_level_by_id(&self, id: i64) -> Option<u8> {
+        self.player_table
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.level)
+    }
+
     pub fn get_id_by_name(&self, name: &str) -> i64 {
         let r = self.player_table.iter().find(|p| p.name.as_ref() == name);
         if let Some(r) = r {
@@ -442,19 +498,71 @@ pub fn do_reboot(
                 prune_crlf(&mut db.greetings);
             }
         }
+        "quests" => {
+            boot_quests(db);
+            for &chid in &db.character_list {
+                forget_removed_quests(db, chars.get_mut(chid));
+            }
+        }
+        "spells" => {
+            if !mag_assign_spells(db) {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    "The spell file can't be read or has errors; spells left unchanged.\r\n",
+                );
+                return;
+            }
+            sort_spells(db);
+        }
+        "lang" => {
+            if !boot_languages(db) {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    "Cannot read the language files; messages left unchanged.\r\n",
+                );
+                return;
+            }
+        }
+        "channels" => {
+            if !boot_channels(db) {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    "Cannot read the channel file; channels left unchanged.\r\n",
+                );
+                return;
+            }
+        }
         "xhelp" => {
             db.help_table.clear();
             db.index_boot(texts, DbBootMode::Help);
         }
+        "spec" => {
+            if db.no_specials {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    "Special procedures are disabled.\r\n",
+                );
+                return;
+            }
+            assign_spec_procs(db);
+        }
         _ => {
             send_to_char(&mut game.descriptors, ch, "Unknown reload option.\r\n");
             return;
         }
     }
     let ch = chars.get(chid);
-    send_to_char(&mut game.descriptors, ch, OK);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
 }
 
+/* boot_world() reads relative paths; tests booting a world take turns. */
+#[cfg(test)]
+pub(crate) static BOOT_DIR_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
 pub(crate) fn boot_world(
     game: &mut Game,
     db: &mut DB,
@@ -486,6 +594,153 @@ pub(crate) fn boot_world(
         info!("Loading shops.");
         db.index_boot(texts, DbBootMode::Shop);
     }
+
+    info!("Loading triggers.");
+    let triggers = load_triggers(db.mini_mud);
+    db.scripts.set_triggers(triggers);
+}
+
+/* A mini mud booted in its own copy of lib, with players added at will */
+#[cfg(test)]
+pub(crate) struct TestWorld {
+    pub game: Game,
+    pub db: DB,
+    pub chars: Depot<CharData>,
+    pub texts: Depot<TextData>,
+    pub objs: Depot<ObjData>,
+    lib: std::path::PathBuf,
+    cwd: std::path::PathBuf,
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let dest = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &dest);
+        } else {
+            fs::copy(&path, &dest).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+impl TestWorld {
+    pub(crate) fn boot(name: &str) -> TestWorld {
+        TestWorld::boot_with(name, &[])
+    }
+
+    /* Boot with some files of the copy written anew, such as a world index */
+    pub(crate) fn boot_with(name: &str, files: &[(&str, &str)]) -> TestWorld {
+        let lib = std::env::temp_dir().join(format!("{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&lib);
+        copy_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join("lib"), &lib);
+        for (path, text) in files {
+            fs::write(lib.join(path), text).unwrap();
+        }
+        let _lock = BOOT_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let cwd = std::env::current_dir().unwrap();
+        std::env::set_current_dir(&lib).unwrap();
+
+        let mut game = Game::new();
+        let mut chars = Depot::default();
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        db.mini_mud = true;
+        boot_world(&mut game, &mut db, &mut chars, &mut texts);
+        TestWorld {
+            game,
+            db,
+            chars,
+            texts,
+            objs: Depot::default(),
+            lib,
+            cwd,
+            _lock,
+        }
+    }
+
+    pub(crate) fn add_player(&mut self, name: &str, idnum: i64, room: RoomVnum) -> DepotId {
+        let mut ch = CharData::default();
+        clear_char(&mut ch);
+        ch.player.name = Rc::from(name);
+        ch.set_idnum(idnum);
+        ch.set_level(10);
+        self.db.player_table.push(PlayerIndexElement {
+            name: Rc::from(name),
+            id: idnum,
+            level: 10,
+        });
+        let chid = self.chars.push(ch);
+        self.db.character_list.push(chid);
+        let room = self.db.real_room(room);
+        self.db
+            .char_to_room(&mut self.chars, &self.objs, chid, room);
+        chid
+    }
+
+    /* A player known to the player index, who isn't in the game */
+    pub(crate) fn add_offline_player(&mut self, name: &str, idnum: i64, level: u8) {
+        self.db.player_table.push(PlayerIndexElement {
+            name: Rc::from(name),
+            id: idnum,
+            level,
+        });
+    }
+
+    /* Have a character type a command line */
+    pub(crate) fn command(&mut self, chid: DepotId, line: &str) {
+        crate::interpreter::command_interpreter(
+            &mut self.game,
+            &mut self.db,
+            &mut self.chars,
+            &mut self.texts,
+            &mut self.objs,
+            chid,
+            line,
+        );
+    }
+
+    pub(crate) fn give_obj(&mut self, chid: DepotId, vnum: ObjVnum) -> DepotId {
+        let oid = self
+            .db
+            .read_object(&mut self.objs, vnum, LoadType::Virtual)
+            .unwrap();
+        obj_to_char(self.objs.get_mut(oid), self.chars.get_mut(chid));
+        oid
+    }
+
+    pub(crate) fn put_obj(&mut self, vnum: ObjVnum, room: RoomVnum) -> DepotId {
+        let oid = self
+            .db
+            .read_object(&mut self.objs, vnum, LoadType::Virtual)
+            .unwrap();
+        let room = self.db.real_room(room);
+        self.db.obj_to_room(self.objs.get_mut(oid), room);
+        oid
+    }
+
+    pub(crate) fn add_mob(&mut self, vnum: MobVnum, room: RoomVnum) -> DepotId {
+        let mid = self
+            .db
+            .read_mobile(&mut self.chars, vnum, LoadType::Virtual)
+            .unwrap();
+        let room = self.db.real_room(room);
+        self.db.char_to_room(&mut self.chars, &self.objs, mid, room);
+        mid
+    }
+}
+
+/* Leave the tests that follow the directory they started in, and no copy */
+#[cfg(test)]
+impl Drop for TestWorld {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.cwd);
+        let _ = fs::remove_dir_all(&self.lib);
+    }
 }
 impl DB {
     /* Free the world, in a memory allocation sense. */
@@ -566,6 +821,7 @@ impl DB {
             r_mortal_start_room: NOWHERE,
             r_immort_start_room: NOWHERE,
             r_frozen_start_room: NOWHERE,
+            dangling_exits: vec![],
             credits: Rc::from("CREDITS placeholder"),
             news: Rc::from("NEWS placeholder"),
             motd: Rc::from("MOTD placeholder"),
@@ -585,12 +841,7 @@ impl DB {
                 month: 0,
                 year: 0,
             },
-            weather_info: WeatherData {
-                pressure: 0,
-                change: 0,
-                sky: SkyCondition::Cloudless,
-                sunlight: SunState::Dark,
-            },
+            sunlight: SunState::Dark,
             reset_q: vec![],
             extractions_pending: 0,
             timer: 0,
@@ -598,17 +849,25 @@ impl DB {
             combat_list: vec![],
             shop_index: vec![],
             spell_sort_info: [0; MAX_SKILLS + 1],
-            spell_info: [SpellInfoType::default(); TOP_SPELL_DEFINE + 1],
+            spell_info: std::array::from_fn(|_| SpellInfoType::default()),
             soc_mess_list: vec![],
             ban_list: vec![],
             invalid_list: vec![],
             boards: BoardSystem::new(texts),
             house_control: [HouseControlRec::new(); MAX_HOUSES],
             num_of_houses: 0,
+            clans: vec![],
+            channels: vec![],
+            auctions: AuctionHouse::default(),
+            offline_tells: vec![],
+            quests: vec![],
+            catalog: MsgCatalog::default(),
             mails: MailSystem::default(),
             mayor: Mayor::new(),
             king_welmar: KingWelmar::new(),
             scheck: false,
+            scripts: Scripts::default(),
+            spec_scripts: SpecScripts::new(),
         }
     }
 
@@ -654,7 +913,17 @@ impl DB {
         prune_crlf(&mut self.greetings);
 
         info!("Loading spell definitions.");
-        mag_assign_spells(self);
+        if !mag_assign_spells(self) {
+            process::exit(1);
+        }
+
+        info!("Loading message catalogs.");
+        if !boot_languages(self) {
+            process::exit(1);
+        }
+
+        info!("Loading channels.");
+        boot_channels(self);
 
         boot_world(game, self, chars, texts);
 
@@ -670,22 +939,18 @@ impl DB {
         info!("Loading social messages.");
         boot_social_messages(self);
 
+        info!("Loading quests.");
+        boot_quests(self);
+
         info!("Assigning function pointers:");
 
         if !self.no_specials {
-            info!("   Mobiles.");
-            assign_mobiles(self);
+            info!("   Mobiles, objects and rooms.");
+            assign_spec_procs(self);
             info!("   Shopkeepers.");
             assign_the_shopkeepers(self);
-            info!("   Objects.");
-            assign_objects(self);
-            info!("   Rooms.");
-            assign_rooms(self);
         }
 
-        info!("Assigning spell and skill levels.");
-        init_spell_levels(self);
-        //
         info!("Sorting command list and spells.");
         sort_commands(self);
         sort_spells(self);
@@ -711,6 +976,18 @@ impl DB {
             house_boot(self, objs);
         }
 
+        info!("Booting clans.");
+        clan_boot(self);
+
+        info!("Booting private channels.");
+        boot_private_channels(self);
+
+        info!("Booting auctions.");
+        auction_boot(self);
+
+        info!("Booting offline tells.");
+        tells_boot(self);
+
         let zone_count = self.zone_table.len();
         for i in 0..zone_count {
             info!(
@@ -757,7 +1034,7 @@ impl DB {
 
         self.time_info = mud_time_passed(time_now(), beginning_of_time as u64);
 
-        self.weather_info.sunlight = match self.time_info.hours {
+        self.sunlight = match self.time_info.hours {
             0..=4 => SunState::Dark,
             5 => SunState::Rise,
             6..=20 => SunState::Light,
@@ -769,22 +1046,6 @@ impl DB {
             "   Current Gametime: {}H {}D {}M {}Y.",
             self.time_info.hours, self.time_info.day, self.time_info.month, self.time_info.year
         );
-
-        self.weather_info.pressure = 960;
-        if (self.time_info.month >= 7) && (self.time_info.month <= 12) {
-            self.weather_info.pressure += dice(1, 50);
-        } else {
-            self.weather_info.pressure += dice(1, 80);
-        }
-
-        self.weather_info.change = 0;
-
-        self.weather_info.sky = match self.weather_info.pressure {
-            ..=980 => SkyCondition::Lightning,
-            981..=1000 => SkyCondition::Raining,
-            1001..=1020 => SkyCondition::Cloudy,
-            _ => SkyCondition::Cloudless,
-        };
     }
 }
 
@@ -885,6 +1146,7 @@ impl DB {
             let pie = PlayerIndexElement {
                 name: Rc::from(parse_c_string(&dummy.name).to_lowercase().as_str()),
                 id: dummy.char_specials_saved.idnum,
+                level: dummy.level,
             };
             self.player_table.push(pie);
             self.top_idnum = max(self.top_idnum, dummy.char_specials_saved.idnum as i32);
@@ -950,16 +1212,19 @@ fn count_hash_records(fl: File) -> i32 {
 
 const INDEX_FILE: &str = "index"; /* index of world files		*/
 const MINDEX_FILE: &str = "index.mini"; /* ... and for mini-mud-mode	*/
-const WLD_PREFIX: &str = "world/wld/"; /* room definitions	*/
-const MOB_PREFIX: &str = "world/mob/"; /* monster prototypes	*/
-const OBJ_PREFIX: &str = "world/obj/"; /* object prototypes	*/
-const ZON_PREFIX: &str = "world/zon/"; /* zon defs & command tables */
-const SHP_PREFIX: &str = "world/shp/"; /* shop definitions	*/
+pub(crate) const WLD_PREFIX: &str = "world/wld/"; /* room definitions	*/
+pub(crate) const MOB_PREFIX: &str = "world/mob/"; /* monster prototypes	*/
+pub(crate) const OBJ_PREFIX: &str = "world/obj/"; /* object prototypes	*/
+pub(crate) const ZON_PREFIX: &str = "world/zon/"; /* zon defs & command tables */
+pub(crate) const SHP_PREFIX: &str = "world/shp/"; /* shop definitions	*/
+pub const TRG_PREFIX: &str = "world/trg/"; /* trigger scripts	*/
+pub const SPEC_PREFIX: &str = "spec/"; /* spec procs written as scripts */
+pub const SPEC_LIST_FILE: &str = "world/spec.lst"; /* spec proc assignments */
 const HLP_PREFIX: &str = "text/help/"; /* for HELP <keyword>	*/
 /* arbitrary constants used by index_boot() (must be unique) */
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u8)]
-enum DbBootMode {
+pub(crate) enum DbBootMode {
     World = 0,
     Mob = 1,
     Object = 2,
@@ -968,6 +1233,24 @@ enum DbBootMode {
     Help = 5,
 }
 
+/* A world file we can't parse; what is wrong has already been logged. */
+#[derive(Debug, PartialEq)]
+pub(crate) struct FormatError;
+
+/* A number the format promised, but that doesn't fit where it goes. */
+pub(crate) fn parse_num<T: FromStr>(num: &str, what: &str) -> Result<T, FormatError>
+where
+    T::Err: Display,
+{
+    num.parse::<T>().map_err(|e| {
+        error!(
+            "SYSERR: Format error in {}, bad number '{}': {}",
+            what, num, e
+        );
+        FormatError
+    })
+}
+
 impl DB {
     fn index_boot(&mut self, texts: &mut Depot<TextData>, mode: DbBootMode) {
         let mut rec_count = 0;
@@ -1103,23 +1386,23 @@ impl DB {
                 Ok(file) => file,
             };
 
-            match mode {
+            let parsed = match mode {
                 DbBootMode::World | DbBootMode::Object | DbBootMode::Mob => {
-                    self.discrete_load(texts, db_file, mode, buf2.as_str());
-                }
-                DbBootMode::Zone => {
-                    self.load_zones(db_file, buf2.as_str());
+                    self.discrete_load(texts, db_file, mode, buf2.as_str())
                 }
+                DbBootMode::Zone => self.load_zones(db_file, buf2.as_str()),
                 DbBootMode::Help => {
                     /*
                      * If you think about it, we have a race here.  Although, this is the
                      * "point-the-gun-at-your-own-foot" type of race.
                      */
                     self.load_help(db_file);
+                    Ok(())
                 }
-                DbBootMode::Shop => {
-                    boot_the_shops(self, db_file, &buf2, rec_count);
-                }
+                DbBootMode::Shop => boot_the_shops(self, db_file, &buf2, rec_count),
+            };
+            if parsed.is_err() {
+                process::exit(1);
             }
 
             buf1.clear();
@@ -1136,13 +1419,13 @@ impl DB {
         }
     }
 
-    fn discrete_load(
+    pub(crate) fn discrete_load(
         &mut self,
         texts: &mut Depot<TextData>,
         file: File,
         mode: DbBootMode,
         filename: &str,
-    ) {
+    ) -> Result<(), FormatError> {
         let mut nr = -1;
         let mut last: i32;
         let mut line = String::new();
@@ -1166,10 +1449,10 @@ impl DB {
                     error!("SYSERR: Format error in {} after {} #{}\n...expecting a new {}, but file ended!\n(maybe the file is not terminated with '$'?)", filename,
                             MODES[mode as usize], nr, MODES[mode as usize]);
                 }
-                process::exit(1);
+                return Err(FormatError);
             }
             if line.starts_with('$') {
-                return;
+                return Ok(());
             }
 
             if line.starts_with('#') {
@@ -1185,20 +1468,20 @@ impl DB {
                         "SYSERR: Format error after {} #{}",
                         MODES[mode as usize], last
                     );
-                    process::exit(1);
+                    return Err(FormatError);
                 }
                 if nr >= 99999 {
-                    return;
+                    return Ok(());
                 } else {
                     match mode {
                         DbBootMode::World => {
-                            self.parse_room(&mut reader, nr);
+                            self.parse_room(&mut reader, nr)?;
                         }
                         DbBootMode::Mob => {
-                            self.parse_mobile(texts, &mut reader, nr);
+                            self.parse_mobile(texts, &mut reader, nr)?;
                         }
                         DbBootMode::Object => {
-                            line = self.parse_object(texts, &mut reader, nr as MobVnum)
+                            line = self.parse_object(texts, &mut reader, nr as MobVnum)?
                         }
                         _ => {}
                     }
@@ -1209,7 +1492,7 @@ impl DB {
                     MODES[mode as usize], filename, MODES[mode as usize], nr
                 );
                 error!("SYSERR: ... offending line: '{}'", line);
-                process::exit(1);
+                return Err(FormatError);
             }
         }
     }
@@ -1242,7 +1525,11 @@ fn asciiflag_conv(flag: &str) -> i64 {
 
 impl DB {
     /* load the rooms */
-    fn parse_room(&mut self, reader: &mut BufReader<File>, virtual_nr: i32) {
+    fn parse_room(
+        &mut self,
+        reader: &mut BufReader<File>,
+        virtual_nr: i32,
+    ) -> Result<(), FormatError> {
         let mut t = [0; 10];
         let mut line = String::new();
         let mut zone = 0;
@@ -1252,24 +1539,26 @@ impl DB {
 
         if virtual_nr < self.zone_table[zone].bot as i32 {
             error!("SYSERR: Room #{} is below zone {}.", virtual_nr, zone);
-            process::exit(1);
+            return Err(FormatError);
         }
         while virtual_nr > self.zone_table[zone].top as i32 {
             zone += 1;
             if zone >= self.zone_table.len() {
                 error!("SYSERR: Room {} is outside of any zone.", virtual_nr);
-                process::exit(1);
+                return Err(FormatError);
             }
         }
         let mut rd = RoomData {
             number: virtual_nr as RoomRnum,
             zone: zone as ZoneRnum,
             sector_type: SectorType::default(),
-            name: fread_string(reader, buf2.as_str()),
-            description: fread_string(reader, buf2.as_str()),
+            name: fread_string(reader, buf2.as_str())?,
+            description: fread_string(reader, buf2.as_str())?,
             ex_descriptions: vec![],
+            alt_descriptions: vec![],
             dir_option: [None, None, None, None, None, None],
             room_flags: RoomFlags::empty(),
+            affected: vec![],
             light: 0, /* Zero light sources */
             func: None,
             contents: vec![],
@@ -1281,7 +1570,7 @@ impl DB {
                 "SYSERR: Expecting roomflags/sector type of room #{} but file ended!",
                 virtual_nr,
             );
-            process::exit(1);
+            return Err(FormatError);
         }
 
         let regex = Regex::new(r"^(\d{1,9})\s(\S*)\s(\d{1,9})")
@@ -1292,16 +1581,12 @@ impl DB {
                     "SYSERR: Format error in roomflags/sector type of room #{}",
                     virtual_nr,
                 );
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
-                t[0] = f[1]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
+                t[0] = parse_num::<i32>(&f[1], &buf2)?;
                 rd.room_flags = RoomFlags::from_bits_truncate(asciiflag_conv(&f[2]));
-                t[2] = f[3]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
+                t[2] = parse_num::<i32>(&f[3], &buf2)?;
             }
         }
 
@@ -1312,28 +1597,42 @@ impl DB {
         rd.sector_type = SectorType::from_i32(t[2]);
 
         let buf = format!(
-            "SYSERR: Format error in room #{} (expecting D/E/S)",
+            "SYSERR: Format error in room #{} (expecting A/D/E/S)",
             virtual_nr
         );
 
         loop {
             if get_line(reader, &mut line) == 0 {
                 error!("{}", buf);
-                process::exit(1);
+                return Err(FormatError);
             }
             match line.remove(0) {
                 'D' => {
-                    DB::setup_dir(
-                        reader,
-                        &mut rd,
-                        line.parse::<i32>()
-                            .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                    );
+                    let dir = parse_num::<i32>(&line, &buf2)?;
+                    if dir < 0 || dir >= NUM_OF_DIRS as i32 {
+                        error!("SYSERR: Bad direction D{} in room #{}", dir, virtual_nr);
+                        return Err(FormatError);
+                    }
+                    DB::setup_dir(reader, &mut rd, dir)?;
                 }
                 'E' => {
                     rd.ex_descriptions.push(ExtraDescrData {
-                        keyword: Rc::from(fread_string(reader, buf2.as_str()).as_str()),
-                        description: Rc::from(fread_string(reader, buf2.as_str())),
+                        keyword: Rc::from(fread_string(reader, buf2.as_str())?.as_str()),
+                        description: Rc::from(fread_string(reader, buf2.as_str())?),
+                    });
+                }
+                'A' => {
+                    let conds = fread_string(reader, buf2.as_str())?;
+                    let Some(conditions) = parse_desc_conds(&conds) else {
+                        error!(
+                            "SYSERR: Bad conditions '{}' for alternate description of room #{}",
+                            conds, virtual_nr
+                        );
+                        return Err(FormatError);
+                    };
+                    rd.alt_descriptions.push(AltDescrData {
+                        conditions,
+                        description: fread_string(reader, buf2.as_str())?,
                     });
                 }
                 'S' => {
@@ -1342,23 +1641,28 @@ impl DB {
                 }
                 _ => {
                     error!("{}", buf);
-                    process::exit(1);
+                    return Err(FormatError);
                 }
             }
         }
         self.world.push(rd);
+        Ok(())
     }
 
     /* read direction data */
-    fn setup_dir(reader: &mut BufReader<File>, room: &mut RoomData, dir: i32) {
+    fn setup_dir(
+        reader: &mut BufReader<File>,
+        room: &mut RoomData,
+        dir: i32,
+    ) -> Result<(), FormatError> {
         let mut t = [0; 5];
         let mut line = String::new();
 
         let buf2 = format!("room #{}, direction D{}", room.number, dir);
 
         let mut rdr = RoomDirectionData {
-            general_description: Rc::from(fread_string(reader, buf2.as_str())),
-            keyword: Rc::from(fread_string(reader, buf2.as_str())),
+            general_description: Rc::from(fread_string(reader, buf2.as_str())?),
+            keyword: Rc::from(fread_string(reader, buf2.as_str())?),
             exit_info: ExitFlags::empty(),
             key: 0,
             to_room: 0,
@@ -1366,7 +1670,7 @@ impl DB {
 
         if get_line(reader, &mut line) == 0 {
             error!("SYSERR: Format error, {}", buf2);
-            process::exit(1);
+            return Err(FormatError);
         }
 
         let regex = Regex::new(r"^(-?\d{1,9})\s(-?\d{1,9})\s(-?\d{1,9})")
@@ -1374,18 +1678,12 @@ impl DB {
         match regex.captures(line.as_str()) {
             None => {
                 error!("SYSERR: Format error, {}", buf2);
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
-                t[0] = f[1]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
-                t[1] = f[2]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
-                t[2] = f[3]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
+                t[0] = parse_num::<i32>(&f[1], &buf2)?;
+                t[1] = parse_num::<i32>(&f[2], &buf2)?;
+                t[2] = parse_num::<i32>(&f[3], &buf2)?;
                 if t[0] == 1 {
                     rdr.exit_info = ExitFlags::ISDOOR;
                 } else if t[0] == 2 {
@@ -1399,6 +1697,7 @@ impl DB {
                 room.dir_option[dir as usize] = Some(rdr);
             }
         }
+        Ok(())
     }
 
     // /* make sure the start rooms exist & resolve their vnums to rnums */
@@ -1427,6 +1726,9 @@ impl DB {
                 if let Some(dir) = &self.world[i].dir_option[door] {
                     if dir.to_room != NOWHERE {
                         let rn = self.real_room(dir.to_room);
+                        if rn == NOWHERE {
+                            self.dangling_exits.push((i as RoomRnum, door, dir.to_room));
+                        }
                         if let Some(dir) = &mut self.world[i].dir_option[door] {
                             dir.to_room = rn;
                         }
@@ -1449,100 +1751,171 @@ impl DB {
      */
 }
 fn renum_zone_table(game: &mut Game, db: &mut DB, chars: &mut Depot<CharData>) {
+    for idx in 0..db.zone_table.len() {
+        renum_zone(game, db, chars, idx);
+    }
+}
+
+/* resolve the vnums of a single zone's reset table (also used by zreload) */
+pub(crate) fn renum_zone(game: &mut Game, db: &mut DB, chars: &mut Depot<CharData>, idx: usize) {
     let mut olda;
     let mut oldb;
     let mut oldc;
 
-    for idx in 0..db.zone_table.len() {
-        for cmd_no in 0..db.zone_table[idx].cmd.len() {
-            if db.zone_table[idx].cmd[cmd_no].command == 'S' {
-                break;
+    for cmd_no in 0..db.zone_table[idx].cmd.len() {
+        if db.zone_table[idx].cmd[cmd_no].command == 'S' {
+            break;
+        }
+        let mut a = 0;
+        let mut b = 0;
+        let mut c = 0;
+        olda = db.zone_table[idx].cmd[cmd_no].arg1;
+        oldb = db.zone_table[idx].cmd[cmd_no].arg2;
+        oldc = db.zone_table[idx].cmd[cmd_no].arg3;
+        match db.zone_table[idx].cmd[cmd_no].command {
+            'M' => {
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_mobile(db.zone_table[idx].cmd[cmd_no].arg1 as MobVnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+                db.zone_table[idx].cmd[cmd_no].arg3 =
+                    db.real_room(db.zone_table[idx].cmd[cmd_no].arg3 as RoomRnum) as i32;
+                c = db.zone_table[idx].cmd[cmd_no].arg3;
             }
-            let mut a = 0;
-            let mut b = 0;
-            let mut c = 0;
-            olda = db.zone_table[idx].cmd[cmd_no].arg1;
-            oldb = db.zone_table[idx].cmd[cmd_no].arg2;
-            oldc = db.zone_table[idx].cmd[cmd_no].arg3;
-            match db.zone_table[idx].cmd[cmd_no].command {
-                'M' => {
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_mobile(db.zone_table[idx].cmd[cmd_no].arg1 as MobVnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
+            'O' => {
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+                if db.zone_table[idx].cmd[cmd_no].arg3 != NOWHERE as i32 {
                     db.zone_table[idx].cmd[cmd_no].arg3 =
                         db.real_room(db.zone_table[idx].cmd[cmd_no].arg3 as RoomRnum) as i32;
                     c = db.zone_table[idx].cmd[cmd_no].arg3;
                 }
-                'O' => {
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
-                    if db.zone_table[idx].cmd[cmd_no].arg3 != NOWHERE as i32 {
-                        db.zone_table[idx].cmd[cmd_no].arg3 =
-                            db.real_room(db.zone_table[idx].cmd[cmd_no].arg3 as RoomRnum) as i32;
-                        c = db.zone_table[idx].cmd[cmd_no].arg3;
-                    }
-                }
-                'G' => {
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
-                }
-                'E' => {
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
-                }
-                'P' => {
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
-                    db.zone_table[idx].cmd[cmd_no].arg3 =
-                        db.real_object(db.zone_table[idx].cmd[cmd_no].arg3 as ObjVnum) as i32;
-                    c = db.zone_table[idx].cmd[cmd_no].arg3;
-                }
-                'D' => {
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_room(db.zone_table[idx].cmd[cmd_no].arg1 as RoomRnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
-                }
-                'R' => {
-                    /* rem obj from room */
-                    db.zone_table[idx].cmd[cmd_no].arg1 =
-                        db.real_room(db.zone_table[idx].cmd[cmd_no].arg1 as RoomRnum) as i32;
-                    a = db.zone_table[idx].cmd[cmd_no].arg1;
-                    db.zone_table[idx].cmd[cmd_no].arg2 =
-                        db.real_object(db.zone_table[idx].cmd[cmd_no].arg2 as RoomRnum) as i32;
-                    b = db.zone_table[idx].cmd[cmd_no].arg2;
-                }
-                _ => {}
             }
+            'G' => {
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+            }
+            'E' => {
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+            }
+            'P' => {
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_object(db.zone_table[idx].cmd[cmd_no].arg1 as ObjVnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+                db.zone_table[idx].cmd[cmd_no].arg3 =
+                    db.real_object(db.zone_table[idx].cmd[cmd_no].arg3 as ObjVnum) as i32;
+                c = db.zone_table[idx].cmd[cmd_no].arg3;
+            }
+            'D' => {
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_room(db.zone_table[idx].cmd[cmd_no].arg1 as RoomRnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+            }
+            'R' => {
+                /* rem obj from room */
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_room(db.zone_table[idx].cmd[cmd_no].arg1 as RoomRnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+                db.zone_table[idx].cmd[cmd_no].arg2 =
+                    db.real_object(db.zone_table[idx].cmd[cmd_no].arg2 as RoomRnum) as i32;
+                b = db.zone_table[idx].cmd[cmd_no].arg2;
+            }
+            'Z' => {
+                /* mob to random room */
+                db.zone_table[idx].cmd[cmd_no].arg1 =
+                    db.real_mobile(db.zone_table[idx].cmd[cmd_no].arg1 as MobVnum) as i32;
+                a = db.zone_table[idx].cmd[cmd_no].arg1;
+            }
+            _ => {}
+        }
 
-            if a == NOWHERE as i32 || b == NOWHERE as i32 || c == NOWHERE as i32 {
-                if !db.mini_mud {
-                    let buf = format!(
-                        "Invalid vnum {}, cmd disabled",
-                        if a == NOWHERE as i32 {
-                            olda
-                        } else if b == NOWHERE as i32 {
-                            oldb
-                        } else {
-                            oldc
-                        }
-                    );
-                    let mut cmd_no2 = cmd_no as i32;
-                    let zone = db.zone_table[idx].number as usize;
-                    let zcmd_command = db.zone_table[idx].cmd[cmd_no].command;
-                    let zcmd_line = db.zone_table[idx].cmd[cmd_no].line;
-                    game.log_zone_error(chars, zone, zcmd_command, zcmd_line, &buf, &mut cmd_no2);
-                }
-                db.zone_table[idx].cmd[cmd_no].command = '*';
+        if let Err(message) = check_zone_cmd(&db.zone_table[idx].cmd[cmd_no]) {
+            let mut cmd_no2 = cmd_no as i32;
+            let zone = db.zone_table[idx].number as usize;
+            let zcmd_command = db.zone_table[idx].cmd[cmd_no].command;
+            let zcmd_line = db.zone_table[idx].cmd[cmd_no].line;
+            game.log_zone_error(chars, zone, zcmd_command, zcmd_line, message, &mut cmd_no2);
+            db.zone_table[idx].cmd[cmd_no].command = '*';
+            continue;
+        }
+
+        if a == NOWHERE as i32 || b == NOWHERE as i32 || c == NOWHERE as i32 {
+            if !db.mini_mud {
+                let buf = format!(
+                    "Invalid vnum {}, cmd disabled",
+                    if a == NOWHERE as i32 {
+                        olda
+                    } else if b == NOWHERE as i32 {
+                        oldb
+                    } else {
+                        oldc
+                    }
+                );
+                let mut cmd_no2 = cmd_no as i32;
+                let zone = db.zone_table[idx].number as usize;
+                let zcmd_command = db.zone_table[idx].cmd[cmd_no].command;
+                let zcmd_line = db.zone_table[idx].cmd[cmd_no].line;
+                game.log_zone_error(chars, zone, zcmd_command, zcmd_line, &buf, &mut cmd_no2);
             }
+            db.zone_table[idx].cmd[cmd_no].command = '*';
         }
     }
 }
-fn parse_simple_mob(reader: &mut BufReader<File>, mobch: &mut CharData, nr: i32) {
+/* Check the arguments of the reset commands that don't refer to the world. */
+fn check_zone_cmd(zcmd: &ResetCom) -> Result<(), &'static str> {
+    let hours = 0..24;
+    let skies = SkyCondition::Cloudless as i32..=SkyCondition::Lightning as i32;
+    match zcmd.command {
+        '%' if !(1..=100).contains(&zcmd.arg1) => Err("chance is not 1-100%, cmd disabled"),
+        'H' if !hours.contains(&zcmd.arg1) || !hours.contains(&zcmd.arg2) => {
+            Err("invalid hour, cmd disabled")
+        }
+        'W' if !skies.contains(&zcmd.arg1)
+            || !skies.contains(&zcmd.arg2)
+            || zcmd.arg1 > zcmd.arg2 =>
+        {
+            Err("invalid sky condition, cmd disabled")
+        }
+        'L' if zcmd.arg1 < 1 => Err("empty list, cmd disabled"),
+        _ => Ok(()),
+    }
+}
+
+/* Is the hour in the window from..to, which may span midnight? */
+fn hour_in_window(hour: i32, from: i32, to: i32) -> bool {
+    if from <= to {
+        hour >= from && hour <= to
+    } else {
+        hour >= from || hour <= to
+    }
+}
+
+/*
+ * Read the conditions of an alternate room description, a list of words
+ * from DESC_CONDS.  None if a word is unknown or there is none at all.
+ */
+pub(crate) fn parse_desc_conds(conds: &str) -> Option<DescConds> {
+    let mut flags = DescConds::empty();
+    for word in conds.split_whitespace() {
+        let bit = DESC_CONDS
+            .iter()
+            .take_while(|&&name| name != "\n")
+            .position(|&name| name.eq_ignore_ascii_case(word))?;
+        flags |= DescConds::from_bits_truncate(1 << bit);
+    }
+    (!flags.is_empty()).then_some(flags)
+}
+
+fn parse_simple_mob(
+    reader: &mut BufReader<File>,
+    mobch: &mut CharData,
+    nr: i32,
+) -> Result<(), FormatError> {
     let mut line = String::new();
+    let buf2 = format!("mob #{}", nr);
 
     mobch.real_abils.str = 11;
     mobch.real_abils.intel = 11;
@@ -1556,65 +1929,38 @@ fn parse_simple_mob(reader: &mut BufReader<File>, mobch: &mut CharData, nr: i32)
             "SYSERR: Format error in mob #{}, file ended after S flag!",
             nr
         );
-        process::exit(1);
+        return Err(FormatError);
     }
 
     let regex = Regex::new(r"^(-?\d{1,9})\s(-?\d{1,9})\s(-?\d{1,9})\s(-?\d{1,9})d(-?\d{1,9})\+(-?\d{1,9})\s(-?\d{1,9})d(-?\d{1,9})\+(-?\d{1,9})").unwrap_or_else(|e| panic!("regex error: {}", e));
     match regex.captures(line.as_str()) {
         None => {
             error!("SYSERR: Format error in mob #{}, first line after S flag\n...expecting line of form '# # # #d#+# #d#+#'", nr);
-            process::exit(1);
+            return Err(FormatError);
         }
         Some(t) => {
-            mobch.set_level(
-                t[1].parse::<u8>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
-            mobch.set_hitroll(
-                20 - t[2]
-                    .parse::<i8>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
-            mobch.set_ac(
-                10 * t[3]
-                    .parse::<i16>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
+            mobch.set_level(parse_num::<u8>(&t[1], &buf2)?);
+            mobch.set_hitroll(20 - parse_num::<i8>(&t[2], &buf2)?);
+            mobch.set_ac(10 * parse_num::<i16>(&t[3], &buf2)?);
 
             /* max hit = 0 is a flag that H, M, V is xdy+z */
             mobch.set_max_hit(0);
-            mobch.set_hit(
-                t[4].parse::<i16>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
-            mobch.set_mana(
-                t[5].parse::<i16>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
-            mobch.set_move(
-                t[6].parse::<i16>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
+            mobch.set_hit(parse_num::<i16>(&t[4], &buf2)?);
+            mobch.set_mana(parse_num::<i16>(&t[5], &buf2)?);
+            mobch.set_move(parse_num::<i16>(&t[6], &buf2)?);
 
             mobch.set_max_mana(10);
             mobch.set_max_move(50);
 
-            mobch.mob_specials.damnodice = t[7]
-                .parse::<u8>()
-                .unwrap_or_else(|e| panic!("parse error: {}", e));
-            mobch.mob_specials.damsizedice = t[8]
-                .parse::<u8>()
-                .unwrap_or_else(|e| panic!("parse error: {}", e));
-            mobch.set_damroll(
-                t[9].parse::<i8>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
+            mobch.mob_specials.damnodice = parse_num::<u8>(&t[7], &buf2)?;
+            mobch.mob_specials.damsizedice = parse_num::<u8>(&t[8], &buf2)?;
+            mobch.set_damroll(parse_num::<i8>(&t[9], &buf2)?);
         }
     }
 
     if get_line(reader, &mut line) == 0 {
         error!("SYSERR: Format error in mob #{}, second line after S flag\n...expecting line of form '# #', but file ended!", nr);
-        process::exit(1);
+        return Err(FormatError);
     }
 
     let regex =
@@ -1622,23 +1968,17 @@ fn parse_simple_mob(reader: &mut BufReader<File>, mobch: &mut CharData, nr: i32)
     match regex.captures(line.as_str()) {
         None => {
             error!("SYSERR: Format error in mob #{}, second line after S flag\n...expecting line of form '# #'", nr);
-            process::exit(1);
+            return Err(FormatError);
         }
         Some(t) => {
-            mobch.set_gold(
-                t[1].parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
-            mobch.set_exp(
-                t[2].parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-            );
+            mobch.set_gold(parse_num::<i32>(&t[1], &buf2)?);
+            mobch.set_exp(parse_num::<i32>(&t[2], &buf2)?);
         }
     }
 
     if get_line(reader, &mut line) == 0 {
         error!("SYSERR: Format error in last line of mob #{}\n...expecting line of form '# # #', but file ended!", nr);
-        process::exit(1);
+        return Err(FormatError);
     }
 
     let regex = Regex::new(r"^(-?\d{1,9})\s(-?\d{1,9})\s(-?\d{1,9})")
@@ -1649,24 +1989,12 @@ fn parse_simple_mob(reader: &mut BufReader<File>, mobch: &mut CharData, nr: i32)
                 "SYSERR: Format error in last line of mob #{}\n...expecting line of form '# # #'",
                 nr
             );
-            process::exit(1);
+            return Err(FormatError);
         }
         Some(t) => {
-            mobch.set_pos(
-                t[1].parse::<u8>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e))
-                    .into(),
-            );
-            mobch.set_default_pos(
-                t[2].parse::<u8>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e))
-                    .into(),
-            );
-            mobch.set_sex(
-                t[3].parse::<u8>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e))
-                    .into(),
-            );
+            mobch.set_pos(parse_num::<u8>(&t[1], &buf2)?.into());
+            mobch.set_default_pos(parse_num::<u8>(&t[2], &buf2)?.into());
+            mobch.set_sex(parse_num::<u8>(&t[3], &buf2)?.into());
 
             mobch.set_class(Class::Undefined);
             mobch.set_weight(200);
@@ -1681,6 +2009,7 @@ fn parse_simple_mob(reader: &mut BufReader<File>, mobch: &mut CharData, nr: i32)
     for j in 0..5 {
         mobch.set_save(j, 0);
     }
+    Ok(())
 }
 
 /*
@@ -1765,37 +2094,40 @@ fn parse_espec(buf: &str, mobch: &mut CharData, nr: i32) {
     interpret_espec(buf, ptr, mobch, nr);
 }
 
-fn parse_enhanced_mob(reader: &mut BufReader<File>, mobch: &mut CharData, nr: i32) {
+fn parse_enhanced_mob(
+    reader: &mut BufReader<File>,
+    mobch: &mut CharData,
+    nr: i32,
+) -> Result<(), FormatError> {
     let mut line = String::new();
 
-    parse_simple_mob(reader, mobch, nr);
+    parse_simple_mob(reader, mobch, nr)?;
 
     while get_line(reader, &mut line) != 0 {
         if line == "E" {
             /* end of the enhanced section */
-            return;
+            return Ok(());
         } else if line.starts_with('#') {
             /* we've hit the next mob, maybe? */
             error!("SYSERR: Unterminated E section in mob #{}", nr);
-            process::exit(1);
+            return Err(FormatError);
         } else {
             parse_espec(&line, mobch, nr);
         }
     }
 
     error!("SYSERR: Unexpected end of file reached after mob #{}", nr);
-    process::exit(1);
+    Err(FormatError)
 }
 impl DB {
-    fn parse_mobile(&mut self, texts: &mut Depot<TextData>, reader: &mut BufReader<File>, nr: i32) {
+    fn parse_mobile(
+        &mut self,
+        texts: &mut Depot<TextData>,
+        reader: &mut BufReader<File>,
+        nr: i32,
+    ) -> Result<(), FormatError> {
         let mut line = String::new();
 
-        self.mob_index.push(IndexData {
-            vnum: nr as MobVnum,
-            number: 0,
-            func: None,
-        });
-
         let mut mobch = CharData::default();
         clear_char(&mut mobch);
 
@@ -1808,8 +2140,8 @@ impl DB {
         let buf2 = format!("mob vnum {}", nr);
 
         /***** String data *****/
-        mobch.player.name = Rc::from(fread_string(reader, buf2.as_str()).as_str());
-        let mut tmpstr = fread_string(reader, buf2.as_str());
+        mobch.player.name = Rc::from(fread_string(reader, buf2.as_str())?.as_str());
+        let mut tmpstr = fread_string(reader, buf2.as_str())?;
         if !tmpstr.is_empty() {
             let f1 = fname(tmpstr.as_str());
             let f = f1.as_ref();
@@ -1819,14 +2151,14 @@ impl DB {
             }
         }
         mobch.player.short_descr = tmpstr.into();
-        mobch.player.long_descr = Rc::from(fread_string(reader, buf2.as_str()).as_str());
-        mobch.player.description = texts.add_text(fread_string(reader, buf2.as_str()));
+        mobch.player.long_descr = Rc::from(fread_string(reader, buf2.as_str())?.as_str());
+        let description = fread_string(reader, buf2.as_str())?;
         mobch.set_title(None);
 
         /* *** Numeric data *** */
         if get_line(reader, &mut line) == 0 {
             error!("SYSERR: Format error after string section of mob #{}\n...expecting line of form '# # # {{S | E}}', but file ended!", nr);
-            process::exit(1);
+            return Err(FormatError);
         }
 
         let regex = Regex::new(r"^(\S+)\s(\S+)\s(-?\+?\d{1,9})\s([SE])")
@@ -1834,7 +2166,7 @@ impl DB {
         match regex.captures(line.as_str()) {
             None => {
                 error!("SYSERR: Format error after string section of mob #{}\n...expecting line of form '# # # {{S | E}}'", nr);
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
                 mobch.set_mob_flags(asciiflag_conv(&f[1]));
@@ -1859,10 +2191,7 @@ impl DB {
                     "mobile affect",
                 );
 
-                mobch.set_alignment(
-                    f[3].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
+                mobch.set_alignment(parse_num::<i32>(&f[3], &buf2)?);
 
                 /* AGGR_TO_ALIGN is ignored if the mob is AGGRESSIVE. */
                 if mobch.mob_flagged(MOB_AGGRESSIVE)
@@ -1877,16 +2206,16 @@ impl DB {
                 match f[4].to_uppercase().as_str() {
                     "S" => {
                         /* Simple monsters */
-                        parse_simple_mob(reader, &mut mobch, nr);
+                        parse_simple_mob(reader, &mut mobch, nr)?;
                     }
                     "E" => {
                         /* Circle3 Enhanced monsters */
-                        parse_enhanced_mob(reader, &mut mobch, nr);
+                        parse_enhanced_mob(reader, &mut mobch, nr)?;
                     }
                     /* add new mob types here.. */
                     _ => {
                         error!("SYSERR: Unsupported mob type '{}' in mob #{}", &f[4], nr);
-                        process::exit(1);
+                        return Err(FormatError);
                     }
                 }
 
@@ -1899,9 +2228,17 @@ impl DB {
                 mobch.nr = self.mob_protos.len() as MobRnum;
                 mobch.desc = None;
 
+                /* nothing is kept of a mob that failed to parse */
+                mobch.player.description = texts.add_text(description);
+                self.mob_index.push(IndexData {
+                    vnum: nr as MobVnum,
+                    number: 0,
+                    func: None,
+                });
                 self.mob_protos.push(mobch);
             }
         }
+        Ok(())
     }
 
     /* read all objects from obj file; generate index and prototypes */
@@ -1910,15 +2247,10 @@ impl DB {
         texts: &mut Depot<TextData>,
         reader: &mut BufReader<File>,
         nr: MobVnum,
-    ) -> String {
+    ) -> Result<String, FormatError> {
         let mut line = String::new();
 
         let i = self.obj_index.len() as ObjVnum;
-        self.obj_index.push(IndexData {
-            vnum: nr,
-            number: 0,
-            func: None,
-        });
 
         let mut obj = ObjData {
             id: Default::default(),
@@ -1979,12 +2311,12 @@ impl DB {
         let buf2 = format!("object #{}", nr); /* sprintf: OK (for 'buf2 >= 19') */
 
         /* *** string data *** */
-        obj.name = Rc::from(fread_string(reader, &buf2).as_str());
+        obj.name = Rc::from(fread_string(reader, &buf2)?.as_str());
         if obj.name.is_empty() {
             error!("SYSERR: Null obj name or format error at or near {}", buf2);
-            process::exit(1);
+            return Err(FormatError);
         }
-        let mut tmpstr = fread_string(reader, &buf2);
+        let mut tmpstr = fread_string(reader, &buf2)?;
         if !tmpstr.is_empty() {
             let f = fname(tmpstr.as_str());
             if f.as_ref() == "a" || f.as_ref() == "an" || f.as_ref() == "the" {
@@ -1994,9 +2326,9 @@ impl DB {
         }
         obj.short_description = Rc::from(tmpstr.as_str());
 
-        let tmpptr = fread_string(reader, &buf2);
+        let tmpptr = fread_string(reader, &buf2)?;
         obj.description = Rc::from(tmpptr.as_str());
-        obj.action_description = texts.add_text(fread_string(reader, &buf2));
+        let action_description = fread_string(reader, &buf2)?;
 
         /* *** numeric data *** */
         if get_line(reader, &mut line) == 0 {
@@ -2004,7 +2336,7 @@ impl DB {
                 "SYSERR: Expecting first numeric line of {}, but file ended!",
                 buf2
             );
-            process::exit(1);
+            return Err(FormatError);
         }
 
         let regex = Regex::new(r"^(\d{1,9})\s(\S+)\s(\S+)")
@@ -2015,14 +2347,11 @@ impl DB {
                     "SYSERR: Format error in first numeric line (expecting 3 args), {}",
                     buf2
                 );
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
                 /* Object flags checked in check_object(). */
-                obj.set_obj_type(ItemType::from_u8(
-                    f[1].parse::<u8>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                ));
+                obj.set_obj_type(ItemType::from_u8(parse_num::<u8>(&f[1], &buf2)?));
                 obj.set_obj_extra(ExtraFlags::from_bits_truncate(asciiflag_conv(&f[2]) as i32));
                 obj.set_obj_wear(WearFlags::from_bits_truncate(asciiflag_conv(&f[3]) as i32));
             }
@@ -2032,7 +2361,7 @@ impl DB {
                 "SYSERR: Expecting second numeric line of {}, but file ended!",
                 buf2
             );
-            process::exit(1);
+            return Err(FormatError);
         }
         let regex = Regex::new(r"^(-?\+?\d{1,9})\s(-?\+?\d{1,9})\s(-?\+?\d{1,9})\s(-?\+?\d{1,9})")
             .unwrap_or_else(|e| panic!("regex error: {}", e));
@@ -2042,29 +2371,13 @@ impl DB {
                     "SYSERR: Format error in second numeric line (expecting 4 args), {}",
                     buf2
                 );
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
-                obj.set_obj_val(
-                    0,
-                    f[1].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
-                obj.set_obj_val(
-                    1,
-                    f[2].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
-                obj.set_obj_val(
-                    2,
-                    f[3].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
-                obj.set_obj_val(
-                    3,
-                    f[4].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
+                obj.set_obj_val(0, parse_num::<i32>(&f[1], &buf2)?);
+                obj.set_obj_val(1, parse_num::<i32>(&f[2], &buf2)?);
+                obj.set_obj_val(2, parse_num::<i32>(&f[3], &buf2)?);
+                obj.set_obj_val(3, parse_num::<i32>(&f[4], &buf2)?);
             }
         }
         if get_line(reader, &mut line) == 0 {
@@ -2072,7 +2385,7 @@ impl DB {
                 "SYSERR: Expecting third numeric line of {}, but file ended!",
                 buf2
             );
-            process::exit(1);
+            return Err(FormatError);
         }
         let regex = Regex::new(r"^(-?\+?\d{1,9})\s(-?\+?\d{1,9})\s(-?\+?\d{1,9})")
             .unwrap_or_else(|e| panic!("regex error: {}", e));
@@ -2082,21 +2395,12 @@ impl DB {
                     "SYSERR: Format error in third numeric line (expecting 3 args), {}",
                     buf2
                 );
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
-                obj.set_obj_weight(
-                    f[1].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
-                obj.set_obj_cost(
-                    f[2].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
-                obj.set_obj_rent(
-                    f[3].parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                );
+                obj.set_obj_weight(parse_num::<i32>(&f[1], &buf2)?);
+                obj.set_obj_cost(parse_num::<i32>(&f[2], &buf2)?);
+                obj.set_obj_rent(parse_num::<i32>(&f[3], &buf2)?);
             }
         }
         /* check to make sure that weight of containers exceeds curr. quantity */
@@ -2120,13 +2424,13 @@ impl DB {
         loop {
             if get_line(reader, &mut line) == 0 {
                 error!("SYSERR: Format error in {}", buf2);
-                process::exit(1);
+                return Err(FormatError);
             }
             match line.chars().next() {
                 Some('E') => {
                     let new_descr = ExtraDescrData {
-                        keyword: Rc::from(fread_string(reader, buf2).as_str()),
-                        description: Rc::from(fread_string(reader, buf2).as_str()),
+                        keyword: Rc::from(fread_string(reader, buf2)?.as_str()),
+                        description: Rc::from(fread_string(reader, buf2)?.as_str()),
                     };
                     obj.ex_descriptions.push(new_descr);
                 }
@@ -2136,44 +2440,47 @@ impl DB {
                             "SYSERR: Too many A fields ({} max), {}",
                             MAX_OBJ_AFFECT, buf2
                         );
-                        process::exit(1);
+                        return Err(FormatError);
                     }
                     if get_line(reader, &mut line) == 0 {
                         error!("SYSERR: Format error in 'A' field, {}\n...expecting 2 numeric constants but file ended!", buf2);
-                        process::exit(1);
+                        return Err(FormatError);
                     }
                     match regex.captures(line.as_str()) {
                         None => {
                             error!("SYSERR: Format error in 'A' field, {}\n...expecting 2 numeric arguments\n...offending line: '{}'", buf2, line);
-                            process::exit(1);
+                            return Err(FormatError);
                         }
                         Some(f) => {
-                            obj.affected[j].location = ApplyType::from_u8(
-                                f[1].parse::<u8>()
-                                    .unwrap_or_else(|e| panic!("parse error: {}", e)),
-                            );
-                            obj.affected[j].modifier = f[2]
-                                .parse()
-                                .unwrap_or_else(|e| panic!("parse error: {}", e));
+                            obj.affected[j].location =
+                                ApplyType::from_u8(parse_num::<u8>(&f[1], buf2)?);
+                            obj.affected[j].modifier = parse_num(&f[2], buf2)?;
                             j += 1;
                         }
                     }
                 }
                 Some('$') | Some('#') => {
+                    /* nothing is kept of an object that failed to parse */
+                    obj.action_description = texts.add_text(action_description);
                     self.check_object(&obj);
+                    self.obj_index.push(IndexData {
+                        vnum: nr,
+                        number: 0,
+                        func: None,
+                    });
                     self.obj_proto.push(obj);
-                    return line.clone();
+                    return Ok(line.clone());
                 }
                 _ => {
                     error!("SYSERR: Format error in ({}): {}", line, buf2);
-                    process::exit(1);
+                    return Err(FormatError);
                 }
             }
         }
     }
 
     /* load the zone table and command tables */
-    fn load_zones(&mut self, fl: File, zonename: &str) {
+    pub(crate) fn load_zones(&mut self, fl: File, zonename: &str) -> Result<(), FormatError> {
         let mut line_num = 0;
         let mut z = ZoneData {
             name: "".to_string(),
@@ -2184,6 +2491,12 @@ impl DB {
             reset_mode: 0,
             number: 0,
             cmd: vec![],
+            climate: Climate::Temperate,
+            weather: WeatherData {
+                pressure: 0,
+                change: 0,
+                sky: SkyCondition::Cloudless,
+            },
         };
 
         let zname = zonename;
@@ -2208,7 +2521,7 @@ impl DB {
         while reader.read_line(&mut buf).is_ok() {
             buf = buf.trim_end().to_string();
             if let Some(c) = buf.chars().next() {
-                if "MOPGERD".contains(c) || buf == "S" {
+                if "MOPGERD%HWLZ".contains(c) || buf == "S" {
                     num_of_cmds += 1;
                 }
             } else {
@@ -2222,7 +2535,7 @@ impl DB {
 
         if num_of_cmds == 0 {
             error!("SYSERR: {} is empty!", zname);
-            process::exit(1);
+            return Err(FormatError);
         } else {
             z.cmd.reserve_exact(num_of_cmds);
         }
@@ -2233,12 +2546,10 @@ impl DB {
         match regex.captures(buf.as_str()) {
             None => {
                 error!("SYSERR: Format error #1 in {}, line {}", zname, line_num);
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
-                z.number = f[1]
-                    .parse::<ZoneVnum>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
+                z.number = parse_num::<ZoneVnum>(&f[1], zname)?;
             }
         }
 
@@ -2250,7 +2561,7 @@ impl DB {
         z.name = buf.clone();
 
         line_num += get_line(&mut reader, &mut buf);
-        let regex = Regex::new(r"^(\d{1,9})\s(\d{0,9})\s(\d{0,9})\s(\d{0,9})")
+        let regex = Regex::new(r"^(\d{1,9})\s(\d{0,9})\s(\d{0,9})\s(\d{0,9})(?:\s+(\d{1,9}))?")
             .unwrap_or_else(|e| panic!("regex error: {}", e));
         match regex.captures(buf.as_str()) {
             None => {
@@ -2258,33 +2569,39 @@ impl DB {
                     "SYSERR: Format error #1 in numeric constant line of {}",
                     zname,
                 );
-                process::exit(1);
+                return Err(FormatError);
             }
             Some(f) => {
-                z.bot = f[1]
-                    .parse::<RoomRnum>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
-                z.top = f[2]
-                    .parse::<RoomRnum>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
-                z.lifespan = f[3]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
-                z.reset_mode = f[4]
-                    .parse::<i32>()
-                    .unwrap_or_else(|e| panic!("parse error: {}", e));
+                z.bot = parse_num::<RoomRnum>(&f[1], zname)?;
+                z.top = parse_num::<RoomRnum>(&f[2], zname)?;
+                z.lifespan = parse_num::<i32>(&f[3], zname)?;
+                z.reset_mode = parse_num::<i32>(&f[4], zname)?;
+                /* the climate is optional, older zones are temperate */
+                if let Some(climate) = f.get(5) {
+                    let climate = climate.as_str().parse::<i32>().unwrap_or(-1);
+                    match Climate::from_i32(climate) {
+                        Some(climate) => z.climate = climate,
+                        None => error!(
+                            "SYSERR: Zone {} has unknown climate {}, using temperate.",
+                            z.number, climate
+                        ),
+                    }
+                }
             }
         }
+        z.weather = init_weather(z.climate, self.time_info.month);
 
         if z.bot > z.top {
             error!(
                 "SYSERR: Zone {} bottom ({}) > top ({}).",
                 z.number, z.bot, z.top
             );
-            process::exit(1);
+            return Err(FormatError);
         }
 
         let mut cmd_no = 0;
+        let regex2 =
+            Regex::new(r"^\s(\d{1,9})\s(\d{1,9})").unwrap_or_else(|e| panic!("regex error: {}", e));
         let regex3 = Regex::new(r"^\s(\d{1,9})\s(\d{0,9})\s(\d{0,9})")
             .unwrap_or_else(|e| panic!("regex error: {}", e));
         let regex4 = Regex::new(r"^\s(\d{1,9})\s(\d{0,9})\s(\d{0,9})\s(\d{0,9})")
@@ -2293,7 +2610,7 @@ impl DB {
             let tmp = get_line(&mut reader, &mut buf);
             if tmp == 0 {
                 error!("SYSERR: Format error in {} - premature end of file", zname);
-                process::exit(1);
+                return Err(FormatError);
             }
             line_num += tmp;
             buf = buf.trim_start().to_string();
@@ -2320,37 +2637,32 @@ impl DB {
             }
             let mut error = 0;
             let mut tmp: i32 = -1;
-            if "MOEPD".find(zcmd.command).is_none() {
+            if "%L".contains(zcmd.command) {
+                /* a 2-arg command */
+                let f = regex2.captures(buf.as_str());
+                if let Some(f) = f {
+                    tmp = parse_num::<i32>(&f[1], zname)?;
+                    zcmd.arg1 = parse_num::<i32>(&f[2], zname)?;
+                } else {
+                    error = 1;
+                }
+            } else if "MOEPD".find(zcmd.command).is_none() {
                 /* a 3-arg command */
                 let f = regex3.captures(buf.as_str());
                 if let Some(f) = f {
-                    tmp = f[1]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
-                    zcmd.arg1 = f[2]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
-                    zcmd.arg2 = f[3]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
+                    tmp = parse_num::<i32>(&f[1], zname)?;
+                    zcmd.arg1 = parse_num::<i32>(&f[2], zname)?;
+                    zcmd.arg2 = parse_num::<i32>(&f[3], zname)?;
                 } else {
                     error = 1;
                 }
             } else {
                 let f = regex4.captures(buf.as_str());
                 if let Some(f) = f {
-                    tmp = f[1]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
-                    zcmd.arg1 = f[2]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
-                    zcmd.arg2 = f[3]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
-                    zcmd.arg3 = f[4]
-                        .parse::<i32>()
-                        .unwrap_or_else(|e| panic!("parse error: {}", e));
+                    tmp = parse_num::<i32>(&f[1], zname)?;
+                    zcmd.arg1 = parse_num::<i32>(&f[2], zname)?;
+                    zcmd.arg2 = parse_num::<i32>(&f[3], zname)?;
+                    zcmd.arg3 = parse_num::<i32>(&f[4], zname)?;
                 } else {
                     error = 1;
                 }
@@ -2363,7 +2675,7 @@ impl DB {
                     "SYSERR: Format error in {}, line {}: '{}'",
                     zname, line_num, original_buf
                 );
-                process::exit(1);
+                return Err(FormatError);
             }
             zcmd.line = line_num;
             cmd_no += 1;
@@ -2377,10 +2689,11 @@ impl DB {
                 num_of_cmds,
                 cmd_no + 1,
             );
-            process::exit(1);
+            return Err(FormatError);
         }
 
         self.zone_table.push(z);
+        Ok(())
     }
 }
 
@@ -2728,13 +3041,29 @@ impl Game {
     ) {
         let mut last_cmd = 0;
         let mut mob_id = None;
+        /* commands left in the list of an 'L' command, and the one to run */
+        let mut alternatives = 0;
+        let mut chosen = -1;
         let cmd_count = db.zone_table[zone].cmd.len();
         for cmd_no in 0..cmd_count {
             // let zcmd = &db.zone_table[zone].cmd[cmd_no];
             if db.zone_table[zone].cmd[cmd_no].command == 'S' {
                 break;
             }
+            /* the commands depending on an alternative go with it */
+            if !db.zone_table[zone].cmd[cmd_no].if_flag && alternatives > 0 {
+                alternatives -= 1;
+                if alternatives != chosen {
+                    last_cmd = 0;
+                    continue;
+                }
+            }
             if db.zone_table[zone].cmd[cmd_no].if_flag && last_cmd == 0 {
+                if db.zone_table[zone].cmd[cmd_no].command == 'L' {
+                    /* none of the list runs */
+                    alternatives = db.zone_table[zone].cmd[cmd_no].arg1;
+                    chosen = -1;
+                }
                 continue;
             }
 
@@ -2987,6 +3316,73 @@ impl Game {
                     last_cmd = 1;
                 }
 
+                'Z' => {
+                    /* read a mobile into a random room of the zone */
+                    if db.mob_index[db.zone_table[zone].cmd[cmd_no].arg1 as usize].number
+                        < db.zone_table[zone].cmd[cmd_no].arg2
+                    {
+                        let rooms: Vec<RoomRnum> = (0..db.world.len() as RoomRnum)
+                            .filter(|&r| {
+                                db.world[r as usize].zone as usize == zone
+                                    && !db.room_flagged(r, RoomFlags::NOMOB | RoomFlags::DEATH)
+                            })
+                            .collect();
+                        if rooms.is_empty() {
+                            let zcmd_command = db.zone_table[zone].cmd[cmd_no].command;
+                            let zcmd_line = db.zone_table[zone].cmd[cmd_no].line;
+                            self.log_zone_error(
+                                chars,
+                                zone,
+                                zcmd_command,
+                                zcmd_line,
+                                "no room for a mobile in zone, command disabled",
+                                &mut last_cmd,
+                            );
+                            db.zone_table[zone].cmd[cmd_no].command = '*';
+                            continue;
+                        }
+                        let nr = db.zone_table[zone].cmd[cmd_no].arg1 as MobVnum;
+                        if let Some(id) = db.read_mobile(chars, nr, LoadType::Real) {
+                            mob_id = Some(id);
+                            let room = rooms[rand_number(0, rooms.len() as u32 - 1) as usize];
+                            db.char_to_room(chars, objs, id, room);
+                        }
+                        last_cmd = 1;
+                    } else {
+                        last_cmd = 0;
+                    }
+                }
+
+                '%' => {
+                    /* go on by chance */
+                    last_cmd =
+                        (rand_number(1, 100) as i32 <= db.zone_table[zone].cmd[cmd_no].arg1) as i32;
+                }
+
+                'H' => {
+                    /* go on at some hours of the day */
+                    last_cmd = hour_in_window(
+                        db.time_info.hours,
+                        db.zone_table[zone].cmd[cmd_no].arg1,
+                        db.zone_table[zone].cmd[cmd_no].arg2,
+                    ) as i32;
+                }
+
+                'W' => {
+                    /* go on when the sky is so */
+                    let sky = db.zone_table[zone].weather.sky as i32;
+                    last_cmd = (sky >= db.zone_table[zone].cmd[cmd_no].arg1
+                        && sky <= db.zone_table[zone].cmd[cmd_no].arg2)
+                        as i32;
+                }
+
+                'L' => {
+                    /* run one of the next commands, picked at random */
+                    alternatives = db.zone_table[zone].cmd[cmd_no].arg1;
+                    chosen = rand_number(0, alternatives as u32 - 1) as i32;
+                    last_cmd = 1;
+                }
+
                 _ => {
                     let zcmd_command = db.zone_table[zone].cmd[cmd_no].command;
                     let zcmd_line = db.zone_table[zone].cmd[cmd_no].line;
@@ -3038,7 +3434,7 @@ impl DB {
     fn get_ptable_by_name(&self, name: &str) -> Option<usize> {
         self.player_table
             .iter()
-            .position(|pie| pie.name.as_ref() == name)
+            .position(|pie| pie.name.eq_ignore_ascii_case(name))
     }
 
     /* Load a char, TRUE if loaded, FALSE if not */
@@ -3060,6 +3456,22 @@ impl DB {
         }
         Some(player_i)
     }
+
+    /* Write a char loaded by load_char back to its place in the player file */
+    pub fn write_char(&mut self, player_i: usize, char_element: &CharFileU) {
+        let Some(pfile) = self.player_fl.as_mut() else {
+            error!("SYSERR: write_char: no player file");
+            return;
+        };
+        let record_size = mem::size_of::<CharFileU>();
+        unsafe {
+            let player_slice =
+                slice::from_raw_parts(char_element as *const _ as *const u8, record_size);
+            pfile
+                .write_all_at(player_slice, (player_i * record_size) as u64)
+                .expect("Error while writing player record to file");
+        }
+    }
 }
 /*
  * write the vital data of a player to the player file
@@ -3092,6 +3504,7 @@ pub fn save_char(
     char_to_store(descs, texts, objs, db, chars, chid, &mut st);
     let ch = chars.get(chid);
     copy_to_stored(&mut st.host, descs.get(ch_desc).host.as_ref());
+    db.player_table[ch.get_pfilepos() as usize].level = st.level;
 
     unsafe {
         let player_slice =
@@ -3105,6 +3518,8 @@ pub fn save_char(
             panic!("Player file not found");
         }
     }
+    write_quests(ch);
+    write_prefs(ch);
 }
 
 impl CharFileU {
@@ -3147,7 +3562,7 @@ impl CharFileU {
                 spare4: 0,
                 spare5: 0,
                 spells_to_learn: 0,
-                spare7: 0,
+                quest_points: 0,
                 spare8: 0,
                 spare9: 0,
                 spare10: 0,
@@ -3409,6 +3824,7 @@ impl DB {
             self.player_table.push(PlayerIndexElement {
                 name: Rc::from(name.to_lowercase()),
                 id: i as i64,
+                level: 0,
             });
             i
         }
@@ -3420,18 +3836,23 @@ impl DB {
  ************************************************************************/
 
 /* read and allocate space for a '~'-terminated string from a given file */
-pub fn fread_string(reader: &mut BufReader<File>, error: &str) -> String {
+pub fn fread_string(reader: &mut BufReader<File>, error: &str) -> Result<String, FormatError> {
     let mut buf = String::new();
     let mut tmp = String::new();
     let mut done = false;
     loop {
         tmp.clear();
-        reader.read_line(&mut tmp).unwrap_or_else(|e| {
-            panic!(
+        let read = reader.read_line(&mut tmp).map_err(|e| {
+            error!(
                 "SYSERR: fread_string: format error at or near {}: {}",
                 error, e
-            )
-        });
+            );
+            FormatError
+        })?;
+        if read == 0 {
+            error!("SYSERR: fread_string: unexpected EOF near {}", error);
+            return Err(FormatError);
+        }
 
         /* If there is a '~', end the string; else put an "\r\n" over the '\n'. */
         let point = tmp.find('~');
@@ -3446,7 +3867,7 @@ pub fn fread_string(reader: &mut BufReader<File>, error: &str) -> String {
         }
     }
 
-    buf
+    Ok(buf)
 }
 
 impl DB {
@@ -3459,6 +3880,7 @@ impl DB {
         chid: DepotId,
     ) {
         let mut ch = chars.take(chid);
+        self.scripts.forget(ScriptOwner::Char(chid));
 
         ch.player_specials.aliases.clear();
 
@@ -3474,6 +3896,7 @@ impl DB {
 
     pub fn free_obj(&mut self, objs: &mut Depot<ObjData>, oid: DepotId) {
         objs.take(oid);
+        self.scripts.forget(ScriptOwner::Obj(oid));
     }
 }
 
@@ -3648,12 +4071,12 @@ impl DB {
             ch.set_height(rand_number(150, 180) as u8); /* 5'0" - 6'0" */
         }
         let ch = chars.get(chid);
-        let i = self.get_ptable_by_name(ch.get_name().as_ref());
+        let i = self.get_ptable_by_name(&ch.get_name().to_lowercase());
         if let Some(i) = i {
-            let top_n = self.player_table.len();
-            self.player_table[i].id = top_n as i64; //*self.top_idnum.borrow() as i64;
+            self.top_idnum += 1;
+            self.player_table[i].id = self.top_idnum as i64;
             let ch = chars.get_mut(chid);
-            ch.set_idnum(top_n as i64); /*self.top_idnum.borrow()*/
+            ch.set_idnum(self.top_idnum as i64);
         } else {
             error!(
                 "SYSERR: init_char: Character '{}' not found in player table.",
@@ -4032,7 +4455,7 @@ impl Default for CharData {
                     spare4: 0,
                     spare5: 0,
                     spells_to_learn: 0,
-                    spare7: 0,
+                    quest_points: 0,
                     spare8: 0,
                     spare9: 0,
                     spare10: 0,
@@ -4052,6 +4475,17 @@ impl Default for CharData {
                 poofout: Rc::from(""),
                 aliases: vec![],
                 last_tell: 0,
+                quests: vec![],
+                quests_done: vec![],
+                prompt: Rc::from(""),
+                screen_width: 0,
+                color_depth: None,
+                color_theme: 0,
+                colors: Default::default(),
+                language: Rc::from(""),
+                channels_off: vec![],
+                ignored: vec![],
+                friends: vec![],
             },
             mob_specials: MobSpecialData {
                 memory: vec![],
@@ -4125,7 +4559,7 @@ impl CharData {
                     spare4: 0,
                     spare5: 0,
                     spells_to_learn: 0,
-                    spare7: 0,
+                    quest_points: 0,
                     spare8: 0,
                     spare9: 0,
                     spare10: 0,
@@ -4145,6 +4579,17 @@ impl CharData {
                 poofout: Rc::from(""),
                 aliases: vec![],
                 last_tell: 0,
+                quests: vec![],
+                quests_done: vec![],
+                prompt: Rc::from(""),
+                screen_width: 0,
+                color_depth: None,
+                color_theme: 0,
+                colors: Default::default(),
+                language: Rc::from(""),
+                channels_off: vec![],
+                ignored: vec![],
+                friends: vec![],
             },
             mob_specials: MobSpecialData {
                 memory: vec![],
@@ -4288,3 +4733,234 @@ impl ObjData {
         ret
     }
 }
+
+#[cfg(test)]
+mod db_tests {
+    use super::*;
+    use crate::handler::affect_to_room;
+    use crate::spells::{SPELL_CONSECRATE, SPELL_WALL_OF_FORCE};
+    use crate::structs::{RoomAffectFlags, RoomAffectedType};
+    use std::env;
+
+    fn zcmd(command: char, arg1: i32, arg2: i32) -> ResetCom {
+        ResetCom {
+            command,
+            if_flag: false,
+            arg1,
+            arg2,
+            arg3: 0,
+            line: 0,
+        }
+    }
+
+    #[test]
+    fn test_load_zones_reads_extended_commands() {
+        let path = env::temp_dir().join(format!("zone-{}.zon", process::id()));
+        fs::write(
+            &path,
+            "#99\nTest zone~\n9900 9999 10 2 2\n\
+             L 0 2\n\
+             M 0 9900 1 9901\n\
+             E 1 9910 5 16\n\
+             Z 0 9901 2\n\
+             H 0 21 4\n\
+             % 1 5\n\
+             W 1 2 3\n\
+             S\n$\n",
+        )
+        .unwrap();
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        db.load_zones(File::open(&path).unwrap(), "zone-test")
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(db.zone_table[0].climate, Climate::Arctic);
+        let cmds: Vec<_> = db.zone_table[0]
+            .cmd
+            .iter()
+            .map(|c| (c.command, c.if_flag, c.arg1, c.arg2, c.arg3))
+            .collect();
+        assert_eq!(
+            cmds,
+            vec![
+                ('L', false, 2, 0, 0),
+                ('M', false, 9900, 1, 9901),
+                ('E', true, 9910, 5, 16),
+                ('Z', false, 9901, 2, 0),
+                ('H', false, 21, 4, 0),
+                ('%', true, 5, 0, 0),
+                ('W', true, 2, 3, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zone_climate_header() {
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        for (n, header) in [
+            "9900 9999 10 2",
+            "9900 9999 10 2 1",
+            "9900 9999 10 2 3",
+            "9900 9999 10 2 7",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let path = env::temp_dir().join(format!("climate-{}-{}.zon", process::id(), n));
+            fs::write(
+                &path,
+                format!("#{}\nTest zone~\n{}\nS\n$\n", 90 + n, header),
+            )
+            .unwrap();
+            db.load_zones(File::open(&path).unwrap(), "climate-test")
+                .unwrap();
+            fs::remove_file(&path).unwrap();
+        }
+        let climates: Vec<_> = db.zone_table.iter().map(|z| z.climate).collect();
+        assert_eq!(
+            climates,
+            vec![
+                Climate::Temperate,
+                Climate::Desert,
+                Climate::Underground,
+                /* unknown, so the default */
+                Climate::Temperate,
+            ]
+        );
+
+        /* the shipped zones that aren't temperate say so */
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("lib/world/zon");
+        let mut db = DB::new(&mut texts);
+        for zone in ["30", "50", "40"] {
+            let path = dir.join(format!("{}.zon", zone));
+            db.load_zones(File::open(&path).unwrap(), zone).unwrap();
+        }
+        let climates: Vec<_> = db.zone_table.iter().map(|z| z.climate).collect();
+        assert_eq!(
+            climates,
+            vec![Climate::Temperate, Climate::Desert, Climate::Underground]
+        );
+    }
+
+    #[test]
+    fn test_alternate_room_descriptions() {
+        assert_eq!(
+            parse_desc_conds("night Rain"),
+            Some(DescConds::NIGHT | DescConds::RAIN)
+        );
+        assert_eq!(parse_desc_conds("noon"), None);
+        assert_eq!(parse_desc_conds(" "), None);
+
+        let zon = env::temp_dir().join(format!("alt-{}.zon", process::id()));
+        let wld = env::temp_dir().join(format!("alt-{}.wld", process::id()));
+        fs::write(&zon, "#99\nTest zone~\n9900 9999 10 2\nS\n$\n").unwrap();
+        fs::write(
+            &wld,
+            "#9900\nMarket~\nBusy.\n~\n99 0 1\n\
+             A\nnight~\nEmpty.\n~\n\
+             A\nnight rain~\nEmpty and wet.\n~\n\
+             A\nwinter~\nCold.\n~\nS\n$~\n",
+        )
+        .unwrap();
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        db.load_zones(File::open(&zon).unwrap(), "alt-test")
+            .unwrap();
+        db.discrete_load(
+            &mut texts,
+            File::open(&wld).unwrap(),
+            DbBootMode::World,
+            "alt-test",
+        )
+        .unwrap();
+        fs::remove_file(&zon).unwrap();
+        fs::remove_file(&wld).unwrap();
+
+        db.time_info.month = 6;
+        db.sunlight = SunState::Light;
+        db.zone_table[0].weather.sky = SkyCondition::Raining;
+        assert_eq!(db.room_description(0), "Busy.\n");
+        db.sunlight = SunState::Dark;
+        assert_eq!(db.room_description(0), "Empty and wet.\n");
+        db.zone_table[0].weather.sky = SkyCondition::Cloudy;
+        assert_eq!(db.room_description(0), "Empty.\n");
+        db.time_info.month = 0;
+        assert_eq!(db.room_description(0), "Empty.\n");
+        db.sunlight = SunState::Light;
+        assert_eq!(db.room_description(0), "Cold.\n");
+    }
+
+    #[test]
+    fn test_room_affects_replace_and_query() {
+        let zon = env::temp_dir().join(format!("raff-{}.zon", process::id()));
+        let wld = env::temp_dir().join(format!("raff-{}.wld", process::id()));
+        fs::write(&zon, "#99\nTest zone~\n9900 9999 10 2\nS\n$\n").unwrap();
+        fs::write(&wld, "#9900\nChapel~\nQuiet.\n~\n99 0 0\nS\n$~\n").unwrap();
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        db.load_zones(File::open(&zon).unwrap(), "raff-test")
+            .unwrap();
+        db.discrete_load(
+            &mut texts,
+            File::open(&wld).unwrap(),
+            DbBootMode::World,
+            "raff-test",
+        )
+        .unwrap();
+        fs::remove_file(&zon).unwrap();
+        fs::remove_file(&wld).unwrap();
+        assert!(db.world[0].affected.is_empty());
+
+        let wall = |dir: i16| RoomAffectedType {
+            _type: SPELL_WALL_OF_FORCE as i16,
+            duration: 3,
+            modifier: dir,
+            bitvector: RoomAffectFlags::WALL,
+        };
+        let consecrate = |bonus: i16| RoomAffectedType {
+            _type: SPELL_CONSECRATE as i16,
+            duration: 5,
+            modifier: bonus,
+            bitvector: RoomAffectFlags::CONSECRATED,
+        };
+        assert_eq!(db.room_regen_bonus(0), 0);
+        assert!(!db.room_affected(0, RoomAffectFlags::WALL));
+
+        affect_to_room(&mut db, 0, wall(0));
+        affect_to_room(&mut db, 0, wall(1));
+        affect_to_room(&mut db, 0, wall(0));
+        assert_eq!(db.world[0].affected.len(), 2);
+        assert!(db.exit_walled(0, 0));
+        assert!(db.exit_walled(0, 1));
+        assert!(!db.exit_walled(0, 2));
+
+        affect_to_room(&mut db, 0, consecrate(60));
+        affect_to_room(&mut db, 0, consecrate(90));
+        assert_eq!(db.world[0].affected.len(), 3);
+        assert_eq!(db.room_regen_bonus(0), 90);
+        assert!(!db.room_affected(0, RoomAffectFlags::FOG | RoomAffectFlags::SILENCE));
+        assert!(!db.room_affected(NOWHERE, RoomAffectFlags::WALL));
+    }
+
+    #[test]
+    fn test_zone_cmd_arguments_are_checked() {
+        assert!(check_zone_cmd(&zcmd('%', 100, 0)).is_ok());
+        assert!(check_zone_cmd(&zcmd('%', 0, 0)).is_err());
+        assert!(check_zone_cmd(&zcmd('H', 22, 3)).is_ok());
+        assert!(check_zone_cmd(&zcmd('H', 6, 24)).is_err());
+        assert!(check_zone_cmd(&zcmd('W', 2, 3)).is_ok());
+        assert!(check_zone_cmd(&zcmd('W', 3, 2)).is_err());
+        assert!(check_zone_cmd(&zcmd('L', 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_hour_windows_can_span_midnight() {
+        assert!(hour_in_window(6, 6, 18));
+        assert!(!hour_in_window(19, 6, 18));
+        assert!(hour_in_window(23, 21, 4));
+        assert!(hour_in_window(2, 21, 4));
+        assert!(!hour_in_window(12, 21, 4));
+    }
+}
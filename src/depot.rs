@@ -1,6 +1,6 @@
 //use std::marker::PhantomData;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
 pub struct DepotId {
     index: u32,
     seq: u32,
@@ -111,6 +111,13 @@ where
         std::mem::take(&mut self.slots[id.index as usize].value)
     }
 
+    /* Is id still a live item of this depot? */
+    pub fn contains(&self, id: DepotId) -> bool {
+        (id.index as usize) < self.slots.len()
+            && !self.slots[id.index as usize].free
+            && self.slots[id.index as usize].seq == id.seq
+    }
+
     pub fn get(&self, id: DepotId) -> &T {
         if id.index as usize >= self.slots.len() {
             panic!(
@@ -345,6 +352,24 @@ mod depot_tests {
         assert!(depot.is_empty());
     }
 
+    #[test]
+    fn test_depot_contains() {
+        let mut depot: Depot<TestItem> = Depot::default();
+        let id = depot.push(TestItem {
+            id: DepotId::default(),
+            value: 1,
+        });
+        assert!(depot.contains(id));
+        depot.take(id);
+        assert!(!depot.contains(id));
+        let id2 = depot.push(TestItem {
+            id: DepotId::default(),
+            value: 2,
+        });
+        assert!(depot.contains(id2));
+        assert!(!depot.contains(id));
+    }
+
     #[test]
     fn test_depot_get_mut() {
         let mut depot: Depot<TestItem> = Depot::default();
@@ -29,7 +29,11 @@ use crate::handler::{
 };
 use crate::limits::gain_exp;
 use crate::mobact::{forget, remember};
-use crate::screen::{C_CMP, C_SPR, KNRM, KNUL, KRED, KYEL};
+use crate::quest::quest_kill;
+use crate::screen::{
+    collapse_repeats, screen_reader, MsgCategory, C_SPR, KNRM, KNUL, KRED, MARKUP_NRM,
+};
+use crate::scripts::{death_mtrigger, fight_mtrigger};
 use crate::shops::ok_damage_shopkeeper;
 use crate::spells::{
     AttackHitType, SKILL_BACKSTAB, SPELL_INVISIBLE, SPELL_SLEEP, TYPE_HIT, TYPE_SUFFERING,
@@ -42,10 +46,8 @@ use crate::structs::{
     PULSE_VIOLENCE, WEAR_WIELD,
 };
 use crate::util::{dice, rand_number, stop_follower, DisplayMode};
-use crate::{
-    _clrlevel, clr, Game, CCNRM, CCRED, CCYEL, TO_CHAR, TO_NOTVICT, TO_ROOM, TO_SLEEP, TO_VICT,
-};
-use crate::{act, send_to_char, send_to_room, DescriptorData, TextData, VictimRef};
+use crate::{_clrlevel, clr, Game, CCNRM, CCRED, TO_CHAR, TO_NOTVICT, TO_ROOM, TO_SLEEP, TO_VICT};
+use crate::{act, act_msg, send_msg_to_room, send_to_char, DescriptorData, TextData, VictimRef};
 
 /* Weapon attack texts */
 pub const ATTACK_HIT_TEXT: [AttackHitType; 15] = [
@@ -132,11 +134,12 @@ pub fn appear(
     ch.remove_aff_flags(AffectFlags::INVISIBLE | AffectFlags::HIDE);
     let ch = chars.get(chid);
     if ch.get_level() < LVL_IMMORT {
-        act(
+        act_msg(
             descs,
             chars,
             db,
-            "$n slowly fades into existence.",
+            "fight.appear",
+            &[],
             false,
             Some(ch),
             None,
@@ -144,11 +147,12 @@ pub fn appear(
             TO_ROOM,
         );
     } else {
-        act(
+        act_msg(
             descs,
             chars,
             db,
-            "You feel a strange presence as $n appears, seemingly from nowhere.",
+            "fight.appear_immortal",
+            &[],
             false,
             Some(ch),
             None,
@@ -290,11 +294,7 @@ pub fn check_killer(
     let ch = chars.get_mut(chid);
     ch.set_plr_flag_bit(PLR_KILLER);
 
-    send_to_char(
-        &mut game.descriptors,
-        ch,
-        "If you want to be a PLAYER KILLER, so be it...\r\n",
-    );
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "fight.player_killer"));
     let ch = chars.get(chid);
     let vict = chars.get(vict_id);
     game.mudlog(
@@ -468,11 +468,12 @@ pub fn death_cry(
     db: &DB,
     ch: &CharData,
 ) {
-    act(
+    act_msg(
         descs,
         chars,
         db,
-        "Your blood freezes as you hear $n's death cry.",
+        "fight.death_cry",
+        &[],
         false,
         Some(ch),
         None,
@@ -483,13 +484,7 @@ pub fn death_cry(
     for door in 0..NUM_OF_DIRS {
         if db.can_go(ch, door) {
             if let Some(dir_option) = &db.world[ch_in_room as usize].dir_option[door] {
-                send_to_room(
-                    descs,
-                    chars,
-                    db,
-                    dir_option.to_room,
-                    "Your blood freezes as you hear someone's death cry.\r\n",
-                );
+                send_msg_to_room(descs, chars, db, dir_option.to_room, "fight.death_cry_near");
             } else {
                 error!(
                     "Failed to send death cry from room {}, door {}",
@@ -525,20 +520,26 @@ pub fn raw_kill(
     db.extract_char(chars, chid);
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn die(
     chid: DepotId,
+    killer: Option<DepotId>,
     game: &mut Game,
     chars: &mut Depot<CharData>,
     db: &mut DB,
     texts: &mut Depot<TextData>,
     objs: &mut Depot<ObjData>,
 ) {
+    death_mtrigger(game, db, chars, texts, objs, chid, killer);
     let ch = chars.get(chid);
     gain_exp(chid, -(ch.get_exp() / 2), game, chars, db, texts, objs);
     let ch = chars.get_mut(chid);
     if !ch.is_npc() {
         ch.remove_plr_flag(PLR_KILLER | PLR_THIEF);
     }
+    if let Some(killer) = killer {
+        quest_kill(game, chars, db, killer, chid);
+    }
     raw_kill(&mut game.descriptors, chars, db, objs, chid);
 }
 
@@ -560,18 +561,10 @@ pub fn perform_group_gain(
         send_to_char(
             &mut game.descriptors,
             ch,
-            format!(
-                "You receive your share of experience -- {} points.\r\n",
-                share
-            )
-            .as_str(),
+            &db.msgf(ch, "fight.group_exp", &[&share]),
         );
     } else {
-        send_to_char(
-            &mut game.descriptors,
-            ch,
-            "You receive your share of experience -- one measly little point!\r\n",
-        );
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "fight.group_exp_one"));
     }
     gain_exp(chid, share, game, chars, db, texts, objs);
     change_alignment(chars, chid, victim_id);
@@ -661,47 +654,15 @@ pub fn solo_gain(
         send_to_char(
             &mut game.descriptors,
             ch,
-            format!("You receive {} experience points.\r\n", exp).as_str(),
+            &db.msgf(ch, "fight.exp", &[&exp]),
         );
     } else {
-        send_to_char(
-            &mut game.descriptors,
-            ch,
-            "You receive one lousy experience point.\r\n",
-        );
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "fight.exp_one"));
     }
     gain_exp(chid, exp, game, chars, db, texts, objs);
     change_alignment(chars, chid, victim_id);
 }
 
-pub fn replace_string(str: &str, weapon_singular: &str, weapon_plural: &str) -> String {
-    let mut buf = String::new();
-
-    let mut iter = str.chars();
-    loop {
-        match iter.next() {
-            None => {
-                break;
-            }
-            Some('#') => match iter.next() {
-                Some('W') => {
-                    buf.push_str(weapon_plural);
-                }
-                Some('w') => {
-                    buf.push_str(weapon_singular);
-                }
-                _ => {
-                    buf.push('#');
-                }
-            },
-            Some(c) => {
-                buf.push(c);
-            }
-        }
-    } /* For */
-    buf
-}
-
 /* message for doing damage with a weapon */
 pub fn dam_message(
     descs: &mut Depot<DescriptorData>,
@@ -712,60 +673,6 @@ pub fn dam_message(
     victim: &CharData,
     mut w_type: i32,
 ) {
-    struct DamWeaponType {
-        to_room: &'static str,
-        to_char: &'static str,
-        to_victim: &'static str,
-    }
-    const DAM_WEAPONS: [DamWeaponType; 9] = [
-        /* use #w for singular (i.e. "slash") and #W for plural (i.e. "slashes") */
-        DamWeaponType {
-            to_room: "$n tries to #w $N, but misses.",
-            to_char: "You try to #w $N, but miss.",
-            to_victim: "$n tries to #w you, but misses.",
-        }, /* 0: 0     */
-        DamWeaponType {
-            to_room: "$n tickles $N as $e #W $M.",
-            to_char: "You tickle $N as you #w $M.",
-            to_victim: "$n tickles you as $e #W you.",
-        }, /* 1: 1..2  */
-        DamWeaponType {
-            to_room: "$n barely #W $N.",
-            to_char: "You barely #w $N.",
-            to_victim: "$n barely #W you.",
-        }, /* 2: 3..4  */
-        DamWeaponType {
-            to_room: "$n #W $N.",
-            to_char: "You #w $N.",
-            to_victim: "$n #W you.",
-        }, /* 3: 5..6  */
-        DamWeaponType {
-            to_room: "$n #W $N hard.",
-            to_char: "You #w $N hard.",
-            to_victim: "$n #W you hard.",
-        }, /* 4: 7..10  */
-        DamWeaponType {
-            to_room: "$n #W $N very hard.",
-            to_char: "You #w $N very hard.",
-            to_victim: "$n #W you very hard.",
-        }, /* 5: 11..14  */
-        DamWeaponType {
-            to_room: "$n #W $N extremely hard.",
-            to_char: "You #w $N extremely hard.",
-            to_victim: "$n #W you extremely hard.",
-        }, /* 6: 15..19  */
-        DamWeaponType {
-            to_room: "$n massacres $N to small fragments with $s #w.",
-            to_char: "You massacre $N to small fragments with your #w.",
-            to_victim: "$n massacres you to small fragments with $s #w.",
-        }, /* 7: 19..23 */
-        DamWeaponType {
-            to_room: "$n OBLITERATES $N with $s deadly #w!!",
-            to_char: "You OBLITERATE $N with your deadly #w!!",
-            to_victim: "$n OBLITERATES you with $s deadly #w!!",
-        }, /* 8: > 23   */
-    ];
-
     w_type -= TYPE_HIT; /* Change to base of table with text */
     let w_type = w_type as usize;
     let msgnum;
@@ -789,17 +696,22 @@ pub fn dam_message(
         msgnum = 8
     };
 
+    /*
+     * The messages are "dam.<msgnum>.room", ".char" and ".victim" in the
+     * catalogs, with the singular (i.e. "slash") and the plural (i.e.
+     * "slashes") of the attack as arguments.
+     */
+    let singular = format!("attack.{}", ATTACK_HIT_TEXT[w_type].singular);
+    let plural = format!("attack.{}", ATTACK_HIT_TEXT[w_type].plural);
+    let attack = [singular.as_str(), plural.as_str()];
+
     /* damage message to onlookers */
-    let buf = replace_string(
-        DAM_WEAPONS[msgnum].to_room,
-        ATTACK_HIT_TEXT[w_type].singular,
-        ATTACK_HIT_TEXT[w_type].plural,
-    );
-    act(
+    act_msg(
         descs,
         chars,
         db,
-        &buf,
+        &format!("dam.{}.room", msgnum),
+        &attack,
         false,
         Some(ch),
         None,
@@ -807,45 +719,43 @@ pub fn dam_message(
         TO_NOTVICT,
     );
 
+    let (hit, taken) = if msgnum == 0 {
+        (MsgCategory::CombatMiss, MsgCategory::CombatMiss)
+    } else {
+        (MsgCategory::CombatHit, MsgCategory::DamageTaken)
+    };
+
     /* damage message to damager */
-    send_to_char(descs, ch, CCYEL!(ch, C_CMP));
-    let buf = replace_string(
-        DAM_WEAPONS[msgnum].to_char,
-        ATTACK_HIT_TEXT[w_type].singular,
-        ATTACK_HIT_TEXT[w_type].plural,
-    );
-    act(
+    send_to_char(descs, ch, hit.tag());
+    act_msg(
         descs,
         chars,
         db,
-        &buf,
+        &format!("dam.{}.char", msgnum),
+        &attack,
         false,
         Some(ch),
         None,
         Some(VictimRef::Char(victim)),
         TO_CHAR,
     );
-    send_to_char(descs, ch, CCNRM!(ch, C_CMP));
+    send_to_char(descs, ch, MARKUP_NRM);
 
     /* damage message to damagee */
-    send_to_char(descs, victim, CCRED!(victim, C_CMP));
-    let buf = replace_string(
-        DAM_WEAPONS[msgnum].to_victim,
-        ATTACK_HIT_TEXT[w_type].singular,
-        ATTACK_HIT_TEXT[w_type].plural,
-    );
-    act(
+    send_to_char(descs, victim, taken.tag());
+    act_msg(
         descs,
         chars,
         db,
-        &buf,
+        &format!("dam.{}.victim", msgnum),
+        &attack,
         false,
         Some(ch),
         None,
         Some(VictimRef::Char(victim)),
         TO_VICT | TO_SLEEP,
     );
-    send_to_char(descs, victim, CCNRM!(victim, C_CMP));
+    send_to_char(descs, victim, MARKUP_NRM);
 }
 
 /*
@@ -920,7 +830,7 @@ pub fn skill_message(
                         &db.fight_messages[i].messages[nr].die_msg.victim_msg;
                     let room_msg: &Rc<str> = &db.fight_messages[i].messages[nr].die_msg.room_msg;
                     if !attacker_msg.is_empty() {
-                        send_to_char(descs, ch, CCYEL!(ch, C_CMP));
+                        send_to_char(descs, ch, MsgCategory::CombatHit.tag());
                         act(
                             descs,
                             chars,
@@ -932,9 +842,9 @@ pub fn skill_message(
                             Some(VictimRef::Char(vict)),
                             TO_CHAR,
                         );
-                        send_to_char(descs, ch, CCNRM!(ch, C_CMP));
+                        send_to_char(descs, ch, MARKUP_NRM);
                     }
-                    send_to_char(descs, vict, CCRED!(vict, C_CMP));
+                    send_to_char(descs, vict, MsgCategory::DamageTaken.tag());
                     act(
                         descs,
                         chars,
@@ -946,7 +856,7 @@ pub fn skill_message(
                         Some(VictimRef::Char(vict)),
                         TO_VICT | TO_SLEEP,
                     );
-                    send_to_char(descs, vict, CCNRM!(vict, C_CMP));
+                    send_to_char(descs, vict, MARKUP_NRM);
 
                     act(
                         descs,
@@ -966,7 +876,7 @@ pub fn skill_message(
                         &db.fight_messages[i].messages[nr].hit_msg.victim_msg;
                     let room_msg: &Rc<str> = &db.fight_messages[i].messages[nr].hit_msg.room_msg;
                     if !attacker_msg.is_empty() {
-                        send_to_char(descs, ch, CCYEL!(ch, C_CMP));
+                        send_to_char(descs, ch, MsgCategory::CombatHit.tag());
                         act(
                             descs,
                             chars,
@@ -978,9 +888,9 @@ pub fn skill_message(
                             Some(VictimRef::Char(vict)),
                             TO_CHAR,
                         );
-                        send_to_char(descs, ch, CCNRM!(ch, C_CMP));
+                        send_to_char(descs, ch, MARKUP_NRM);
                     }
-                    send_to_char(descs, vict, CCRED!(vict, C_CMP));
+                    send_to_char(descs, vict, MsgCategory::DamageTaken.tag());
                     act(
                         descs,
                         chars,
@@ -992,7 +902,7 @@ pub fn skill_message(
                         Some(VictimRef::Char(vict)),
                         TO_VICT | TO_SLEEP,
                     );
-                    send_to_char(descs, vict, CCNRM!(vict, C_CMP));
+                    send_to_char(descs, vict, MARKUP_NRM);
 
                     act(
                         descs,
@@ -1013,7 +923,7 @@ pub fn skill_message(
                 let room_msg: &Rc<str> = &db.fight_messages[i].messages[nr].miss_msg.room_msg;
                 /* Dam == 0 */
                 if !attacker_msg.is_empty() {
-                    send_to_char(descs, ch, CCYEL!(ch, C_CMP));
+                    send_to_char(descs, ch, MsgCategory::CombatMiss.tag());
                     act(
                         descs,
                         chars,
@@ -1025,9 +935,9 @@ pub fn skill_message(
                         Some(VictimRef::Char(vict)),
                         TO_CHAR,
                     );
-                    send_to_char(descs, ch, CCNRM!(ch, C_CMP));
+                    send_to_char(descs, ch, MARKUP_NRM);
                 }
-                send_to_char(descs, vict, CCRED!(vict, C_CMP));
+                send_to_char(descs, vict, MsgCategory::CombatMiss.tag());
                 act(
                     descs,
                     chars,
@@ -1039,7 +949,7 @@ pub fn skill_message(
                     Some(VictimRef::Char(vict)),
                     TO_VICT | TO_SLEEP,
                 );
-                send_to_char(descs, vict, CCNRM!(vict, C_CMP));
+                send_to_char(descs, vict, MARKUP_NRM);
 
                 act(
                     descs,
@@ -1093,17 +1003,13 @@ impl Game {
                 db.get_room_vnum(victim.in_room()),
                 ch.get_name()
             );
-            die(victim_id, self, chars, db, texts, objs);
+            die(victim_id, Some(chid), self, chars, db, texts, objs);
             return -1; /* -je, 7/7/92 */
         }
 
         /* peaceful rooms */
         if chid != victim_id && db.room_flagged(ch.in_room(), RoomFlags::PEACEFUL) {
-            send_to_char(
-                &mut self.descriptors,
-                ch,
-                "This room just has such a peaceful, easy feeling...\r\n",
-            );
+            send_to_char(&mut self.descriptors, ch, db.msg(ch, "fight.peaceful"));
             return 0;
         }
 
@@ -1246,11 +1152,12 @@ impl Game {
         let victim = chars.get(victim_id);
         match victim.get_pos() {
             Position::MortallyWounded => {
-                act(
+                act_msg(
                     &mut self.descriptors,
                     chars,
                     db,
-                    "$n is mortally wounded, and will die soon, if not aided.",
+                    "fight.mortally_wounded_room",
+                    &[],
                     true,
                     Some(victim),
                     None,
@@ -1260,16 +1167,17 @@ impl Game {
                 send_to_char(
                     &mut self.descriptors,
                     victim,
-                    "You are mortally wounded, and will die soon, if not aided.\r\n",
+                    db.msg(victim, "fight.mortally_wounded"),
                 );
             }
 
             Position::Incapacitated => {
-                act(
+                act_msg(
                     &mut self.descriptors,
                     chars,
                     db,
-                    "$n is incapacitated and will slowly die, if not aided.",
+                    "fight.incapacitated_room",
+                    &[],
                     true,
                     Some(victim),
                     None,
@@ -1279,15 +1187,16 @@ impl Game {
                 send_to_char(
                     &mut self.descriptors,
                     victim,
-                    "You are incapacitated an will slowly die, if not aided.\r\n",
+                    db.msg(victim, "fight.incapacitated"),
                 );
             }
             Position::Stunned => {
-                act(
+                act_msg(
                     &mut self.descriptors,
                     chars,
                     db,
-                    "$n is stunned, but will probably regain consciousness again.",
+                    "fight.stunned_room",
+                    &[],
                     true,
                     Some(victim),
                     None,
@@ -1297,28 +1206,29 @@ impl Game {
                 send_to_char(
                     &mut self.descriptors,
                     victim,
-                    "You're stunned, but will probably regain consciousness again.\r\n",
+                    db.msg(victim, "fight.stunned"),
                 );
             }
             Position::Dead => {
-                act(
+                act_msg(
                     &mut self.descriptors,
                     chars,
                     db,
-                    "$n is dead!  R.I.P.",
+                    "fight.dead_room",
+                    &[],
                     false,
                     Some(victim),
                     None,
                     None,
                     TO_ROOM,
                 );
-                send_to_char(&mut self.descriptors, victim, "You are dead!  Sorry...\r\n");
+                send_to_char(&mut self.descriptors, victim, db.msg(victim, "fight.dead"));
             }
 
             _ => {
                 /* >= POSITION SLEEPING */
                 if dam > (victim.get_max_hit() / 4) as i32 {
-                    send_to_char(&mut self.descriptors, victim, "That really did HURT!\r\n");
+                    send_to_char(&mut self.descriptors, victim, db.msg(victim, "fight.hurt"));
                 }
                 let victim = chars.get(victim_id);
                 if victim.get_hit() < victim.get_max_hit() / 4 {
@@ -1326,8 +1236,9 @@ impl Game {
                         &mut self.descriptors,
                         victim,
                         format!(
-                            "{}You wish that your wounds would stop BLEEDING so much!{}\r\n",
+                            "{}{}{}\r\n",
                             CCRED!(victim, C_SPR),
+                            db.msg(victim, "fight.bleeding"),
                             CCNRM!(victim, C_SPR)
                         )
                         .as_str(),
@@ -1344,11 +1255,7 @@ impl Game {
                     && victim.get_hit() < victim.get_wimp_lev() as i16
                     && victim.get_hit() > 0
                 {
-                    send_to_char(
-                        &mut self.descriptors,
-                        victim,
-                        "You wimp out, and attempt to flee!\r\n",
-                    );
+                    send_to_char(&mut self.descriptors, victim, db.msg(victim, "fight.wimp"));
                     do_flee(self, db, chars, texts, objs, victim_id, "", 0, 0);
                 }
             }
@@ -1360,11 +1267,12 @@ impl Game {
             do_flee(self, db, chars, texts, objs, victim_id, "", 0, 0);
             let victim = chars.get(victim_id);
             if victim.fighting_id().is_none() {
-                act(
+                act_msg(
                     &mut self.descriptors,
                     chars,
                     db,
-                    "$n is rescued by divine forces.",
+                    "fight.rescued",
+                    &[],
                     false,
                     Some(victim),
                     None,
@@ -1417,7 +1325,7 @@ impl Game {
                     forget(chars, chid, victim_id);
                 }
             }
-            die(victim_id, self, chars, db, texts, objs);
+            die(victim_id, Some(chid), self, chars, db, texts, objs);
             return -1;
         }
         dam
@@ -1612,6 +1520,14 @@ impl Game {
             old_combat_list.push(*c);
         }
 
+        /* screen readers hear each line of the round once, with a count */
+        let rounds = self
+            .descriptor_list
+            .iter()
+            .filter(|&&d_id| self.desc(d_id).player(chars).is_some_and(screen_reader))
+            .map(|&d_id| (d_id, self.desc(d_id).output.len()))
+            .collect::<Vec<_>>();
+
         for chid in old_combat_list.into_iter() {
             //next_combat_list = ch->next_fighting;
             let ch = chars.get(chid);
@@ -1636,11 +1552,12 @@ impl Game {
                 if ch.get_pos() < Position::Fighting {
                     ch.set_pos(Position::Fighting);
                     let ch = chars.get(chid);
-                    act(
+                    act_msg(
                         &mut self.descriptors,
                         chars,
                         db,
-                        "$n scrambles to $s feet!",
+                        "fight.scramble",
+                        &[],
                         true,
                         Some(ch),
                         None,
@@ -1651,11 +1568,7 @@ impl Game {
             }
             let ch = chars.get(chid);
             if ch.get_pos() < Position::Fighting {
-                send_to_char(
-                    &mut self.descriptors,
-                    ch,
-                    "You can't fight while sitting!!\r\n",
-                );
+                send_to_char(&mut self.descriptors, ch, db.msg(ch, "fight.sitting"));
                 continue;
             }
 
@@ -1685,6 +1598,16 @@ impl Game {
                     );
                 }
             }
+            fight_mtrigger(self, db, chars, texts, objs, chid);
+        }
+
+        for (d_id, start) in rounds {
+            let d = self.desc_mut(d_id);
+            if d.output.len() > start {
+                let round = collapse_repeats(&d.output[start..]);
+                d.output.truncate(start);
+                d.output.extend_from_slice(&round);
+            }
         }
     }
 }
@@ -0,0 +1,233 @@
+/* ************************************************************************
+*   File: friends.rs                                    Part of CircleMUD *
+*  Usage: Friends lists, and telling friends when one comes or goes       *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+use chrono::{TimeZone, Utc};
+
+use crate::clan::{find_player, member_name};
+use crate::db::DB;
+use crate::depot::{Depot, DepotId};
+use crate::interpreter::one_argument;
+use crate::structs::ConState::ConPlaying;
+use crate::structs::{CharData, CharFileU, PrefFlags};
+use crate::util::{SECS_PER_MUD_HOUR, SECS_PER_REAL_MIN};
+use crate::{send_to_char, Game, ObjData, TextData};
+
+pub const MAX_FRIENDS: usize = 25;
+
+/*
+ * Tell those who count ch among their friends that ch came in or left,
+ * unless ch would rather keep it private.
+ */
+pub fn friends_notify(game: &mut Game, chars: &Depot<CharData>, chid: DepotId, arrived: bool) {
+    let ch = chars.get(chid);
+    if ch.is_npc() || ch.prf_flagged(PrefFlags::PRIVACY) {
+        return;
+    }
+    let msg = format!(
+        "Your friend {} has {}.\r\n",
+        ch.get_name(),
+        if arrived { "come in" } else { "left" }
+    );
+    for d_id in game.descriptor_list.clone() {
+        let d = game.desc(d_id);
+        let Some(ic_id) = d.character else {
+            continue;
+        };
+        let ic = chars.get(ic_id);
+        if d.state() != ConPlaying || ic_id == chid || !friend_hears(ch, ic) {
+            continue;
+        }
+        send_to_char(&mut game.descriptors, ic, &msg);
+    }
+}
+
+/* Does ic count ch among their friends, and may ic see ch come and go? */
+fn friend_hears(ch: &CharData, ic: &CharData) -> bool {
+    ic.player_specials.friends.contains(&ch.get_idnum())
+        && ch.get_invis_lev() <= ic.get_level() as i16
+}
+
+#[derive(Debug, PartialEq)]
+enum FriendChange {
+    Added,
+    Removed,
+    Full,
+}
+
+/* Put idnum on the list, or take it off if it is already there */
+fn friend_toggle(friends: &mut Vec<i64>, idnum: i64) -> FriendChange {
+    if friends.contains(&idnum) {
+        friends.retain(|&id| id != idnum);
+        return FriendChange::Removed;
+    }
+    if friends.len() >= MAX_FRIENDS {
+        return FriendChange::Full;
+    }
+    friends.push(idnum);
+    FriendChange::Added
+}
+
+/* What the friends list says about a friend */
+fn friend_status(db: &mut DB, chars: &Depot<CharData>, ch: &CharData, idnum: i64) -> String {
+    if let Some(f) = find_player(db, chars, idnum).map(|id| chars.get(id)) {
+        if f.prf_flagged(PrefFlags::PRIVACY) {
+            return "private".to_string();
+        }
+        if f.get_invis_lev() <= ch.get_level() as i16 {
+            if f.desc.is_none() {
+                return "online, linkless".to_string();
+            }
+            let idle = f.char_specials.timer * SECS_PER_MUD_HOUR as i32 / SECS_PER_REAL_MIN as i32;
+            return if idle > 0 {
+                format!("online, idle {} min", idle)
+            } else {
+                "online".to_string()
+            };
+        }
+    }
+    let Some(name) = db.get_name_by_id(idnum).map(str::to_string) else {
+        return "no longer exists".to_string();
+    };
+    let mut chdata = CharFileU::default();
+    if db.load_char(&name, &mut chdata).is_none() {
+        return "no longer exists".to_string();
+    }
+    let pref = chdata.player_specials_saved.pref;
+    if pref.intersects(PrefFlags::PRIVACY) {
+        return "private".to_string();
+    }
+    Utc.timestamp_opt(chdata.last_logon as i64, 0)
+        .single()
+        .map_or_else(
+            || "offline".to_string(),
+            |t| format!("last seen {}", t.format("%b %e %Y")),
+        )
+}
+
+fn friend_list(game: &mut Game, db: &mut DB, chars: &Depot<CharData>, chid: DepotId) {
+    let ch = chars.get(chid);
+    if ch.player_specials.friends.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You haven't made any friends yet.\r\n",
+        );
+        return;
+    }
+    let mut buf = String::from("Your friends:\r\n");
+    for &idnum in &ch.player_specials.friends {
+        let status = friend_status(db, chars, ch, idnum);
+        buf.push_str(&format!("  {:12} {}\r\n", member_name(db, idnum), status));
+    }
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_friend(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg = String::new();
+    one_argument(argument, &mut arg);
+    if arg.is_empty() {
+        friend_list(game, db, chars, chid);
+        return;
+    }
+    let idnum = db.get_id_by_name(&arg.to_lowercase());
+    if idnum < 0 {
+        send_to_char(&mut game.descriptors, ch, "There is no such player.\r\n");
+        return;
+    }
+    let name = member_name(db, idnum);
+    if idnum == ch.get_idnum() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You are already your own best friend.\r\n",
+        );
+        return;
+    }
+    let ch = chars.get_mut(chid);
+    let msg = match friend_toggle(&mut ch.player_specials.friends, idnum) {
+        FriendChange::Added => format!("{} is now on your friends list.\r\n", name),
+        FriendChange::Removed => format!("{} is no longer on your friends list.\r\n", name),
+        FriendChange::Full => format!("You can't have more than {} friends.\r\n", MAX_FRIENDS),
+    };
+    let ch = chars.get(chid);
+    send_to_char(&mut game.descriptors, ch, &msg);
+}
+
+#[cfg(test)]
+mod friends_tests {
+    use super::*;
+    use crate::prefs::{parse_prefs, write_prefs_to};
+
+    #[test]
+    fn test_friend_toggle() {
+        let mut friends = vec![];
+        assert_eq!(friend_toggle(&mut friends, 7), FriendChange::Added);
+        assert_eq!(friend_toggle(&mut friends, 9), FriendChange::Added);
+        assert_eq!(friends, vec![7, 9]);
+        assert_eq!(friend_toggle(&mut friends, 7), FriendChange::Removed);
+        assert_eq!(friends, vec![9]);
+
+        let mut friends: Vec<i64> = (1..=MAX_FRIENDS as i64).collect();
+        assert_eq!(friend_toggle(&mut friends, 100), FriendChange::Full);
+        assert_eq!(friends.len(), MAX_FRIENDS);
+        assert_eq!(friend_toggle(&mut friends, 3), FriendChange::Removed);
+        assert_eq!(friend_toggle(&mut friends, 100), FriendChange::Added);
+    }
+
+    #[test]
+    fn test_friends_survive_save() {
+        let mut ch = CharData::default();
+        for idnum in [4, 11, 2] {
+            friend_toggle(&mut ch.player_specials.friends, idnum);
+        }
+        friend_toggle(&mut ch.player_specials.friends, 11);
+        let mut buf = vec![];
+        write_prefs_to(&mut buf, &ch.player_specials).unwrap();
+        let mut other = CharData::default();
+        parse_prefs(buf.as_slice(), &mut other.player_specials);
+        assert_eq!(other.player_specials.friends, vec![4, 2]);
+    }
+
+    #[test]
+    fn test_friend_hears() {
+        let mut ch = CharData::default();
+        ch.set_idnum(5);
+        let mut friend = CharData::default();
+        friend.set_level(10);
+        friend.player_specials.friends = vec![5];
+        let mut stranger = CharData::default();
+        stranger.set_level(10);
+        stranger.player_specials.friends = vec![6];
+
+        assert!(friend_hears(&ch, &friend));
+        assert!(!friend_hears(&ch, &stranger));
+
+        ch.set_invis_lev(31);
+        assert!(!friend_hears(&ch, &friend));
+        friend.set_level(31);
+        assert!(friend_hears(&ch, &friend));
+    }
+}
@@ -9,17 +9,27 @@
 *  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
 ************************************************************************ */
 
+use std::cmp::max;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
 use log::error;
 
-use crate::constants::DIRS;
-use crate::db::DB;
+use crate::constants::{DIRS, ROOM_BITS, ROOM_BITS_COUNT, SECTOR_TYPES};
+use crate::db::{DB, MAP_DIR};
 use crate::depot::{Depot, DepotId, HasId};
 use crate::handler::{get_char_vis, FindFlags};
 use crate::interpreter::one_argument;
+use crate::modify::page_string;
 use crate::spells::SKILL_TRACK;
-use crate::structs::{AffectFlags, ExitFlags, RoomFlags, RoomRnum, NOWHERE, NUM_OF_DIRS};
-use crate::util::{hmhr, rand_number, BFS_ALREADY_THERE, BFS_ERROR, BFS_NO_PATH};
-use crate::{send_to_char, CharData, Game, ObjData, TextData};
+use crate::structs::{AffectFlags, ExitFlags, RoomFlags, RoomRnum, LVL_GOD, NOWHERE, NUM_OF_DIRS};
+use crate::util::{
+    can_see, hmhr, rand_number, DisplayMode, BFS_ALREADY_THERE, BFS_ERROR, BFS_NO_PATH,
+};
+use crate::worldcheck::json_string;
+use crate::{send_to_char, CharData, DescriptorData, Game, ObjData, TextData};
 
 struct BfsQueueStruct {
     room: RoomRnum,
@@ -55,7 +65,7 @@ fn is_closed(db: &DB, x: RoomRnum, y: usize) -> bool {
 }
 
 fn valid_edge(game: &Game, db: &DB, x: RoomRnum, y: usize) -> bool {
-    if db.world[x as usize].dir_option[y].is_none() || toroom(db, x, y) == NOWHERE {
+    if !has_edge(db, x, y) {
         return false;
     }
     if !game.config.track_through_doors && is_closed(db, x, y) {
@@ -148,6 +158,228 @@ fn find_first_step(game: &Game, db: &mut DB, src: RoomRnum, target: RoomRnum) ->
     BFS_NO_PATH
 }
 
+/*
+ * Map export: the exit graph of a zone, or of the whole world, written as
+ * Graphviz DOT (for pictures) and as JSON (for other tools).  Door states
+ * are the current ones, so a map exported from a running game shows which
+ * doors are closed or locked right now.
+ */
+
+/* DOT fill colors, indexed by sector type. */
+const SECTOR_COLORS: [&str; 10] = [
+    "lightgray",
+    "gray80",
+    "palegreen",
+    "darkseagreen",
+    "khaki",
+    "burlywood",
+    "lightblue",
+    "lightskyblue",
+    "white",
+    "cadetblue",
+];
+
+/* Is there an exit from room x in direction y which leads anywhere? */
+fn has_edge(db: &DB, x: RoomRnum, y: usize) -> bool {
+    db.world[x as usize].dir_option[y].is_some() && toroom(db, x, y) != NOWHERE
+}
+
+/* Rooms of the zone with real number zone, or of the world for None. */
+fn map_rooms(db: &DB, zone: Option<usize>) -> Vec<RoomRnum> {
+    (0..db.world.len())
+        .filter(|&r| zone.is_none_or(|z| db.world[r].zone as usize == z))
+        .map(|r| r as RoomRnum)
+        .collect()
+}
+
+fn door_state(exit_info: ExitFlags) -> &'static str {
+    if !exit_info.contains(ExitFlags::ISDOOR) {
+        "none"
+    } else if exit_info.contains(ExitFlags::LOCKED) {
+        "locked"
+    } else if exit_info.contains(ExitFlags::CLOSED) {
+        "closed"
+    } else {
+        "open"
+    }
+}
+
+fn room_flag_names(flags: RoomFlags) -> Vec<&'static str> {
+    (0..ROOM_BITS_COUNT)
+        .filter(|&i| flags.bits() & (1 << i) != 0 && ROOM_BITS[i] != "*")
+        .map(|i| ROOM_BITS[i])
+        .collect()
+}
+
+fn dot_string(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+pub fn write_map_dot(db: &DB, zone: Option<usize>, out: &mut dyn Write) -> io::Result<()> {
+    let rooms = map_rooms(db, zone);
+    let name = zone.map_or("world".to_string(), |z| {
+        format!("zone {}", db.zone_table[z].number)
+    });
+
+    writeln!(out, "digraph {} {{", dot_string(&name))?;
+    writeln!(out, "  node [shape=box, style=filled];")?;
+    for (znr, z) in db.zone_table.iter().enumerate() {
+        if zone.is_some_and(|zone| zone != znr) {
+            continue;
+        }
+        writeln!(out, "  subgraph \"cluster_{}\" {{", z.number)?;
+        writeln!(
+            out,
+            "    label={};",
+            dot_string(&format!("#{} {}", z.number, z.name))
+        )?;
+        for &r in rooms
+            .iter()
+            .filter(|&&r| db.world[r as usize].zone as usize == znr)
+        {
+            let room = &db.world[r as usize];
+            writeln!(
+                out,
+                "    \"{}\" [label={}, fillcolor={}];",
+                room.number,
+                dot_string(&format!("#{}\n{}", room.number, room.name)),
+                SECTOR_COLORS[room.sector_type as usize]
+            )?;
+        }
+        writeln!(out, "  }}")?;
+    }
+
+    /* Rooms outside of the exported zone which its exits lead to. */
+    let mut outside = vec![];
+    for &r in &rooms {
+        for dir in 0..NUM_OF_DIRS {
+            if !has_edge(db, r, dir) {
+                continue;
+            }
+            let to = toroom(db, r, dir);
+            if zone.is_some_and(|z| db.world[to as usize].zone as usize != z)
+                && !outside.contains(&to)
+            {
+                outside.push(to);
+                writeln!(
+                    out,
+                    "  \"{}\" [label=\"#{}\", style=dotted];",
+                    db.world[to as usize].number, db.world[to as usize].number
+                )?;
+            }
+        }
+    }
+
+    for &r in &rooms {
+        for (dir, exit) in db.world[r as usize].dir_option.iter().enumerate() {
+            let Some(exit) = exit.as_ref().filter(|_| has_edge(db, r, dir)) else {
+                continue;
+            };
+            let style = match door_state(exit.exit_info) {
+                "none" => "",
+                "open" => ", style=dashed",
+                "closed" => ", style=dashed, color=orange",
+                _ => ", style=dashed, color=red",
+            };
+            writeln!(
+                out,
+                "  \"{}\" -> \"{}\" [label=\"{}\"{}];",
+                db.world[r as usize].number,
+                db.world[toroom(db, r, dir) as usize].number,
+                DIRS[dir],
+                style
+            )?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+pub fn write_map_json(db: &DB, zone: Option<usize>, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    write!(out, "  \"zones\": [")?;
+    let mut first = true;
+    for (znr, z) in db.zone_table.iter().enumerate() {
+        if zone.is_some_and(|zone| zone != znr) {
+            continue;
+        }
+        write!(
+            out,
+            "{}\n    {{\"vnum\": {}, \"name\": {}, \"bot\": {}, \"top\": {}}}",
+            if first { "" } else { "," },
+            z.number,
+            json_string(&z.name),
+            z.bot,
+            z.top
+        )?;
+        first = false;
+    }
+    writeln!(out, "\n  ],")?;
+
+    write!(out, "  \"rooms\": [")?;
+    for (n, &r) in map_rooms(db, zone).iter().enumerate() {
+        let room = &db.world[r as usize];
+        let flags: Vec<String> = room_flag_names(room.room_flags)
+            .iter()
+            .map(|f| json_string(f))
+            .collect();
+        let mut exits = vec![];
+        for (dir, exit) in room.dir_option.iter().enumerate() {
+            let Some(exit) = exit else { continue };
+            let to = if has_edge(db, r, dir) {
+                db.world[exit.to_room as usize].number.to_string()
+            } else {
+                "null".to_string()
+            };
+            exits.push(format!(
+                "{{\"dir\": \"{}\", \"to\": {}, \"keyword\": {}, \"door\": \"{}\", \"key\": {}, \"pickproof\": {}}}",
+                DIRS[dir],
+                to,
+                json_string(&exit.keyword),
+                door_state(exit.exit_info),
+                exit.key,
+                exit.exit_info.contains(ExitFlags::PICKPROOF)
+            ));
+        }
+        write!(
+            out,
+            "{}\n    {{\"vnum\": {}, \"zone\": {}, \"name\": {}, \"sector\": {}, \"flags\": [{}], \"exits\": [{}]}}",
+            if n == 0 { "" } else { "," },
+            room.number,
+            db.zone_table[room.zone as usize].number,
+            json_string(&room.name),
+            json_string(SECTOR_TYPES[room.sector_type as usize]),
+            flags.join(", "),
+            exits.join(", ")
+        )?;
+    }
+    writeln!(out, "\n  ]")?;
+    writeln!(out, "}}")
+}
+
+/*
+ * Write <name>.dot and <name>.json below dir, where name is the zone
+ * number, or "world" for the whole world.  Returns the path without
+ * extension.
+ */
+pub fn export_map(db: &DB, zone: Option<usize>, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let base = dir.join(zone.map_or("world".to_string(), |z| db.zone_table[z].number.to_string()));
+
+    let mut dot = BufWriter::new(File::create(base.with_extension("dot"))?);
+    write_map_dot(db, zone, &mut dot)?;
+    dot.flush()?;
+    let mut json = BufWriter::new(File::create(base.with_extension("json"))?);
+    write_map_json(db, zone, &mut json)?;
+    json.flush()?;
+
+    Ok(base)
+}
+
 /********************************************************
 * Functions and Commands which use the above functions. *
 ********************************************************/
@@ -263,6 +495,269 @@ pub fn do_track(
     }
 }
 
+/*
+ * The automapper: a BFS out from the character's room, laying rooms on a
+ * grid by the compass direction they were reached through.  Up and down
+ * exits are only marked on the room, since the map is flat.  The search
+ * does not pass closed doors, nor rooms too dark for the character to see.
+ */
+
+/* Automap glyphs, indexed by sector type. */
+const SECTOR_GLYPHS: [char; 10] = ['.', ':', '"', '*', 'n', 'M', '~', '=', 'o', 'u'];
+
+const MAP_LEGEND: &str = "@ you  ! someone  ? too dark  # closed door  / open door  ^ up  v down  % up and down\r\n\
+                          . inside  : city  \" field  * forest  n hills  M mountains  ~ water  = deep water  o air  u underwater\r\n";
+
+/* Each room takes a glyph, an up/down marker and a connector. */
+const MAP_CELL_WIDTH: i32 = 3;
+const MAP_MAX_RADIUS_Y: i32 = 5;
+const MAP_MIN_WIDTH: i32 = 40; /* still shows a few rooms each way */
+
+/* Exits which are not drawn on the grid, only marked. */
+const UP: usize = 4;
+const DOWN: usize = 5;
+
+/* Grid offsets of the compass directions: north, east, south, west. */
+const MAP_DX: [i32; 4] = [0, 1, 0, -1];
+const MAP_DY: [i32; 4] = [-1, 0, 1, 0];
+
+fn map_connector(exit_info: ExitFlags, passage: char) -> char {
+    match door_state(exit_info) {
+        "none" => passage,
+        "open" => '/',
+        _ => '#',
+    }
+}
+
+pub fn render_map(
+    descs: &Depot<DescriptorData>,
+    chars: &Depot<CharData>,
+    db: &DB,
+    ch: &CharData,
+    radius_x: i32,
+    radius_y: i32,
+) -> String {
+    let (w, h) = (2 * radius_x + 1, 2 * radius_y + 1);
+    let cell = |x: i32, y: i32| ((y + radius_y) * w + x + radius_x) as usize;
+    let inside = |x: i32, y: i32| x.abs() <= radius_x && y.abs() <= radius_y;
+
+    let mut rooms: Vec<Option<RoomRnum>> = vec![None; (w * h) as usize];
+    /* Connectors east of and south of each cell. */
+    let mut east = vec![' '; (w * h) as usize];
+    let mut south = vec![' '; (w * h) as usize];
+    let mut seen = vec![false; db.world.len()];
+    let mut queue = VecDeque::new();
+
+    rooms[cell(0, 0)] = Some(ch.in_room());
+    seen[ch.in_room() as usize] = true;
+    queue.push_back((ch.in_room(), 0, 0));
+
+    while let Some((room, x, y)) = queue.pop_front() {
+        /* Rooms the character cannot see are drawn, but not looked past. */
+        if (x, y) != (0, 0) && db.is_dark(room) && !ch.can_see_in_dark() {
+            continue;
+        }
+        for dir in 0..MAP_DX.len() {
+            if !has_edge(db, room, dir) {
+                continue;
+            }
+            let exit = db.world[room as usize].dir_option[dir]
+                .as_ref()
+                .expect("No direction option");
+            let (nx, ny) = (x + MAP_DX[dir], y + MAP_DY[dir]);
+            match dir {
+                0 if inside(x, ny) => south[cell(x, ny)] = map_connector(exit.exit_info, '|'),
+                1 if inside(x, y) => east[cell(x, y)] = map_connector(exit.exit_info, '-'),
+                2 if inside(x, y) => south[cell(x, y)] = map_connector(exit.exit_info, '|'),
+                3 if inside(nx, y) => east[cell(nx, y)] = map_connector(exit.exit_info, '-'),
+                _ => {}
+            }
+            let to = toroom(db, room, dir);
+            if exit.exit_flagged(ExitFlags::CLOSED)
+                || seen[to as usize]
+                || !inside(nx, ny)
+                || rooms[cell(nx, ny)].is_some()
+            {
+                continue;
+            }
+            seen[to as usize] = true;
+            rooms[cell(nx, ny)] = Some(to);
+            queue.push_back((to, nx, ny));
+        }
+    }
+
+    /* Only draw the part of the grid which has rooms in it. */
+    let used = |x: i32, y: i32| rooms[cell(x, y)].is_some();
+    let xs: Vec<i32> = (-radius_x..=radius_x)
+        .filter(|&x| (-radius_y..=radius_y).any(|y| used(x, y)))
+        .collect();
+    let ys: Vec<i32> = (-radius_y..=radius_y)
+        .filter(|&y| (-radius_x..=radius_x).any(|x| used(x, y)))
+        .collect();
+
+    let mut buf = String::new();
+    for &y in &ys {
+        let mut line = String::new();
+        let mut below = String::new();
+        for &x in &xs {
+            let i = cell(x, y);
+            let Some(room) = rooms[i] else {
+                line.push_str("   ");
+                below.push_str("   ");
+                continue;
+            };
+            let dark = db.is_dark(room) && !ch.can_see_in_dark();
+            let glyph = if (x, y) == (0, 0) {
+                '@'
+            } else if dark {
+                '?'
+            } else if db.world[room as usize].peoples.iter().any(|&id| {
+                let other = chars.get(id);
+                other.id() != ch.id() && can_see(descs, chars, db, ch, other)
+            }) {
+                '!'
+            } else {
+                SECTOR_GLYPHS[db.world[room as usize].sector_type as usize]
+            };
+            let (up, down) = if dark {
+                (false, false)
+            } else {
+                (has_edge(db, room, UP), has_edge(db, room, DOWN))
+            };
+            line.push(glyph);
+            line.push(match (up, down) {
+                (true, true) => '%',
+                (true, false) => '^',
+                (false, true) => 'v',
+                (false, false) => ' ',
+            });
+            line.push(if x < radius_x { east[i] } else { ' ' });
+            below.push(if y < radius_y { south[i] } else { ' ' });
+            below.push_str("  ");
+        }
+        buf.push_str(line.trim_end());
+        buf.push_str("\r\n");
+        if below.trim_end().is_empty() {
+            continue;
+        }
+        buf.push_str(below.trim_end());
+        buf.push_str("\r\n");
+    }
+    buf
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_map(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.aff_flagged(AffectFlags::BLIND) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You can't see a damned thing, you're blind!\r\n",
+        );
+        return;
+    }
+
+    /* As wide as the screen allows, and as tall as fits in a page. */
+    let width = ch.get_screen_width().max(MAP_MIN_WIDTH);
+    let mut radius_x = (width / MAP_CELL_WIDTH - 1) / 2;
+    let mut radius_y = MAP_MAX_RADIUS_Y;
+    let mut arg = String::new();
+    one_argument(argument, &mut arg);
+    if !arg.is_empty() {
+        match arg.parse::<i32>() {
+            Ok(radius) if radius > 0 => {
+                radius_x = radius_x.min(radius);
+                radius_y = radius_y.min(radius);
+            }
+            _ => {
+                send_to_char(&mut game.descriptors, ch, "Usage: map [radius]\r\n");
+                return;
+            }
+        }
+    }
+
+    let Some(d_id) = ch.desc else {
+        return;
+    };
+    let mut buf = render_map(&game.descriptors, chars, db, ch, radius_x, radius_y);
+    buf.push_str("\r\n");
+    buf.push_str(MAP_LEGEND);
+    page_string(&mut game.descriptors, chars, d_id, &buf, true);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_mapexport(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    let mut arg = String::new();
+    one_argument(argument, &mut arg);
+
+    let zone = if arg.is_empty() || arg == "." {
+        Some(db.world[ch.in_room() as usize].zone as usize)
+    } else if arg == "*" {
+        None
+    } else if let Some(zone) = arg.parse::<i16>().ok().and_then(|v| db.real_zone(v)) {
+        Some(zone)
+    } else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Usage: mapexport [ . | * | <zone number> ]\r\n",
+        );
+        return;
+    };
+
+    match export_map(db, zone, Path::new(MAP_DIR)) {
+        Ok(base) => {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                format!(
+                    "Map written to {}.dot and {}.json.\r\n",
+                    base.display(),
+                    base.display()
+                )
+                .as_str(),
+            );
+            game.mudlog(
+                chars,
+                DisplayMode::Brief,
+                max(LVL_GOD as i32, ch.get_invis_lev() as i32),
+                true,
+                format!("(GC) {} exported map {}.", ch.get_name(), base.display()).as_str(),
+            );
+        }
+        Err(err) => {
+            error!("SYSERR: Unable to export map: {}", err);
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "Unable to write the map, see the log.\r\n",
+            );
+        }
+    }
+}
+
 // void hunt_victim(struct char_data *ch)
 // {
 // int dir;
@@ -296,3 +791,66 @@ pub fn do_track(
 // hit(ch, HUNTING(ch), TYPE_UNDEFINED);
 // }
 // }
+
+#[cfg(test)]
+mod graph_tests {
+    use super::*;
+    use crate::db::{boot_world, LoadType, BOOT_DIR_LOCK};
+
+    #[test]
+    fn test_door_state() {
+        assert_eq!(door_state(ExitFlags::empty()), "none");
+        assert_eq!(door_state(ExitFlags::ISDOOR), "open");
+        assert_eq!(door_state(ExitFlags::ISDOOR | ExitFlags::CLOSED), "closed");
+        assert_eq!(
+            door_state(ExitFlags::ISDOOR | ExitFlags::CLOSED | ExitFlags::LOCKED),
+            "locked"
+        );
+    }
+
+    #[test]
+    fn test_dot_string() {
+        assert_eq!(dot_string("#3001\nThe Temple"), "\"#3001\\nThe Temple\"");
+        assert_eq!(dot_string("a \"b\" \\"), "\"a \\\"b\\\" \\\\\"");
+    }
+
+    #[test]
+    fn test_room_flag_names() {
+        assert_eq!(
+            room_flag_names(RoomFlags::DARK | RoomFlags::INDOORS | RoomFlags::BFS_MARK),
+            vec!["DARK", "INDOORS"]
+        );
+    }
+
+    #[test]
+    fn test_render_map() {
+        let lib = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("lib");
+        let _lock = BOOT_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_current_dir(&lib).expect("Cannot change to library directory");
+        let mut game = Game::new();
+        let mut chars = Depot::default();
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        db.scheck = true;
+        db.mini_mud = true;
+        boot_world(&mut game, &mut db, &mut chars, &mut texts);
+
+        let chid = db
+            .read_mobile(&mut chars, 3001, LoadType::Virtual)
+            .expect("No mobile #3001");
+        chars.get_mut(chid).set_in_room(db.real_room(3014));
+        let map = render_map(&game.descriptors, &chars, &db, chars.get(chid), 3, 2);
+        let expected = [
+            "      . -.v-:",
+            "      |  |  |",
+            "   .  . -: -.^-.",
+            "   |  |  |  |  |",
+            ": -: -: -@ -: -: -:",
+            "|  |  |  |  |  |  |",
+            ": -: -: -: -.  . -.",
+            "|     |  |",
+            ":     .  ?",
+        ];
+        assert_eq!(map, expected.join("\r\n") + "\r\n");
+    }
+}
@@ -18,18 +18,18 @@ use log::error;
 
 use crate::act_wizard::do_return;
 use crate::class::invalid_class;
-use crate::config::MENU;
 use crate::db::DB;
 use crate::depot::{Depot, DepotId, HasId};
+use crate::friends::friends_notify;
 use crate::interpreter::one_argument;
 use crate::objsave::crash_delete_crashfile;
 use crate::spells::{SAVING_BREATH, SAVING_PARA, SAVING_PETRI, SAVING_ROD, SAVING_SPELL};
 use crate::structs::ConState::{ConClose, ConMenu};
 use crate::structs::{
     AffectFlags, AffectedType, ApplyType, CharData, ExtraDescrData, ExtraFlags, ItemType, MobRnum,
-    ObjData, ObjRnum, RoomFlags, RoomRnum, WearFlags, LVL_GRGOD, MAX_OBJ_AFFECT, MOB_NOTDEADYET,
-    NOTHING, NOWHERE, NUM_WEARS, PLR_CRASH, PLR_NOTDEADYET, WEAR_BODY, WEAR_HEAD, WEAR_LEGS,
-    WEAR_LIGHT,
+    ObjData, ObjRnum, RoomAffectFlags, RoomAffectedType, RoomFlags, RoomRnum, WearFlags, LVL_GRGOD,
+    MAX_OBJ_AFFECT, MOB_NOTDEADYET, NOTHING, NOWHERE, NUM_WEARS, PLR_CRASH, PLR_NOTDEADYET,
+    WEAR_BODY, WEAR_HEAD, WEAR_LEGS, WEAR_LIGHT,
 };
 use crate::util::{can_see, can_see_obj, die_follower, rand_number, SECS_PER_MUD_YEAR};
 use crate::{act, save_char, send_to_char, DescriptorData, Game, TextData, TO_CHAR, TO_ROOM};
@@ -336,6 +336,19 @@ pub fn affect_join(
     ch.affected = list;
     affect_to_char(objs, ch, af);
 }
+/*
+ * Put an affect on a room.  One already there from the same spell, on the
+ * same exit for walls, is replaced.
+ */
+pub fn affect_to_room(db: &mut DB, room: RoomRnum, af: RoomAffectedType) {
+    let affected = &mut db.world[room as usize].affected;
+    affected.retain(|old| {
+        old._type != af._type
+            || (af.bitvector.intersects(RoomAffectFlags::WALL) && old.modifier != af.modifier)
+    });
+    affected.push(af);
+}
+
 impl DB {
     /* move a player out of a room */
     pub fn char_from_room(&mut self, objs: &Depot<ObjData>, ch: &mut CharData) {
@@ -1013,9 +1026,10 @@ impl Game {
                         _ => {}
                     }
                 }
+                friends_notify(self, chars, chid, false);
                 let desc = self.desc_mut(ch_desc_id);
                 desc.set_state(ConMenu);
-                desc.write_to_output(MENU);
+                desc.write_to_output(db.msg(chars.get(chid), "nanny.menu"));
             }
         }
 
@@ -1210,7 +1224,7 @@ pub fn get_player_vis<'a>(
         if inroom == FindFlags::CHAR_ROOM && ch.in_room() != i.in_room() {
             continue;
         }
-        if i.player.name.as_ref() != name {
+        if !i.player.name.eq_ignore_ascii_case(name) {
             continue;
         }
         if !can_see(descs, chars, db, ch, i) {
@@ -35,7 +35,7 @@ pub const HOUSE_PRIVATE: i32 = 0;
 
 #[derive(Clone, Copy)]
 pub struct HouseControlRec {
-    vnum: RoomVnum,
+    pub(crate) vnum: RoomVnum,
     /* vnum of this house		*/
     atrium: RoomVnum,
     /* vnum of atrium		*/
@@ -53,7 +53,8 @@ pub struct HouseControlRec {
     /* idnums of house's guests	*/
     last_payment: u64,
     /* date of last house payment   */
-    _spare0: i64,
+    pub(crate) gold: i64,
+    /* coins in the house account   */
     _spare1: i64,
     _spare2: i64,
     _spare3: i64,
@@ -75,7 +76,7 @@ impl HouseControlRec {
             num_of_guests: 0,
             guests: [0; MAX_GUESTS],
             last_payment: 0,
-            _spare0: 0,
+            gold: 0,
             _spare1: 0,
             _spare2: 0,
             _spare3: 0,
@@ -297,8 +298,18 @@ fn find_house(db: &DB, vnum: RoomVnum) -> Option<usize> {
     db.house_control.iter().position(|hc| hc.vnum == vnum)
 }
 
+/* The house whose account a player shares: their own first, else one they are a guest in */
+pub fn find_house_account(db: &DB, idnum: i64) -> Option<usize> {
+    let houses = &db.house_control[0..db.num_of_houses];
+    houses.iter().position(|hc| hc.owner == idnum).or_else(|| {
+        houses
+            .iter()
+            .position(|hc| hc.guests[0..hc.num_of_guests as usize].contains(&idnum))
+    })
+}
+
 /* Save the house control information */
-fn house_save_control(db: &mut DB) {
+pub fn house_save_control(db: &mut DB) {
     let mut fl = match OpenOptions::new()
         .create(true)
         .truncate(true)
@@ -401,6 +412,20 @@ pub fn house_boot(db: &mut DB, objs: &mut Depot<ObjData>) {
     house_save_control(db);
 }
 
+/* Flag the houses and their atriums again after their rooms were rebuilt. */
+pub fn house_flag_rooms(db: &mut DB) {
+    for i in 0..db.num_of_houses {
+        let house = db.real_room(db.house_control[i].vnum);
+        if house != NOWHERE {
+            db.set_room_flags_bit(house, RoomFlags::HOUSE | RoomFlags::PRIVATE);
+        }
+        let atrium = db.real_room(db.house_control[i].atrium);
+        if atrium != NOWHERE {
+            db.set_room_flags_bit(atrium, RoomFlags::ATRIUM);
+        }
+    }
+}
+
 /* "House Control" functions */
 
 const HCONTROL_FORMAT: &str =
@@ -570,7 +595,7 @@ fn hcontrol_build_house(
         num_of_guests: 0,
         guests: [0; MAX_GUESTS],
         last_payment: 0,
-        _spare0: 0,
+        gold: 0,
         _spare1: 0,
         _spare2: 0,
         _spare3: 0,
@@ -21,9 +21,9 @@ use crate::act_comm::{
     do_gen_comm, do_gsay, do_page, do_qcomm, do_reply, do_say, do_spec_comm, do_tell, do_write,
 };
 use crate::act_informative::{
-    do_color, do_commands, do_consider, do_diagnose, do_equipment, do_examine, do_exits, do_gen_ps,
-    do_gold, do_help, do_inventory, do_levels, do_look, do_score, do_time, do_toggle, do_users,
-    do_weather, do_where, do_who, look_at_room,
+    do_color, do_colorset, do_commands, do_consider, do_diagnose, do_equipment, do_examine,
+    do_exits, do_gen_ps, do_gold, do_help, do_inventory, do_levels, do_look, do_score, do_time,
+    do_toggle, do_users, do_weather, do_where, do_who, look_at_room,
 };
 use crate::act_item::{
     do_drink, do_drop, do_eat, do_get, do_give, do_grab, do_pour, do_put, do_remove, do_wear,
@@ -37,9 +37,9 @@ use crate::act_offensive::{
     do_assist, do_backstab, do_bash, do_flee, do_hit, do_kick, do_kill, do_order, do_rescue,
 };
 use crate::act_other::{
-    do_display, do_gen_tog, do_gen_write, do_group, do_hide, do_not_here, do_practice, do_quit,
-    do_report, do_save, do_sneak, do_split, do_steal, do_title, do_ungroup, do_use, do_visible,
-    do_wimpy,
+    do_display, do_gen_tog, do_gen_write, do_group, do_hide, do_not_here, do_practice, do_prompt,
+    do_quit, do_report, do_save, do_sneak, do_split, do_steal, do_title, do_ungroup, do_use,
+    do_visible, do_width, do_wimpy,
 };
 use crate::act_social::{do_action, do_insult};
 use crate::act_wizard::{
@@ -49,21 +49,30 @@ use crate::act_wizard::{
     do_wiznet, do_wizutil, do_zreset,
 };
 use crate::alias::{delete_aliases, read_aliases};
+use crate::auction::{auction_login, do_market};
 use crate::ban::{do_ban, do_unban, isbanned, valid_name};
-use crate::class::{do_start, parse_class, CLASS_MENU};
-use crate::config::{MAX_BAD_PWS, MENU, START_MESSG, WELC_MESSG};
+use crate::bank::{delete_ledger, do_audit};
+use crate::channels::{channel_allowed, channel_talk, do_channel, find_channel};
+use crate::clan::{do_ccontrol, do_clan};
+use crate::class::{do_start, parse_class};
+use crate::config::MAX_BAD_PWS;
 use crate::db::{clear_char, do_reboot, reset_char, store_to_char, BanType};
 use crate::depot::{Depot, DepotId, HasId};
-use crate::graph::do_track;
+use crate::friends::{do_friend, friends_notify};
+use crate::graph::{do_map, do_mapexport, do_track};
 use crate::house::{do_hcontrol, do_house};
+use crate::lang::{do_language, fill_args, DEFAULT_LANG};
 use crate::modify::{do_skillset, page_string};
 use crate::objsave::{crash_delete_file, crash_load};
+use crate::prefs::{delete_prefs, read_prefs, write_prefs};
+use crate::quest::{delete_quests, do_quest, read_quests};
 use crate::screen::{C_SPR, KNRM, KNUL, KRED};
+use crate::scripts::command_trigger;
 use crate::spell_parser::do_cast;
 use crate::structs::ConState::{
     ConChpwdGetnew, ConChpwdGetold, ConChpwdVrfy, ConClose, ConCnfpasswd, ConDelcnf2,
-    ConDisconnect, ConGetName, ConMenu, ConNameCnfrm, ConNewpasswd, ConPassword, ConQclass,
-    ConQsex, ConRmotd,
+    ConDisconnect, ConGetName, ConLanguage, ConMenu, ConNameCnfrm, ConNewpasswd, ConPassword,
+    ConQclass, ConQlang, ConQsex, ConRmotd,
 };
 use crate::structs::ConState::{ConDelcnf1, ConExdesc, ConPlaying};
 use crate::structs::{
@@ -75,10 +84,12 @@ use crate::structs::{
     CharFileU, EXDSCR_LENGTH, LVL_IMMORT, MAX_NAME_LENGTH, MAX_PWD_LENGTH, PLR_CRYO, PLR_MAILING,
     PLR_WRITING,
 };
+use crate::tells::{do_ignore, tells_login};
 use crate::util::DisplayMode;
+use crate::zreload::do_zreload;
 use crate::{
-    _clrlevel, act, clr, save_char, send_to_char, write_to_q, Game, ObjData, TextData, CCNRM,
-    CCRED, DB, PLR_DELETED, TO_ROOM,
+    _clrlevel, act, act_msg, clr, save_char, send_to_char, write_to_q, DescriptorData, Game,
+    ObjData, TextData, CCNRM, CCRED, DB, PLR_DELETED, TO_ROOM,
 };
 
 /*
@@ -146,6 +157,9 @@ pub const SCMD_HOLYLIGHT: i32 = 13;
 pub const SCMD_SLOWNS: i32 = 14;
 pub const SCMD_AUTOEXIT: i32 = 15;
 pub const SCMD_TRACK: i32 = 16;
+pub const SCMD_NOCLAN: i32 = 17;
+pub const SCMD_SCREENREADER: i32 = 18;
+pub const SCMD_PRIVACY: i32 = 19;
 
 /* do_wizutil */
 pub const SCMD_REROLL: i32 = 0;
@@ -160,13 +174,6 @@ pub const SCMD_UNAFFECT: i32 = 6;
 pub const SCMD_WHISPER: i32 = 0;
 pub const SCMD_ASK: i32 = 1;
 
-/* do_gen_com */
-pub const SCMD_HOLLER: i32 = 0;
-pub const SCMD_SHOUT: i32 = 1;
-pub const SCMD_GOSSIP: i32 = 2;
-pub const SCMD_AUCTION: i32 = 3;
-pub const SCMD_GRATZ: i32 = 4;
-
 /* do_shutdown */
 pub const SCMD_SHUTDOW: i32 = 0;
 pub const SCMD_SHUTDOWN: i32 = 1;
@@ -286,7 +293,7 @@ pub fn do_nothing(
 ) {
 }
 
-pub const CMD_INFO: [CommandInfo; 308] = [
+pub const CMD_INFO: [CommandInfo; 327] = [
     CommandInfo {
         command: "",
         minimum_position: Position::Dead,
@@ -394,13 +401,21 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: SCMD_ASK,
     },
-    // { "auction"  , Position::Sleeping, do_gen_comm , 0, SCMD_AUCTION },
+    // { "auction"  , Position::Sleeping, do_gen_comm , 0, 0 },
     CommandInfo {
         command: "auction",
         minimum_position: Position::Sleeping,
         command_pointer: do_gen_comm,
         minimum_level: 0,
-        subcmd: SCMD_AUCTION,
+        subcmd: 0,
+    },
+    // { "audit"    , Position::Dead    , do_audit    , LVL_GOD, 0 },
+    CommandInfo {
+        command: "audit",
+        minimum_position: Position::Dead,
+        command_pointer: do_audit,
+        minimum_level: LVL_GOD,
+        subcmd: 0,
     },
     // { "autoexit" , Position::Dead    , do_gen_tog  , 0, SCMD_AUTOEXIT },
     CommandInfo {
@@ -540,6 +555,22 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
+    // { "ccontrol" , Position::Dead    , do_ccontrol , LVL_GRGOD, 0 },
+    CommandInfo {
+        command: "ccontrol",
+        minimum_position: Position::Dead,
+        command_pointer: do_ccontrol,
+        minimum_level: LVL_GRGOD,
+        subcmd: 0,
+    },
+    // { "channel"  , Position::Dead    , do_channel  , 0, 0 },
+    CommandInfo {
+        command: "channel",
+        minimum_position: Position::Dead,
+        command_pointer: do_channel,
+        minimum_level: 0,
+        subcmd: 0,
+    },
     // { "check"    , Position::Standing, do_not_here , 1, 0 },
     CommandInfo {
         command: "check",
@@ -564,6 +595,22 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
+    // { "clan"     , Position::Resting , do_clan     , 1, 0 },
+    CommandInfo {
+        command: "clan",
+        minimum_position: Position::Resting,
+        command_pointer: do_clan,
+        minimum_level: 1,
+        subcmd: 0,
+    },
+    // { "clantalk" , Position::Sleeping, do_gen_comm , 1, 0 },
+    CommandInfo {
+        command: "clantalk",
+        minimum_position: Position::Sleeping,
+        command_pointer: do_gen_comm,
+        minimum_level: 1,
+        subcmd: 0,
+    },
     // { "clear"    , Position::Dead    , do_gen_ps   , 0, SCMD_CLEAR },
     CommandInfo {
         command: "clear",
@@ -604,6 +651,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
+    // { "colorset" , Position::Dead    , do_colorset , 0, 0 },
+    CommandInfo {
+        command: "colorset",
+        minimum_position: Position::Dead,
+        command_pointer: do_colorset,
+        minimum_level: 0,
+        subcmd: 0,
+    },
     // { "comfort"  , Position::Resting , do_action   , 0, 0 },
     CommandInfo {
         command: "comfort",
@@ -927,6 +982,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: LVL_FREEZE,
         subcmd: SCMD_FREEZE,
     },
+    // { "friend"   , Position::Dead    , do_friend   , 0, 0 },
+    CommandInfo {
+        command: "friend",
+        minimum_position: Position::Dead,
+        command_pointer: do_friend,
+        minimum_level: 0,
+        subcmd: 0,
+    },
     // { "french"   , Position::Resting , do_action   , 0, 0 },
     CommandInfo {
         command: "french",
@@ -1016,13 +1079,13 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
-    // { "gossip"   , Position::Sleeping, do_gen_comm , 0, SCMD_GOSSIP },
+    // { "gossip"   , Position::Sleeping, do_gen_comm , 0, 0 },
     CommandInfo {
         command: "gossip",
         minimum_position: Position::Sleeping,
         command_pointer: do_gen_comm,
         minimum_level: 0,
-        subcmd: SCMD_GOSSIP,
+        subcmd: 0,
     },
     // { "group"    , Position::Resting , do_group    , 1, 0 },
     CommandInfo {
@@ -1040,13 +1103,13 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
-    // { "grats"    , Position::Sleeping, do_gen_comm , 0, SCMD_GRATZ },
+    // { "grats"    , Position::Sleeping, do_gen_comm , 0, 0 },
     CommandInfo {
         command: "grats",
         minimum_position: Position::Sleeping,
         command_pointer: do_gen_comm,
         minimum_level: 0,
-        subcmd: SCMD_GRATZ,
+        subcmd: 0,
     },
     // { "greet"    , Position::Resting , do_action   , 0, 0 },
     CommandInfo {
@@ -1155,13 +1218,13 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         subcmd: SCMD_HIT,
     },
     // { "hold"     , Position::Resting , do_grab     , 1, 0 },
-    // { "holler"   , Position::Resting , do_gen_comm , 1, SCMD_HOLLER },
+    // { "holler"   , Position::Resting , do_gen_comm , 1, 0 },
     CommandInfo {
         command: "holler",
         minimum_position: Position::Resting,
         command_pointer: do_gen_comm,
         minimum_level: 0,
-        subcmd: SCMD_HOLLER,
+        subcmd: 0,
     },
     // { "holylight", Position::Dead    , do_gen_tog  , LVL_IMMORT, SCMD_HOLYLIGHT },
     CommandInfo {
@@ -1212,6 +1275,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: SCMD_IDEA,
     },
+    // { "ignore"   , Position::Dead    , do_ignore   , 0, 0 },
+    CommandInfo {
+        command: "ignore",
+        minimum_position: Position::Dead,
+        command_pointer: do_ignore,
+        minimum_level: 0,
+        subcmd: 0,
+    },
     // { "imotd"    , Position::Dead    , do_gen_ps   , LVL_IMMORT, SCMD_IMOTD },
     CommandInfo {
         command: "imotd",
@@ -1302,6 +1373,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
+    // { "language" , Position::Dead    , do_language , 0, 0 },
+    CommandInfo {
+        command: "language",
+        minimum_position: Position::Dead,
+        command_pointer: do_language,
+        minimum_level: 0,
+        subcmd: 0,
+    },
     // { "last"     , Position::Dead    , do_last     , LVL_GOD, 0 },
     CommandInfo {
         command: "last",
@@ -1391,6 +1470,30 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 1,
         subcmd: 0,
     },
+    // { "map"      , Position::Resting , do_map      , 0, 0 },
+    CommandInfo {
+        command: "map",
+        minimum_position: Position::Resting,
+        command_pointer: do_map,
+        minimum_level: 0,
+        subcmd: 0,
+    },
+    // { "mapexport", Position::Dead    , do_mapexport, LVL_GOD, 0 },
+    CommandInfo {
+        command: "mapexport",
+        minimum_position: Position::Dead,
+        command_pointer: do_mapexport,
+        minimum_level: LVL_GOD,
+        subcmd: 0,
+    },
+    // { "market"   , Position::Standing, do_market   , 1, 0 },
+    CommandInfo {
+        command: "market",
+        minimum_position: Position::Standing,
+        command_pointer: do_market,
+        minimum_level: 1,
+        subcmd: 0,
+    },
     // { "massage"  , Position::Resting , do_action   , 0, 0 },
     CommandInfo {
         command: "massage",
@@ -1448,6 +1551,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: SCMD_NOAUCTION,
     },
+    // { "noclan"   , Position::Dead    , do_gen_tog  , 1, SCMD_NOCLAN },
+    CommandInfo {
+        command: "noclan",
+        minimum_position: Position::Dead,
+        command_pointer: do_gen_tog,
+        minimum_level: 1,
+        subcmd: SCMD_NOCLAN,
+    },
     // { "nogossip" , Position::Dead    , do_gen_tog  , 0, SCMD_NOGOSSIP },
     CommandInfo {
         command: "nogossip",
@@ -1675,11 +1786,11 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
-    // { "prompt"   , Position::Dead    , do_display  , 0, 0 },
+    // { "prompt"   , Position::Dead    , do_prompt   , 0, 0 },
     CommandInfo {
         command: "prompt",
         minimum_position: Position::Dead,
-        command_pointer: do_display,
+        command_pointer: do_prompt,
         minimum_level: 0,
         subcmd: 0,
     },
@@ -1691,6 +1802,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 1,
         subcmd: 0,
     },
+    // { "privacy"  , Position::Dead    , do_gen_tog  , 0, SCMD_PRIVACY },
+    CommandInfo {
+        command: "privacy",
+        minimum_position: Position::Dead,
+        command_pointer: do_gen_tog,
+        minimum_level: 0,
+        subcmd: SCMD_PRIVACY,
+    },
     // { "pray"     , Position::Sitting , do_action   , 0, 0 },
     CommandInfo {
         command: "pray",
@@ -1748,13 +1867,13 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: LVL_IMMORT,
         subcmd: SCMD_QECHO,
     },
-    // { "quest"    , Position::Dead    , do_gen_tog  , 0, SCMD_QUEST },
+    // { "quest"    , Position::Dead    , do_quest    , 0, 0 },
     CommandInfo {
         command: "quest",
         minimum_position: Position::Dead,
-        command_pointer: do_gen_tog,
+        command_pointer: do_quest,
         minimum_level: 0,
-        subcmd: SCMD_QUEST,
+        subcmd: 0,
     },
     // { "qui"      , Position::Dead    , do_quit     , 0, 0 },
     CommandInfo {
@@ -1950,6 +2069,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 0,
         subcmd: 0,
     },
+    // { "screenreader", Position::Dead , do_gen_tog  , 0, SCMD_SCREENREADER },
+    CommandInfo {
+        command: "screenreader",
+        minimum_position: Position::Dead,
+        command_pointer: do_gen_tog,
+        minimum_level: 0,
+        subcmd: SCMD_SCREENREADER,
+    },
     // { "sell"     , Position::Standing, do_not_here , 0, 0 },
     CommandInfo {
         command: "sell",
@@ -1974,13 +2101,13 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: LVL_GOD,
         subcmd: 0,
     },
-    // { "shout"    , Position::Resting , do_gen_comm , 0, SCMD_SHOUT },
+    // { "shout"    , Position::Resting , do_gen_comm , 0, 0 },
     CommandInfo {
         command: "shout",
         minimum_position: Position::Resting,
         command_pointer: do_gen_comm,
         minimum_level: 0,
-        subcmd: SCMD_SHOUT,
+        subcmd: 0,
     },
     // { "shake"    , Position::Resting , do_action   , 0, 0 },
     CommandInfo {
@@ -2254,6 +2381,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: LVL_IMMORT,
         subcmd: 0,
     },
+    // { "statement", Position::Standing, do_not_here , 1, 0 },
+    CommandInfo {
+        command: "statement",
+        minimum_position: Position::Standing,
+        command_pointer: do_not_here,
+        minimum_level: 1,
+        subcmd: 0,
+    },
     // { "steal"    , Position::Standing, do_steal    , 1, 0 },
     CommandInfo {
         command: "steal",
@@ -2447,6 +2582,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: LVL_GOD,
         subcmd: 0,
     },
+    // { "transfer" , Position::Standing, do_not_here , 1, 0 },
+    CommandInfo {
+        command: "transfer",
+        minimum_position: Position::Standing,
+        command_pointer: do_not_here,
+        minimum_level: 1,
+        subcmd: 0,
+    },
     // { "twiddle"  , Position::Resting , do_action   , 0, 0 },
     CommandInfo {
         command: "twiddle",
@@ -2681,6 +2824,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         minimum_level: 1,
         subcmd: 0,
     },
+    // { "width"    , Position::Dead    , do_width    , 0, 0 },
+    CommandInfo {
+        command: "width",
+        minimum_position: Position::Dead,
+        command_pointer: do_width,
+        minimum_level: 0,
+        subcmd: 0,
+    },
     // { "wiznet"   , Position::Dead    , do_wiznet   , LVL_IMMORT, 0 },
     CommandInfo {
         command: "wiznet",
@@ -2755,6 +2906,14 @@ pub const CMD_INFO: [CommandInfo; 308] = [
         subcmd: 0,
     },
     //
+    // { "zreload"  , Position::Dead    , do_zreload  , LVL_GRGOD, 0 },
+    CommandInfo {
+        command: "zreload",
+        minimum_position: Position::Dead,
+        command_pointer: do_zreload,
+        minimum_level: LVL_GRGOD,
+        subcmd: 0,
+    },
     // { "zreset"   , Position::Dead    , do_zreset   , LVL_GRGOD, 0 },
     CommandInfo {
         command: "zreset",
@@ -2827,18 +2986,41 @@ pub fn command_interpreter(
     }
 
     /* otherwise, find the command */
+    let ch = chars.get(chid);
     let mut cmd_idx = CMD_INFO.len() - 1;
     let mut cmd = &CMD_INFO[cmd_idx];
     for (i, cmd_info) in CMD_INFO.iter().enumerate() {
-        if cmd_info.command == arg && ch.get_level() >= cmd_info.minimum_level {
+        if cmd_info.command.starts_with(arg.as_str()) && ch.get_level() >= cmd_info.minimum_level {
             cmd = cmd_info;
             cmd_idx = i;
             break;
         }
     }
 
+    /*
+     * command triggers may take over any word: a command by its full name,
+     * however it was abbreviated, or else the word as typed
+     */
+    let word = if cmd.command == "\n" {
+        arg.as_str()
+    } else {
+        cmd.command
+    };
+    if command_trigger(game, db, chars, texts, objs, chid, word, line) {
+        return;
+    }
+    let ch = chars.get(chid);
+
     if cmd.command == "\n" {
-        send_to_char(&mut game.descriptors, ch, "Huh?!?\r\n");
+        /* channels without a command of their own, such as private ones */
+        match find_channel(db, &arg).filter(|&c| channel_allowed(db, ch, c)) {
+            Some(c) if !ch.plr_flagged(PLR_FROZEN) && ch.get_pos() >= Position::Sleeping => {
+                channel_talk(game, db, chars, chid, c, line);
+            }
+            _ => {
+                send_to_char(&mut game.descriptors, ch, "Huh?!?\r\n");
+            }
+        }
     } else if !ch.is_npc() && ch.plr_flagged(PLR_FROZEN) && ch.get_level() < LVL_IMPL {
         send_to_char(
             &mut game.descriptors,
@@ -3599,6 +3781,7 @@ fn perform_dupe_check(
                 true,
                 msg.as_str(),
             );
+            friends_notify(game, chars, target_id, true);
         }
         Some(DupeCheckMode::Usurp) => {
             desc.write_to_output("You take over your own body, already in use!\r\n");
@@ -3641,6 +3824,28 @@ fn perform_dupe_check(
 }
 
 /* deal with newcomers and other non-playing sockets */
+/* a login message in the language of the player connecting */
+fn nanny_msg<'a>(
+    db: &'a DB,
+    chars: &Depot<CharData>,
+    desc: &DescriptorData,
+    id: &'a str,
+) -> &'a str {
+    match desc.character {
+        Some(chid) => db.msg(chars.get(chid), id),
+        None => db.catalog.get("", id),
+    }
+}
+
+fn language_menu(db: &DB, ch: &CharData) -> String {
+    let mut buf = db.msg(ch, "nanny.choose_language").to_string();
+    for lang in &db.catalog.langs {
+        buf.push_str(&format!("  {:<4} {}\r\n", lang.code, lang.name));
+    }
+    buf.push_str(db.msg(ch, "nanny.language"));
+    buf
+}
+
 pub fn nanny(
     game: &mut Game,
     db: &mut DB,
@@ -3670,7 +3875,7 @@ pub fn nanny(
                 let Some(tmp_name) = _parse_name(arg) else {
                     let desc = game.desc_mut(d_id);
 
-                    desc.write_to_output("Invalid name, please try another.\r\nName: ");
+                    desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_name"));
                     return;
                 };
 
@@ -3682,7 +3887,7 @@ pub fn nanny(
                 {
                     let desc = game.desc_mut(d_id);
 
-                    desc.write_to_output("Invalid name, please try another.\r\nName: ");
+                    desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_name"));
                     return;
                 }
                 let desc = game.desc_mut(d_id);
@@ -3702,7 +3907,7 @@ pub fn nanny(
                         if !valid_name(game, chars, db, tmp_name) {
                             let desc = game.desc_mut(d_id);
 
-                            desc.write_to_output("Invalid name, please try another.\r\nName: ");
+                            desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_name"));
                             return;
                         }
                         let desc = game.desc_mut(d_id);
@@ -3714,15 +3919,18 @@ pub fn nanny(
                         new_char.pfilepos = player_i as i32;
                         let new_char_id = chars.push(new_char);
                         desc.character = Some(new_char_id);
-                        desc.write_to_output(
-                            format!("Did I get that right, {} (Y/N)? ", tmp_name).as_str(),
-                        );
+                        desc.write_to_output(&fill_args(
+                            nanny_msg(db, chars, desc, "nanny.name_confirm"),
+                            &[&tmp_name],
+                        ));
                         desc.set_state(ConNameCnfrm);
                     } else {
                         /* undo it just in case they are set */
                         character.remove_plr_flag(PLR_WRITING | PLR_MAILING | PLR_CRYO);
                         character.remove_aff_flags(AffectFlags::GROUP);
-                        desc.write_to_output("Password: ");
+                        /* the preferences say which language to go on in */
+                        read_prefs(character);
+                        desc.write_to_output(nanny_msg(db, chars, desc, "nanny.password"));
                         desc.echo_off();
                         desc.idle_tics = 0;
                         desc.set_state(ConPassword);
@@ -3733,7 +3941,7 @@ pub fn nanny(
                     /* Check for multiple creations of a character. */
                     if !valid_name(game, chars, db, tmp_name) {
                         let desc = game.desc_mut(d_id);
-                        desc.write_to_output("Invalid name, please try another.\r\nName: ");
+                        desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_name"));
                         return;
                     }
                     let desc = game.desc_mut(d_id);
@@ -3741,9 +3949,10 @@ pub fn nanny(
                     let character = chars.get_mut(character_id);
                     character.player.name = Rc::from(tmp_name);
 
-                    desc.write_to_output(
-                        format!("Did I get that right, {} (Y/N)? ", tmp_name).as_str(),
-                    );
+                    desc.write_to_output(&fill_args(
+                        nanny_msg(db, chars, desc, "nanny.name_confirm"),
+                        &[&tmp_name],
+                    ));
                     desc.set_state(ConNameCnfrm);
                 }
             }
@@ -3768,14 +3977,12 @@ pub fn nanny(
                     );
                     let desc = game.desc_mut(d_id);
 
-                    desc.write_to_output(
-                        "Sorry, new characters are not allowed from your site!\r\n",
-                    );
+                    desc.write_to_output(nanny_msg(db, chars, desc, "nanny.new_site_banned"));
                     desc.set_state(ConClose);
                     return;
                 }
                 if db.circle_restrict != 0 {
-                    desc.write_to_output("Sorry, new players can't be created at the moment.\r\n");
+                    desc.write_to_output(nanny_msg(db, chars, desc, "nanny.new_wizlocked"));
                     let msg = format!(
                         "Request for new char {} denied from [{}] (wizlock)",
                         chars
@@ -3796,22 +4003,22 @@ pub fn nanny(
                     return;
                 }
 
-                let msg = format!(
-                    "New character.\r\nGive me a password for {}: ",
-                    chars
+                let msg = fill_args(
+                    nanny_msg(db, chars, desc, "nanny.new_password"),
+                    &[chars
                         .get(desc.character.expect("descriptor with no character !"))
-                        .get_pc_name()
+                        .get_pc_name()],
                 );
                 desc.write_to_output(msg.as_str());
                 desc.echo_off();
                 desc.set_state(ConNewpasswd);
             } else if arg.starts_with('n') || arg.starts_with('N') {
-                desc.write_to_output("Okay, what IS it, then? ");
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.name_again"));
                 desc.set_state(ConGetName);
                 let chid = desc.character.expect("descriptor with no character !");
                 db.free_char(&mut game.descriptors, chars, objs, chid);
             } else {
-                desc.write_to_output("Please type Yes or No: ");
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.yes_or_no"));
             }
         }
         ConPassword => {
@@ -3871,10 +4078,15 @@ pub fn nanny(
                         desc.bad_pws += 1;
                         if desc.bad_pws >= MAX_BAD_PWS {
                             /* 3 strikes and you're out. */
-                            desc.write_to_output("Wrong password... disconnecting.\r\n");
+                            desc.write_to_output(nanny_msg(
+                                db,
+                                chars,
+                                desc,
+                                "nanny.bad_password_close",
+                            ));
                             desc.set_state(ConClose);
                         } else {
-                            desc.write_to_output("Wrong password.\r\nPassword: ");
+                            desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_password"));
                             desc.echo_off();
                         }
                         return;
@@ -3890,9 +4102,7 @@ pub fn nanny(
                             .get(desc.character.expect("descriptor with no character !"))
                             .plr_flagged(PLR_SITEOK)
                     {
-                        desc.write_to_output(
-                            "Sorry, this char has not been cleared for login from your site!\r\n",
-                        );
+                        desc.write_to_output(nanny_msg(db, chars, desc, "nanny.site_not_cleared"));
                         desc.set_state(ConClose);
                         let msg = format!(
                             "Connection attempt for {} denied from {}",
@@ -3917,9 +4127,7 @@ pub fn nanny(
                         .get_level()
                         < db.circle_restrict
                     {
-                        desc.write_to_output(
-                            "The game is temporarily restricted.. try again later.\r\n",
-                        );
+                        desc.write_to_output(nanny_msg(db, chars, desc, "nanny.restricted"));
                         desc.set_state(ConClose);
                         let msg = format!(
                             "Request for login denied for {} [{}] (wizlock)",
@@ -3978,14 +4186,23 @@ pub fn nanny(
                         color1 = CCRED!(character, C_SPR);
                         color2 = CCNRM!(character, C_SPR);
                     }
+                    let failures = db.msgf(
+                        character,
+                        if load_result > 1 {
+                            "nanny.login_failures"
+                        } else {
+                            "nanny.login_failure"
+                        },
+                        &[&load_result],
+                    );
                     desc.write_to_output(
-                        format!("\r\n\r\n\007\007\007{}{} LOGIN FAILURE{} SINCE LAST SUCCESSFUL LOGIN.{}\r\n",
-                                color1, load_result, if load_result > 1 { "S" } else { "" }, color2).as_str(),
+                        format!("\r\n\r\n\007\007\007{}{}{}\r\n", color1, failures, color2)
+                            .as_str(),
                     );
                     let character = chars.get(character_id);
                     character.get_bad_pws();
                 }
-                desc.write_to_output("\r\n*** PRESS RETURN: ");
+                desc.write_to_output(db.msg(character, "nanny.press_return"));
                 desc.set_state(ConRmotd);
             }
         }
@@ -3997,7 +4214,7 @@ pub fn nanny(
                 || arg.len() < 3
                 || arg == character.get_pc_name().as_ref()
             {
-                desc.write_to_output("\r\nIllegal password.\r\nPassword: ");
+                desc.write_to_output(db.msg(character, "nanny.illegal_password"));
                 return;
             }
             {
@@ -4008,7 +4225,8 @@ pub fn nanny(
                 let character = chars.get_mut(character_id);
                 character.set_passwd(tmp);
             }
-            desc.write_to_output("\r\nPlease retype password: ");
+            let character = chars.get(character_id);
+            desc.write_to_output(db.msg(character, "nanny.retype_password"));
             if desc.state() == ConNewpasswd {
                 desc.set_state(ConCnfpasswd);
             } else {
@@ -4028,7 +4246,7 @@ pub fn nanny(
                 pwd_equals = passwd == passwd2;
             }
             if !pwd_equals {
-                desc.write_to_output("\r\nPasswords don't match... start over.\r\nPassword: ");
+                desc.write_to_output(db.msg(character, "nanny.password_mismatch"));
                 if desc.state() == ConCnfpasswd {
                     desc.set_state(ConNewpasswd);
                 } else {
@@ -4038,10 +4256,16 @@ pub fn nanny(
             desc.echo_on();
 
             if desc.state() == ConCnfpasswd {
-                desc.write_to_output("\r\nWhat is your sex (M/F)? ");
-                desc.set_state(ConQsex);
+                if db.catalog.langs.len() > 1 {
+                    desc.write_to_output(&language_menu(db, character));
+                    desc.set_state(ConQlang);
+                } else {
+                    desc.write_to_output(db.msg(character, "nanny.sex"));
+                    desc.set_state(ConQsex);
+                }
             } else {
-                desc.write_to_output(format!("\r\nDone.\r\n{}", MENU).as_str());
+                desc.write_to_output(db.msg(character, "nanny.password_done"));
+                desc.write_to_output(db.msg(character, "nanny.menu"));
                 desc.set_state(ConMenu);
             }
         }
@@ -4057,24 +4281,24 @@ pub fn nanny(
                     character.player.sex = Sex::Female;
                 }
                 _ => {
-                    desc.write_to_output("That is not a sex..\r\nWhat IS your sex? ");
+                    desc.write_to_output(db.msg(character, "nanny.bad_sex"));
                     return;
                 }
             }
 
-            desc.write_to_output(format!("{}\r\nClass: ", CLASS_MENU).as_str());
+            desc.write_to_output(db.msg(character, "nanny.class"));
             desc.set_state(ConQclass);
         }
         ConQclass => {
             let character_id = desc.character.expect("descriptor with no character !");
 
             let Some(c) = arg.chars().next() else {
-                desc.write_to_output("\r\nThat's not a class.\r\nClass: ");
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_class"));
                 return;
             };
             let load_result = parse_class(c);
             if load_result == Class::Undefined {
-                desc.write_to_output("\r\nThat's not a class.\r\nClass: ");
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_class"));
                 return;
             } else {
                 let character = chars.get_mut(character_id);
@@ -4094,7 +4318,10 @@ pub fn nanny(
             save_char(&mut game.descriptors, db, chars, texts, objs, character_id);
             let desc = game.desc_mut(d_id);
 
-            desc.write_to_output(format!("{}\r\n*** PRESS RETURN: ", db.motd).as_str());
+            let character = chars.get(character_id);
+            desc.write_to_output(
+                format!("{}{}", db.motd, db.msg(character, "nanny.press_return")).as_str(),
+            );
             desc.set_state(ConRmotd);
 
             {
@@ -4111,7 +4338,7 @@ pub fn nanny(
         }
         ConRmotd => {
             /* read CR after printing motd   */
-            desc.write_to_output(MENU);
+            desc.write_to_output(nanny_msg(db, chars, desc, "nanny.menu"));
             desc.set_state(ConMenu);
         }
         ConMenu => {
@@ -4121,7 +4348,7 @@ pub fn nanny(
             let character = chars.get(character_id);
             match arg.chars().last().unwrap_or('\0') {
                 '0' => {
-                    desc.write_to_output("Goodbye.\r\n");
+                    desc.write_to_output(db.msg(character, "nanny.goodbye"));
                     desc.set_state(ConClose);
                 }
 
@@ -4130,6 +4357,7 @@ pub fn nanny(
                         reset_char(chars.get_mut(character_id));
                         let character = chars.get_mut(character_id);
                         read_aliases(character);
+                        read_quests(db, character);
                         if character.plr_flagged(PLR_INVSTART) {
                             character.set_invis_lev(character.get_level() as i16);
                         }
@@ -4160,7 +4388,7 @@ pub fn nanny(
                         send_to_char(
                             &mut game.descriptors,
                             character,
-                            WELC_MESSG.to_string().as_str(),
+                            db.msg(character, "nanny.welcome"),
                         );
                         db.character_list.push(character.id());
                         db.char_to_room(chars, objs, character_id, load_room);
@@ -4173,11 +4401,12 @@ pub fn nanny(
                         }
                         save_char(&mut game.descriptors, db, chars, texts, objs, character_id);
                         let character = chars.get(character_id);
-                        act(
+                        act_msg(
                             &mut game.descriptors,
                             chars,
                             db,
-                            "$n has entered the game.",
+                            "nanny.entered_game",
+                            &[],
                             true,
                             Some(character),
                             None,
@@ -4194,7 +4423,7 @@ pub fn nanny(
                         send_to_char(
                             &mut game.descriptors,
                             character,
-                            START_MESSG.to_string().as_str(),
+                            db.msg(character, "nanny.start"),
                         );
                         look_at_room(
                             &mut game.descriptors,
@@ -4214,14 +4443,18 @@ pub fn nanny(
                     ) {
                         let chid = desc.character.expect("descriptor with no character !");
                         let ch = chars.get(chid);
-                        send_to_char(&mut game.descriptors, ch, "You have mail waiting.\r\n");
+                        send_to_char(&mut game.descriptors, ch, db.msg(ch, "nanny.mail_waiting"));
                     }
+                    auction_login(game, chars, db, texts, objs, character_id);
+                    tells_login(game, chars, db, character_id);
+                    friends_notify(game, chars, character_id, true);
                     let desc = game.desc_mut(d_id);
                     if load_result == 2 {
                         /* rented items lost */
                         let chid = desc.character.expect("descriptor with no character");
                         let ch = chars.get(chid);
-                        send_to_char(&mut game.descriptors, ch, "\r\n\x07You could not afford your rent!\r\nYour possesions have been donated to the Salvation Army!\r\n");
+                        let msg = format!("\r\n\x07{}", db.msg(ch, "nanny.rent_lost"));
+                        send_to_char(&mut game.descriptors, ch, &msg);
                     }
                     let desc = game.desc_mut(d_id);
                     desc.has_prompt = false;
@@ -4230,11 +4463,12 @@ pub fn nanny(
                 '2' => {
                     let text = &mut texts.get_mut(character.player.description).text;
                     if text.is_empty() {
-                        let mesg = format!("Old description:\r\n{}", text);
+                        let mesg =
+                            format!("{}{}", db.msg(character, "nanny.old_description"), text);
                         desc.write_to_output(&mesg);
                         text.clear();
                     }
-                    desc.write_to_output( "Enter the new text you'd like others to see when they look at you.\r\nTerminate with a '@' on a new line.\r\n");
+                    desc.write_to_output(db.msg(character, "nanny.enter_description"));
                     desc.str = Some(character.player.description);
                     desc.max_str = EXDSCR_LENGTH;
                     desc.set_state(ConExdesc);
@@ -4246,19 +4480,22 @@ pub fn nanny(
                     desc.set_state(ConRmotd);
                 }
                 '4' => {
-                    desc.write_to_output("\r\nEnter your old password: ");
+                    desc.write_to_output(db.msg(character, "nanny.old_password"));
                     desc.echo_off();
                     desc.set_state(ConChpwdGetold);
                 }
                 '5' => {
-                    desc.write_to_output("\r\nEnter your password for verification: ");
+                    desc.write_to_output(db.msg(character, "nanny.verify_password"));
                     desc.echo_off();
                     desc.set_state(ConDelcnf1);
                 }
+                '6' => {
+                    desc.write_to_output(&language_menu(db, character));
+                    desc.set_state(ConLanguage);
+                }
                 _ => {
-                    desc.write_to_output(
-                        format!("\r\nThat's not a menu choice!\r\n{}", MENU).as_str(),
-                    );
+                    desc.write_to_output(db.msg(character, "nanny.bad_choice"));
+                    desc.write_to_output(db.msg(character, "nanny.menu"));
                 }
             }
         }
@@ -4278,10 +4515,11 @@ pub fn nanny(
 
             if !matching_pwd {
                 desc.echo_on();
-                desc.write_to_output(format!("\r\nIncorrect password.\r\n{}", MENU).as_str());
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.incorrect_password"));
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.menu"));
                 desc.set_state(ConMenu);
             } else {
-                desc.write_to_output("\r\nEnter a new password: ");
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.enter_new_password"));
                 desc.set_state(ConChpwdGetnew);
             }
         }
@@ -4300,27 +4538,23 @@ pub fn nanny(
                 matching_pwd = passwd == passwd2;
             }
             if !matching_pwd {
-                desc.write_to_output(format!("\r\nIncorrect password.\r\n{}", MENU).as_str());
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.incorrect_password"));
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.menu"));
                 desc.set_state(ConMenu);
             } else {
-                desc.write_to_output(
-                    "\r\nYOU ARE ABOUT TO DELETE THIS CHARACTER PERMANENTLY.\r\n\
-                                ARE YOU ABSOLUTELY SURE?\r\n\r\n\
-                                Please type \"yes\" to confirm: ",
-                );
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.delete_confirm"));
                 desc.set_state(ConDelcnf2);
             }
         }
 
         ConDelcnf2 => {
-            if arg == "yes" || arg == "YES" {
+            if arg.eq_ignore_ascii_case("yes")
+                || arg.to_lowercase() == nanny_msg(db, chars, desc, "nanny.yes")
+            {
                 let d_chid = desc.character.expect("descriptor with no character !");
                 let d_ch = chars.get(d_chid);
                 if d_ch.plr_flagged(PLR_FROZEN) {
-                    desc.write_to_output(
-                        "You try to kill yourself, but the ice stops you.\r\n\
-                                    Character not deleted.\r\n\r\n",
-                    );
+                    desc.write_to_output(db.msg(d_ch, "nanny.delete_frozen"));
                     desc.set_state(ConClose);
                     return;
                 }
@@ -4333,11 +4567,10 @@ pub fn nanny(
                 let d_ch = chars.get(d_chid);
                 crash_delete_file(d_ch.get_name());
                 delete_aliases(d_ch.get_name().as_ref());
-                let txt = format!(
-                    "Character '{}' deleted!\r\n\
-                            Goodbye.\r\n",
-                    d_ch.get_name()
-                );
+                delete_quests(d_ch.get_name().as_ref());
+                delete_prefs(d_ch.get_name().as_ref());
+                delete_ledger(d_ch.get_name().as_ref());
+                let txt = db.msgf(d_ch, "nanny.deleted", &[&d_ch.get_name()]);
                 desc.write_to_output(txt.as_str());
                 let d_ch = chars.get(d_chid);
                 let txt = format!(
@@ -4355,7 +4588,32 @@ pub fn nanny(
                 let desc = game.desc_mut(d_id);
                 desc.set_state(ConClose);
             } else {
-                desc.write_to_output(format!("\r\nCharacter not deleted.\r\n{}", MENU).as_str());
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.not_deleted"));
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.menu"));
+                desc.set_state(ConMenu);
+            }
+        }
+
+        ConQlang | ConLanguage => {
+            let character_id = desc.character.expect("descriptor with no character !");
+            let code = db.catalog.find(arg).map(|lang| lang.code.clone());
+            let Some(code) = code.filter(|_| !arg.is_empty()) else {
+                desc.write_to_output(nanny_msg(db, chars, desc, "nanny.bad_language"));
+                return;
+            };
+            let character = chars.get_mut(character_id);
+            character.player_specials.language = if *code == *DEFAULT_LANG {
+                Rc::from("")
+            } else {
+                code
+            };
+            let character = chars.get(character_id);
+            if desc.state() == ConQlang {
+                desc.write_to_output(db.msg(character, "nanny.sex"));
+                desc.set_state(ConQsex);
+            } else {
+                write_prefs(character);
+                desc.write_to_output(db.msg(character, "nanny.menu"));
                 desc.set_state(ConMenu);
             }
         }
@@ -0,0 +1,355 @@
+/* ************************************************************************
+*   File: lang.rs                                       Part of CircleMUD *
+*  Usage: message catalogs for playing in other languages than English    *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * Player-facing messages are looked up by id in a catalog, one file per
+ * language in misc/lang/ named after the language code.  The English
+ * catalog is required; a message missing from a translation is shown in
+ * English.
+ *
+ * The messages keep the $-codes of act().  In addition, $1 to $9 stand for
+ * values given by the caller: plain values for a message sent to a single
+ * player, or the ids of other messages (translated for each reader) when
+ * the message goes through act_msg().
+ */
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::rc::Rc;
+
+use log::{error, info};
+
+use crate::db::{DB, LANG_DIR};
+use crate::depot::{Depot, DepotId};
+use crate::interpreter::one_argument;
+use crate::structs::CharData;
+use crate::{send_to_char, Game, ObjData, TextData};
+
+pub const DEFAULT_LANG: &str = "en";
+
+pub struct Language {
+    pub code: Rc<str>,
+    pub name: Rc<str>,
+    msgs: HashMap<Rc<str>, Rc<str>>,
+}
+
+/* the default language comes first once booted */
+#[derive(Default)]
+pub struct MsgCatalog {
+    pub langs: Vec<Language>,
+}
+
+/*
+ * A catalog file is made of records: '#' and the message id on one line,
+ * then the text up to a '~', which may span several lines.  Lines between
+ * records starting with '*' are comments, and "Name:" gives the name of
+ * the language as players see it.
+ */
+pub(crate) fn parse_language<R: BufRead>(reader: R, code: &str) -> Language {
+    let mut lang = Language {
+        code: Rc::from(code),
+        name: Rc::from(code),
+        msgs: HashMap::new(),
+    };
+    let mut lines = reader.lines().map_while(Result::ok);
+    while let Some(line) = lines.next() {
+        if let Some(name) = line.strip_prefix("Name:") {
+            lang.name = Rc::from(name.trim());
+            continue;
+        }
+        let Some(id) = line.strip_prefix('#') else {
+            if !line.trim().is_empty() && !line.starts_with('*') {
+                error!("SYSERR: Format error in language file '{}': {}", code, line);
+            }
+            continue;
+        };
+        let id = id.trim();
+        let mut text = String::new();
+        let mut ended = false;
+        for line in lines.by_ref() {
+            if let Some(end) = line.find('~') {
+                text.push_str(&line[..end]);
+                ended = true;
+                break;
+            }
+            text.push_str(&line);
+            text.push_str("\r\n");
+        }
+        if !ended {
+            error!(
+                "SYSERR: Language file '{}': message {} has no ending '~'",
+                code, id
+            );
+        }
+        if lang.msgs.insert(Rc::from(id), Rc::from(text)).is_some() {
+            error!("SYSERR: Language file '{}': duplicate message {}", code, id);
+        }
+    }
+    lang
+}
+
+/* Replace $1 to $9 with the given values, leaving the act() codes alone */
+pub fn fill_args(text: &str, args: &[&dyn Display]) -> String {
+    let mut buf = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        buf.push_str(&rest[..pos]);
+        let code = rest[pos + 1..].chars().next();
+        match code.and_then(|c| c.to_digit(10)).filter(|&n| n >= 1) {
+            Some(n) => {
+                if let Some(arg) = args.get(n as usize - 1) {
+                    buf.push_str(&arg.to_string());
+                }
+                rest = &rest[pos + 2..];
+            }
+            None => {
+                buf.push('$');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    buf.push_str(rest);
+    buf
+}
+
+impl MsgCatalog {
+    /* find a language by its code or the beginning of its name */
+    pub fn find(&self, arg: &str) -> Option<&Language> {
+        let arg = arg.to_lowercase();
+        self.langs.iter().find(|l| *l.code == arg).or_else(|| {
+            self.langs
+                .iter()
+                .find(|l| l.name.to_lowercase().starts_with(&arg))
+        })
+    }
+
+    pub fn get<'a>(&'a self, code: &str, id: &'a str) -> &'a str {
+        let wanted = self.langs.iter().find(|l| *l.code == *code);
+        match wanted
+            .and_then(|l| l.msgs.get(id))
+            .or_else(|| self.langs.first().and_then(|l| l.msgs.get(id)))
+        {
+            Some(text) => text,
+            None => {
+                error!("SYSERR: No message '{}' in the language files", id);
+                id
+            }
+        }
+    }
+}
+
+impl DB {
+    /* the message in the language of the one reading it */
+    pub fn msg<'a>(&'a self, ch: &CharData, id: &'a str) -> &'a str {
+        self.catalog.get(&ch.player_specials.language, id)
+    }
+
+    pub fn msgf(&self, ch: &CharData, id: &str, args: &[&dyn Display]) -> String {
+        fill_args(self.msg(ch, id), args)
+    }
+}
+
+/* Read the catalogs at boot time (and on 'reload lang') */
+pub fn boot_languages(db: &mut DB) -> bool {
+    let entries = match fs::read_dir(LANG_DIR) {
+        Ok(entries) => entries,
+        Err(err) => {
+            error!(
+                "SYSERR: Cannot read language directory {}: {}",
+                LANG_DIR, err
+            );
+            return false;
+        }
+    };
+    let mut langs = vec![];
+    for entry in entries.map_while(Result::ok) {
+        let code = entry.file_name().to_string_lossy().to_string();
+        /* skip editor backups and the like */
+        if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        match File::open(entry.path()) {
+            Ok(fl) => langs.push(parse_language(BufReader::new(fl), &code)),
+            Err(err) => error!("SYSERR: Cannot read language file {}: {}", code, err),
+        }
+    }
+    let Some(default) = langs.iter().position(|l| *l.code == *DEFAULT_LANG) else {
+        error!(
+            "SYSERR: No '{}' language file in {}",
+            DEFAULT_LANG, LANG_DIR
+        );
+        return false;
+    };
+    let default = langs.remove(default);
+    langs.sort_by(|a, b| a.code.cmp(&b.code));
+    langs.insert(0, default);
+    for lang in &langs {
+        info!(
+            "   {} ({}): {} messages.",
+            lang.name,
+            lang.code,
+            lang.msgs.len()
+        );
+    }
+    db.catalog.langs = langs;
+    true
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_language(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg = String::new();
+    one_argument(argument, &mut arg);
+
+    if arg.is_empty() {
+        let mut buf = db.msg(ch, "lang.list").to_string();
+        for lang in &db.catalog.langs {
+            let current = if *lang.code == *ch.player_specials.language
+                || (ch.player_specials.language.is_empty() && *lang.code == *DEFAULT_LANG)
+            {
+                " *"
+            } else {
+                ""
+            };
+            buf.push_str(&format!("  {:<4} {}{}\r\n", lang.code, lang.name, current));
+        }
+        send_to_char(&mut game.descriptors, ch, &buf);
+        return;
+    }
+    let Some(lang) = db.catalog.find(&arg) else {
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "lang.unknown"));
+        return;
+    };
+    let code = if *lang.code == *DEFAULT_LANG {
+        Rc::from("")
+    } else {
+        lang.code.clone()
+    };
+    let ch = chars.get_mut(chid);
+    ch.player_specials.language = code;
+    let ch = chars.get(chid);
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "lang.set"));
+}
+
+#[cfg(test)]
+mod lang_tests {
+    use super::*;
+
+    /* other tests change the current directory */
+    fn lang_dir() -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("lib/misc/lang")
+    }
+
+    fn load(code: &str) -> Language {
+        let fl = File::open(lang_dir().join(code)).expect("language file");
+        parse_language(BufReader::new(fl), code)
+    }
+
+    #[test]
+    fn test_records_parse() {
+        let text = "\
+* A comment.
+Name: Test
+#one
+Password: ~
+#two
+
+Illegal password.
+Password: ~
+#three
+$n leaves $1.~
+";
+        let lang = parse_language(text.as_bytes(), "xx");
+        assert_eq!(&*lang.name, "Test");
+        assert_eq!(lang.msgs.len(), 3);
+        assert_eq!(&*lang.msgs["one"], "Password: ");
+        assert_eq!(&*lang.msgs["two"], "\r\nIllegal password.\r\nPassword: ");
+        assert_eq!(&*lang.msgs["three"], "$n leaves $1.");
+    }
+
+    #[test]
+    fn test_args_are_filled() {
+        assert_eq!(
+            fill_args("$n gives you $1 coins, $2.", &[&12, &"friend"]),
+            "$n gives you 12 coins, friend."
+        );
+        assert_eq!(fill_args("$$ and $3 and $", &[&1]), "$$ and  and $");
+    }
+
+    #[test]
+    fn test_missing_messages_fall_back() {
+        let mut catalog = MsgCatalog::default();
+        catalog
+            .langs
+            .push(parse_language("#a\nA~\n#b\nB~\n".as_bytes(), "en"));
+        catalog.langs.push(parse_language(
+            "Name: Fran\u{e7}ais\n#a\nAh~\n".as_bytes(),
+            "fr",
+        ));
+        assert_eq!(catalog.get("fr", "a"), "Ah");
+        assert_eq!(catalog.get("fr", "b"), "B");
+        assert_eq!(catalog.get("", "a"), "A");
+        assert_eq!(catalog.get("fr", "c"), "c");
+        assert_eq!(
+            &*catalog.find("fran").map(|l| l.code.clone()).unwrap(),
+            "fr"
+        );
+        assert!(catalog.find("german").is_none());
+    }
+
+    /* a translation may not use ids or arguments the English file lacks */
+    #[test]
+    fn test_translations_match_english() {
+        let en = load(DEFAULT_LANG);
+        let args = |text: &str| -> Vec<char> {
+            text.split('$')
+                .skip(1)
+                .filter_map(|s| s.chars().next())
+                .filter(char::is_ascii_digit)
+                .collect()
+        };
+        for entry in fs::read_dir(lang_dir()).expect("language directory") {
+            let code = entry.unwrap().file_name().to_string_lossy().to_string();
+            if code == DEFAULT_LANG {
+                continue;
+            }
+            let lang = load(&code);
+            for (id, text) in &lang.msgs {
+                let Some(english) = en.msgs.get(id) else {
+                    panic!("{}: unknown message {}", code, id);
+                };
+                let known = args(english);
+                assert!(
+                    args(text).iter().all(|arg| known.contains(arg)),
+                    "{}: message {} has arguments the English one has not",
+                    code,
+                    id
+                );
+            }
+        }
+    }
+}
@@ -18,6 +18,7 @@ use crate::config::{
 };
 use crate::depot::{Depot, DepotId};
 use crate::fight::update_pos;
+use crate::friends::friends_notify;
 use crate::handler::{obj_from_obj, obj_to_obj, update_char_objects};
 use crate::objsave::{crash_crashsave, crash_idlesave, crash_rentsave};
 use crate::spells::{SPELL_POISON, TYPE_SUFFERING};
@@ -461,6 +462,7 @@ impl Game {
                 db.char_to_room(chars, objs, chid, 3);
                 let ch = chars.get(chid);
                 if let Some(desc_id) = ch.desc {
+                    friends_notify(self, chars, chid, false);
                     self.desc_mut(desc_id).set_state(ConDisconnect);
 
                     /*
@@ -502,15 +504,21 @@ impl Game {
     ) {
         /* characters */
         for &i_id in &db.character_list.clone() {
+            let bonus = db.room_regen_bonus(chars.get(i_id).in_room());
             let i = chars.get_mut(i_id);
             let descs = &mut self.descriptors;
             gain_condition(descs, i, FULL, -1);
             gain_condition(descs, i, DRUNK, -1);
             gain_condition(descs, i, THIRST, -1);
             if i.get_pos() >= Position::Stunned {
-                i.set_hit(min(i.get_hit() + hit_gain(i) as i16, i.get_max_hit()));
-                i.set_mana(min(i.get_mana() + mana_gain(i) as i16, i.get_max_mana()));
-                i.set_move(min(i.get_move() + move_gain(i) as i16, i.get_max_move()));
+                let (hit, mana, mov) = (
+                    hit_gain(i) as i32 * (100 + bonus) / 100,
+                    mana_gain(i) as i32 * (100 + bonus) / 100,
+                    move_gain(i) as i32 * (100 + bonus) / 100,
+                );
+                i.set_hit(min(i.get_hit() + hit as i16, i.get_max_hit()));
+                i.set_mana(min(i.get_mana() + mana as i16, i.get_max_mana()));
+                i.set_move(min(i.get_move() + mov as i16, i.get_max_move()));
                 if i.aff_flagged(AffectFlags::POISON)
                     && self.damage(chars, db, texts, objs, i_id, i_id, 2, SPELL_POISON) == -1
                 {
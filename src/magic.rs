@@ -12,30 +12,31 @@
 use std::cmp::{max, min};
 
 use crate::depot::{Depot, DepotId};
-use crate::{act, send_to_char, ObjData, TextData, VictimRef};
+use crate::{act, send_to_char, send_to_room, ObjData, TextData, VictimRef};
 use log::error;
 
 use crate::class::saving_throws;
-use crate::config::{NOEFFECT, PK_ALLOWED};
+use crate::config::PK_ALLOWED;
+use crate::constants::{DIRS, REV_DIR};
 use crate::db::{LoadType, DB};
 use crate::fight::update_pos;
 use crate::handler::{
-    affect_from_char, affect_join, affect_remove, affected_by_spell, obj_from_obj, obj_to_char,
+    affect_from_char, affect_join, affect_remove, affect_to_room, affected_by_spell, obj_from_obj,
+    obj_to_char,
 };
 use crate::spells::{
     spell_recall, MAX_SPELLS, SPELL_ANIMATE_DEAD, SPELL_ARMOR, SPELL_BLESS, SPELL_BLINDNESS,
-    SPELL_BURNING_HANDS, SPELL_CALL_LIGHTNING, SPELL_CHILL_TOUCH, SPELL_CLONE, SPELL_COLOR_SPRAY,
-    SPELL_CREATE_FOOD, SPELL_CURE_BLIND, SPELL_CURE_CRITIC, SPELL_CURE_LIGHT, SPELL_CURSE,
-    SPELL_DETECT_ALIGN, SPELL_DETECT_INVIS, SPELL_DETECT_MAGIC, SPELL_DISPEL_EVIL,
-    SPELL_DISPEL_GOOD, SPELL_EARTHQUAKE, SPELL_ENERGY_DRAIN, SPELL_FIREBALL, SPELL_GROUP_ARMOR,
-    SPELL_GROUP_HEAL, SPELL_GROUP_RECALL, SPELL_HARM, SPELL_HEAL, SPELL_INFRAVISION,
-    SPELL_INVISIBLE, SPELL_LIGHTNING_BOLT, SPELL_MAGIC_MISSILE, SPELL_POISON, SPELL_PROT_FROM_EVIL,
-    SPELL_REMOVE_CURSE, SPELL_REMOVE_POISON, SPELL_SANCTUARY, SPELL_SENSE_LIFE,
-    SPELL_SHOCKING_GRASP, SPELL_SLEEP, SPELL_STRENGTH, SPELL_WATERWALK,
+    SPELL_CHILL_TOUCH, SPELL_CLONE, SPELL_CONSECRATE, SPELL_CREATE_FOOD, SPELL_CURE_BLIND,
+    SPELL_CURE_CRITIC, SPELL_CURE_LIGHT, SPELL_CURSE, SPELL_DARKNESS, SPELL_DETECT_ALIGN,
+    SPELL_DETECT_INVIS, SPELL_DETECT_MAGIC, SPELL_DISPEL_EVIL, SPELL_DISPEL_GOOD, SPELL_EARTHQUAKE,
+    SPELL_ENERGY_DRAIN, SPELL_FOG, SPELL_GROUP_ARMOR, SPELL_GROUP_HEAL, SPELL_GROUP_RECALL,
+    SPELL_HEAL, SPELL_INFRAVISION, SPELL_INVISIBLE, SPELL_POISON, SPELL_PROT_FROM_EVIL,
+    SPELL_REMOVE_CURSE, SPELL_REMOVE_POISON, SPELL_SANCTUARY, SPELL_SENSE_LIFE, SPELL_SILENCE,
+    SPELL_SLEEP, SPELL_STRENGTH, SPELL_WALL_OF_FORCE, SPELL_WATERWALK,
 };
 use crate::structs::{
     AffectFlags, AffectedType, ApplyType, CharData, Class, ExtraFlags, ItemType, MobVnum, Position,
-    LVL_IMMORT, MOB_NOBLIND, MOB_NOSLEEP,
+    RoomAffectFlags, RoomAffectedType, RoomRnum, LVL_IMMORT, MOB_NOBLIND, MOB_NOSLEEP, NOWHERE,
 };
 use crate::util::{add_follower, dice, rand_number};
 use crate::{Game, TO_CHAR, TO_ROOM};
@@ -94,7 +95,7 @@ pub fn affect_update(
                     && af._type <= MAX_SPELLS as i16
                     && af._type != last_type_notification
                 {
-                    if let Some(wear_off_msg) = db.spell_info[af._type as usize].wear_off_msg {
+                    if let Some(wear_off_msg) = &db.spell_info[af._type as usize].wear_off_msg {
                         let i = chars.get(i_id);
                         send_to_char(
                             &mut game.descriptors,
@@ -112,6 +113,8 @@ pub fn affect_update(
         let i = chars.get_mut(i_id);
         i.affected = list;
     }
+
+    room_affect_update(game, chars, db);
 }
 
 // /*
@@ -193,58 +196,24 @@ pub fn mag_damage(
 ) -> i32 {
     let ch = chars.get(chid);
     let victim = chars.get(victim_id);
-    let mut dam = 0;
     let mut victim = victim;
 
-    match spellnum {
-        /* Mostly mages */
-        SPELL_MAGIC_MISSILE | SPELL_CHILL_TOUCH => {
-            /* chill touch also has an affect */
-            if ch.is_magic_user() {
-                dam = dice(1, 8) + 1;
-            } else {
-                dam = dice(1, 6) + 1;
-            }
-        }
-        SPELL_BURNING_HANDS => {
-            if ch.is_magic_user() {
-                dam = dice(3, 8) + 3;
-            } else {
-                dam = dice(3, 6) + 3;
-            }
-        }
-        SPELL_SHOCKING_GRASP => {
-            if ch.is_magic_user() {
-                dam = dice(5, 8) + 5;
-            } else {
-                dam = dice(5, 6) + 5;
-            }
-        }
-        SPELL_LIGHTNING_BOLT => {
-            if ch.is_magic_user() {
-                dam = dice(7, 8) + 7;
-            } else {
-                dam = dice(7, 6) + 7;
-            }
-        }
-        SPELL_COLOR_SPRAY => {
-            if ch.is_magic_user() {
-                dam = dice(9, 8) + 9;
-            } else {
-                dam = dice(9, 6) + 9;
-            }
-        }
-        SPELL_FIREBALL => {
-            if ch.is_magic_user() {
-                dam = dice(11, 8) + 11;
-            } else {
-                dam = dice(11, 6) + 11;
-            }
-        }
+    /* the dice come from the spell file, per class for players */
+    let sinfo = &db.spell_info[spellnum as usize];
+    let class_dice = if ch.is_npc() {
+        None
+    } else {
+        sinfo
+            .class_damage
+            .get(ch.get_class() as usize)
+            .copied()
+            .flatten()
+    };
+    let mut dam = class_dice.unwrap_or(sinfo.damage).roll(level);
 
+    match spellnum {
         /* Mostly clerics */
         SPELL_DISPEL_EVIL => {
-            dam = dice(6, 8) + 6;
             if ch.is_evil() {
                 victim = ch;
                 dam = (ch.get_hit() - 1) as i32;
@@ -264,7 +233,6 @@ pub fn mag_damage(
             }
         }
         SPELL_DISPEL_GOOD => {
-            dam = dice(6, 8) + 6;
             if ch.is_good() {
                 victim = ch;
                 dam = (ch.get_hit() - 1) as i32;
@@ -284,25 +252,8 @@ pub fn mag_damage(
             }
         }
 
-        SPELL_CALL_LIGHTNING => {
-            dam = dice(7, 8) + 7;
-        }
-
-        SPELL_HARM => {
-            dam = dice(8, 8) + 8;
-        }
-
-        SPELL_ENERGY_DRAIN => {
-            if victim.get_level() <= 2 {
-                dam = 100;
-            } else {
-                dam = dice(1, 10);
-            }
-        }
-
-        /* Area spells */
-        SPELL_EARTHQUAKE => {
-            dam = dice(2, 8) + level as i32;
+        SPELL_ENERGY_DRAIN if victim.get_level() <= 2 => {
+            dam = 100;
         }
         _ => {}
     } /* switch(spellnum) */
@@ -411,7 +362,7 @@ pub fn mag_affects(
         }
         SPELL_CURSE => {
             if mag_savingthrow(victim, savetype, 0) {
-                send_to_char(&mut game.descriptors, ch, NOEFFECT);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noeffect"));
                 return;
             }
 
@@ -467,7 +418,7 @@ pub fn mag_affects(
         }
         SPELL_POISON => {
             if mag_savingthrow(victim, savetype, 0) {
-                send_to_char(&mut game.descriptors, ch, NOEFFECT);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noeffect"));
                 return;
             }
             af[0].location = ApplyType::Str;
@@ -564,7 +515,7 @@ pub fn mag_affects(
     if victim.is_npc() && !affected_by_spell(victim, spellnum as i16) {
         for af in af.iter() {
             if victim.aff_flagged(af.bitvector) {
-                send_to_char(&mut game.descriptors, ch, NOEFFECT);
+                send_to_char(&mut game.descriptors, ch, db.msg(ch, "noeffect"));
                 return;
             }
         }
@@ -576,7 +527,7 @@ pub fn mag_affects(
      */
     let ch = chars.get(chid);
     if affected_by_spell(victim, spellnum as i16) && !(accum_duration || accum_affect) {
-        send_to_char(&mut game.descriptors, ch, NOEFFECT);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noeffect"));
         return;
     }
 
@@ -839,6 +790,170 @@ pub fn mag_areas(
     }
 }
 
+/*
+ * Room spells: they put an affect on the caster's room, or on an exit of
+ * it, which wears off by itself in room_affect_update().  Casting one
+ * again on the same room (or exit) only renews it.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn mag_rooms(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    level: u8,
+    chid: DepotId,
+    tdir: Option<usize>,
+    spellnum: i32,
+) {
+    let ch = chars.get(chid);
+    let room = ch.in_room();
+    let mut af = RoomAffectedType {
+        _type: spellnum as i16,
+        duration: 0,
+        modifier: 0,
+        bitvector: RoomAffectFlags::empty(),
+    };
+    let to_char;
+    let to_room;
+
+    match spellnum {
+        SPELL_DARKNESS => {
+            af.bitvector = RoomAffectFlags::DARKNESS;
+            af.duration = 2 + level as i16 / 10;
+            to_char = "You call upon the shadows and darkness swallows the room.";
+            to_room = "$n calls upon the shadows and darkness swallows the room!";
+        }
+        SPELL_FOG => {
+            af.bitvector = RoomAffectFlags::FOG;
+            af.duration = 3 + level as i16 / 8;
+            to_char = "A thick fog rolls out of your hands and fills the room.";
+            to_room = "A thick fog rolls out of $n's hands and fills the room.";
+        }
+        SPELL_SILENCE => {
+            af.bitvector = RoomAffectFlags::SILENCE;
+            af.duration = 1 + level as i16 / 10;
+            to_char = "You raise a finger to your lips and all sound dies away.";
+            to_room = "$n raises a finger to $s lips and all sound dies away.";
+        }
+        SPELL_WALL_OF_FORCE => {
+            let Some(dir) = tdir else {
+                error!("SYSERR: wall of force cast without an exit");
+                return;
+            };
+            af.bitvector = RoomAffectFlags::WALL;
+            af.duration = 1 + level as i16 / 12;
+            af.modifier = dir as i16;
+            to_char = "A shimmering wall of force springs up to the $T.";
+            to_room = "A shimmering wall of force springs up to the $T at $n's command.";
+
+            /* the wall blocks the way back too */
+            if let Some(exit) = db.world[room as usize].dir_option[dir].as_ref() {
+                let other = exit.to_room;
+                let back = REV_DIR[dir] as usize;
+                if other != NOWHERE
+                    && db.world[other as usize].dir_option[back]
+                        .as_ref()
+                        .is_some_and(|exit| exit.to_room == room)
+                {
+                    affect_to_room(
+                        db,
+                        other,
+                        RoomAffectedType {
+                            modifier: back as i16,
+                            ..af
+                        },
+                    );
+                    send_to_room(
+                        &mut game.descriptors,
+                        chars,
+                        db,
+                        other,
+                        format!(
+                            "A shimmering wall of force springs up to the {}.\r\n",
+                            DIRS[back]
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+        }
+        SPELL_CONSECRATE => {
+            af.bitvector = RoomAffectFlags::CONSECRATED;
+            af.duration = 4 + level as i16 / 5;
+            af.modifier = 50 + 2 * level as i16;
+            to_char = "You consecrate the ground and a soft golden glow fills the room.";
+            to_room = "$n consecrates the ground and a soft golden glow fills the room.";
+        }
+        _ => {
+            return;
+        }
+    }
+
+    affect_to_room(db, room, af);
+
+    let dir_name = tdir.map_or("", |dir| DIRS[dir]);
+    let ch = chars.get(chid);
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        to_char,
+        false,
+        Some(ch),
+        None,
+        Some(VictimRef::Str(dir_name)),
+        TO_CHAR,
+    );
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        to_room,
+        false,
+        Some(ch),
+        None,
+        Some(VictimRef::Str(dir_name)),
+        TO_ROOM,
+    );
+}
+
+/* Room affects wear off like those on characters: once a tick. */
+pub fn room_affect_update(game: &mut Game, chars: &Depot<CharData>, db: &mut DB) {
+    for room in 0..db.world.len() {
+        if db.world[room].affected.is_empty() {
+            continue;
+        }
+        let mut worn_off = vec![];
+        db.world[room].affected.retain_mut(|af| {
+            if af.duration >= 1 {
+                af.duration -= 1;
+                true
+            } else if af.duration == -1 {
+                /* permanent */
+                true
+            } else {
+                worn_off.push(af._type);
+                false
+            }
+        });
+        worn_off.dedup();
+        for spellnum in worn_off {
+            if spellnum <= 0 || spellnum > MAX_SPELLS as i16 {
+                continue;
+            }
+            if let Some(wear_off_msg) = &db.spell_info[spellnum as usize].wear_off_msg {
+                send_to_room(
+                    &mut game.descriptors,
+                    chars,
+                    db,
+                    room as RoomRnum,
+                    format!("{}\r\n", wear_off_msg).as_str(),
+                );
+            }
+        }
+    }
+}
+
 /*
  *  Every spell which summons/gates/conjours a mob comes through here.
  *
@@ -1110,7 +1225,7 @@ pub fn mag_unaffects(
 
     if !affected_by_spell(victim, spell as i16) {
         if msg_not_affected {
-            send_to_char(&mut game.descriptors, ch, NOEFFECT);
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "noeffect"));
         }
         return;
     }
@@ -1224,7 +1339,7 @@ pub fn mag_alter_objs(
     let ch = chars.get(chid);
     let obj = objs.get(oid);
     if to_char.is_empty() {
-        send_to_char(&mut game.descriptors, ch, NOEFFECT);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noeffect"));
     } else {
         act(
             &mut game.descriptors,
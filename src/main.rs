@@ -13,7 +13,7 @@ use std::cmp::max;
 use std::collections::LinkedList;
 use std::io::{Error, ErrorKind, Read, Write};
 use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::rc::Rc;
 use std::string::ToString;
@@ -31,30 +31,44 @@ use tungstenite::{accept, Message, WebSocket};
 use depot::{Depot, DepotId, HasId};
 use log::{debug, error, info, warn, LevelFilter};
 
-use log4rs::append::console::ConsoleAppender;
+use log4rs::append::console::{ConsoleAppender, Target};
 use log4rs::append::file::FileAppender;
 use log4rs::config::Appender;
 use log4rs::config::Root;
 use log4rs::encode::pattern::PatternEncoder;
 use util::{can_see, objn, objs, pers};
 
+use crate::act_other::expand_prompt;
 use crate::act_social::free_social_messages;
+use crate::auction::auction_update;
 use crate::ban::{free_invalid_list, isbanned};
 use crate::boards::board_clear_all;
 use crate::config::*;
 use crate::constants::*;
 use crate::db::*;
 use crate::fight::free_messages;
+use crate::friends::friends_notify;
+use crate::graph::export_map;
 use crate::handler::fname;
 use crate::house::house_save_all;
 use crate::interpreter::{command_interpreter, is_abbrev, nanny, perform_alias};
 use crate::magic::affect_update;
 use crate::modify::{show_string, string_add};
 use crate::objsave::crash_save_all;
+use crate::screen::{
+    render_color, screen_reader, strip_decorations, terminal_color_depth, theme_color, ColorDepth,
+    Markup, C_SPR,
+};
+use crate::scripts::script_pulse;
 use crate::structs::ConState::{ConClose, ConDisconnect, ConGetName, ConPassword, ConPlaying};
 use crate::structs::*;
-use crate::telnet::{IAC, TELOPT_ECHO, WILL, WONT};
+use crate::tells::is_ignoring;
+use crate::telnet::{
+    strip_telnet, TelnetEvent, DO, IAC, SB, SE, TELOPT_ECHO, TELOPT_TTYPE, TELQUAL_SEND, WILL, WONT,
+};
 use crate::util::{hmhr, hshr, hssh, sana, touch, DisplayMode, SECS_PER_MUD_HOUR};
+use crate::worldcheck::lint_world;
+use crate::worldsave::save_world;
 
 mod act_comm;
 mod act_informative;
@@ -65,35 +79,50 @@ mod act_other;
 mod act_social;
 mod act_wizard;
 mod alias;
+mod auction;
 mod ban;
+mod bank;
 mod boards;
 mod castle;
+mod channels;
+mod clan;
 mod class;
 mod config;
 mod constants;
 mod db;
 mod depot;
 mod fight;
+mod friends;
 mod graph;
 mod handler;
 mod house;
 mod interpreter;
+mod lang;
 mod limits;
 mod magic;
 mod mail;
 mod mobact;
 mod modify;
 mod objsave;
+mod prefs;
+mod quest;
 mod screen;
+mod scripts;
 mod shops;
+mod sidefile;
 mod spec_assign;
 mod spec_procs;
+mod specscript;
 mod spell_parser;
 mod spells;
 mod structs;
+mod tells;
 mod telnet;
 mod util;
 mod weather;
+mod worldcheck;
+mod worldsave;
+mod zreload;
 
 /// CircleMUD server - A classic text-based multiplayer online role-playing game
 #[derive(Parser, Debug)]
@@ -129,6 +158,22 @@ struct Args {
     #[arg(short = 's', long = "no-specials")]
     no_specials: bool,
 
+    /// Like --check, and also write the world report as JSON to FILE ('-' for stdout)
+    #[arg(long = "check-json", value_name = "FILE")]
+    check_json: Option<String>,
+
+    /// Boot the world, write it back out below DIR and exit
+    #[arg(long = "export-world", value_name = "DIR")]
+    export_world: Option<String>,
+
+    /// Boot the world, write its map as DOT and JSON below DIR and exit
+    #[arg(long = "export-map", value_name = "DIR")]
+    export_map: Option<String>,
+
+    /// Limit --export-map to the zone with this number
+    #[arg(long = "map-zone", value_name = "ZONE", requires = "export_map")]
+    map_zone: Option<i16>,
+
     /// Port number to listen on (must be > 1024)
     #[arg(value_name = "PORT")]
     port: Option<u16>,
@@ -227,6 +272,14 @@ pub struct DescriptorData {
     /* And who is snooping this char	*/
     websocket_input_buffer: Vec<u8>,
     /* Buffer for WebSocket input messages */
+    telnet_pending: Vec<u8>,
+    /* Telnet command split across reads	*/
+    ttype_requests: u8,
+    /* Terminal types asked for so far	*/
+    last_ttype: String,
+    /* Last terminal type received		*/
+    color_depth: ColorDepth,
+    /* Colors the terminal says it handles	*/
 }
 
 impl HasId for DescriptorData {
@@ -269,6 +322,10 @@ impl Default for DescriptorData {
             original: None,
             snooping: None,
             snoop_by: None,
+            telnet_pending: vec![],
+            ttype_requests: 0,
+            last_ttype: String::new(),
+            color_depth: ColorDepth::default(),
         }
     }
 }
@@ -305,21 +362,7 @@ fn main() -> ExitCode {
 
     let mut port = DFLT_PORT;
 
-    let mut game = Game {
-        descriptors: Depot::default(),
-        descriptor_list: vec![],
-        last_desc: 0,
-        circle_shutdown: false,
-        circle_reboot: false,
-        mother_desc: None,
-        websocket_listener: None,
-        mins_since_crashsave: 0,
-        config: Config {
-            nameserver_is_slow: false,
-            track_through_doors: true,
-        },
-        max_players: 0,
-    };
+    let mut game = Game::new();
     let mut texts: Depot<TextData> = Depot::default();
     let mut objs: Depot<ObjData> = Depot::default();
     let mut chars: Depot<CharData> = Depot::default();
@@ -374,8 +417,42 @@ fn main() -> ExitCode {
         info!("Suppressing assignment of special routines.");
     }
 
+    let lint = args.syntax_check || args.check_json.is_some();
+    if lint {
+        db.scheck = true;
+    }
+
+    /* Resolved now, before we change to the data directory. */
+    let check_json = args.check_json.map(|file| {
+        if file == "-" {
+            PathBuf::from(file)
+        } else {
+            env::current_dir()
+                .expect("Failed to get current directory")
+                .join(file)
+        }
+    });
+    let export_dir = args.export_world.map(|export_dir| {
+        env::current_dir()
+            .expect("Failed to get current directory")
+            .join(export_dir)
+    });
+    if export_dir.is_some() {
+        db.scheck = true;
+        info!("World export mode enabled.");
+    }
+    let map_dir = args.export_map.map(|map_dir| {
+        env::current_dir()
+            .expect("Failed to get current directory")
+            .join(map_dir)
+    });
+    if map_dir.is_some() {
+        db.scheck = true;
+        info!("Map export mode enabled.");
+    }
+
     /* All arguments have been parsed, try to open log file. */
-    setup_log(logname);
+    setup_log(logname, lint);
 
     /*
      * Moved here to distinguish command line options and to show up
@@ -397,8 +474,70 @@ fn main() -> ExitCode {
 
     info!("Using {} as data directory.", dir);
 
+    let mut exit_code = ExitCode::SUCCESS;
     if db.scheck {
         boot_world(&mut game, &mut db, &mut chars, &mut texts);
+        if lint {
+            let report = lint_world(&db);
+            let json_to_stdout = check_json.as_deref() == Some(Path::new("-"));
+            if !json_to_stdout {
+                if let Err(err) = report.write_human(&mut std::io::stdout()) {
+                    error!("SYSERR: Unable to write world report: {}", err);
+                }
+            }
+            if let Some(check_json) = &check_json {
+                let written = if json_to_stdout {
+                    report.write_json(&mut std::io::stdout())
+                } else {
+                    fs::File::create(check_json).and_then(|mut file| report.write_json(&mut file))
+                };
+                if let Err(err) = written {
+                    error!(
+                        "SYSERR: Unable to write world report to {}: {}",
+                        check_json.display(),
+                        err
+                    );
+                    exit_code = ExitCode::FAILURE;
+                }
+            }
+            if report.has_errors() {
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+        if let Some(export_dir) = export_dir {
+            info!("Exporting world to {}.", export_dir.display());
+            if let Err(err) = save_world(&db, &texts, &export_dir) {
+                error!(
+                    "SYSERR: Unable to export world to {}: {}",
+                    export_dir.display(),
+                    err
+                );
+                return ExitCode::FAILURE;
+            }
+        }
+        if let Some(map_dir) = map_dir {
+            let zone = match args.map_zone {
+                None => None,
+                Some(vnum) => match db.real_zone(vnum) {
+                    Some(zone) => Some(zone),
+                    None => {
+                        error!("SYSERR: Zone #{} does not exist.", vnum);
+                        return ExitCode::FAILURE;
+                    }
+                },
+            };
+            match export_map(&db, zone, &map_dir) {
+                Ok(base) => info!("Map written to {}.dot and .json.", base.display()),
+                Err(err) => {
+                    error!(
+                        "SYSERR: Unable to export map to {}: {}",
+                        map_dir.display(),
+                        err
+                    );
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
     } else {
         info!("Running game on port {}.", port);
         game.mother_desc = Some(init_socket(port));
@@ -424,10 +563,28 @@ fn main() -> ExitCode {
     }
 
     info!("Done.");
-    ExitCode::SUCCESS
+    exit_code
 }
 
 impl Game {
+    pub(crate) fn new() -> Game {
+        Game {
+            descriptors: Depot::default(),
+            descriptor_list: vec![],
+            last_desc: 0,
+            circle_shutdown: false,
+            circle_reboot: false,
+            mother_desc: None,
+            websocket_listener: None,
+            mins_since_crashsave: 0,
+            config: Config {
+                nameserver_is_slow: false,
+                track_through_doors: true,
+            },
+            max_players: 0,
+        }
+    }
+
     /* Init sockets, run game, and cleanup sockets */
     fn init_game(
         &mut self,
@@ -712,7 +869,7 @@ impl Game {
             for d_id in self.descriptor_list.clone() {
                 let desc = self.desc_mut(d_id);
                 if !desc.output.is_empty() {
-                    _ = process_output(&mut self.descriptors, chars, d_id);
+                    _ = process_output(&mut self.descriptors, chars, db, d_id);
                     let desc = self.desc_mut(d_id);
                     if desc.output.is_empty() {
                         desc.has_prompt = true;
@@ -724,10 +881,11 @@ impl Game {
             for d_id in self.descriptor_list.clone() {
                 let d = self.desc_mut(d_id);
                 if !d.has_prompt && d.output.is_empty() {
-                    let text = &d.make_prompt(chars);
+                    let text = d.make_prompt(chars, db);
+                    let text = render_color(text.as_bytes(), &d.markup(chars));
                     let d = self.desc_mut(d_id);
                     if let Some(ConnectionType::Telnet(ref mut stream)) = d.connection {
-                        _ = write_to_descriptor(stream, text.as_bytes());
+                        _ = write_to_descriptor(stream, &text);
                     }
                     d.has_prompt = true;
                 }
@@ -832,6 +990,14 @@ impl Game {
             self.perform_violence(chars, db, texts, objs);
         }
 
+        if pulse.is_multiple_of(PULSE_SCRIPT) {
+            script_pulse(self, db, chars, texts, objs);
+        }
+
+        if pulse.is_multiple_of(PULSE_AUCTION) {
+            auction_update(self, chars, db, texts, objs);
+        }
+
         if (pulse as u64).is_multiple_of(SECS_PER_MUD_HOUR * PASSES_PER_SEC as u64) {
             self.weather_and_time(chars, db, 1);
             affect_update(self, chars, db, objs);
@@ -924,7 +1090,58 @@ impl DescriptorData {
         }
     }
 
-    fn make_prompt(&mut self, chars: &Depot<CharData>) -> String {
+    /* The player behind this descriptor, even while switched into a mob. */
+    fn player<'a>(&self, chars: &'a Depot<CharData>) -> Option<&'a CharData> {
+        self.original.or(self.character).map(|chid| chars.get(chid))
+    }
+
+    /*
+     * How the color markup is shown on this descriptor: not at all until a
+     * player is attached, then as deep as the player asked for with the
+     * color command, or else as the terminal announced, in the colors of
+     * the player's theme.
+     */
+    fn markup<'a>(&self, chars: &'a Depot<CharData>) -> Markup<'a> {
+        let Some(ch) = self.player(chars) else {
+            return Markup::new(ColorDepth::None);
+        };
+        if !clr!(ch, C_SPR) {
+            return Markup::new(ColorDepth::None);
+        }
+        Markup {
+            depth: ch.player_specials.color_depth.unwrap_or(self.color_depth),
+            theme: std::array::from_fn(|cat| theme_color(ch, cat)),
+        }
+    }
+
+    /*
+     * Terminal type negotiation (RFC 1091).  Each request gets the next name
+     * the client knows; MTTS clients answer with their name, their terminal
+     * type and then "MTTS <bits>", and repeat the last one when done.
+     */
+    fn telnet_event(&mut self, event: TelnetEvent) {
+        match event {
+            TelnetEvent::WillTtype => self.request_ttype(),
+            TelnetEvent::Ttype(ttype) => {
+                if let Some(depth) = terminal_color_depth(&ttype) {
+                    self.color_depth = max(self.color_depth, depth);
+                }
+                if ttype != self.last_ttype && self.ttype_requests < 3 {
+                    self.request_ttype();
+                }
+                self.last_ttype = ttype;
+            }
+        }
+    }
+
+    fn request_ttype(&mut self) {
+        self.ttype_requests += 1;
+        if let Some(ConnectionType::Telnet(ref mut stream)) = self.connection {
+            _ = write_to_descriptor(stream, &[IAC, SB, TELOPT_TTYPE, TELQUAL_SEND, IAC, SE]);
+        }
+    }
+
+    fn make_prompt(&mut self, chars: &Depot<CharData>, db: &DB) -> String {
         let Some(character_id) = self.character else {
             error!("SYSERR: make_prompt: no character");
             return "".to_string();
@@ -940,6 +1157,17 @@ impl DescriptorData {
                 "\r\n[ Return to continue, (q)uit, (r)efresh, (b)ack, or page number ({}/{}) ]",
                 self.showstr_page, self.showstr_count
             ));
+        } else if self.connected == ConPlaying
+            && !chars.get(character_id).is_npc()
+            && !chars.get(character_id).player_specials.prompt.is_empty()
+        {
+            let character = chars.get(character_id);
+            prompt.push_str(&expand_prompt(
+                chars,
+                db,
+                character,
+                &character.player_specials.prompt,
+            ));
         } else if self.connected == ConPlaying && !chars.get(character_id).is_npc() {
             let character = chars.get(character_id);
             if character.get_invis_lev() != 0 && prompt.len() < MAX_PROMPT_LENGTH {
@@ -1123,6 +1351,8 @@ impl Game {
         }
         newd.desc_num = self.last_desc;
 
+        /* ask for the terminal type, it tells which colors can be used */
+        newd.output.extend_from_slice(&[IAC, DO, TELOPT_TTYPE]);
         newd.write_to_output(&db.greetings);
 
         /* append to list */
@@ -1262,6 +1492,7 @@ impl Game {
 fn process_output(
     descs: &mut Depot<DescriptorData>,
     chars: &Depot<CharData>,
+    db: &DB,
     desc_id: DepotId,
 ) -> Result<usize, Error> {
     /* we may need this \r\n for later -- see below */
@@ -1283,7 +1514,15 @@ fn process_output(
     }
 
     /* add a prompt */
-    i.extend_from_slice(desc.make_prompt(chars).as_bytes());
+    i.extend_from_slice(desc.make_prompt(chars, db).as_bytes());
+
+    /* screen readers do without the drawings */
+    if desc.player(chars).is_some_and(screen_reader) {
+        i = strip_decorations(&i);
+    }
+
+    /* and turn the color markup into whatever the terminal can show */
+    let mut i = render_color(&i, &desc.markup(chars));
 
     /*
      * now, send the output.  If this is an 'interruption', use the prepended
@@ -1393,7 +1632,6 @@ fn write_to_descriptor(stream: &mut TcpStream, txt: &[u8]) -> Result<usize, Erro
  * standards, there are so many of them. -gg 6/30/98
  */
 fn perform_socket_read(d: &mut DescriptorData) -> std::io::Result<usize> {
-    let input = &mut d.inbuf;
     let mut buf = [0u8; 4096];
 
     let read_result = match &mut d.connection {
@@ -1417,6 +1655,20 @@ fn perform_socket_read(d: &mut DescriptorData) -> std::io::Result<usize> {
         }
     };
 
+    let read_result = match (read_result, &d.connection) {
+        (Ok(r), Some(ConnectionType::Telnet(_))) if r > 0 => {
+            let mut events = vec![];
+            let data = strip_telnet(&mut d.telnet_pending, &buf[..r], &mut events);
+            for event in events {
+                d.telnet_event(event);
+            }
+            buf[..data.len()].copy_from_slice(&data);
+            Ok(data.len())
+        }
+        (read_result, _) => read_result,
+    };
+    let input = &mut d.inbuf;
+
     match read_result {
         Err(err) => {
             error!("{:?}", err);
@@ -1424,16 +1676,12 @@ fn perform_socket_read(d: &mut DescriptorData) -> std::io::Result<usize> {
         }
         Ok(r) => match std::str::from_utf8(&buf[..r]) {
             Err(err) => {
-                if err.valid_up_to() == 0 && buf[0] == IAC && r == 3 {
-                    // this is a telnet command, no worries we can ignore that read
-                } else {
-                    error!(
-                        "UTF-8 ERROR read={} invalid={:?} err={:?}",
-                        r,
-                        buf[err.valid_up_to()],
-                        err
-                    );
-                }
+                error!(
+                    "UTF-8 ERROR read={} invalid={:?} err={:?}",
+                    r,
+                    buf[err.valid_up_to()],
+                    err
+                );
                 Ok(0)
             }
             Ok(s) => {
@@ -1772,6 +2020,7 @@ impl Game {
                             )
                             .as_str(),
                         );
+                        friends_notify(self, chars, link_challenged_id, false);
                     }
                     _ => {
                         let name = chars.get(character_id).get_name();
@@ -1953,7 +2202,39 @@ impl Game {
                 continue;
             };
             let character = chars.get(character_id);
-            if !character.awake() || !db.outside(character) {
+            if !character.awake() || !db.under_sky(character) {
+                continue;
+            }
+            let desc = self.desc_mut(desc_id);
+
+            desc.write_to_output(messg);
+        }
+    }
+
+    fn send_to_zone_outdoor(
+        &mut self,
+        chars: &Depot<CharData>,
+        db: &DB,
+        zone: ZoneRnum,
+        messg: &str,
+    ) {
+        if messg.is_empty() {
+            return;
+        }
+
+        for desc_id in self.descriptor_list.clone() {
+            let desc = self.desc(desc_id);
+            if desc.state() != ConPlaying {
+                continue;
+            }
+            let Some(character_id) = desc.character else {
+                continue;
+            };
+            let character = chars.get(character_id);
+            if !character.awake()
+                || !db.under_sky(character)
+                || db.world[character.in_room() as usize].zone != zone
+            {
                 continue;
             }
             let desc = self.desc_mut(desc_id);
@@ -1978,21 +2259,53 @@ pub fn send_to_room(
     }
 }
 
+/* send_to_room() with a message of the catalog, in the language of each */
+pub fn send_msg_to_room(
+    descs: &mut Depot<DescriptorData>,
+    chars: &Depot<CharData>,
+    db: &DB,
+    room: RoomRnum,
+    id: &str,
+) {
+    for &chid in &db.world[room as usize].peoples {
+        let ch = chars.get(chid);
+        if let Some(desc_id) = ch.desc {
+            let desc = descs.get_mut(desc_id);
+            desc.write_to_output(db.msg(ch, id));
+        }
+    }
+}
+
 const ACTNULL: &str = "<NULL>";
 
+/* the text given to act(), or the id of a message translated for each reader */
+#[derive(Clone, Copy)]
+enum ActText<'a> {
+    Plain(&'a str),
+    Msg(&'a str, &'a [&'a str]),
+}
+
 /* higher-level communication: the act() function */
 #[allow(clippy::too_many_arguments)]
 fn perform_act(
     descs: &mut Depot<DescriptorData>,
     chars: &Depot<CharData>,
     db: &DB,
-    orig: &str,
+    text: ActText,
     ch: Option<&CharData>,
     obj: Option<&ObjData>,
     vict_obj: Option<VictimRef>,
     to: &CharData,
 ) {
     let mut uppercasenext = false;
+    let reader = to
+        .desc
+        .and_then(|d_id| descs.get(d_id).player(chars))
+        .unwrap_or(to);
+    let (orig, args) = match text {
+        ActText::Plain(orig) => (orig, &[][..]),
+        ActText::Msg(id, args) => (db.msg(reader, id), args),
+    };
     let mut orig = orig.to_string();
     let mut i: Rc<str>;
     let mut buf = String::new();
@@ -2147,6 +2460,13 @@ fn perform_act(
                 '$' => {
                     i = Rc::from("$");
                 }
+                /* arguments of a catalog message, translated as well */
+                c @ '1'..='9' if !args.is_empty() => {
+                    i = match args.get(c as usize - '1' as usize) {
+                        Some(arg) => Rc::from(db.msg(reader, arg)),
+                        None => Rc::from(ACTNULL),
+                    };
+                }
                 _ => {
                     error!("SYSERR: Illegal $-code to act(): {}", orig);
                     error!("SYSERR: {}", orig);
@@ -2192,6 +2512,13 @@ macro_rules! sendok {
     };
 }
 
+/* what an ignored player does isn't shown, but for the blows of a fight */
+fn act_ignored(to: &CharData, ch: Option<&CharData>) -> bool {
+    ch.is_some_and(|c| {
+        is_ignoring(to, c) && to.fighting_id() != Some(c.id()) && c.fighting_id() != Some(to.id())
+    })
+}
+
 #[derive(Clone, Copy)]
 pub enum VictimRef<'a> {
     Char(&'a CharData),
@@ -2214,7 +2541,58 @@ pub fn act(
     if str.is_empty() {
         return;
     }
+    act_text(
+        descs,
+        chars,
+        db,
+        ActText::Plain(str),
+        hide_invisible,
+        ch,
+        obj,
+        vict_obj,
+        _type,
+    );
+}
 
+/* act() with a message of the catalog, each reader getting it in their language */
+#[allow(clippy::too_many_arguments)]
+pub fn act_msg(
+    descs: &mut Depot<DescriptorData>,
+    chars: &Depot<CharData>,
+    db: &DB,
+    id: &str,
+    args: &[&str],
+    hide_invisible: bool,
+    ch: Option<&CharData>,
+    obj: Option<&ObjData>,
+    vict_obj: Option<VictimRef>,
+    _type: i32,
+) {
+    act_text(
+        descs,
+        chars,
+        db,
+        ActText::Msg(id, args),
+        hide_invisible,
+        ch,
+        obj,
+        vict_obj,
+        _type,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn act_text(
+    descs: &mut Depot<DescriptorData>,
+    chars: &Depot<CharData>,
+    db: &DB,
+    str: ActText,
+    hide_invisible: bool,
+    ch: Option<&CharData>,
+    obj: Option<&ObjData>,
+    vict_obj: Option<VictimRef>,
+    _type: i32,
+) {
     /*
      * Warning: the following TO_SLEEP code is a hack.
      *
@@ -2246,7 +2624,7 @@ pub fn act(
     if _type == TO_VICT {
         if vict_obj.is_some() {
             if let Some(VictimRef::Char(to_ch)) = vict_obj {
-                if sendok!(to_ch, to_sleeping) {
+                if sendok!(to_ch, to_sleeping) && !act_ignored(to_ch, ch) {
                     perform_act(descs, chars, db, str, ch, obj, vict_obj, to_ch);
                 }
             } else {
@@ -2283,7 +2661,7 @@ pub fn act(
         } else {
             false
         };
-        if !sendok!(to, to_sleeping) || same_id {
+        if !sendok!(to, to_sleeping) || same_id || act_ignored(to, ch) {
             continue;
         }
         let cannot_see = if let Some(c) = ch {
@@ -2297,17 +2675,8 @@ pub fn act(
         if _type != TO_ROOM && vict_obj.is_none() {
             continue;
         }
-        let same_chr;
-        if vict_obj.is_some() {
-            if let Some(VictimRef::Char(p)) = vict_obj {
-                same_chr = to_id == p.id();
-            } else {
-                error!("Error in act: invalid CharData ref");
-                continue;
-            }
-        } else {
-            same_chr = false;
-        }
+        /* the victim may also be an object or a text ($T), as for TO_ROOM */
+        let same_chr = matches!(vict_obj, Some(VictimRef::Char(p)) if p.id() == to_id);
         if _type != TO_ROOM && same_chr {
             continue;
         }
@@ -2316,8 +2685,14 @@ pub fn act(
     }
 }
 
-fn setup_log(logfile: Option<&str>) {
-    let stdout = ConsoleAppender::builder().build();
+/* with 'to_stderr', the console log keeps out of a report written to stdout */
+fn setup_log(logfile: Option<&str>, to_stderr: bool) {
+    let target = if to_stderr {
+        Target::Stderr
+    } else {
+        Target::Stdout
+    };
+    let stdout = ConsoleAppender::builder().target(target).build();
 
     let mut config_builder = log4rs::config::Config::builder()
         .appender(Appender::builder().build("stdout", Box::new(stdout)));
@@ -105,10 +105,14 @@ impl Game {
             /* Mob Movement */
             let door = rand_number(0, 18);
             let ch = chars.get(chid);
-            if let Some(exit) = db.exit(ch, door as usize) {
+            let exit = if door < NUM_OF_DIRS as u32 {
+                db.exit(ch, door as usize)
+            } else {
+                None
+            };
+            if let Some(exit) = exit {
                 if !ch.mob_flagged(MOB_SENTINEL)
                     && ch.get_pos() == Position::Standing
-                    && door < NUM_OF_DIRS as u32
                     && db.can_go(ch, door as usize)
                     && !db.room_flagged(exit.to_room, RoomFlags::NOMOB | RoomFlags::DEATH)
                     && (!ch.mob_flagged(MOB_STAY_ZONE)
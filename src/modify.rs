@@ -44,10 +44,10 @@ use std::rc::Rc;
 use log::error;
 
 use crate::boards::{board_save_board, BOARD_MAGIC};
-use crate::config::{MENU, NOPERSON};
 use crate::depot::{Depot, DepotId, HasId};
 use crate::handler::{get_char_vis, FindFlags};
 use crate::interpreter::{any_one_arg, delete_doubledollar, one_argument};
+use crate::screen::close_markup;
 use crate::spell_parser::{find_skill_num, UNUSED_SPELLNAME};
 use crate::spells::TOP_SPELL_DEFINE;
 use crate::structs::ConState::{ConExdesc, ConMenu, ConPlaying};
@@ -146,6 +146,8 @@ pub fn string_add(
     };
     let desc = game.desc_mut(d_id);
     if terminator {
+        /* what was written keeps its colors to itself */
+        *text = close_markup(text);
         if desc.state() == ConPlaying && chars.get(chid).plr_flagged(PLR_MAILING) {
             let mail_to = desc.mail_to;
             let from = chars.get(chid).get_idnum();
@@ -168,7 +170,7 @@ pub fn string_add(
             desc.mail_to = 0;
         }
         if desc.state() == ConExdesc {
-            desc.write_to_output(MENU);
+            desc.write_to_output(db.msg(chars.get(chid), "nanny.menu"));
             desc.set_state(ConMenu);
         }
         if game.desc(d_id).state() == ConPlaying && !chars.get(chid).is_npc() {
@@ -210,7 +212,7 @@ Skill being one of the following:\r\n",
         );
         let mut qend = 0;
         for i in 0..TOP_SPELL_DEFINE + 1 {
-            if db.spell_info[i].name == UNUSED_SPELLNAME {
+            if &*db.spell_info[i].name == UNUSED_SPELLNAME {
                 /* This is valid. */
                 continue;
             }
@@ -239,7 +241,7 @@ Skill being one of the following:\r\n",
         None,
         FindFlags::CHAR_WORLD,
     ) else {
-        send_to_char(&mut game.descriptors, ch, NOPERSON);
+        send_to_char(&mut game.descriptors, ch, db.msg(ch, "noperson"));
         return;
     };
     let vict_id = vict.id();
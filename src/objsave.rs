@@ -964,7 +964,7 @@ fn crash_extract_objs(
     db.extract_obj(chars, objs, oid);
 }
 
-fn crash_is_unrentable(obj: &ObjData) -> bool {
+pub(crate) fn crash_is_unrentable(obj: &ObjData) -> bool {
     if obj.obj_flagged(ExtraFlags::NORENT)
         || obj.get_obj_rent() < 0
         || obj.get_obj_rnum() == NOTHING
@@ -1664,7 +1664,7 @@ fn gen_receptionist(
         return false;
     }
 
-    if recep.awake() {
+    if !recep.awake() {
         send_to_char(
             &mut game.descriptors,
             ch,
@@ -0,0 +1,242 @@
+/* ************************************************************************
+*   File: prefs.rs                                      Part of CircleMUD *
+*  Usage: loading/saving player preferences kept outside the player file  *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * The player file is a fixed-size binary record, so settings made of text
+ * (such as the prompt template) live in plrprefs/, as a side file of a
+ * single record.  Unknown tags are ignored so that older servers can read
+ * newer files.
+ */
+
+use std::fs;
+use std::io::{BufRead, ErrorKind, Write};
+use std::rc::Rc;
+
+use log::error;
+
+use crate::screen::{valid_color_code, ColorDepth, COLOR_THEMES, MSG_CATEGORIES};
+use crate::sidefile::{open_side_file, read_records, save_side_file, write_record, SideRecord};
+use crate::structs::{CharData, PlayerSpecialData, MAX_SCREEN_WIDTH, MIN_SCREEN_WIDTH};
+use crate::util::{get_filename, FileType};
+
+fn apply_prefs(rec: &SideRecord, ps: &mut PlayerSpecialData) {
+    for (tag, value) in &rec.fields {
+        let value = value.as_str();
+        match tag.as_str() {
+            "Prompt" => ps.prompt = Rc::from(value),
+            "Language" => ps.language = Rc::from(value),
+            "Width" => {
+                if let Some(width) = value
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|w| (MIN_SCREEN_WIDTH..=MAX_SCREEN_WIDTH).contains(w))
+                {
+                    ps.screen_width = width;
+                }
+            }
+            "ChannelOff" => ps.channels_off.push(Rc::from(value)),
+            "Ignore" => {
+                if let Ok(idnum) = value.parse::<i64>() {
+                    ps.ignored.push(idnum);
+                }
+            }
+            "Friend" => {
+                if let Ok(idnum) = value.parse::<i64>() {
+                    ps.friends.push(idnum);
+                }
+            }
+            "ColorDepth" => ps.color_depth = ColorDepth::from_name(value),
+            "Theme" => {
+                if let Some(theme) = COLOR_THEMES.iter().position(|t| t.name == value) {
+                    ps.color_theme = theme;
+                }
+            }
+            "Color" => {
+                let (name, color) = value.split_once(' ').unwrap_or((value, ""));
+                let Some(cat) = MSG_CATEGORIES.iter().position(|(n, _)| *n == name) else {
+                    continue;
+                };
+                if color == "none" {
+                    ps.colors[cat] = Some(Rc::from(""));
+                } else if valid_color_code(color) {
+                    ps.colors[cat] = Some(Rc::from(color));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn parse_prefs<R: BufRead>(reader: R, ps: &mut PlayerSpecialData) {
+    read_records(reader, "preference file", |rec| {
+        apply_prefs(rec, ps);
+        Ok(())
+    });
+}
+
+pub fn write_prefs_to<W: Write>(writer: &mut W, ps: &PlayerSpecialData) -> std::io::Result<()> {
+    let mut fields = vec![];
+    if !ps.prompt.is_empty() {
+        fields.push(("Prompt", ps.prompt.to_string()));
+    }
+    if !ps.language.is_empty() {
+        fields.push(("Language", ps.language.to_string()));
+    }
+    if ps.screen_width != 0 {
+        fields.push(("Width", ps.screen_width.to_string()));
+    }
+    for name in &ps.channels_off {
+        fields.push(("ChannelOff", name.to_string()));
+    }
+    for idnum in &ps.ignored {
+        fields.push(("Ignore", idnum.to_string()));
+    }
+    for idnum in &ps.friends {
+        fields.push(("Friend", idnum.to_string()));
+    }
+    if let Some(depth) = ps.color_depth {
+        fields.push(("ColorDepth", depth.name().to_string()));
+    }
+    if ps.color_theme != 0 {
+        fields.push(("Theme", COLOR_THEMES[ps.color_theme].name.to_string()));
+    }
+    for (cat, color) in ps.colors.iter().enumerate() {
+        if let Some(color) = color {
+            let color = if color.is_empty() { "none" } else { color };
+            fields.push(("Color", format!("{} {}", MSG_CATEGORIES[cat].0, color)));
+        }
+    }
+    write_record(writer, "prefs", &fields)
+}
+
+fn has_prefs(ps: &PlayerSpecialData) -> bool {
+    !ps.prompt.is_empty()
+        || !ps.language.is_empty()
+        || ps.screen_width != 0
+        || !ps.channels_off.is_empty()
+        || !ps.ignored.is_empty()
+        || !ps.friends.is_empty()
+        || ps.color_depth.is_some()
+        || ps.color_theme != 0
+        || ps.colors.iter().any(Option::is_some)
+}
+
+pub fn read_prefs(ch: &mut CharData) {
+    let mut fname = String::new();
+    if !get_filename(&mut fname, FileType::Prefs, ch.get_name()) {
+        return;
+    }
+    if let Some(reader) = open_side_file(&fname) {
+        parse_prefs(reader, &mut ch.player_specials);
+    }
+}
+
+/* Saved along with the rest of the player (see save_char()) */
+pub fn write_prefs(ch: &CharData) {
+    let mut fname = String::new();
+    if !get_filename(&mut fname, FileType::Prefs, ch.get_name()) {
+        return;
+    }
+    if !has_prefs(&ch.player_specials) {
+        delete_prefs(ch.get_name());
+        return;
+    }
+    save_side_file(&fname, |w| write_prefs_to(w, &ch.player_specials));
+}
+
+pub fn delete_prefs(charname: &str) {
+    let mut filename = String::new();
+
+    if !get_filename(&mut filename, FileType::Prefs, charname) {
+        return;
+    }
+
+    if let Err(err) = fs::remove_file(&filename) {
+        if err.kind() != ErrorKind::NotFound {
+            error!("SYSERR: deleting preference file {}: {}", filename, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod prefs_tests {
+    use super::*;
+    use crate::screen::MsgCategory;
+
+    fn read(fields: &str) -> PlayerSpecialData {
+        let mut ps = CharData::default().player_specials;
+        parse_prefs(format!("#prefs\n{}End\n", fields).as_bytes(), &mut ps);
+        ps
+    }
+
+    #[test]
+    fn test_prefs_roundtrip() {
+        let mut ps = CharData::default().player_specials;
+        ps.prompt = Rc::from("%hH %mM %vV> ");
+        ps.language = Rc::from("fr");
+        ps.screen_width = 120;
+        ps.channels_off = vec![Rc::from("newbie"), Rc::from("trade")];
+        ps.ignored = vec![12, 15];
+        ps.friends = vec![7];
+        ps.color_depth = Some(ColorDepth::Ansi256);
+        let mut buf = vec![];
+        write_prefs_to(&mut buf, &ps).unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "#prefs\nPrompt: %hH %mM %vV> \nLanguage: fr\nWidth: 120\n\
+             ChannelOff: newbie\nChannelOff: trade\nIgnore: 12\nIgnore: 15\n\
+             Friend: 7\nColorDepth: 256\nEnd\n"
+        );
+
+        let mut other = CharData::default().player_specials;
+        parse_prefs(buf.as_slice(), &mut other);
+        /* the space the prompt ends with is kept */
+        assert_eq!(&*other.prompt, "%hH %mM %vV> ");
+        assert_eq!(&*other.language, "fr");
+        assert_eq!(other.screen_width, 120);
+        assert_eq!(other.channels_off, ps.channels_off);
+        assert_eq!(other.ignored, vec![12, 15]);
+        assert_eq!(other.friends, vec![7]);
+        assert_eq!(other.color_depth, Some(ColorDepth::Ansi256));
+    }
+
+    #[test]
+    fn test_color_prefs() {
+        let mut ps = CharData::default().player_specials;
+        ps.color_theme = 1;
+        ps.colors[MsgCategory::Say as usize] = Some(Rc::from(""));
+        ps.colors[MsgCategory::Tell as usize] = Some(Rc::from("&#ff8800"));
+        let mut buf = vec![];
+        write_prefs_to(&mut buf, &ps).unwrap();
+        let mut other = CharData::default().player_specials;
+        parse_prefs(buf.as_slice(), &mut other);
+        assert_eq!(other.color_theme, 1);
+        assert_eq!(other.colors, ps.colors);
+
+        /* a color turned off is kept apart from one never set */
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("Color: say none\n"));
+
+        /* what this server can't show is dropped, not guessed at */
+        let ps = read("Theme: plaid\nColor: say &zz\nColor: shouting &r\nColorDepth: 99\n");
+        assert_eq!(ps.color_theme, 0);
+        assert!(ps.colors.iter().all(Option::is_none));
+        assert_eq!(ps.color_depth, None);
+    }
+
+    #[test]
+    fn test_unknown_prefs_are_ignored() {
+        let ps = read("Shiny: yes\nWidth: 5\nPrompt: %h:%m> \n");
+        assert_eq!(&*ps.prompt, "%h:%m> ");
+        assert_eq!(ps.screen_width, 0);
+        assert!(has_prefs(&ps));
+    }
+}
@@ -0,0 +1,1267 @@
+/* ************************************************************************
+*   File: quest.rs                                      Part of CircleMUD *
+*  Usage: Quests offered by quest masters and the players' quest journal  *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+use std::cmp::{max, min};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+
+use log::{error, info};
+
+use crate::act_other::do_gen_tog;
+use crate::db::{LoadType, DB, QUEST_FILE};
+use crate::depot::{Depot, DepotId};
+use crate::handler::obj_to_char;
+use crate::interpreter::{half_chop, is_abbrev, SCMD_QUEST};
+use crate::limits::gain_exp;
+use crate::sidefile::{open_side_file, read_records, save_side_file, write_record, SideRecord};
+use crate::structs::{
+    CharData, IdxType, MobVnum, ObjRnum, ObjVnum, RoomVnum, NOBODY, NOTHING, NOWHERE,
+};
+use crate::util::{get_filename, FileType};
+use crate::{act, send_to_char, Game, ObjData, TextData, VictimRef, TO_CHAR, TO_ROOM};
+
+pub type QuestVnum = IdxType;
+
+/* How many quests a player may have under way at the same time */
+pub const MAX_ACTIVE_QUESTS: usize = 5;
+
+/* What must be done to complete a quest */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestType {
+    Kill,    /* kill <amount> of mob <target> */
+    Fetch,   /* bring <amount> of obj <target> back to the quest master */
+    Visit,   /* walk into room <target> */
+    Deliver, /* give <amount> of obj <target> to mob <recipient> */
+}
+
+const QUEST_TYPE_LETTERS: [(char, QuestType); 4] = [
+    ('K', QuestType::Kill),
+    ('F', QuestType::Fetch),
+    ('V', QuestType::Visit),
+    ('D', QuestType::Deliver),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestData {
+    pub vnum: QuestVnum,
+    pub name: String,
+    pub description: String,
+    /* told by the quest master when the quest is accepted */
+    pub giver: MobVnum,
+    /* vnum of the quest master offering it */
+    pub qtype: QuestType,
+    pub target: IdxType,
+    /* mob, obj or room vnum, depending on the type */
+    pub amount: i32,
+    pub min_level: u8,
+    pub recipient: MobVnum,
+    /* who gets the goods of a delivery, NOBODY otherwise */
+    pub gold: i32,
+    pub exp: i32,
+    pub reward_obj: ObjVnum,
+    /* NOTHING for no object */
+    pub quest_points: i32,
+}
+
+/* A quest under way, as kept in the player's journal */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuestEntry {
+    pub vnum: QuestVnum,
+    pub progress: i32,
+    pub fetch_rnum: ObjRnum,
+    /* rnum of the object to fetch, to count how many are carried */
+}
+
+/* Find a quest definition by its vnum */
+pub fn find_quest(db: &DB, vnum: QuestVnum) -> Option<&QuestData> {
+    db.quests
+        .binary_search_by_key(&vnum, |q| q.vnum)
+        .ok()
+        .map(|i| &db.quests[i])
+}
+
+/* Read the quest definitions at boot time (and on 'reload quests') */
+pub fn boot_quests(db: &mut DB) {
+    let fl = match OpenOptions::new().read(true).open(QUEST_FILE) {
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                info!("   Quest file '{}' does not exist.", QUEST_FILE);
+            } else {
+                error!("SYSERR: {} {}", QUEST_FILE, err);
+            }
+            db.quests.clear();
+            return;
+        }
+        Ok(f) => f,
+    };
+    let mut quests = parse_quests(BufReader::new(fl));
+    quests.retain(|q| {
+        let missing = if db.real_mobile(q.giver) == NOBODY {
+            Some(("quest master", q.giver))
+        } else if q.recipient != NOBODY && db.real_mobile(q.recipient) == NOBODY {
+            Some(("recipient", q.recipient))
+        } else if q.reward_obj != NOTHING && db.real_object(q.reward_obj) == NOTHING {
+            Some(("reward object", q.reward_obj))
+        } else {
+            let found = match q.qtype {
+                QuestType::Kill => db.real_mobile(q.target) != NOBODY,
+                QuestType::Fetch | QuestType::Deliver => db.real_object(q.target) != NOTHING,
+                QuestType::Visit => db.real_room(q.target) != NOWHERE,
+            };
+            if found {
+                None
+            } else {
+                Some(("target", q.target))
+            }
+        };
+        if let Some((what, vnum)) = missing {
+            error!(
+                "SYSERR: Quest #{} has a nonexistent {} #{}, skipped",
+                q.vnum, what, vnum
+            );
+            return false;
+        }
+        true
+    });
+    quests.sort_by_key(|q| q.vnum);
+    info!("   {} quests.", quests.len());
+    db.quests = quests;
+}
+
+fn fread_text(lines: &[String], i: &mut usize) -> Result<String, &'static str> {
+    let mut text = String::new();
+    loop {
+        let line = lines.get(*i).ok_or("unexpected end of file")?.trim_end();
+        *i += 1;
+        if let Some(last) = line.strip_suffix('~') {
+            text.push_str(last);
+            return Ok(text);
+        }
+        text.push_str(line);
+        text.push_str("\r\n");
+    }
+}
+
+fn fread_numbers(lines: &[String], i: &mut usize, count: usize) -> Result<Vec<i32>, &'static str> {
+    let numbers: Vec<i32> = lines
+        .get(*i)
+        .ok_or("unexpected end of file")?
+        .split_whitespace()
+        .map_while(|n| n.parse::<i32>().ok())
+        .collect();
+    *i += 1;
+    if numbers.len() != count {
+        return Err("wrong number of values");
+    }
+    Ok(numbers)
+}
+
+fn parse_quest(lines: &[String], i: &mut usize) -> Result<QuestData, &'static str> {
+    let vnum = lines[*i]
+        .trim()
+        .strip_prefix('#')
+        .and_then(|n| n.parse::<QuestVnum>().ok())
+        .ok_or("expected #<quest vnum>")?;
+    *i += 1;
+    let name = fread_text(lines, i)?;
+    let description = fread_text(lines, i)?;
+
+    let line = lines.get(*i).ok_or("unexpected end of file")?;
+    let mut args = line.split_whitespace();
+    let giver = args.next().and_then(|n| n.parse::<MobVnum>().ok());
+    let qtype = args.next().and_then(|t| {
+        QUEST_TYPE_LETTERS
+            .iter()
+            .find(|(letter, _)| t.len() == 1 && t.starts_with(*letter))
+            .map(|(_, qtype)| *qtype)
+    });
+    let rest: Vec<i32> = args.map_while(|n| n.parse::<i32>().ok()).collect();
+    *i += 1;
+    let (Some(giver), Some(qtype), [target, amount, min_level, recipient]) =
+        (giver, qtype, rest.as_slice())
+    else {
+        return Err("expected <giver> <K|F|V|D> <target> <amount> <min level> <recipient>");
+    };
+    if *amount < 1 {
+        return Err("amount must be at least 1");
+    }
+    if qtype == QuestType::Deliver && *recipient < 0 {
+        return Err("a delivery needs a recipient");
+    }
+    let rewards = fread_numbers(lines, i, 4)
+        .map_err(|_| "expected <gold> <exp> <reward obj> <quest points>")?;
+
+    if lines.get(*i).map(|l| l.trim()) != Some("S") {
+        return Err("expected S");
+    }
+    *i += 1;
+
+    Ok(QuestData {
+        vnum,
+        name,
+        description,
+        giver,
+        qtype,
+        target: *target as IdxType,
+        amount: if qtype == QuestType::Visit {
+            1
+        } else {
+            *amount
+        },
+        min_level: (*min_level).clamp(0, u8::MAX as i32) as u8,
+        recipient: if qtype == QuestType::Deliver {
+            *recipient as MobVnum
+        } else {
+            NOBODY
+        },
+        gold: max(rewards[0], 0),
+        exp: max(rewards[1], 0),
+        reward_obj: if rewards[2] < 0 {
+            NOTHING
+        } else {
+            rewards[2] as ObjVnum
+        },
+        quest_points: max(rewards[3], 0),
+    })
+}
+
+/*
+ * The quest file holds one entry per quest:
+ *
+ *   #<vnum>
+ *   <name>~
+ *   <description, told by the quest master>~
+ *   <giver mob> <K|F|V|D> <target vnum> <amount> <min level> <recipient mob>
+ *   <gold> <exp> <reward obj vnum> <quest points>
+ *   S
+ *
+ * and ends with a '$'.  The target is a mob to kill (K), an object to
+ * fetch (F) or deliver (D), or a room to visit (V).  Only deliveries have
+ * a recipient; use -1 for the others, and for no reward object.
+ */
+pub(crate) fn parse_quests(reader: impl BufRead) -> Vec<QuestData> {
+    let lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
+    let mut quests: Vec<QuestData> = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line == "$" {
+            break;
+        }
+        if line.is_empty() || line.starts_with('*') {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        match parse_quest(&lines, &mut i) {
+            Ok(quest) if quests.iter().any(|q| q.vnum == quest.vnum) => {
+                error!(
+                    "SYSERR: Duplicate quest #{} in '{}', skipped",
+                    quest.vnum, QUEST_FILE
+                );
+            }
+            Ok(quest) => quests.push(quest),
+            Err(msg) => {
+                error!(
+                    "SYSERR: Format error in quest file '{}' near line {}: {}",
+                    QUEST_FILE, i, msg
+                );
+                /* skip to the next quest */
+                i = max(i, start + 1);
+                while i < lines.len() && !lines[i].starts_with('#') && lines[i].trim() != "$" {
+                    i += 1;
+                }
+            }
+        }
+    }
+    quests
+}
+
+/*
+ * The journal of a player is a side file of a single record, listing the
+ * quests under way and those already done:
+ *
+ *   #journal
+ *   Active: <quest vnum> <progress>
+ *   Done: <quest vnum>
+ *   End
+ */
+fn parse_journal_record(rec: &SideRecord) -> Result<(Vec<QuestEntry>, Vec<QuestVnum>), String> {
+    let mut active = vec![];
+    let mut done = vec![];
+    for entry in rec.all("Active") {
+        let args: Vec<i32> = entry
+            .split_whitespace()
+            .map_while(|n| n.parse::<i32>().ok())
+            .collect();
+        let [vnum, progress] = args.as_slice() else {
+            return Err(format!("bad Active '{}'", entry));
+        };
+        active.push(QuestEntry {
+            vnum: *vnum as QuestVnum,
+            progress: *progress,
+            fetch_rnum: NOTHING,
+        });
+    }
+    for vnum in rec.all("Done") {
+        let vnum = vnum
+            .trim()
+            .parse::<QuestVnum>()
+            .map_err(|_| format!("bad Done '{}'", vnum))?;
+        done.push(vnum);
+    }
+    Ok((active, done))
+}
+
+pub(crate) fn parse_journal(reader: impl BufRead) -> (Vec<QuestEntry>, Vec<QuestVnum>) {
+    let (journals, _) = read_records(reader, "quest journal", parse_journal_record);
+    journals.into_iter().next().unwrap_or_default()
+}
+
+pub(crate) fn write_journal(
+    w: &mut impl Write,
+    active: &[QuestEntry],
+    done: &[QuestVnum],
+) -> io::Result<()> {
+    let mut fields = vec![];
+    for entry in active {
+        fields.push(("Active", format!("{} {}", entry.vnum, entry.progress)));
+    }
+    for vnum in done {
+        fields.push(("Done", vnum.to_string()));
+    }
+    write_record(w, "journal", &fields)
+}
+
+/*
+ * Forget the quests under way that are no longer in the quest file, so
+ * that they don't count toward MAX_ACTIVE_QUESTS.
+ */
+pub fn forget_removed_quests(db: &DB, ch: &mut CharData) {
+    ch.player_specials
+        .quests
+        .retain(|entry| find_quest(db, entry.vnum).is_some());
+    for entry in ch.player_specials.quests.iter_mut() {
+        entry.fetch_rnum = fetch_rnum(db, entry.vnum);
+    }
+}
+
+/* Load the journal when the player enters the game */
+pub fn read_quests(db: &DB, ch: &mut CharData) {
+    let mut fname = String::new();
+    if !get_filename(&mut fname, FileType::Quest, ch.get_name()) {
+        return;
+    }
+    let Some(reader) = open_side_file(&fname) else {
+        return;
+    };
+    let (active, done) = parse_journal(reader);
+    ch.player_specials.quests = active;
+    ch.player_specials.quests_done = done;
+    forget_removed_quests(db, ch);
+}
+
+/* Save the journal along with the rest of the player (see save_char()) */
+pub fn write_quests(ch: &CharData) {
+    let mut fname = String::new();
+    if !get_filename(&mut fname, FileType::Quest, ch.get_name()) {
+        return;
+    }
+    if ch.player_specials.quests.is_empty() && ch.player_specials.quests_done.is_empty() {
+        delete_quests(ch.get_name());
+        return;
+    }
+    save_side_file(&fname, |w| {
+        write_journal(
+            w,
+            &ch.player_specials.quests,
+            &ch.player_specials.quests_done,
+        )
+    });
+}
+
+pub fn delete_quests(charname: &str) {
+    let mut filename = String::new();
+
+    if !get_filename(&mut filename, FileType::Quest, charname) {
+        return;
+    }
+
+    if let Err(err) = fs::remove_file(&filename) {
+        if err.kind() != ErrorKind::NotFound {
+            error!("SYSERR: deleting quest file {}: {}", filename, err);
+        }
+    }
+}
+
+fn fetch_rnum(db: &DB, vnum: QuestVnum) -> ObjRnum {
+    match find_quest(db, vnum) {
+        Some(q) if q.qtype == QuestType::Fetch => db.real_object(q.target),
+        _ => NOTHING,
+    }
+}
+
+fn mob_name(db: &DB, vnum: MobVnum) -> String {
+    let rnum = db.real_mobile(vnum);
+    if rnum == NOBODY {
+        return "someone".to_string();
+    }
+    db.mob_protos[rnum as usize].player.short_descr.to_string()
+}
+
+fn obj_name(db: &DB, vnum: ObjVnum) -> String {
+    let rnum = db.real_object(vnum);
+    if rnum == NOTHING {
+        return "something".to_string();
+    }
+    db.obj_proto[rnum as usize].short_description.to_string()
+}
+
+fn room_name(db: &DB, vnum: RoomVnum) -> String {
+    let rnum = db.real_room(vnum);
+    if rnum == NOWHERE {
+        return "somewhere".to_string();
+    }
+    db.world[rnum as usize].name.to_string()
+}
+
+/* What the player must do, in words */
+fn quest_objective(db: &DB, q: &QuestData) -> String {
+    match q.qtype {
+        QuestType::Kill => format!("Kill {} of {}", q.amount, mob_name(db, q.target)),
+        QuestType::Fetch => format!("Bring back {} of {}", q.amount, obj_name(db, q.target)),
+        QuestType::Visit => format!("Visit {}", room_name(db, q.target)),
+        QuestType::Deliver => format!(
+            "Deliver {} of {} to {}",
+            q.amount,
+            obj_name(db, q.target),
+            mob_name(db, q.recipient)
+        ),
+    }
+}
+
+fn quest_rewards(db: &DB, q: &QuestData) -> String {
+    let mut rewards = vec![];
+    if q.gold > 0 {
+        rewards.push(format!("{} gold", q.gold));
+    }
+    if q.exp > 0 {
+        rewards.push(format!("{} exp", q.exp));
+    }
+    if q.reward_obj != NOTHING {
+        rewards.push(obj_name(db, q.reward_obj));
+    }
+    if q.quest_points > 0 {
+        rewards.push(format!(
+            "{} quest point{}",
+            q.quest_points,
+            if q.quest_points == 1 { "" } else { "s" }
+        ));
+    }
+    if rewards.is_empty() {
+        "nothing but thanks".to_string()
+    } else {
+        rewards.join(", ")
+    }
+}
+
+/* How many of the objects with this rnum the character carries */
+fn count_carried(objs: &Depot<ObjData>, ch: &CharData, rnum: ObjRnum) -> i32 {
+    ch.carrying
+        .iter()
+        .filter(|&&oid| objs.get(oid).get_obj_rnum() == rnum)
+        .count() as i32
+}
+
+fn quest_done(objs: &Depot<ObjData>, ch: &CharData, q: &QuestData, entry: &QuestEntry) -> bool {
+    if q.qtype == QuestType::Fetch {
+        count_carried(objs, ch, entry.fetch_rnum) >= q.amount
+    } else {
+        entry.progress >= q.amount
+    }
+}
+
+fn quest_progress(
+    objs: &Depot<ObjData>,
+    ch: &CharData,
+    q: &QuestData,
+    entry: &QuestEntry,
+) -> String {
+    match q.qtype {
+        QuestType::Visit if entry.progress > 0 => "done".to_string(),
+        QuestType::Visit => "not yet".to_string(),
+        QuestType::Fetch => format!(
+            "carrying {}/{}",
+            min(count_carried(objs, ch, entry.fetch_rnum), q.amount),
+            q.amount
+        ),
+        _ => format!("{}/{}", min(entry.progress, q.amount), q.amount),
+    }
+}
+
+/* The quest masters standing in the room with the character */
+fn quest_masters_here(db: &DB, chars: &Depot<CharData>, ch: &CharData) -> Vec<DepotId> {
+    db.world[ch.in_room() as usize]
+        .peoples
+        .iter()
+        .copied()
+        .filter(|&mid| {
+            let mob = chars.get(mid);
+            mob.is_npc() && {
+                let vnum = db.get_mob_vnum(mob);
+                db.quests.iter().any(|q| q.giver == vnum)
+            }
+        })
+        .collect()
+}
+
+/*
+ * Count one step of progress on every matching quest of the character,
+ * and tell them about it.
+ */
+fn advance_quests(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &DB,
+    chid: DepotId,
+    matches: impl Fn(&QuestData) -> bool,
+) {
+    let ch = chars.get_mut(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut messages = vec![];
+    for entry in ch.player_specials.quests.iter_mut() {
+        let Some(q) = find_quest(db, entry.vnum) else {
+            continue;
+        };
+        if entry.progress >= q.amount || !matches(q) {
+            continue;
+        }
+        entry.progress += 1;
+        if entry.progress >= q.amount {
+            messages.push(format!(
+                "You have done what {} asked of you in '{}'.\r\n",
+                mob_name(db, q.giver),
+                q.name
+            ));
+        } else {
+            messages.push(format!(
+                "Quest '{}': {}/{}.\r\n",
+                q.name, entry.progress, q.amount
+            ));
+        }
+    }
+    let ch = chars.get(chid);
+    for msg in messages {
+        send_to_char(&mut game.descriptors, ch, &msg);
+    }
+}
+
+/* Called from die(): the killer, or the master of a charmed killer, gets credit. */
+pub fn quest_kill(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &DB,
+    killer_id: DepotId,
+    victim_id: DepotId,
+) {
+    let victim = chars.get(victim_id);
+    if !victim.is_npc() {
+        return;
+    }
+    let vnum = db.get_mob_vnum(victim);
+    let killer = chars.get(killer_id);
+    let credit_id = match killer.master {
+        Some(master) if killer.is_npc() => master,
+        _ => killer_id,
+    };
+    advance_quests(game, chars, db, credit_id, |q| {
+        q.qtype == QuestType::Kill && q.target == vnum
+    });
+}
+
+/* Called from do_simple_move() when the character arrives in a room */
+pub fn quest_visit(game: &mut Game, chars: &mut Depot<CharData>, db: &DB, chid: DepotId) {
+    let vnum = db.get_room_vnum(chars.get(chid).in_room());
+    advance_quests(game, chars, db, chid, |q| {
+        q.qtype == QuestType::Visit && q.target == vnum
+    });
+}
+
+/* Called from perform_give() once the object has changed hands */
+pub fn quest_deliver(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &DB,
+    objs: &Depot<ObjData>,
+    chid: DepotId,
+    vict_id: DepotId,
+    oid: DepotId,
+) {
+    let vict = chars.get(vict_id);
+    if !vict.is_npc() {
+        return;
+    }
+    let recipient = db.get_mob_vnum(vict);
+    let obj_vnum = db.get_obj_vnum(objs.get(oid));
+    advance_quests(game, chars, db, chid, |q| {
+        q.qtype == QuestType::Deliver && q.target == obj_vnum && q.recipient == recipient
+    });
+}
+
+const QUEST_FORMAT: &str = "Usage: quest [log]\r\n\
+       quest list\r\n\
+       quest info <quest>\r\n\
+       quest accept <quest>\r\n\
+       quest abandon <quest>\r\n\
+       quest complete\r\n\
+       quest channel\r\n";
+
+fn quest_log(game: &mut Game, db: &DB, objs: &Depot<ObjData>, ch: &CharData) {
+    let mut buf = String::new();
+    if ch.player_specials.quests.is_empty() {
+        buf.push_str("You are not on any quest.\r\n");
+    } else {
+        buf.push_str("Your quests:\r\n");
+        for entry in ch.player_specials.quests.iter() {
+            let Some(q) = find_quest(db, entry.vnum) else {
+                continue;
+            };
+            buf.push_str(
+                format!(
+                    "  [{:4}] {:<28} {}: {}{}\r\n",
+                    q.vnum,
+                    q.name,
+                    quest_objective(db, q),
+                    quest_progress(objs, ch, q, entry),
+                    if quest_done(objs, ch, q, entry) {
+                        " (return to the quest master)"
+                    } else {
+                        ""
+                    }
+                )
+                .as_str(),
+            );
+        }
+    }
+    let done = ch.player_specials.quests_done.len();
+    buf.push_str(
+        format!(
+            "You have completed {} quest{} and have {} quest point{}.\r\n",
+            done,
+            if done == 1 { "" } else { "s" },
+            ch.get_quest_points(),
+            if ch.get_quest_points() == 1 { "" } else { "s" }
+        )
+        .as_str(),
+    );
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+fn quest_list(game: &mut Game, chars: &Depot<CharData>, db: &DB, ch: &CharData) {
+    let masters = quest_masters_here(db, chars, ch);
+    if masters.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "There is nobody here offering quests.\r\n",
+        );
+        return;
+    }
+    let mut buf = String::new();
+    for mid in masters {
+        let master = chars.get(mid);
+        let vnum = db.get_mob_vnum(master);
+        buf.push_str(format!("{} offers:\r\n", master.get_name()).as_str());
+        for q in db.quests.iter().filter(|q| q.giver == vnum) {
+            let status = if ch
+                .player_specials
+                .quests
+                .iter()
+                .any(|entry| entry.vnum == q.vnum)
+            {
+                "(under way)".to_string()
+            } else if ch.player_specials.quests_done.contains(&q.vnum) {
+                "(completed)".to_string()
+            } else if ch.get_level() < q.min_level {
+                format!("(level {})", q.min_level)
+            } else {
+                String::new()
+            };
+            buf.push_str(format!("  [{:4}] {:<28} {}\r\n", q.vnum, q.name, status).as_str());
+        }
+    }
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+fn parse_quest_arg(db: &DB, arg: &str) -> Option<QuestVnum> {
+    arg.trim()
+        .parse::<QuestVnum>()
+        .ok()
+        .filter(|&vnum| find_quest(db, vnum).is_some())
+}
+
+fn quest_info(
+    game: &mut Game,
+    chars: &Depot<CharData>,
+    db: &DB,
+    objs: &Depot<ObjData>,
+    ch: &CharData,
+    arg: &str,
+) {
+    let Some(vnum) = parse_quest_arg(db, arg) else {
+        send_to_char(&mut game.descriptors, ch, "There is no such quest.\r\n");
+        return;
+    };
+    let entry = ch
+        .player_specials
+        .quests
+        .iter()
+        .find(|entry| entry.vnum == vnum);
+    let offered = quest_masters_here(db, chars, ch)
+        .iter()
+        .any(|&mid| find_quest(db, vnum).unwrap().giver == db.get_mob_vnum(chars.get(mid)));
+    if entry.is_none() && !offered && !ch.player_specials.quests_done.contains(&vnum) {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You know nothing about that quest.\r\n",
+        );
+        return;
+    }
+    let q = find_quest(db, vnum).unwrap();
+    let mut buf = format!(
+        "Quest [{}] {}, given by {}\r\n{}\r\nObjective: {}\r\nReward: {}\r\n",
+        q.vnum,
+        q.name,
+        mob_name(db, q.giver),
+        q.description,
+        quest_objective(db, q),
+        quest_rewards(db, q)
+    );
+    if let Some(entry) = entry {
+        buf.push_str(format!("Progress: {}\r\n", quest_progress(objs, ch, q, entry)).as_str());
+    }
+    send_to_char(&mut game.descriptors, ch, &buf);
+}
+
+fn quest_accept(game: &mut Game, chars: &mut Depot<CharData>, db: &DB, chid: DepotId, arg: &str) {
+    let ch = chars.get(chid);
+    let vnum = parse_quest_arg(db, arg);
+    let master = vnum.and_then(|vnum| {
+        let giver = find_quest(db, vnum).unwrap().giver;
+        quest_masters_here(db, chars, ch)
+            .into_iter()
+            .find(|&mid| db.get_mob_vnum(chars.get(mid)) == giver)
+    });
+    let (Some(vnum), Some(master_id)) = (vnum, master) else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Nobody here offers such a quest.\r\n",
+        );
+        return;
+    };
+    let q = find_quest(db, vnum).unwrap();
+    let msg = if ch
+        .player_specials
+        .quests
+        .iter()
+        .any(|entry| entry.vnum == vnum)
+    {
+        Some("You are already on that quest.\r\n".to_string())
+    } else if ch.player_specials.quests_done.contains(&vnum) {
+        Some("You have already completed that quest.\r\n".to_string())
+    } else if ch.get_level() < q.min_level {
+        Some(format!(
+            "You must be level {} to undertake that quest.\r\n",
+            q.min_level
+        ))
+    } else if ch.player_specials.quests.len() >= MAX_ACTIVE_QUESTS {
+        Some(format!(
+            "You may not have more than {} quests under way.\r\n",
+            MAX_ACTIVE_QUESTS
+        ))
+    } else {
+        None
+    };
+    if let Some(msg) = msg {
+        send_to_char(&mut game.descriptors, ch, &msg);
+        return;
+    }
+    /* a fetch is only counted from what is carried, when handed in */
+    let fetch_rnum = fetch_rnum(db, vnum);
+    let master = chars.get(master_id);
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        "$N gives you a quest.",
+        false,
+        Some(ch),
+        None,
+        Some(VictimRef::Char(master)),
+        TO_CHAR,
+    );
+    act(
+        &mut game.descriptors,
+        chars,
+        db,
+        "$N speaks quietly with $n.",
+        true,
+        Some(ch),
+        None,
+        Some(VictimRef::Char(master)),
+        TO_ROOM,
+    );
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        format!(
+            "{}\r\nObjective: {}\r\n",
+            q.description,
+            quest_objective(db, q)
+        )
+        .as_str(),
+    );
+    let ch = chars.get_mut(chid);
+    ch.player_specials.quests.push(QuestEntry {
+        vnum,
+        progress: 0,
+        fetch_rnum,
+    });
+}
+
+fn quest_abandon(game: &mut Game, chars: &mut Depot<CharData>, db: &DB, chid: DepotId, arg: &str) {
+    let ch = chars.get_mut(chid);
+    let vnum = parse_quest_arg(db, arg);
+    let Some(pos) = vnum.and_then(|vnum| {
+        ch.player_specials
+            .quests
+            .iter()
+            .position(|entry| entry.vnum == vnum)
+    }) else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You are not on such a quest.\r\n",
+        );
+        return;
+    };
+    ch.player_specials.quests.remove(pos);
+    let q = find_quest(db, vnum.unwrap()).unwrap();
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        format!("You abandon the quest '{}'.\r\n", q.name).as_str(),
+    );
+}
+
+/*
+ * Hand in every finished quest whose quest master is in the room.  Each one
+ * is checked just before it is handed in, as the objects fetched for a quest
+ * are gone afterwards and can't count toward another one.
+ */
+fn quest_complete(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+) {
+    let ch = chars.get(chid);
+    let masters = quest_masters_here(db, chars, ch);
+    let here: Vec<(QuestVnum, DepotId)> = ch
+        .player_specials
+        .quests
+        .iter()
+        .filter_map(|entry| {
+            let q = find_quest(db, entry.vnum)?;
+            let master = masters
+                .iter()
+                .copied()
+                .find(|&mid| db.get_mob_vnum(chars.get(mid)) == q.giver)?;
+            Some((entry.vnum, master))
+        })
+        .collect();
+    let mut completed = false;
+    for (vnum, master_id) in here {
+        let ch = chars.get(chid);
+        let q = find_quest(db, vnum).unwrap().clone();
+        let done = ch
+            .player_specials
+            .quests
+            .iter()
+            .find(|entry| entry.vnum == vnum)
+            .is_some_and(|entry| quest_done(objs, ch, &q, entry));
+        if !done {
+            continue;
+        }
+        completed = true;
+
+        /* the quest master keeps what was fetched */
+        if q.qtype == QuestType::Fetch {
+            let rnum = db.real_object(q.target);
+            let taken: Vec<DepotId> = chars
+                .get(chid)
+                .carrying
+                .iter()
+                .copied()
+                .filter(|&oid| objs.get(oid).get_obj_rnum() == rnum)
+                .take(q.amount as usize)
+                .collect();
+            for oid in taken {
+                db.extract_obj(chars, objs, oid);
+            }
+        }
+
+        let ch = chars.get(chid);
+        let master = chars.get(master_id);
+        act(
+            &mut game.descriptors,
+            chars,
+            db,
+            "$N thanks you for your help.",
+            false,
+            Some(ch),
+            None,
+            Some(VictimRef::Char(master)),
+            TO_CHAR,
+        );
+        act(
+            &mut game.descriptors,
+            chars,
+            db,
+            "$N thanks $n for $s help.",
+            true,
+            Some(ch),
+            None,
+            Some(VictimRef::Char(master)),
+            TO_ROOM,
+        );
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "You have completed '{}' and receive {}.\r\n",
+                q.name,
+                quest_rewards(db, &q)
+            )
+            .as_str(),
+        );
+
+        let ch = chars.get_mut(chid);
+        ch.player_specials.quests.retain(|entry| entry.vnum != vnum);
+        ch.player_specials.quests_done.push(vnum);
+        ch.set_gold(ch.get_gold() + q.gold);
+        ch.set_quest_points(ch.get_quest_points() + q.quest_points);
+        if q.reward_obj != NOTHING {
+            if let Some(oid) = db.read_object(objs, q.reward_obj, LoadType::Virtual) {
+                obj_to_char(objs.get_mut(oid), chars.get_mut(chid));
+            }
+        }
+        if q.exp > 0 {
+            gain_exp(chid, q.exp, game, chars, db, texts, objs);
+        }
+    }
+    if !completed {
+        send_to_char(
+            &mut game.descriptors,
+            chars.get(chid),
+            "You have nothing to hand in to anybody here.\r\n",
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_quest(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg1 = String::new();
+    let mut arg2 = String::new();
+    let mut argument = argument.to_string();
+    half_chop(&mut argument, &mut arg1, &mut arg2);
+
+    if arg1.is_empty() || is_abbrev(&arg1, "log") {
+        quest_log(game, db, objs, ch);
+    } else if is_abbrev(&arg1, "list") {
+        quest_list(game, chars, db, ch);
+    } else if is_abbrev(&arg1, "info") {
+        quest_info(game, chars, db, objs, ch, &arg2);
+    } else if is_abbrev(&arg1, "accept") {
+        quest_accept(game, chars, db, chid, &arg2);
+    } else if is_abbrev(&arg1, "abandon") {
+        quest_abandon(game, chars, db, chid, &arg2);
+    } else if is_abbrev(&arg1, "complete") {
+        quest_complete(game, chars, db, texts, objs, chid);
+    } else if is_abbrev(&arg1, "channel") {
+        /* the old 'quest' toggle, used by qecho and qsay */
+        do_gen_tog(game, db, chars, texts, objs, chid, "", cmd, SCMD_QUEST);
+    } else {
+        send_to_char(&mut game.descriptors, ch, QUEST_FORMAT);
+    }
+}
+
+#[cfg(test)]
+mod quest_tests {
+    use super::*;
+    use crate::db::TestWorld;
+    use crate::structs::LVL_IMPL;
+
+    const QUESTS: &str = "\
+#1
+Fido trouble~
+The fidos are a nuisance.
+Kill a few of them.~
+3021 K 3062 3 1 -1
+50 200 -1 1
+S
+#2
+Bread for the poor~
+Take this to the beggar.~
+3021 D 3010 1 1 3065
+0 100 3009 2
+S
+$
+";
+
+    #[test]
+    fn test_quest_file_parses() {
+        let quests = parse_quests(QUESTS.as_bytes());
+        assert_eq!(quests.len(), 2);
+        assert_eq!(quests[0].vnum, 1);
+        assert_eq!(quests[0].qtype, QuestType::Kill);
+        assert_eq!(
+            quests[0].description,
+            "The fidos are a nuisance.\r\nKill a few of them."
+        );
+        assert_eq!(quests[0].recipient, NOBODY);
+        assert_eq!(quests[0].reward_obj, NOTHING);
+        assert_eq!(quests[1].qtype, QuestType::Deliver);
+        assert_eq!(quests[1].recipient, 3065);
+        assert_eq!(quests[1].reward_obj, 3009);
+        assert_eq!(quests[1].quest_points, 2);
+    }
+
+    #[test]
+    fn test_bad_quests_are_skipped() {
+        let text = "\
+#1
+No recipient~
+Deliver it.~
+3021 D 3010 1 1 -1
+0 0 -1 0
+S
+#2
+Unknown type~
+Whatever.~
+3021 X 3010 1 1 -1
+0 0 -1 0
+S
+#3
+Good~
+Go there.~
+3021 V 3014 5 1 -1
+10 0 -1 0
+S
+#3
+Duplicate~
+Again.~
+3021 V 3014 1 1 -1
+0 0 -1 0
+S
+$
+";
+        let quests = parse_quests(text.as_bytes());
+        assert_eq!(quests.len(), 1);
+        assert_eq!(quests[0].name, "Good");
+        /* there is only one room to visit */
+        assert_eq!(quests[0].amount, 1);
+    }
+
+    #[test]
+    fn test_journal_roundtrip() {
+        let active = vec![
+            QuestEntry {
+                vnum: 1,
+                progress: 2,
+                fetch_rnum: NOTHING,
+            },
+            QuestEntry {
+                vnum: 4,
+                progress: 0,
+                fetch_rnum: NOTHING,
+            },
+        ];
+        let done = vec![2, 3];
+        let mut buf = vec![];
+        write_journal(&mut buf, &active, &done).unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "#journal\nActive: 1 2\nActive: 4 0\nDone: 2\nDone: 3\nEnd\n"
+        );
+        let (read_active, read_done) = parse_journal(buf.as_slice());
+        assert_eq!(read_active, active);
+        assert_eq!(read_done, done);
+
+        /* a journal that can't be read is an empty one */
+        let (read_active, read_done) = parse_journal("#journal\nActive: 1\nEnd\n".as_bytes());
+        assert!(read_active.is_empty() && read_done.is_empty());
+    }
+
+    /* A world with the shipped quests, and a player on all four of them */
+    fn quest_world(name: &str, room: RoomVnum) -> (TestWorld, DepotId) {
+        let mut w = TestWorld::boot(name);
+        boot_quests(&mut w.db);
+        let alice = w.add_player("alice", 1, room);
+        for vnum in 1..=4 {
+            let entry = QuestEntry {
+                vnum,
+                progress: 0,
+                fetch_rnum: fetch_rnum(&w.db, vnum),
+            };
+            w.chars.get_mut(alice).player_specials.quests.push(entry);
+        }
+        (w, alice)
+    }
+
+    fn progress(w: &TestWorld, chid: DepotId, vnum: QuestVnum) -> Option<i32> {
+        let ch = w.chars.get(chid);
+        let entry = ch.player_specials.quests.iter().find(|e| e.vnum == vnum);
+        entry.map(|e| e.progress)
+    }
+
+    #[test]
+    fn test_quest_kill() {
+        let (mut w, alice) = quest_world("quest_kill", 3001);
+        let fido = w.add_mob(3062, 3001);
+        let beggar = w.add_mob(3065, 3001);
+        quest_kill(&mut w.game, &mut w.chars, &w.db, alice, fido);
+        quest_kill(&mut w.game, &mut w.chars, &w.db, alice, beggar);
+        assert_eq!(progress(&w, alice, 1), Some(1));
+
+        /* a charmed follower kills for its master */
+        let pet = w.add_mob(3062, 3001);
+        w.chars.get_mut(pet).master = Some(alice);
+        quest_kill(&mut w.game, &mut w.chars, &w.db, pet, fido);
+        assert_eq!(progress(&w, alice, 1), Some(2));
+
+        /* killing a player counts for nothing, nor does killing past the amount */
+        let bob = w.add_player("bob", 2, 3001);
+        quest_kill(&mut w.game, &mut w.chars, &w.db, alice, bob);
+        for _ in 0..3 {
+            quest_kill(&mut w.game, &mut w.chars, &w.db, alice, fido);
+        }
+        assert_eq!(progress(&w, alice, 1), Some(3));
+        assert_eq!(progress(&w, alice, 4), Some(0));
+    }
+
+    #[test]
+    fn test_quest_visit() {
+        let (mut w, alice) = quest_world("quest_visit", 3012);
+        w.chars.get_mut(alice).set_move(100);
+        w.command(alice, "east");
+        assert_eq!(progress(&w, alice, 2), Some(0));
+        w.command(alice, "west");
+        w.command(alice, "west");
+        assert_eq!(w.db.get_room_vnum(w.chars.get(alice).in_room()), 3040);
+        assert_eq!(progress(&w, alice, 2), Some(1));
+    }
+
+    #[test]
+    fn test_quest_deliver() {
+        let (mut w, alice) = quest_world("quest_deliver", 3001);
+        w.add_mob(3065, 3001);
+        w.add_mob(3062, 3001);
+        w.give_obj(alice, 3010);
+        w.give_obj(alice, 3010);
+        /* only the recipient will do */
+        w.command(alice, "give bread fido");
+        assert_eq!(progress(&w, alice, 4), Some(0));
+        w.command(alice, "give bread beggar");
+        assert_eq!(progress(&w, alice, 4), Some(1));
+        assert_eq!(w.chars.get(alice).carrying.len(), 0);
+    }
+
+    #[test]
+    fn test_quest_complete() {
+        let (mut w, alice) = quest_world("quest_complete", 3001);
+        w.give_obj(alice, 3010);
+        w.give_obj(alice, 3010);
+        w.give_obj(alice, 3010);
+        w.chars.get_mut(alice).player_specials.quests[0].progress = 3;
+
+        /* nothing is handed in while the quest master is away */
+        w.command(alice, "quest complete");
+        assert_eq!(w.chars.get(alice).player_specials.quests.len(), 4);
+
+        w.add_mob(3021, 3001);
+        w.command(alice, "quest complete");
+        let ch = w.chars.get(alice);
+        assert_eq!(ch.player_specials.quests_done, vec![1, 3]);
+        assert_eq!(
+            ch.player_specials
+                .quests
+                .iter()
+                .map(|e| e.vnum)
+                .collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(ch.get_gold(), 80);
+        assert_eq!(ch.get_quest_points(), 2);
+        /* the quest master keeps two loaves and leaves the third */
+        assert_eq!(ch.carrying.len(), 1);
+
+        /* once done, a quest can't be taken again */
+        w.command(alice, "quest accept 1");
+        assert_eq!(w.chars.get(alice).player_specials.quests.len(), 2);
+    }
+
+    #[test]
+    fn test_removed_quests_are_forgotten() {
+        let (mut w, alice) = quest_world("quest_reload", 3001);
+        let quests = fs::read_to_string(QUEST_FILE).unwrap();
+        let (head, tail) = quests.split_once("#2\n").unwrap();
+        let tail = &tail[tail.find("#3\n").unwrap()..];
+        fs::write(QUEST_FILE, format!("{}{}", head, tail)).unwrap();
+
+        w.chars.get_mut(alice).set_level(LVL_IMPL);
+        w.command(alice, "reload quests");
+        let ch = w.chars.get(alice);
+        assert_eq!(
+            ch.player_specials
+                .quests
+                .iter()
+                .map(|e| e.vnum)
+                .collect::<Vec<_>>(),
+            vec![1, 3, 4]
+        );
+        assert_eq!(
+            ch.player_specials.quests[1].fetch_rnum,
+            w.db.real_object(3010)
+        );
+    }
+}
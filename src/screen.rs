@@ -1,3 +1,5 @@
+use crate::structs::{CharData, PrefFlags};
+
 pub const KNRM: &str = "\x1B[0m";
 pub const KRED: &str = "\x1B[31m";
 pub const KGRN: &str = "\x1B[32m";
@@ -18,7 +20,7 @@ pub const C_CMP: u8 = 3;
 #[macro_export]
 macro_rules! _clrlevel {
     ($ch:expr) => {
-        (if !($ch).is_npc() {
+        (if !($ch).is_npc() && !($ch).prf_flagged($crate::structs::PrefFlags::SCREENREADER) {
             (if ($ch).prf_flagged($crate::structs::PrefFlags::COLOR_1) {
                 1
             } else {
@@ -105,3 +107,576 @@ macro_rules! COLOR_LEV {
 // #define QMAG CCMAG(ch,C_SPR)
 // #define QCYN CCCYN(ch,C_SPR)
 // #define QWHT CCWHT(ch,C_SPR)
+
+/*
+ * Inline color markup.  Text anywhere in the game (room descriptions,
+ * titles, board posts, channels...) may carry &x codes which are turned
+ * into escape sequences by process_output(), as deep as the player's
+ * terminal allows:
+ *
+ *   &k &r &g &y &b &m &c &w   normal black, red, green, ..., white
+ *   &K &R &G &Y &B &M &C &W   bright versions of the above
+ *   &#rrggbb                  any color, approximated on smaller palettes
+ *   &n                        back to normal
+ *   &(say)                    the color the player chose for says (see
+ *                             MSG_CATEGORIES for the other names)
+ *   &&                        a literal '&'
+ *
+ * Anything else following an '&' is left alone.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ColorDepth {
+    None,
+    #[default]
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+pub const COLOR_DEPTHS: [&str; 4] = ["none", "16", "256", "truecolor"];
+
+impl ColorDepth {
+    pub fn name(self) -> &'static str {
+        COLOR_DEPTHS[self as usize]
+    }
+
+    pub fn from_name(name: &str) -> Option<ColorDepth> {
+        match name {
+            "none" => Some(ColorDepth::None),
+            "16" => Some(ColorDepth::Ansi16),
+            "256" => Some(ColorDepth::Ansi256),
+            "truecolor" => Some(ColorDepth::TrueColor),
+            _ => None,
+        }
+    }
+}
+
+/*
+ * Message categories, which every player may color as they like with the
+ * colorset command.  The game writes &(name) in front of such messages.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MsgCategory {
+    RoomTitle,
+    Exits,
+    Say,
+    Tell,
+    /* only named in the channel file, as &(gossip) */
+    #[allow(dead_code)]
+    Gossip,
+    CombatHit,
+    CombatMiss,
+    DamageTaken,
+    ObjList,
+    CharList,
+}
+
+pub const NUM_MSG_CATEGORIES: usize = 10;
+
+/* name of each category and the color level from which it is colored */
+pub const MSG_CATEGORIES: [(&str, u8); NUM_MSG_CATEGORIES] = [
+    ("roomtitle", C_NRM),
+    ("exits", C_NRM),
+    ("say", C_NRM),
+    ("tell", C_NRM),
+    ("gossip", C_NRM),
+    ("hit", C_CMP),
+    ("miss", C_CMP),
+    ("damage", C_CMP),
+    ("objects", C_NRM),
+    ("chars", C_NRM),
+];
+
+const MAX_CATEGORY_TAG: usize = 12;
+
+/* ends a message of any category */
+pub const MARKUP_NRM: &str = "&n";
+
+impl MsgCategory {
+    /* the markup starting a message of this category */
+    pub const fn tag(self) -> &'static str {
+        match self {
+            MsgCategory::RoomTitle => "&(roomtitle)",
+            MsgCategory::Exits => "&(exits)",
+            MsgCategory::Say => "&(say)",
+            MsgCategory::Tell => "&(tell)",
+            MsgCategory::Gossip => "&(gossip)",
+            MsgCategory::CombatHit => "&(hit)",
+            MsgCategory::CombatMiss => "&(miss)",
+            MsgCategory::DamageTaken => "&(damage)",
+            MsgCategory::ObjList => "&(objects)",
+            MsgCategory::CharList => "&(chars)",
+        }
+    }
+}
+
+pub struct ColorTheme {
+    pub name: &'static str,
+    pub colors: [&'static str; NUM_MSG_CATEGORIES],
+}
+
+/* The first theme is the one every player starts with. */
+pub const COLOR_THEMES: [ColorTheme; 3] = [
+    ColorTheme {
+        name: "default",
+        colors: ["&c", "&c", "", "&r", "&y", "&y", "&y", "&r", "&g", "&y"],
+    },
+    ColorTheme {
+        name: "contrast",
+        colors: ["&W", "&C", "&W", "&M", "&Y", "&G", "&W", "&R", "&G", "&Y"],
+    },
+    ColorTheme {
+        name: "plain",
+        colors: [""; NUM_MSG_CATEGORIES],
+    },
+];
+
+/* How render_color() shows the markup to one player. */
+pub struct Markup<'a> {
+    pub depth: ColorDepth,
+    pub theme: [&'a str; NUM_MSG_CATEGORIES],
+}
+
+impl Markup<'_> {
+    pub fn new(depth: ColorDepth) -> Markup<'static> {
+        Markup {
+            depth,
+            theme: COLOR_THEMES[0].colors,
+        }
+    }
+}
+
+/* The color a player sees a category in, "" for none. */
+pub fn theme_color(ch: &CharData, cat: usize) -> &str {
+    if !clr!(ch, MSG_CATEGORIES[cat].1) {
+        return "";
+    }
+    let ps = &ch.player_specials;
+    match &ps.colors[cat] {
+        Some(color) => color,
+        None => COLOR_THEMES[ps.color_theme].colors[cat],
+    }
+}
+
+const MARKUP_COLORS: &[u8; 8] = b"krgybmcw";
+
+/* the usual xterm values of the 16 basic colors */
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn rgb_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn ansi16_code(color: usize) -> String {
+    if color < 8 {
+        format!("\x1B[0;3{}m", color)
+    } else {
+        format!("\x1B[1;3{}m", color - 8)
+    }
+}
+
+fn nearest_ansi16(rgb: (u8, u8, u8)) -> usize {
+    (0..ANSI16_RGB.len())
+        .min_by_key(|&i| rgb_distance(rgb, ANSI16_RGB[i]))
+        .unwrap_or(7)
+}
+
+fn nearest_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let avg = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_idx = (avg.saturating_sub(3) / 10).min(23) as u8;
+    let gray_val = 8 + gray_idx * 10;
+
+    if rgb_distance(rgb, (gray_val, gray_val, gray_val)) < rgb_distance(rgb, cube) {
+        232 + gray_idx
+    } else {
+        16 + 36 * r as u8 + 6 * g as u8 + b as u8
+    }
+}
+
+fn parse_hex_color(hex: &[u8]) -> Option<(u8, u8, u8)> {
+    if hex.len() != 6 || !hex.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    let hex = std::str::from_utf8(hex).ok()?;
+    let v = u32::from_str_radix(hex, 16).ok()?;
+    Some(((v >> 16) as u8, (v >> 8) as u8, v as u8))
+}
+
+/* The escape sequence for a color code (what follows the '&'), and its length. */
+fn color_escape(code: &[u8], depth: ColorDepth) -> Option<(String, usize)> {
+    let c = *code.first()?;
+    if let Some(color) = MARKUP_COLORS
+        .iter()
+        .position(|&m| m == c.to_ascii_lowercase())
+    {
+        let bright = if c.is_ascii_uppercase() { 8 } else { 0 };
+        return Some((ansi16_code(color + bright), 1));
+    }
+    if c != b'#' {
+        return None;
+    }
+    let rgb = code.get(1..7).and_then(parse_hex_color)?;
+    let seq = match depth {
+        ColorDepth::TrueColor => format!("\x1B[38;2;{};{};{}m", rgb.0, rgb.1, rgb.2),
+        ColorDepth::Ansi256 => format!("\x1B[38;5;{}m", nearest_ansi256(rgb)),
+        _ => ansi16_code(nearest_ansi16(rgb)),
+    };
+    Some((seq, 7))
+}
+
+/* Is this a color a player may pick, "&r" or "&#ff8800" for instance? */
+pub fn valid_color_code(code: &str) -> bool {
+    code.strip_prefix('&')
+        .and_then(|c| color_escape(c.as_bytes(), ColorDepth::TrueColor))
+        .is_some_and(|(_, len)| len + 1 == code.len())
+}
+
+/* The category of a "(name)" tag (what follows the '&'), and its length. */
+fn category_tag(code: &[u8]) -> Option<(usize, usize)> {
+    let end = code
+        .iter()
+        .take(MAX_CATEGORY_TAG)
+        .position(|&c| c == b')')?;
+    let name = std::str::from_utf8(&code[1..end]).ok()?;
+    let cat = MSG_CATEGORIES.iter().position(|(n, _)| *n == name)?;
+    Some((cat, end + 1))
+}
+
+/* Replace the color markup in text by escape sequences for the given depth. */
+pub fn render_color(text: &[u8], markup: &Markup) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut colored = false;
+    let mut i = 0;
+
+    while i < text.len() {
+        /* without color, escape sequences written directly go away too */
+        if markup.depth == ColorDepth::None && text[i..].starts_with(b"\x1B[") {
+            i += 2;
+            while i < text.len() && !(0x40..=0x7e).contains(&text[i]) {
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        if text[i] != b'&' {
+            out.push(text[i]);
+            i += 1;
+            continue;
+        }
+        let code = &text[i + 1..];
+        let (seq, len) = match code.first() {
+            Some(b'&') => {
+                out.push(b'&');
+                i += 2;
+                continue;
+            }
+            Some(b'n') => (None, 1),
+            Some(b'(') => match category_tag(code) {
+                /* a category without color is plain text */
+                Some((cat, len)) => match markup.theme[cat].strip_prefix('&') {
+                    Some(color) => (
+                        color_escape(color.as_bytes(), markup.depth).map(|(seq, _)| seq),
+                        len,
+                    ),
+                    None => (None, len),
+                },
+                None => {
+                    out.push(b'&');
+                    i += 1;
+                    continue;
+                }
+            },
+            _ => match color_escape(code, markup.depth) {
+                Some((seq, len)) => (Some(seq), len),
+                None => {
+                    out.push(b'&');
+                    i += 1;
+                    continue;
+                }
+            },
+        };
+        i += 1 + len;
+        if markup.depth == ColorDepth::None {
+            continue;
+        }
+        match seq {
+            Some(seq) => {
+                out.extend_from_slice(seq.as_bytes());
+                colored = true;
+            }
+            None if colored => {
+                out.extend_from_slice(KNRM.as_bytes());
+                colored = false;
+            }
+            None => {}
+        }
+    }
+    if colored {
+        out.extend_from_slice(KNRM.as_bytes());
+    }
+    out
+}
+
+/* Whether the color markup in text is still in effect at its end. */
+fn markup_left_open(text: &[u8]) -> bool {
+    let mut open = false;
+    let mut i = 0;
+    while i < text.len() {
+        if text[i] != b'&' {
+            i += 1;
+            continue;
+        }
+        let code = &text[i + 1..];
+        let len = match code.first() {
+            Some(b'&') => 1,
+            Some(b'n') => {
+                open = false;
+                1
+            }
+            Some(b'(') => category_tag(code).map_or(0, |(_, len)| {
+                open = true;
+                len
+            }),
+            _ => color_escape(code, ColorDepth::TrueColor).map_or(0, |(_, len)| {
+                open = true;
+                len
+            }),
+        };
+        i += 1 + len;
+    }
+    open
+}
+
+/*
+ * Player text, such as a tell or a title, with any color it leaves open
+ * closed at its end, so that it can't color what the game writes after it.
+ */
+pub fn close_markup(text: &str) -> String {
+    let mut text = text.to_string();
+    if markup_left_open(text.as_bytes()) {
+        text.push_str(MARKUP_NRM);
+    }
+    text
+}
+
+/*
+ * Screen reader mode (see the screenreader toggle).  Colors are already
+ * left out by _clrlevel(); what remains is to drop the drawings and
+ * borders, which are read aloud one character at a time.
+ */
+pub fn screen_reader(ch: &CharData) -> bool {
+    !ch.is_npc() && ch.prf_flagged(PrefFlags::SCREENREADER)
+}
+
+const DECORATIONS: &[u8] = b"-=*_~+#|/\\<>^";
+
+/* Take out runs of line-drawing characters, and lines made only of them. */
+pub fn strip_decorations(text: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for line in text.split_inclusive(|&c| c == b'\n') {
+        let drawing = line.iter().filter(|c| DECORATIONS.contains(c)).count();
+        if drawing >= 3 && !line.iter().any(u8::is_ascii_alphanumeric) {
+            continue;
+        }
+        let mut i = 0;
+        while i < line.len() {
+            let run = line[i..].iter().take_while(|&&c| c == line[i]).count();
+            if run < 3 || !DECORATIONS.contains(&line[i]) {
+                out.extend_from_slice(&line[i..i + run]);
+            }
+            i += run;
+        }
+    }
+    out
+}
+
+/* Say "line (3 times)" once instead of repeating the same line. */
+pub fn collapse_repeats(text: &[u8]) -> Vec<u8> {
+    let plain = Markup::new(ColorDepth::None);
+    let mut lines: Vec<(&[u8], Vec<u8>, usize)> = vec![];
+    let mut rest: &[u8] = &[];
+
+    for line in text.split_inclusive(|&c| c == b'\n') {
+        let Some(body) = line.strip_suffix(b"\n") else {
+            rest = line;
+            break;
+        };
+        let body = body.strip_suffix(b"\r").unwrap_or(body);
+        let key = render_color(body, &plain);
+        let blank = key.iter().all(u8::is_ascii_whitespace);
+        match lines.iter_mut().find(|(_, k, _)| !blank && *k == key) {
+            Some((_, _, count)) => *count += 1,
+            None => lines.push((body, key, 1)),
+        }
+    }
+    let mut out = Vec::with_capacity(text.len());
+    for (body, _, count) in lines {
+        out.extend_from_slice(body);
+        if count > 1 {
+            out.extend_from_slice(format!(" ({} times)", count).as_bytes());
+        }
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(rest);
+    out
+}
+
+/* What a terminal type reported through TTYPE/MTTS tells about its colors. */
+pub fn terminal_color_depth(ttype: &str) -> Option<ColorDepth> {
+    let ttype = ttype.to_ascii_uppercase();
+    if let Some(bits) = ttype.strip_prefix("MTTS ") {
+        let bits = bits.trim().parse::<u32>().ok()?;
+        return if bits & 256 != 0 {
+            Some(ColorDepth::TrueColor)
+        } else if bits & 8 != 0 {
+            Some(ColorDepth::Ansi256)
+        } else if bits & 1 != 0 {
+            Some(ColorDepth::Ansi16)
+        } else {
+            None
+        };
+    }
+    if ttype.contains("TRUECOLOR") || ttype.ends_with("-DIRECT") {
+        Some(ColorDepth::TrueColor)
+    } else if ttype.contains("256COLOR") {
+        Some(ColorDepth::Ansi256)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod screen_tests {
+    use super::*;
+
+    fn render(text: &str, depth: ColorDepth) -> String {
+        String::from_utf8(render_color(text.as_bytes(), &Markup::new(depth))).unwrap()
+    }
+
+    #[test]
+    fn test_basic_markup() {
+        assert_eq!(
+            render("&rred&n and &Wwhite", ColorDepth::Ansi16),
+            "\x1B[0;31mred\x1B[0m and \x1B[1;37mwhite\x1B[0m"
+        );
+        assert_eq!(
+            render("rock && roll &", ColorDepth::Ansi16),
+            "rock & roll &"
+        );
+        assert_eq!(render("&x &#12 &n", ColorDepth::Ansi16), "&x &#12 ");
+        assert_eq!(render("&rred&n &&r", ColorDepth::None), "red &r");
+        assert_eq!(render("\x1B[31mred\x1B[0m", ColorDepth::None), "red");
+    }
+
+    #[test]
+    fn test_close_markup() {
+        assert_eq!(close_markup("plain"), "plain");
+        assert_eq!(close_markup("&rred"), "&rred&n");
+        assert_eq!(close_markup("&rred&n"), "&rred&n");
+        assert_eq!(
+            close_markup("&rred&n &#ff8800orange"),
+            "&rred&n &#ff8800orange&n"
+        );
+        assert_eq!(close_markup("&(tell)told"), "&(tell)told&n");
+        /* not markup at all */
+        assert_eq!(
+            close_markup("fish && chips &z &(nothing)"),
+            "fish && chips &z &(nothing)"
+        );
+        assert_eq!(close_markup("&&rnot red"), "&&rnot red");
+        /* what follows is back to normal */
+        let said = format!("{}, he said.", close_markup("&Rshout"));
+        assert!(render(&said, ColorDepth::Ansi16).ends_with("\x1B[0m, he said."));
+    }
+
+    #[test]
+    fn test_hex_colors_downgrade() {
+        assert_eq!(
+            render("&#ff8800x", ColorDepth::TrueColor),
+            "\x1B[38;2;255;136;0mx\x1B[0m"
+        );
+        assert_eq!(
+            render("&#ff8800x", ColorDepth::Ansi256),
+            "\x1B[38;5;208mx\x1B[0m"
+        );
+        assert_eq!(
+            render("&#ff0000x", ColorDepth::Ansi16),
+            "\x1B[1;31mx\x1B[0m"
+        );
+        assert_eq!(
+            render("&#808080x", ColorDepth::Ansi256),
+            "\x1B[38;5;244mx\x1B[0m"
+        );
+        assert_eq!(render("&#ff8800x", ColorDepth::None), "x");
+    }
+
+    #[test]
+    fn test_categories_follow_theme() {
+        assert_eq!(
+            render("&(tell)hi&n &(say)yo &(nope)", ColorDepth::Ansi16),
+            "\x1B[0;31mhi\x1B[0m yo &(nope)"
+        );
+        let mut markup = Markup::new(ColorDepth::TrueColor);
+        markup.theme[MsgCategory::Say as usize] = "&#102030";
+        assert_eq!(
+            render_color(b"&(say)yo", &markup),
+            b"\x1B[38;2;16;32;48myo\x1B[0m"
+        );
+        assert!(valid_color_code("&R") && valid_color_code("&#ff8800"));
+        assert!(!valid_color_code("&Rx") && !valid_color_code("r") && !valid_color_code("&#ff88"));
+    }
+
+    #[test]
+    fn test_screen_reader_output() {
+        assert_eq!(
+            strip_decorations(b"\r\nPlayers\r\n-------\r\n=== Midgaard ===\r\nWhat??? > "),
+            b"\r\nPlayers\r\n Midgaard \r\nWhat??? > "
+        );
+        assert_eq!(
+            collapse_repeats(b"&(miss)A guard misses you.\r\n&n&(miss)A guard misses you.\r\n\r\n\r\nYou hit.\r\n> "),
+            b"&(miss)A guard misses you. (2 times)\r\n\r\n\r\nYou hit.\r\n> "
+        );
+    }
+
+    #[test]
+    fn test_terminal_types() {
+        assert_eq!(
+            terminal_color_depth("MTTS 271"),
+            Some(ColorDepth::TrueColor)
+        );
+        assert_eq!(terminal_color_depth("MTTS 13"), Some(ColorDepth::Ansi256));
+        assert_eq!(
+            terminal_color_depth("xterm-256color"),
+            Some(ColorDepth::Ansi256)
+        );
+        assert_eq!(terminal_color_depth("XTERM"), None);
+    }
+}
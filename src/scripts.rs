@@ -0,0 +1,1476 @@
+/* ************************************************************************
+*   File: scripts.rs                                    Part of CircleMUD *
+*  Usage: Triggers and the script interpreter for mobs, objects and rooms *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * Triggers are small scripts which builders attach to mobs, objects and
+ * rooms, so that behavior can be added without writing a Special routine.
+ * They are read from world/trg/, one file per zone like the other world
+ * files, in this format:
+ *
+ *   #<trigger vnum>
+ *   <name>~
+ *   <MOB | OBJ | ROOM> <event> <number> <vnum> <vnum> ...
+ *   <argument>~
+ *   <script lines>
+ *   ~
+ *
+ * The vnums are the mobs, objects or rooms the trigger is attached to.
+ * The events, and what <number> and <argument> mean for each of them:
+ *
+ *   GREET    MOB       a player enters the mob's room; number is a percent
+ *   ENTRY    MOB ROOM  the mob enters a room / someone enters the room;
+ *                      number is a percent
+ *   SPEECH   MOB ROOM  someone says something containing the argument
+ *                      ('*' for anything)
+ *   COMMAND  MOB OBJ ROOM  a player types one of the words in the argument;
+ *                      the command is not run unless the script does
+ *                      'return 0'
+ *   FIGHT    MOB       each combat round; number is a percent
+ *   DEATH    MOB       the mob is killed
+ *   GIVE     MOB OBJ   the mob is given an object / the object is given
+ *   DROP     OBJ ROOM  the object is dropped / something is dropped here
+ *   RANDOM   MOB OBJ ROOM  every script pulse while a player is in the
+ *                      zone; number is a percent
+ *   TIMER    MOB OBJ ROOM  every <number> script pulses
+ *
+ * Script lines are run one after the other.  Before a line is run, every
+ * %variable% in it is replaced by its value.  The variables are:
+ *
+ *   %actor%, %self%, %victim%, %object%   the name of who or what is
+ *                      involved; add .name, .vnum, .level, .room, .gold,
+ *                      .align, .hit or .is_pc for their other properties
+ *   %arg%              the argument of a command, or what was said
+ *   %cmd%              the command word typed
+ *   %random.N%         a random number from 1 to N
+ *   %time.hour%        the hour of the mud day
+ *   %name%             anything set with 'set', 'eval' or 'global'
+ *
+ * The script commands are:
+ *
+ *   set <var> <text>           eval <var> <expression>
+ *   global <var>               unset <var>
+ *   if <expression> / elseif <expression> / else / end
+ *   halt                       return <number>
+ *   echo <text>                send <target> <text>
+ *   echoaround <target> <text> teleport <target> <room vnum>
+ *   force <target> <command>   load <mob | obj> <vnum>
+ *   purge <target>
+ *
+ * A target is 'actor', 'victim', 'self' or the name of someone in the
+ * room.  Any other line in a mob script is run by the mob as a command,
+ * through command_interpreter().  Expressions know the operators
+ * || && == != < > <= >= /= (contains) + - * / ! and parentheses.
+ */
+
+use std::collections::HashMap;
+use std::process;
+use std::rc::Rc;
+use std::{fs, mem};
+
+use log::{error, info};
+
+use crate::act_informative::look_at_room;
+use crate::db::{LoadType, DB, TRG_PREFIX};
+use crate::depot::{Depot, DepotId, HasId};
+use crate::handler::{isname, obj_to_char};
+use crate::interpreter::command_interpreter;
+use crate::structs::{
+    ConState::ConPlaying, IdxType, RoomRnum, MOB_NOTDEADYET, NOBODY, NOTHING, NOWHERE,
+    PLR_NOTDEADYET,
+};
+use crate::util::rand_number;
+use crate::{send_to_char, send_to_room, CharData, Game, ObjData, TextData};
+
+/* How deep triggers may fire other triggers before we give up. */
+const MAX_SCRIPT_DEPTH: u8 = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AttachType {
+    Mob,
+    Obj,
+    Room,
+}
+
+pub const ATTACH_TYPES: [&str; 3] = ["MOB", "OBJ", "ROOM"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TrigEvent {
+    Greet,
+    Speech,
+    Command,
+    Fight,
+    Death,
+    Give,
+    Random,
+    Entry,
+    Drop,
+    Timer,
+}
+
+pub const TRIG_EVENTS: [&str; 10] = [
+    "GREET", "SPEECH", "COMMAND", "FIGHT", "DEATH", "GIVE", "RANDOM", "ENTRY", "DROP", "TIMER",
+];
+
+const EVENTS: [TrigEvent; 10] = [
+    TrigEvent::Greet,
+    TrigEvent::Speech,
+    TrigEvent::Command,
+    TrigEvent::Fight,
+    TrigEvent::Death,
+    TrigEvent::Give,
+    TrigEvent::Random,
+    TrigEvent::Entry,
+    TrigEvent::Drop,
+    TrigEvent::Timer,
+];
+
+pub struct TrigData {
+    pub vnum: IdxType,
+    pub name: String,
+    pub attach_type: AttachType,
+    pub event: TrigEvent,
+    /* percent chance, pulses, or match mode, depending on the event */
+    pub narg: i32,
+    /* vnums of the mobs, objects or rooms this trigger is attached to */
+    pub attach: Vec<IdxType>,
+    pub arglist: String,
+    pub script: Rc<[String]>,
+}
+
+/* Whatever a running script belongs to. */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ScriptOwner {
+    Room(RoomRnum),
+    Char(DepotId),
+    Obj(DepotId),
+}
+
+#[derive(Default)]
+struct ScriptState {
+    /* variables made global by the owner's scripts */
+    vars: HashMap<String, String>,
+    /* pulses left before each TIMER trigger fires, by trigger index */
+    timers: HashMap<usize, i32>,
+}
+
+#[derive(Default)]
+pub struct Scripts {
+    pub triggers: Vec<TrigData>,
+    mob: HashMap<IdxType, Vec<usize>>,
+    obj: HashMap<IdxType, Vec<usize>>,
+    room: HashMap<IdxType, Vec<usize>>,
+    state: HashMap<ScriptOwner, ScriptState>,
+    depth: u8,
+}
+
+impl Scripts {
+    /* Replace all triggers, and work out what they are attached to. */
+    pub fn set_triggers(&mut self, mut triggers: Vec<TrigData>) {
+        triggers.sort_by_key(|t| t.vnum);
+        self.mob.clear();
+        self.obj.clear();
+        self.room.clear();
+        for (idx, trig) in triggers.iter().enumerate() {
+            let table = match trig.attach_type {
+                AttachType::Mob => &mut self.mob,
+                AttachType::Obj => &mut self.obj,
+                AttachType::Room => &mut self.room,
+            };
+            for &vnum in &trig.attach {
+                table.entry(vnum).or_default().push(idx);
+            }
+        }
+        self.triggers = triggers;
+        for state in self.state.values_mut() {
+            state.timers.clear();
+        }
+    }
+
+    pub fn real_trigger(&self, vnum: IdxType) -> Option<usize> {
+        self.triggers.binary_search_by_key(&vnum, |t| t.vnum).ok()
+    }
+
+    fn attached(&self, attach_type: AttachType, vnum: IdxType, event: TrigEvent) -> Vec<usize> {
+        let table = match attach_type {
+            AttachType::Mob => &self.mob,
+            AttachType::Obj => &self.obj,
+            AttachType::Room => &self.room,
+        };
+        table
+            .get(&vnum)
+            .map(|list| {
+                list.iter()
+                    .copied()
+                    .filter(|&t| self.triggers[t].event == event)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /* Drop the variables of a character or object leaving the game. */
+    pub fn forget(&mut self, owner: ScriptOwner) {
+        self.state.remove(&owner);
+    }
+
+    /* Follow the rooms to their new rnums after the world was rebuilt.  Rooms
+     * mapped to NOWHERE are gone and lose their variables. */
+    pub fn renum_rooms(&mut self, room_map: &[RoomRnum]) {
+        let state = mem::take(&mut self.state);
+        for (owner, st) in state {
+            let owner = match owner {
+                ScriptOwner::Room(rnum) => match room_map.get(rnum as usize) {
+                    Some(&new) if new != NOWHERE => ScriptOwner::Room(new),
+                    _ => continue,
+                },
+                owner => owner,
+            };
+            self.state.insert(owner, st);
+        }
+    }
+}
+
+/**************************************************************************
+*  Loading                                                                *
+**************************************************************************/
+
+fn read_tilde_string(lines: &[&str], pos: &mut usize) -> Option<String> {
+    let mut buf = String::new();
+    while *pos < lines.len() {
+        let line = lines[*pos];
+        *pos += 1;
+        if let Some(end) = line.find('~') {
+            buf.push_str(&line[..end]);
+            return Some(buf);
+        }
+        buf.push_str(line);
+        buf.push('\n');
+    }
+    None
+}
+
+/* Parse one trigger file. */
+pub fn parse_triggers(text: &str, filename: &str) -> Result<Vec<TrigData>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut pos = 0;
+    let mut triggers = vec![];
+
+    loop {
+        while pos < lines.len() && (lines[pos].trim().is_empty() || lines[pos].starts_with('*')) {
+            pos += 1;
+        }
+        if pos >= lines.len() {
+            return Err(format!("{} is not terminated with '$'", filename));
+        }
+        let line = lines[pos].trim();
+        let lineno = pos + 1;
+        pos += 1;
+        if line.starts_with('$') {
+            return Ok(triggers);
+        }
+        let vnum = line
+            .strip_prefix('#')
+            .and_then(|v| v.parse::<IdxType>().ok())
+            .ok_or_else(|| format!("{}:{}: expecting '#<vnum>'", filename, lineno))?;
+        let what = format!("trigger #{} in {}", vnum, filename);
+
+        let name = read_tilde_string(&lines, &mut pos)
+            .ok_or_else(|| format!("{}: file ended in the name", what))?;
+
+        let header = lines
+            .get(pos)
+            .ok_or_else(|| format!("{}: file ended before the type line", what))?;
+        pos += 1;
+        let mut words = header.split_whitespace();
+        let attach_type = match words.next().map(|w| w.to_uppercase()).as_deref() {
+            Some("MOB") => AttachType::Mob,
+            Some("OBJ") => AttachType::Obj,
+            Some("ROOM") => AttachType::Room,
+            _ => return Err(format!("{}: expecting MOB, OBJ or ROOM", what)),
+        };
+        let event = words
+            .next()
+            .and_then(|w| TRIG_EVENTS.iter().position(|e| e.eq_ignore_ascii_case(w)))
+            .map(|i| EVENTS[i])
+            .ok_or_else(|| format!("{}: unknown event in '{}'", what, header))?;
+        let narg = words
+            .next()
+            .and_then(|w| w.parse::<i32>().ok())
+            .ok_or_else(|| format!("{}: expecting a number in '{}'", what, header))?;
+        let attach = words
+            .map(|w| w.parse::<IdxType>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("{}: bad vnum in '{}'", what, header))?;
+
+        let arglist = read_tilde_string(&lines, &mut pos)
+            .ok_or_else(|| format!("{}: file ended in the argument", what))?;
+        let body = read_tilde_string(&lines, &mut pos)
+            .ok_or_else(|| format!("{}: file ended in the script", what))?;
+
+        triggers.push(TrigData {
+            vnum,
+            name,
+            attach_type,
+            event,
+            narg,
+            attach,
+            arglist,
+            script: body.lines().map(|l| l.to_string()).collect(),
+        });
+    }
+}
+
+/* Read every trigger file listed in the trigger index. */
+pub fn load_triggers(mini_mud: bool) -> Vec<TrigData> {
+    let index = format!(
+        "{}{}",
+        TRG_PREFIX,
+        if mini_mud { "index.mini" } else { "index" }
+    );
+    let Ok(list) = fs::read_to_string(&index) else {
+        info!("   No trigger index {}, no triggers loaded.", index);
+        return vec![];
+    };
+
+    let mut triggers: Vec<TrigData> = vec![];
+    for file in list
+        .lines()
+        .map(str::trim)
+        .take_while(|l| !l.starts_with('$'))
+    {
+        if file.is_empty() {
+            continue;
+        }
+        let filename = format!("{}{}", TRG_PREFIX, file);
+        let text = fs::read_to_string(&filename).unwrap_or_else(|err| {
+            error!("SYSERR: File '{}' listed in '{}': {}", filename, index, err);
+            process::exit(1);
+        });
+        match parse_triggers(&text, &filename) {
+            Ok(mut list) => triggers.append(&mut list),
+            Err(err) => {
+                error!("SYSERR: Format error in {}", err);
+                process::exit(1);
+            }
+        }
+    }
+    triggers.sort_by_key(|t| t.vnum);
+    for pair in triggers.windows(2) {
+        if pair[0].vnum == pair[1].vnum {
+            error!("SYSERR: Duplicate trigger #{}.", pair[0].vnum);
+            process::exit(1);
+        }
+    }
+    info!("   {} triggers.", triggers.len());
+    triggers
+}
+
+/**************************************************************************
+*  Expressions                                                            *
+**************************************************************************/
+
+const OPERATORS: [[&str; 7]; 5] = [
+    ["||", "", "", "", "", "", ""],
+    ["&&", "", "", "", "", "", ""],
+    ["==", "!=", "<=", ">=", "<", ">", "/="],
+    ["+", "-", "", "", "", "", ""],
+    ["*", "/", "", "", "", "", ""],
+];
+
+fn is_operator(token: &str) -> bool {
+    token == "("
+        || token == ")"
+        || OPERATORS
+            .iter()
+            .flatten()
+            .any(|op| !op.is_empty() && *op == token)
+}
+
+fn tokenize(expr: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    for word in expr.split_whitespace() {
+        let mut word = word;
+        while let Some(rest) = word.strip_prefix('(') {
+            tokens.push("(".to_string());
+            word = rest;
+        }
+        while word.len() > 1 && word.starts_with('!') && !word.starts_with("!=") {
+            tokens.push("!".to_string());
+            word = &word[1..];
+        }
+        let mut closing = 0;
+        while word.len() > 1 && word.ends_with(')') {
+            closing += 1;
+            word = &word[..word.len() - 1];
+        }
+        if !word.is_empty() {
+            tokens.push(word.to_string());
+        }
+        for _ in 0..closing {
+            tokens.push(")".to_string());
+        }
+    }
+    tokens
+}
+
+pub fn truthy(value: &str) -> bool {
+    !value.is_empty() && value != "0"
+}
+
+fn number(value: &str) -> i64 {
+    value.trim().parse().unwrap_or(0)
+}
+
+fn apply(op: &str, a: &str, b: &str) -> String {
+    let nums = (a.trim().parse::<i64>(), b.trim().parse::<i64>());
+    let bool_str = |b: bool| if b { "1" } else { "0" }.to_string();
+    let cmp = match nums {
+        (Ok(x), Ok(y)) => x.cmp(&y),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    };
+    match op {
+        "||" => bool_str(truthy(a) || truthy(b)),
+        "&&" => bool_str(truthy(a) && truthy(b)),
+        "==" => bool_str(cmp.is_eq()),
+        "!=" => bool_str(!cmp.is_eq()),
+        "<" => bool_str(cmp.is_lt()),
+        ">" => bool_str(cmp.is_gt()),
+        "<=" => bool_str(cmp.is_le()),
+        ">=" => bool_str(cmp.is_ge()),
+        "/=" => bool_str(a.to_lowercase().contains(&b.to_lowercase())),
+        /* The numbers may come from players: wrap rather than panic */
+        "+" => number(a).wrapping_add(number(b)).to_string(),
+        "-" => number(a).wrapping_sub(number(b)).to_string(),
+        "*" => number(a).wrapping_mul(number(b)).to_string(),
+        "/" => number(a).checked_div(number(b)).unwrap_or(0).to_string(),
+        _ => String::new(),
+    }
+}
+
+struct ExprParser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn binary(&mut self, level: usize) -> String {
+        if level == OPERATORS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1);
+        while let Some(op) = self.tokens.get(self.pos).cloned() {
+            if !OPERATORS[level].contains(&op.as_str()) || op.is_empty() {
+                break;
+            }
+            self.pos += 1;
+            let right = self.binary(level + 1);
+            left = apply(&op, &left, &right);
+        }
+        left
+    }
+
+    fn unary(&mut self) -> String {
+        match self.tokens.get(self.pos).map(String::as_str) {
+            Some("!") => {
+                self.pos += 1;
+                let value = self.unary();
+                if truthy(&value) { "0" } else { "1" }.to_string()
+            }
+            Some("(") => {
+                self.pos += 1;
+                let value = self.binary(0);
+                if self.tokens.get(self.pos).map(String::as_str) == Some(")") {
+                    self.pos += 1;
+                }
+                value
+            }
+            _ => {
+                /* Words next to each other make up one string. */
+                let mut words = vec![];
+                while let Some(token) = self.tokens.get(self.pos) {
+                    if is_operator(token) || token == "!" {
+                        break;
+                    }
+                    words.push(token.clone());
+                    self.pos += 1;
+                }
+                words.join(" ")
+            }
+        }
+    }
+}
+
+pub fn eval_expr(expr: &str) -> String {
+    let mut parser = ExprParser {
+        tokens: tokenize(expr),
+        pos: 0,
+    };
+    parser.binary(0)
+}
+
+/**************************************************************************
+*  Running scripts                                                        *
+**************************************************************************/
+
+struct ScriptRun {
+    trig: usize,
+    owner: ScriptOwner,
+    actor: Option<DepotId>,
+    victim: Option<DepotId>,
+    object: Option<DepotId>,
+    vars: HashMap<String, String>,
+}
+
+impl ScriptRun {
+    fn new(trig: usize, owner: ScriptOwner) -> ScriptRun {
+        ScriptRun {
+            trig,
+            owner,
+            actor: None,
+            victim: None,
+            object: None,
+            vars: HashMap::new(),
+        }
+    }
+}
+
+fn char_alive(chars: &Depot<CharData>, id: DepotId) -> bool {
+    chars.contains(id) && {
+        let ch = chars.get(id);
+        let dying = if ch.is_npc() {
+            ch.mob_flagged(MOB_NOTDEADYET)
+        } else {
+            ch.plr_flagged(PLR_NOTDEADYET)
+        };
+        !dying && ch.in_room() != NOWHERE
+    }
+}
+
+fn obj_room(chars: &Depot<CharData>, objs: &Depot<ObjData>, oid: DepotId) -> RoomRnum {
+    let obj = objs.get(oid);
+    if obj.in_room() != NOWHERE {
+        obj.in_room()
+    } else if let Some(holder) = obj.carried_by.or(obj.worn_by) {
+        chars.get(holder).in_room()
+    } else if let Some(container) = obj.in_obj {
+        obj_room(chars, objs, container)
+    } else {
+        NOWHERE
+    }
+}
+
+fn owner_alive(chars: &Depot<CharData>, objs: &Depot<ObjData>, owner: ScriptOwner) -> bool {
+    match owner {
+        ScriptOwner::Room(_) => true,
+        ScriptOwner::Char(id) => char_alive(chars, id),
+        ScriptOwner::Obj(id) => objs.contains(id),
+    }
+}
+
+fn owner_room(chars: &Depot<CharData>, objs: &Depot<ObjData>, owner: ScriptOwner) -> RoomRnum {
+    match owner {
+        ScriptOwner::Room(room) => room,
+        ScriptOwner::Char(id) => chars.get(id).in_room(),
+        ScriptOwner::Obj(id) => obj_room(chars, objs, id),
+    }
+}
+
+fn char_field(db: &DB, ch: &CharData, field: &str) -> String {
+    match field {
+        "" | "name" => ch.get_name().to_string(),
+        "vnum" => {
+            if ch.is_npc() {
+                db.get_mob_vnum(ch).to_string()
+            } else {
+                "-1".to_string()
+            }
+        }
+        "level" => ch.get_level().to_string(),
+        "room" => db.get_room_vnum(ch.in_room()).to_string(),
+        "gold" => ch.get_gold().to_string(),
+        "align" => ch.get_alignment().to_string(),
+        "hit" => ch.get_hit().to_string(),
+        "is_pc" => if ch.is_npc() { "0" } else { "1" }.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn obj_field(
+    db: &DB,
+    chars: &Depot<CharData>,
+    objs: &Depot<ObjData>,
+    oid: DepotId,
+    field: &str,
+) -> String {
+    let obj = objs.get(oid);
+    match field {
+        "" | "name" => obj.short_description.to_string(),
+        "vnum" => db.get_obj_vnum(obj).to_string(),
+        "room" => db.get_room_vnum(obj_room(chars, objs, oid)).to_string(),
+        "cost" => obj.get_obj_cost().to_string(),
+        _ => String::new(),
+    }
+}
+
+/* The value of one %variable%, without the percent signs. */
+fn var_value(
+    db: &DB,
+    chars: &Depot<CharData>,
+    objs: &Depot<ObjData>,
+    run: &ScriptRun,
+    name: &str,
+) -> String {
+    let (base, field) = name.split_once('.').unwrap_or((name, ""));
+    match base {
+        "actor" | "victim" => {
+            let id = if base == "actor" {
+                run.actor
+            } else {
+                run.victim
+            };
+            id.filter(|&id| chars.contains(id))
+                .map(|id| char_field(db, chars.get(id), field))
+                .unwrap_or_default()
+        }
+        "object" => run
+            .object
+            .filter(|&id| objs.contains(id))
+            .map(|id| obj_field(db, chars, objs, id, field))
+            .unwrap_or_default(),
+        "self" => match run.owner {
+            ScriptOwner::Char(id) => char_field(db, chars.get(id), field),
+            ScriptOwner::Obj(id) => obj_field(db, chars, objs, id, field),
+            ScriptOwner::Room(room) => match field {
+                "" | "name" => db.world[room as usize].name.to_string(),
+                "vnum" | "room" => db.get_room_vnum(room).to_string(),
+                _ => String::new(),
+            },
+        },
+        "random" => match field.parse::<u32>() {
+            Ok(n) if n > 0 => rand_number(1, n).to_string(),
+            _ => String::new(),
+        },
+        "time" if field == "hour" => db.time_info.hours.to_string(),
+        _ => run
+            .vars
+            .get(name)
+            .or_else(|| {
+                db.scripts
+                    .state
+                    .get(&run.owner)
+                    .and_then(|s| s.vars.get(name))
+            })
+            .cloned()
+            .unwrap_or_default(),
+    }
+}
+
+fn substitute(
+    db: &DB,
+    chars: &Depot<CharData>,
+    objs: &Depot<ObjData>,
+    run: &ScriptRun,
+    line: &str,
+) -> String {
+    let mut buf = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('%') {
+        buf.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(0) => {
+                buf.push('%');
+                rest = &after[1..];
+            }
+            Some(end) => {
+                buf.push_str(&var_value(
+                    db,
+                    chars,
+                    objs,
+                    run,
+                    &after[..end].to_lowercase(),
+                ));
+                rest = &after[end + 1..];
+            }
+            None => {
+                buf.push('%');
+                rest = after;
+            }
+        }
+    }
+    buf.push_str(rest);
+    buf
+}
+
+fn split_word(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    match line.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (line, ""),
+    }
+}
+
+/* Find who a script command is aimed at. */
+fn find_target(
+    chars: &Depot<CharData>,
+    objs: &Depot<ObjData>,
+    run: &ScriptRun,
+    name: &str,
+) -> Option<DepotId> {
+    let found = match name {
+        "actor" => run.actor,
+        "victim" => run.victim,
+        "self" => match run.owner {
+            ScriptOwner::Char(id) => Some(id),
+            _ => None,
+        },
+        _ => {
+            let room = owner_room(chars, objs, run.owner);
+            if room == NOWHERE {
+                return None;
+            }
+            return chars
+                .iter()
+                .find(|ch| ch.in_room() == room && isname(name, &ch.player.name))
+                .map(|ch| ch.id());
+        }
+    };
+    found.filter(|&id| char_alive(chars, id))
+}
+
+fn script_error(db: &DB, run: &ScriptRun, lineno: usize, msg: &str) {
+    let trig = &db.scripts.triggers[run.trig];
+    error!(
+        "SYSERR: Trigger #{} ({}), line {}: {}",
+        trig.vnum,
+        trig.name,
+        lineno + 1,
+        msg
+    );
+}
+
+/* Skip to after the matching 'end', from inside an if block. */
+fn skip_to_end(script: &[String], mut pc: usize) -> usize {
+    let mut depth = 0;
+    while pc < script.len() {
+        let (word, _) = split_word(&script[pc]);
+        pc += 1;
+        match word {
+            "if" => depth += 1,
+            "end" if depth == 0 => return pc,
+            "end" => depth -= 1,
+            _ => {}
+        }
+    }
+    pc
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_command(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    run: &mut ScriptRun,
+    lineno: usize,
+    word: &str,
+    rest: &str,
+) {
+    let room = owner_room(chars, objs, run.owner);
+    match word {
+        "set" => {
+            let (var, value) = split_word(rest);
+            run.vars.insert(var.to_lowercase(), value.to_string());
+        }
+        "eval" => {
+            let (var, expr) = split_word(rest);
+            run.vars.insert(var.to_lowercase(), eval_expr(expr));
+        }
+        "unset" => {
+            let var = rest.trim().to_lowercase();
+            run.vars.remove(&var);
+            if let Some(state) = db.scripts.state.get_mut(&run.owner) {
+                state.vars.remove(&var);
+            }
+        }
+        "global" => {
+            let var = rest.trim().to_lowercase();
+            let value = run.vars.get(&var).cloned().unwrap_or_default();
+            db.scripts
+                .state
+                .entry(run.owner)
+                .or_default()
+                .vars
+                .insert(var, value);
+        }
+        "echo" => {
+            if room != NOWHERE {
+                send_to_room(
+                    &mut game.descriptors,
+                    chars,
+                    db,
+                    room,
+                    &format!("{}\r\n", rest),
+                );
+            }
+        }
+        "send" | "echoaround" => {
+            let (target, msg) = split_word(rest);
+            let Some(target) = find_target(chars, objs, run, target) else {
+                return;
+            };
+            let msg = format!("{}\r\n", msg);
+            if word == "send" {
+                send_to_char(&mut game.descriptors, chars.get(target), &msg);
+            } else {
+                let troom = chars.get(target).in_room();
+                for id in db.world[troom as usize].peoples.clone() {
+                    if id != target {
+                        send_to_char(&mut game.descriptors, chars.get(id), &msg);
+                    }
+                }
+            }
+        }
+        "teleport" => {
+            let (target, vnum) = split_word(rest);
+            let Some(target) = find_target(chars, objs, run, target) else {
+                return;
+            };
+            let to_room = vnum.parse().map_or(NOWHERE, |v| db.real_room(v));
+            if to_room == NOWHERE {
+                script_error(
+                    db,
+                    run,
+                    lineno,
+                    &format!("no room '{}' to teleport to", vnum),
+                );
+                return;
+            }
+            let ch = chars.get_mut(target);
+            if ch.fighting_id().is_some() {
+                db.stop_fighting(ch);
+            }
+            db.char_from_room(objs, chars.get_mut(target));
+            db.char_to_room(chars, objs, target, to_room);
+            let ch = chars.get(target);
+            if ch.desc.is_some() {
+                look_at_room(&mut game.descriptors, db, chars, texts, objs, ch, false);
+            }
+        }
+        "force" => {
+            let (target, command) = split_word(rest);
+            if let Some(target) = find_target(chars, objs, run, target) {
+                command_interpreter(game, db, chars, texts, objs, target, command);
+            }
+        }
+        "load" => {
+            let (kind, vnum) = split_word(rest);
+            let Ok(vnum) = vnum.parse::<IdxType>() else {
+                script_error(db, run, lineno, &format!("bad vnum '{}' to load", vnum));
+                return;
+            };
+            if room == NOWHERE {
+                return;
+            }
+            match kind {
+                "mob" => match db.read_mobile(chars, vnum, LoadType::Virtual) {
+                    Some(mob) => db.char_to_room(chars, objs, mob, room),
+                    None => script_error(db, run, lineno, &format!("no mob #{}", vnum)),
+                },
+                "obj" => match db.read_object(objs, vnum, LoadType::Virtual) {
+                    Some(oid) => match run.owner {
+                        ScriptOwner::Char(id) => obj_to_char(objs.get_mut(oid), chars.get_mut(id)),
+                        _ => db.obj_to_room(objs.get_mut(oid), room),
+                    },
+                    None => script_error(db, run, lineno, &format!("no obj #{}", vnum)),
+                },
+                _ => script_error(db, run, lineno, "load what, mob or obj?"),
+            }
+        }
+        "purge" => {
+            let name = rest.trim();
+            if let Some(target) = find_target(chars, objs, run, name) {
+                if chars.get(target).is_npc() {
+                    db.extract_char(chars, target);
+                }
+                return;
+            }
+            if room == NOWHERE {
+                return;
+            }
+            if let Some(oid) = db.world[room as usize]
+                .contents
+                .iter()
+                .copied()
+                .find(|&oid| isname(name, &objs.get(oid).name))
+            {
+                db.extract_obj(chars, objs, oid);
+            }
+        }
+        _ => match run.owner {
+            ScriptOwner::Char(id) => {
+                let line = format!("{} {}", word, rest);
+                command_interpreter(game, db, chars, texts, objs, id, &line);
+            }
+            _ => script_error(db, run, lineno, &format!("unknown command '{}'", word)),
+        },
+    }
+}
+
+/* Run a trigger's script; returns the value given to 'return', or 1. */
+fn run_script(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    mut run: ScriptRun,
+) -> i32 {
+    if db.scripts.depth >= MAX_SCRIPT_DEPTH {
+        script_error(db, &run, 0, "triggers nested too deep, not run");
+        return 1;
+    }
+    db.scripts.depth += 1;
+
+    let script = db.scripts.triggers[run.trig].script.clone();
+    let mut ret = 1;
+    let mut pc = 0;
+    while pc < script.len() {
+        if !owner_alive(chars, objs, run.owner) {
+            break;
+        }
+        let lineno = pc;
+        let (word, rest) = split_word(&script[pc]);
+        pc += 1;
+        if word.is_empty() || word.starts_with('*') {
+            continue;
+        }
+        let rest = substitute(db, chars, objs, &run, rest);
+        match word {
+            "if" => {
+                if truthy(&eval_expr(&rest)) {
+                    continue;
+                }
+                /* Look for a branch to take: elseif, else or the end. */
+                let mut depth = 0;
+                while pc < script.len() {
+                    let (word, cond) = split_word(&script[pc]);
+                    pc += 1;
+                    match word {
+                        "if" => depth += 1,
+                        "end" if depth == 0 => break,
+                        "end" => depth -= 1,
+                        "else" if depth == 0 => break,
+                        "elseif" if depth == 0 => {
+                            let cond = substitute(db, chars, objs, &run, cond);
+                            if truthy(&eval_expr(&cond)) {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            /* Reached at the end of the branch which was taken. */
+            "elseif" | "else" => pc = skip_to_end(&script, pc),
+            "end" => {}
+            "halt" => break,
+            "return" => {
+                ret = number(&rest) as i32;
+                break;
+            }
+            _ => run_command(game, db, chars, texts, objs, &mut run, lineno, word, &rest),
+        }
+    }
+
+    db.scripts.depth -= 1;
+    ret
+}
+
+/**************************************************************************
+*  Firing triggers                                                        *
+**************************************************************************/
+
+fn mob_triggers(db: &DB, ch: &CharData, event: TrigEvent) -> Vec<usize> {
+    if !ch.is_npc() || ch.get_mob_rnum() == NOBODY || db.scripts.triggers.is_empty() {
+        return vec![];
+    }
+    db.scripts
+        .attached(AttachType::Mob, db.get_mob_vnum(ch), event)
+}
+
+fn obj_triggers(db: &DB, obj: &ObjData, event: TrigEvent) -> Vec<usize> {
+    if obj.get_obj_rnum() == NOTHING || db.scripts.triggers.is_empty() {
+        return vec![];
+    }
+    db.scripts
+        .attached(AttachType::Obj, db.get_obj_vnum(obj), event)
+}
+
+fn room_triggers(db: &DB, room: RoomRnum, event: TrigEvent) -> Vec<usize> {
+    if room == NOWHERE || db.scripts.triggers.is_empty() {
+        return vec![];
+    }
+    db.scripts
+        .attached(AttachType::Room, db.get_room_vnum(room), event)
+}
+
+fn percent(db: &DB, trig: usize) -> bool {
+    rand_number(1, 100) as i32 <= db.scripts.triggers[trig].narg
+}
+
+/* A player arrived: greet triggers of the mobs who see them. */
+pub fn greet_mtrigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    actor: DepotId,
+) {
+    if chars.get(actor).is_npc() {
+        return;
+    }
+    let room = chars.get(actor).in_room();
+    for mob in db.world[room as usize].peoples.clone() {
+        if mob == actor || !char_alive(chars, mob) || !char_alive(chars, actor) {
+            continue;
+        }
+        let ch = chars.get(mob);
+        if !ch.awake() || !crate::util::can_see(&game.descriptors, chars, db, ch, chars.get(actor))
+        {
+            continue;
+        }
+        for trig in mob_triggers(db, ch, TrigEvent::Greet) {
+            if percent(db, trig) {
+                let mut run = ScriptRun::new(trig, ScriptOwner::Char(mob));
+                run.actor = Some(actor);
+                run_script(game, db, chars, texts, objs, run);
+            }
+        }
+    }
+}
+
+/* Someone entered a room: its entry triggers, and the mob's own. */
+pub fn entry_trigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    actor: DepotId,
+) {
+    let room = chars.get(actor).in_room();
+    for trig in room_triggers(db, room, TrigEvent::Entry) {
+        if char_alive(chars, actor) && percent(db, trig) {
+            let mut run = ScriptRun::new(trig, ScriptOwner::Room(room));
+            run.actor = Some(actor);
+            run_script(game, db, chars, texts, objs, run);
+        }
+    }
+    for trig in mob_triggers(db, chars.get(actor), TrigEvent::Entry) {
+        if char_alive(chars, actor) && percent(db, trig) {
+            let run = ScriptRun::new(trig, ScriptOwner::Char(actor));
+            run_script(game, db, chars, texts, objs, run);
+        }
+    }
+}
+
+fn speech_matches(trig: &TrigData, speech: &str) -> bool {
+    let arglist = trig.arglist.trim();
+    if arglist == "*" {
+        return true;
+    }
+    let speech = speech.to_lowercase();
+    if trig.narg != 0 {
+        /* the whole phrase */
+        return speech.contains(&arglist.to_lowercase());
+    }
+    let words: Vec<&str> = speech
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    arglist
+        .split_whitespace()
+        .any(|a| words.iter().any(|w| w.eq_ignore_ascii_case(a)))
+}
+
+pub fn speech_trigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    actor: DepotId,
+    speech: &str,
+) {
+    let room = chars.get(actor).in_room();
+    let mut runs = vec![];
+    for mob in db.world[room as usize].peoples.clone() {
+        if mob != actor {
+            for trig in mob_triggers(db, chars.get(mob), TrigEvent::Speech) {
+                runs.push((trig, ScriptOwner::Char(mob)));
+            }
+        }
+    }
+    for trig in room_triggers(db, room, TrigEvent::Speech) {
+        runs.push((trig, ScriptOwner::Room(room)));
+    }
+    for (trig, owner) in runs {
+        if !speech_matches(&db.scripts.triggers[trig], speech)
+            || !owner_alive(chars, objs, owner)
+            || !char_alive(chars, actor)
+        {
+            continue;
+        }
+        let mut run = ScriptRun::new(trig, owner);
+        run.actor = Some(actor);
+        run.vars.insert("arg".to_string(), speech.to_string());
+        run.vars.insert("speech".to_string(), speech.to_string());
+        run_script(game, db, chars, texts, objs, run);
+    }
+}
+
+/*
+ * A player typed a command: returns true if a command trigger took care
+ * of it, and the command itself must not be run.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn command_trigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    actor: DepotId,
+    cmd: &str,
+    arg: &str,
+) -> bool {
+    let ch = chars.get(actor);
+    if ch.is_npc() || db.scripts.triggers.is_empty() || ch.in_room() == NOWHERE {
+        return false;
+    }
+    let room = ch.in_room();
+
+    let mut runs = vec![];
+    for trig in room_triggers(db, room, TrigEvent::Command) {
+        runs.push((trig, ScriptOwner::Room(room)));
+    }
+    let mut held: Vec<DepotId> = ch.equipment.iter().flatten().copied().collect();
+    held.extend(ch.carrying.iter().copied());
+    held.extend(db.world[room as usize].contents.iter().copied());
+    for oid in held {
+        for trig in obj_triggers(db, objs.get(oid), TrigEvent::Command) {
+            runs.push((trig, ScriptOwner::Obj(oid)));
+        }
+    }
+    for mob in db.world[room as usize].peoples.clone() {
+        if mob != actor {
+            for trig in mob_triggers(db, chars.get(mob), TrigEvent::Command) {
+                runs.push((trig, ScriptOwner::Char(mob)));
+            }
+        }
+    }
+
+    for (trig, owner) in runs {
+        let matches = db.scripts.triggers[trig]
+            .arglist
+            .split_whitespace()
+            .any(|w| w.eq_ignore_ascii_case(cmd));
+        if !matches || !owner_alive(chars, objs, owner) {
+            continue;
+        }
+        let mut run = ScriptRun::new(trig, owner);
+        run.actor = Some(actor);
+        run.vars.insert("cmd".to_string(), cmd.to_string());
+        run.vars.insert("arg".to_string(), arg.trim().to_string());
+        if run_script(game, db, chars, texts, objs, run) != 0 {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn fight_mtrigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    mob: DepotId,
+) {
+    for trig in mob_triggers(db, chars.get(mob), TrigEvent::Fight) {
+        let Some(victim) = chars.get(mob).fighting_id() else {
+            return;
+        };
+        if char_alive(chars, mob) && percent(db, trig) {
+            let mut run = ScriptRun::new(trig, ScriptOwner::Char(mob));
+            run.actor = Some(victim);
+            run_script(game, db, chars, texts, objs, run);
+        }
+    }
+}
+
+/* Called while the mob is still in the room, before the corpse is made. */
+pub fn death_mtrigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    mob: DepotId,
+    killer: Option<DepotId>,
+) {
+    for trig in mob_triggers(db, chars.get(mob), TrigEvent::Death) {
+        let mut run = ScriptRun::new(trig, ScriptOwner::Char(mob));
+        run.actor = killer.filter(|&k| chars.contains(k));
+        run_script(game, db, chars, texts, objs, run);
+    }
+}
+
+/* obj has just been handed from giver to receiver. */
+#[allow(clippy::too_many_arguments)]
+pub fn give_trigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    giver: DepotId,
+    oid: DepotId,
+    receiver: DepotId,
+) {
+    for trig in obj_triggers(db, objs.get(oid), TrigEvent::Give) {
+        if !objs.contains(oid) {
+            return;
+        }
+        let mut run = ScriptRun::new(trig, ScriptOwner::Obj(oid));
+        run.actor = Some(giver);
+        run.victim = Some(receiver);
+        run.object = Some(oid);
+        run_script(game, db, chars, texts, objs, run);
+    }
+    for trig in mob_triggers(db, chars.get(receiver), TrigEvent::Give) {
+        if !char_alive(chars, receiver) {
+            return;
+        }
+        let mut run = ScriptRun::new(trig, ScriptOwner::Char(receiver));
+        run.actor = Some(giver);
+        run.object = Some(oid).filter(|&o| objs.contains(o));
+        run_script(game, db, chars, texts, objs, run);
+    }
+}
+
+/* obj has just been dropped by actor. */
+pub fn drop_trigger(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    actor: DepotId,
+    oid: DepotId,
+) {
+    let room = objs.get(oid).in_room();
+    for trig in obj_triggers(db, objs.get(oid), TrigEvent::Drop) {
+        if !objs.contains(oid) {
+            return;
+        }
+        let mut run = ScriptRun::new(trig, ScriptOwner::Obj(oid));
+        run.actor = Some(actor);
+        run.object = Some(oid);
+        run_script(game, db, chars, texts, objs, run);
+    }
+    for trig in room_triggers(db, room, TrigEvent::Drop) {
+        let mut run = ScriptRun::new(trig, ScriptOwner::Room(room));
+        run.actor = Some(actor);
+        run.object = Some(oid).filter(|&o| objs.contains(o));
+        run_script(game, db, chars, texts, objs, run);
+    }
+}
+
+/* Random and timer triggers, run every PULSE_SCRIPT. */
+pub fn script_pulse(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+) {
+    if db.scripts.triggers.is_empty() {
+        return;
+    }
+
+    /* Random triggers only fire where there is someone to notice. */
+    let mut zones_with_players = vec![false; db.zone_table.len()];
+    for d_id in game.descriptor_list.clone() {
+        let d = game.desc(d_id);
+        if d.state() != ConPlaying {
+            continue;
+        }
+        if let Some(chid) = d.character {
+            let room = chars.get(chid).in_room();
+            if room != NOWHERE {
+                zones_with_players[db.world[room as usize].zone as usize] = true;
+            }
+        }
+    }
+
+    let mut owners = vec![];
+    for &chid in &db.character_list {
+        let ch = chars.get(chid);
+        if ch.is_npc() && ch.in_room() != NOWHERE {
+            let mut trigs = mob_triggers(db, ch, TrigEvent::Random);
+            trigs.extend(mob_triggers(db, ch, TrigEvent::Timer));
+            for trig in trigs {
+                owners.push((trig, ScriptOwner::Char(chid), ch.in_room()));
+            }
+        }
+    }
+    for &oid in &db.object_list {
+        let obj = objs.get(oid);
+        let mut trigs = obj_triggers(db, obj, TrigEvent::Random);
+        trigs.extend(obj_triggers(db, obj, TrigEvent::Timer));
+        for trig in trigs {
+            owners.push((trig, ScriptOwner::Obj(oid), obj_room(chars, objs, oid)));
+        }
+    }
+    for (&vnum, trigs) in &db.scripts.room {
+        let room = db.real_room(vnum);
+        for &trig in trigs {
+            let event = db.scripts.triggers[trig].event;
+            if room != NOWHERE && (event == TrigEvent::Random || event == TrigEvent::Timer) {
+                owners.push((trig, ScriptOwner::Room(room), room));
+            }
+        }
+    }
+
+    for (trig, owner, room) in owners {
+        if !owner_alive(chars, objs, owner) {
+            continue;
+        }
+        let fire = if db.scripts.triggers[trig].event == TrigEvent::Timer {
+            let pulses = db.scripts.triggers[trig].narg.max(1);
+            let left = db
+                .scripts
+                .state
+                .entry(owner)
+                .or_default()
+                .timers
+                .entry(trig)
+                .or_insert(pulses);
+            *left -= 1;
+            if *left <= 0 {
+                *left = pulses;
+                true
+            } else {
+                false
+            }
+        } else {
+            room != NOWHERE
+                && zones_with_players[db.world[room as usize].zone as usize]
+                && percent(db, trig)
+        };
+        if fire {
+            run_script(game, db, chars, texts, objs, ScriptRun::new(trig, owner));
+        }
+    }
+}
+
+#[cfg(test)]
+mod scripts_tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_expr() {
+        assert_eq!(eval_expr("1 + 2 * 3"), "7");
+        assert_eq!(eval_expr("(1 + 2) * 3"), "9");
+        assert_eq!(eval_expr("10 / 0"), "0");
+        assert_eq!(eval_expr("5 > 3 && 2 > 1"), "1");
+        assert_eq!(eval_expr("5 < 3 || 0"), "0");
+        assert_eq!(eval_expr("!(5 < 3)"), "1");
+        assert_eq!(eval_expr("Bob Smith == bob smith"), "1");
+        assert_eq!(eval_expr("hello world /= WORLD"), "1");
+        assert_eq!(eval_expr("10 >= 9"), "1");
+        assert_eq!(eval_expr("abc != abc"), "0");
+        assert_eq!(eval_expr("9223372036854775807 + 1"), "-9223372036854775808");
+        assert_eq!(eval_expr("-9223372036854775808 - 1"), "9223372036854775807");
+        assert_eq!(eval_expr("4611686018427387904 * 4"), "0");
+        assert_eq!(eval_expr("-9223372036854775808 / -1"), "0");
+        assert_eq!(eval_expr("-7 / 2"), "-3");
+    }
+
+    #[test]
+    fn test_truthy() {
+        assert!(truthy("1"));
+        assert!(truthy("yes"));
+        assert!(!truthy("0"));
+        assert!(!truthy(""));
+    }
+
+    #[test]
+    fn test_parse_triggers() {
+        let text = "#3000\nGreeter~\nMOB GREET 100 3001 3002\n~\nsay Hello, %actor.name%!\nsmile\n~\n\
+                    #3001\nPull lever~\nROOM COMMAND 0 3014\npull~\nif %arg% /= lever\n  echo Click.\nend\n~\n$~\n";
+        let triggers = parse_triggers(text, "test.trg").unwrap();
+        assert_eq!(triggers.len(), 2);
+        assert_eq!(triggers[0].vnum, 3000);
+        assert_eq!(triggers[0].name, "Greeter");
+        assert_eq!(triggers[0].attach_type, AttachType::Mob);
+        assert_eq!(triggers[0].event, TrigEvent::Greet);
+        assert_eq!(triggers[0].narg, 100);
+        assert_eq!(triggers[0].attach, vec![3001, 3002]);
+        assert_eq!(triggers[0].arglist, "");
+        assert_eq!(&*triggers[0].script, ["say Hello, %actor.name%!", "smile"]);
+        assert_eq!(triggers[1].event, TrigEvent::Command);
+        assert_eq!(triggers[1].arglist, "pull");
+        assert_eq!(triggers[1].script.len(), 3);
+
+        assert!(parse_triggers("#1\nBad~\nMOB NOSUCH 0\n~\n~\n$\n", "bad.trg").is_err());
+        assert!(parse_triggers("#1\nBad~\nMOB GREET 0\n~\n~\n", "bad.trg").is_err());
+    }
+
+    #[test]
+    fn test_speech_matches() {
+        let mut trig = parse_triggers("#1\nT~\nMOB SPEECH 0 1\nhello hi~\n~\n$\n", "t.trg")
+            .unwrap()
+            .remove(0);
+        assert!(speech_matches(&trig, "Well, HELLO there"));
+        assert!(!speech_matches(&trig, "othello"));
+        trig.narg = 1;
+        assert!(!speech_matches(&trig, "hello there"));
+        assert!(speech_matches(&trig, "I said hello hi!"));
+        trig.arglist = "*".to_string();
+        assert!(speech_matches(&trig, "anything"));
+    }
+
+    #[test]
+    fn test_set_triggers() {
+        let mut scripts = Scripts::default();
+        scripts.set_triggers(
+            parse_triggers(
+                "#2\nB~\nROOM DROP 0 3001\n~\n~\n#1\nA~\nROOM ENTRY 100 3001\n~\n~\n$\n",
+                "t.trg",
+            )
+            .unwrap(),
+        );
+        assert_eq!(scripts.real_trigger(1), Some(0));
+        assert_eq!(scripts.real_trigger(2), Some(1));
+        assert_eq!(scripts.real_trigger(3), None);
+        assert_eq!(
+            scripts.attached(AttachType::Room, 3001, TrigEvent::Entry),
+            vec![0]
+        );
+        assert!(scripts
+            .attached(AttachType::Mob, 3001, TrigEvent::Entry)
+            .is_empty());
+    }
+}
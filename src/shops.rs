@@ -12,12 +12,11 @@
 use std::cmp::min;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::process;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::depot::{Depot, DepotId, HasId};
-use crate::{act, send_to_char, TextData, VictimRef};
+use crate::{act_msg, send_to_char, TextData, VictimRef};
 use log::error;
 use regex::Regex;
 
@@ -25,7 +24,7 @@ use crate::act_comm::{do_say, do_tell};
 use crate::act_social::do_action;
 use crate::act_wizard::do_echo;
 use crate::constants::{DRINKS, EXTRA_BITS, ITEM_TYPES};
-use crate::db::{fread_string, LoadType, DB};
+use crate::db::{fread_string, parse_num, FormatError, LoadType, DB};
 use crate::handler::{
     fname, get_number, get_obj_in_list_num, get_obj_in_list_vis, isname, obj_from_char, obj_to_char,
 };
@@ -194,15 +193,7 @@ impl ShopData {
 pub const MIN_OUTSIDE_BANK: i32 = 5000;
 pub const MAX_OUTSIDE_BANK: i32 = 15000;
 
-pub const MSG_NOT_OPEN_YET: &str = "Come back later!";
-pub const MSG_NOT_REOPEN_YET: &str = "Sorry, we have closed, but come back later.";
-pub const MSG_CLOSED_FOR_DAY: &str = "Sorry, come back tomorrow.";
-pub const MSG_NO_STEAL_HERE: &str = "$n is a bloody thief!";
-pub const MSG_NO_SEE_CHAR: &str = "I don't trade with someone I can't see!";
-pub const MSG_NO_SELL_ALIGN: &str = "Get out of here before I call the guards!";
-pub const MSG_NO_SELL_CLASS: &str = "We don't serve your kind here!";
-pub const MSG_NO_USED_WANDSTAFF: &str = "I don't buy used up wands or staves!";
-pub const MSG_CANT_KILL_KEEPER: &str = "Get out of here before I call the guards!";
+/* the shopkeepers' own lines are the "shop.*" messages of the catalogs */
 
 /***
  * The entire shop rewrite for Circle 3.0 was done by Jeff Fink.  Thanks Jeff!
@@ -240,6 +231,7 @@ fn is_ok_char(
     let keeper = chars.get(keeper_id);
     // char buf[MAX_INPUT_LENGTH];
     if !can_see(&game.descriptors, chars, db, keeper, ch) {
+        let buf = db.msg(ch, "shop.no_see_char").to_string();
         do_say(
             game,
             db,
@@ -247,7 +239,7 @@ fn is_ok_char(
             texts,
             objs,
             keeper_id,
-            MSG_NO_SEE_CHAR,
+            &buf,
             CMD_SAY.load(Ordering::Relaxed),
             0,
         );
@@ -261,7 +253,7 @@ fn is_ok_char(
         || ch.is_evil() && db.shop_index[shop_nr].notrade_evil()
         || ch.is_neutral() && db.shop_index[shop_nr].notrade_neutral()
     {
-        let buf = format!("{} {}", ch.get_name(), MSG_NO_SELL_ALIGN);
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.no_sell_align"));
         do_tell(
             game,
             db,
@@ -284,7 +276,7 @@ fn is_ok_char(
         || ch.is_thief() && db.shop_index[shop_nr].notrade_thief()
         || ch.is_warrior() && db.shop_index[shop_nr].notrade_warrior()
     {
-        let buf = format!("{} {}", ch.get_name(), MSG_NO_SELL_CLASS);
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.no_sell_class"));
         do_tell(
             game,
             db,
@@ -309,22 +301,25 @@ fn is_open(
     texts: &mut Depot<TextData>,
     objs: &mut Depot<ObjData>,
     keeper_id: DepotId,
+    chid: DepotId,
     shop_nr: usize,
     msg: bool,
 ) -> bool {
-    let mut buf = String::new();
+    let mut id = "";
     if db.shop_index[shop_nr].open1 > db.time_info.hours {
-        buf.push_str(MSG_NOT_OPEN_YET);
+        id = "shop.not_open_yet";
     } else if db.shop_index[shop_nr].close1 < db.time_info.hours {
         if db.shop_index[shop_nr].open2 > db.time_info.hours {
-            buf.push_str(MSG_NOT_REOPEN_YET);
+            id = "shop.not_reopen_yet";
         } else if db.shop_index[shop_nr].close2 < db.time_info.hours {
-            buf.push_str(MSG_CLOSED_FOR_DAY);
+            id = "shop.closed_for_day";
         }
     }
-    if buf.is_empty() {
+    if id.is_empty() {
         return true;
     }
+    /* the keeper answers in the language of the customer */
+    let buf = db.msg(chars.get(chid), id).to_string();
 
     if msg {
         do_say(
@@ -353,7 +348,7 @@ fn is_ok(
     chid: DepotId,
     shop_nr: usize,
 ) -> bool {
-    if is_open(game, chars, db, texts, objs, keeper_id, shop_nr, true) {
+    if is_open(game, chars, db, texts, objs, keeper_id, chid, shop_nr, true) {
         return is_ok_char(game, chars, db, texts, objs, keeper_id, chid, shop_nr);
     }
     false
@@ -804,10 +799,7 @@ fn shopping_buy(
         buynum < 0
     };
     if res {
-        let buf = format!(
-            "{}s A negative amount?  Try selling me something.",
-            ch.get_name()
-        );
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.negative_buy"));
         do_tell(
             game,
             db,
@@ -822,7 +814,7 @@ fn shopping_buy(
         return;
     }
     if arg.is_empty() || buynum == 0 {
-        let buf = format!("{} What do you want to buy??", ch.get_name());
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.what_buy"));
         do_tell(
             game,
             db,
@@ -900,11 +892,11 @@ fn shopping_buy(
         send_to_char(
             &mut game.descriptors,
             ch,
-            format!(
-                "{}: You can't carry any more items.\r\n",
-                fname(objs.get(oid).name.as_ref())
-            )
-            .as_str(),
+            &db.msgf(
+                ch,
+                "shop.cant_carry_n",
+                &[&fname(objs.get(oid).name.as_ref())],
+            ),
         );
         return;
     }
@@ -912,11 +904,11 @@ fn shopping_buy(
         send_to_char(
             &mut game.descriptors,
             ch,
-            format!(
-                "{}: You can't carry that much weight.\r\n",
-                fname(objs.get(oid).name.as_ref())
-            )
-            .as_str(),
+            &db.msgf(
+                ch,
+                "shop.cant_carry_w",
+                &[&fname(objs.get(oid).name.as_ref())],
+            ),
         );
         return;
     }
@@ -967,18 +959,34 @@ fn shopping_buy(
     if bought < buynum {
         let ch = chars.get(chid);
         if oido.is_none_or(|oid| !same_obj(objs.get(last_oid), objs.get(oid))) {
-            buf = format!("{} I only have {} to sell you.", ch.get_name(), bought);
+            buf = format!(
+                "{} {}",
+                ch.get_name(),
+                db.msgf(ch, "shop.only_have", &[&bought])
+            );
         } else if ch.get_gold() < buy_price(chars, db, objs, oid, shop_nr, keeper_id, chid) {
-            buf = format!("{} You can only afford {}.", ch.get_name(), bought);
+            buf = format!(
+                "{} {}",
+                ch.get_name(),
+                db.msgf(ch, "shop.only_afford", &[&bought])
+            );
         } else if ch.is_carrying_n() >= ch.can_carry_n() as u8 {
-            buf = format!("{} You can only hold {}.", ch.get_name(), bought);
+            buf = format!(
+                "{} {}",
+                ch.get_name(),
+                db.msgf(ch, "shop.only_hold", &[&bought])
+            );
         } else if ch.is_carrying_w() + objs.get(oid).get_obj_weight() > ch.can_carry_w() as i32 {
-            buf = format!("{} You can only carry {}.", ch.get_name(), bought);
+            buf = format!(
+                "{} {}",
+                ch.get_name(),
+                db.msgf(ch, "shop.only_carry", &[&bought])
+            );
         } else {
             buf = format!(
-                "{} Something screwy only gave you {}.",
+                "{} {}",
                 ch.get_name(),
-                bought,
+                db.msgf(ch, "shop.screwy_buy", &[&bought])
             );
         }
         do_tell(
@@ -1001,17 +1009,17 @@ fn shopping_buy(
     let ch = chars.get(chid);
     let tempstr = times_message(Some(objs.get(ch.carrying[0])), "", bought);
 
-    let tempbuf = format!("$n buys {}.", tempstr);
     let obj = oido.map(|id| objs.get(id));
-    act(
+    act_msg(
         &mut game.descriptors,
         chars,
         db,
-        &tempbuf,
+        "shop.buys",
+        &[],
         false,
         Some(ch),
         obj,
-        None,
+        Some(VictimRef::Str(&tempstr)),
         TO_ROOM,
     );
     let ch = chars.get(chid);
@@ -1034,7 +1042,7 @@ fn shopping_buy(
     send_to_char(
         &mut game.descriptors,
         ch,
-        format!("You now have {}.\r\n", tempstr).as_str(),
+        &db.msgf(ch, "shop.now_have", &[&tempstr]),
     );
 
     if db.shop_index[shop_nr].shop_uses_bank() {
@@ -1098,23 +1106,20 @@ fn get_selling_obj(
     }
     let buf = match result {
         OBJECT_NOVAL => {
-            format!(
-                "{} You've got to be kidding, that thing is worthless!",
-                ch.get_name()
-            )
+            format!("{} {}", ch.get_name(), db.msg(ch, "shop.worthless"))
         }
         OBJECT_NOTOK => db.shop_index[shop_nr]
             .do_not_buy
             .replace("%s", ch.get_name()),
         OBJECT_DEAD => {
-            format!("{} {}", ch.get_name(), MSG_NO_USED_WANDSTAFF)
+            format!("{} {}", ch.get_name(), db.msg(ch, "shop.no_used_wandstaff"))
         }
         _ => {
             error!(
                 "SYSERR: Illegal return value of {} from trade_with()",
                 result
             ); /* Someone might rename it... */
-            format!("{} An error has occurred.", ch.get_name())
+            format!("{} {}", ch.get_name(), db.msg(ch, "shop.error"))
         }
     };
     do_tell(
@@ -1235,10 +1240,7 @@ fn shopping_sell(
     let sellnum = transaction_amt(&mut arg);
     if sellnum < 0 {
         let ch = chars.get(chid);
-        let buf = format!(
-            "{} A negative amount?  Try buying something.",
-            ch.get_name()
-        );
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.negative_sell"));
         do_tell(
             game,
             db,
@@ -1254,7 +1256,7 @@ fn shopping_sell(
     }
     if arg.is_empty() || sellnum == 0 {
         let ch = chars.get(chid);
-        let buf = format!("{} What do you want to sell??", ch.get_name());
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.what_sell"));
         do_tell(
             game,
             db,
@@ -1326,23 +1328,27 @@ fn shopping_sell(
         let buf = match oid {
             None => {
                 let ch = chars.get(chid);
-                format!("{} You only have {} of those.", ch.get_name(), sold)
+                format!(
+                    "{} {}",
+                    ch.get_name(),
+                    db.msgf(ch, "shop.only_have_sell", &[&sold])
+                )
             }
             Some(oid)
                 if keeper.get_gold() + db.shop_index[shop_nr].bank_account
                     < sell_price(objs.get(oid), &db.shop_index[shop_nr], keeper, ch) =>
             {
                 format!(
-                    "{} I can only afford to buy {} of those.",
+                    "{} {}",
                     ch.get_name(),
-                    sold
+                    db.msgf(ch, "shop.only_afford_buy", &[&sold])
                 )
             }
             _ => {
                 format!(
-                    "{} Something really screwy made me buy {}.",
+                    "{} {}",
                     ch.get_name(),
-                    sold
+                    db.msgf(ch, "shop.screwy_sell", &[&sold])
                 )
             }
         };
@@ -1363,18 +1369,18 @@ fn shopping_sell(
     ch.set_gold(ch.get_gold() + goldamt);
 
     let tempstr = times_message(None, &name, sold);
-    let tempbuf = format!("$n sells {}.", tempstr);
     let ch = chars.get(chid);
     let obj = oid.map(|id| objs.get(id));
-    act(
+    act_msg(
         &mut game.descriptors,
         chars,
         db,
-        &tempbuf,
+        "shop.sells",
+        &[],
         false,
         Some(ch),
         obj,
-        None,
+        Some(VictimRef::Str(&tempstr)),
         TO_ROOM,
     );
     let ch = chars.get(chid);
@@ -1397,7 +1403,7 @@ fn shopping_sell(
     send_to_char(
         &mut game.descriptors,
         ch,
-        format!("The shopkeeper now has {}.\r\n", tempstr).as_str(),
+        &db.msgf(ch, "shop.keeper_now_has", &[&tempstr]),
     );
     let keeper = chars.get(keeper_id);
     if keeper.get_gold() < MIN_OUTSIDE_BANK {
@@ -1429,7 +1435,7 @@ fn shopping_value(
 
     if arg.is_empty() {
         let ch = chars.get(chid);
-        let buf = format!("{} What do you want me to evaluate??", ch.get_name());
+        let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.what_value"));
         do_tell(
             game,
             db,
@@ -1452,11 +1458,8 @@ fn shopping_value(
     };
     let ch = chars.get(chid);
     let keeper = chars.get(keeper_id);
-    let buf = format!(
-        "{} I'll give you {} gold coins for that!",
-        ch.get_name(),
-        sell_price(objs.get(oid), &db.shop_index[shop_nr], keeper, ch)
-    );
+    let price = sell_price(objs.get(oid), &db.shop_index[shop_nr], keeper, ch);
+    let buf = format!("{} {}", ch.get_name(), db.msgf(ch, "shop.value", &[&price]));
     do_tell(
         game,
         db,
@@ -1486,7 +1489,7 @@ fn list_object(
     let mut quantity = String::new();
     let itemname;
     if shop_producing(db, objs, oid, shop_nr) {
-        quantity.push_str("Unlimited");
+        quantity.push_str(db.msg(chars.get(chid), "shop.unlimited"));
     } else {
         quantity.push_str(format!("{}", cnt).as_str());
     }
@@ -1512,7 +1515,7 @@ fn list_object(
                     "{}{}",
                     objs.get(oid).short_description,
                     if objs.get(oid).get_obj_val(2) < objs.get(oid).get_obj_val(1) {
-                        " (partially used)"
+                        db.msg(chars.get(chid), "shop.partially_used")
                     } else {
                         ""
                     }
@@ -1568,7 +1571,7 @@ pub fn shopping_list(
 
     one_argument(arg, &mut name);
 
-    let mut buf = String::from(" ##   Available   Item                                               Cost\r\n-------------------------------------------------------------------------\r\n");
+    let mut buf = db.msg(chars.get(chid), "shop.list_header").to_string();
     let mut last_oid: Option<DepotId> = None;
     let keeper = chars.get(keeper_id);
     if !keeper.carrying.is_empty() {
@@ -1608,16 +1611,12 @@ pub fn shopping_list(
             send_to_char(
                 &mut game.descriptors,
                 ch,
-                "Currently, there is nothing for sale.\r\n",
+                db.msg(ch, "shop.nothing_for_sale"),
             );
         }
         _ if !name.is_empty() && !found => {
             /* nothing the char was looking for was found */
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "Presently, none of those are for sale.\r\n",
-            );
+            send_to_char(&mut game.descriptors, ch, db.msg(ch, "shop.none_of_those"));
         }
         Some(last_oid) => {
             if name.is_empty() || isname(&name, objs.get(last_oid).name.as_ref()) {
@@ -1697,12 +1696,12 @@ pub fn shop_keeper(
     }
     let ch = chars.get(chid);
     if cmd_is(cmd, "steal") {
-        let argm = format!("$N shouts '{}'", MSG_NO_STEAL_HERE);
-        act(
+        act_msg(
             &mut game.descriptors,
             chars,
             db,
-            &argm,
+            "shop.no_steal_here",
+            &[],
             false,
             Some(ch),
             None,
@@ -1777,7 +1776,7 @@ pub fn ok_damage_shopkeeper(
         if victim.get_mob_rnum() == db.shop_index[sindex].keeper
             && !db.shop_index[sindex].shop_kill_chars()
         {
-            let buf = format!("{} {}", ch.get_name(), MSG_CANT_KILL_KEEPER);
+            let buf = format!("{} {}", ch.get_name(), db.msg(ch, "shop.cant_kill_keeper"));
             do_tell(
                 game,
                 db,
@@ -1841,13 +1840,13 @@ fn end_read_list(list: &mut Vec<ShopBuyData>, error: i32) -> usize {
     list.len()
 }
 
-fn read_line_int(db: &DB, reader: &mut BufReader<File>, data: &mut i32) {
+fn read_line_int(db: &DB, reader: &mut BufReader<File>, data: &mut i32) -> Result<(), FormatError> {
     let mut buf = String::new();
     if get_line(reader, &mut buf) != 0 {
         let r = buf.parse::<i32>();
         if let Ok(r) = r {
             *data = r;
-            return;
+            return Ok(());
         }
     }
     error!(
@@ -1855,16 +1854,20 @@ fn read_line_int(db: &DB, reader: &mut BufReader<File>, data: &mut i32) {
         db.shop_index.len(),
         buf
     );
-    process::exit(1);
+    Err(FormatError)
 }
 
-fn read_line_float(db: &DB, reader: &mut BufReader<File>, data: &mut f32) {
+fn read_line_float(
+    db: &DB,
+    reader: &mut BufReader<File>,
+    data: &mut f32,
+) -> Result<(), FormatError> {
     let mut buf = String::new();
     if get_line(reader, &mut buf) != 0 {
         let r = buf.parse::<f32>();
         if let Ok(r) = r {
             *data = r;
-            return;
+            return Ok(());
         }
     }
     error!(
@@ -1872,7 +1875,7 @@ fn read_line_float(db: &DB, reader: &mut BufReader<File>, data: &mut f32) {
         db.shop_index.len(),
         buf
     );
-    process::exit(1);
+    Err(FormatError)
 }
 
 fn read_list(
@@ -1882,12 +1885,12 @@ fn read_list(
     new_format: bool,
     max: i32,
     type_: i32,
-) -> usize {
+) -> Result<usize, FormatError> {
     let mut temp = -1;
     let mut error = 0;
     if new_format {
         loop {
-            read_line_int(db, reader, &mut temp);
+            read_line_int(db, reader, &mut temp)?;
             if temp < 0 {
                 /* Always "-1" the string. */
                 break;
@@ -1896,11 +1899,11 @@ fn read_list(
         }
     } else {
         for _ in 0..max {
-            read_line_int(db, reader, &mut temp);
+            read_line_int(db, reader, &mut temp)?;
             error += add_to_list(db, list, type_, &mut temp);
         }
     }
-    end_read_list(list, error)
+    Ok(end_read_list(list, error))
 }
 
 /* END_OF inefficient. */
@@ -1910,7 +1913,7 @@ fn read_type_list(
     list: &mut Vec<ShopBuyData>,
     new_format: bool,
     max: i32,
-) -> usize {
+) -> Result<usize, FormatError> {
     let mut error = 0;
 
     if !new_format {
@@ -1920,7 +1923,10 @@ fn read_type_list(
 
     loop {
         buf.clear();
-        reader.read_line(&mut buf).expect("Error reading shop");
+        if reader.read_line(&mut buf).is_err() {
+            error!("SYSERR: Error reading the trade list of a shop");
+            return Err(FormatError);
+        }
 
         let pos = buf.find(';');
         if let Some(pos) = pos {
@@ -1967,25 +1973,31 @@ fn read_type_list(
             break;
         }
     }
-    end_read_list(list, error)
+    Ok(end_read_list(list, error))
 }
 
-fn read_shop_message(mnum: i32, shr: RoomRnum, reader: &mut BufReader<File>, why: &str) -> Rc<str> {
+fn read_shop_message(
+    mnum: i32,
+    shr: RoomRnum,
+    reader: &mut BufReader<File>,
+    why: &str,
+) -> Result<Rc<str>, FormatError> {
     let mut err = 0;
     let mut ds = 0;
     let mut ss = 0;
     let tbuf;
     let res = {
-        tbuf = fread_string(reader, why);
+        tbuf = fread_string(reader, why)?;
         tbuf.is_empty()
     };
     if res {
-        return Rc::from("");
+        return Ok(Rc::from(""));
     }
 
-    let cht = tbuf
-        .find('%')
-        .expect("Cannot find % in shop message string");
+    /* a message without any specifier is fine as it is */
+    let Some(cht) = tbuf.find('%') else {
+        return Ok(Rc::from(tbuf));
+    };
     if &tbuf[cht + 1..cht + 2] == "s" {
         ss += 1;
     } else if &tbuf[cht + 1..cht + 2] == "d" && (mnum == 5 || mnum == 6) {
@@ -2013,12 +2025,17 @@ fn read_shop_message(mnum: i32, shr: RoomRnum, reader: &mut BufReader<File>, why
     }
 
     if err != 0 {
-        return Rc::from("");
+        return Ok(Rc::from(""));
     }
-    Rc::from(tbuf)
+    Ok(Rc::from(tbuf))
 }
 
-pub fn boot_the_shops(db: &mut DB, shop_f: File, filename: &str, _rec_count: i32) {
+pub fn boot_the_shops(
+    db: &mut DB,
+    shop_f: File,
+    filename: &str,
+    _rec_count: i32,
+) -> Result<(), FormatError> {
     let mut new_format = false;
     let mut reader = BufReader::new(shop_f);
     let mut done = false;
@@ -2026,12 +2043,15 @@ pub fn boot_the_shops(db: &mut DB, shop_f: File, filename: &str, _rec_count: i32
 
     let regex = Regex::new(r"^#(-?\+?\d{1,9})").expect("Regex compilation failed");
     while !done {
-        let buf = fread_string(&mut reader, &buf2);
+        let buf = fread_string(&mut reader, &buf2)?;
         if buf.starts_with('#') {
             /* New shop */
 
-            let f = regex.captures(&buf).expect("Regex capture failed");
-            let mut temp = f[1].parse::<i32>().expect("should be an int");
+            let Some(f) = regex.captures(&buf) else {
+                error!("SYSERR: Format error in {}: '{}'", buf2, buf);
+                return Err(FormatError);
+            };
+            let mut temp = parse_num::<i32>(&f[1], &buf2)?;
             buf2 = format!("shop #{} in shop file {}", temp, filename);
 
             let mut shop = ShopData {
@@ -2069,17 +2089,17 @@ pub fn boot_the_shops(db: &mut DB, shop_f: File, filename: &str, _rec_count: i32
                 new_format,
                 MAX_PROD,
                 LIST_PRODUCE,
-            ) as i32;
+            )? as i32;
             for count in 0..temp {
                 shop.producing
                     .push(list[count as usize].buy_type() as ObjVnum);
             }
 
-            read_line_float(db, &mut reader, &mut shop.profit_buy);
-            read_line_float(db, &mut reader, &mut shop.profit_sell);
+            read_line_float(db, &mut reader, &mut shop.profit_buy)?;
+            read_line_float(db, &mut reader, &mut shop.profit_sell)?;
 
             list.clear();
-            temp = read_type_list(db, &mut reader, &mut list, new_format, MAX_TRADE) as i32;
+            temp = read_type_list(db, &mut reader, &mut list, new_format, MAX_TRADE)? as i32;
 
             for item in list.iter().take(temp as usize) {
                 shop.type_.push({
@@ -2090,29 +2110,29 @@ pub fn boot_the_shops(db: &mut DB, shop_f: File, filename: &str, _rec_count: i32
                 })
             }
 
-            shop.no_such_item1 = read_shop_message(0, shop.vnum, &mut reader, &buf2);
-            shop.no_such_item2 = read_shop_message(1, shop.vnum, &mut reader, &buf2);
-            shop.do_not_buy = read_shop_message(2, shop.vnum, &mut reader, &buf2);
-            shop.missing_cash1 = read_shop_message(3, shop.vnum, &mut reader, &buf2);
-            shop.missing_cash2 = read_shop_message(4, shop.vnum, &mut reader, &buf2);
-            shop.message_buy = read_shop_message(5, shop.vnum, &mut reader, &buf2);
-            shop.message_sell = read_shop_message(6, shop.vnum, &mut reader, &buf2);
-            read_line_int(db, &mut reader, &mut shop.temper1);
-            read_line_int(db, &mut reader, &mut shop.bitvector);
+            shop.no_such_item1 = read_shop_message(0, shop.vnum, &mut reader, &buf2)?;
+            shop.no_such_item2 = read_shop_message(1, shop.vnum, &mut reader, &buf2)?;
+            shop.do_not_buy = read_shop_message(2, shop.vnum, &mut reader, &buf2)?;
+            shop.missing_cash1 = read_shop_message(3, shop.vnum, &mut reader, &buf2)?;
+            shop.missing_cash2 = read_shop_message(4, shop.vnum, &mut reader, &buf2)?;
+            shop.message_buy = read_shop_message(5, shop.vnum, &mut reader, &buf2)?;
+            shop.message_sell = read_shop_message(6, shop.vnum, &mut reader, &buf2)?;
+            read_line_int(db, &mut reader, &mut shop.temper1)?;
+            read_line_int(db, &mut reader, &mut shop.bitvector)?;
             let mut shop_keeper = NOBODY as i32;
-            read_line_int(db, &mut reader, &mut shop_keeper);
+            read_line_int(db, &mut reader, &mut shop_keeper)?;
             shop.keeper = db.real_mobile(shop_keeper as MobVnum);
-            read_line_int(db, &mut reader, &mut shop.with_who);
+            read_line_int(db, &mut reader, &mut shop.with_who)?;
             let mut list: Vec<ShopBuyData> = vec![];
-            temp = read_list(db, &mut reader, &mut list, new_format, 1, LIST_ROOM) as i32;
+            temp = read_list(db, &mut reader, &mut list, new_format, 1, LIST_ROOM)? as i32;
             for item in list.iter().take(temp as usize) {
                 shop.in_room.push(item.type_ as RoomVnum);
             }
 
-            read_line_int(db, &mut reader, &mut shop.open1);
-            read_line_int(db, &mut reader, &mut shop.close1);
-            read_line_int(db, &mut reader, &mut shop.open2);
-            read_line_int(db, &mut reader, &mut shop.close2);
+            read_line_int(db, &mut reader, &mut shop.open1)?;
+            read_line_int(db, &mut reader, &mut shop.close1)?;
+            read_line_int(db, &mut reader, &mut shop.open2)?;
+            read_line_int(db, &mut reader, &mut shop.close2)?;
 
             db.shop_index.push(shop);
         } else if buf.starts_with('$') {
@@ -2123,6 +2143,7 @@ pub fn boot_the_shops(db: &mut DB, shop_f: File, filename: &str, _rec_count: i32
             new_format = true;
         }
     }
+    Ok(())
 }
 
 static CMD_SAY: AtomicUsize = AtomicUsize::new(0);
@@ -0,0 +1,279 @@
+/* ************************************************************************
+*   File: sidefile.rs                                   Part of CircleMUD *
+*  Usage: Reading and writing the small text files kept beside the world  *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * The small files the game keeps beside the world, such as the clan
+ * file, are all of one format:
+ *
+ *   * a comment, between records
+ *   #<key>
+ *   Tag: value
+ *   Tag: value
+ *   End
+ *   $
+ *
+ * A tag may be repeated.  Its value is everything after the ": ", spaces
+ * included, up to the end of the line.  The final '$' may be left out, so
+ * that records can be added at the end of a file.
+ */
+
+use std::fmt::Display;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::str::FromStr;
+
+use log::error;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SideRecord {
+    pub key: String,
+    pub line: usize,
+    /* where the record starts, for the error messages */
+    pub fields: Vec<(String, String)>,
+}
+
+impl SideRecord {
+    /* The value of a tag, if the record has it */
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /* Every value of a tag, in the order of the file */
+    pub fn all<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.fields
+            .iter()
+            .filter(move |(t, _)| t == tag)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /* A number the record must have */
+    pub fn number<T: FromStr>(&self, tag: &str) -> Result<T, String> {
+        let value = self.get(tag).ok_or_else(|| format!("no {}", tag))?;
+        value
+            .trim()
+            .parse::<T>()
+            .map_err(|_| format!("bad {} '{}'", tag, value))
+    }
+}
+
+/* "Tag: value" cut in two; only the space after the colon is dropped */
+pub fn split_field(line: &str) -> Option<(&str, &str)> {
+    let (tag, value) = line.split_once(':')?;
+    Some((tag.trim(), value.strip_prefix(' ').unwrap_or(value)))
+}
+
+/*
+ * Read the records of a side file and make an item of each.  A record
+ * that is malformed, or that make refuses, is logged and skipped; how
+ * many were is returned with the items.
+ */
+pub fn read_records<R: BufRead, T>(
+    reader: R,
+    file: &str,
+    mut make: impl FnMut(&SideRecord) -> Result<T, String>,
+) -> (Vec<T>, usize) {
+    let mut items = vec![];
+    let mut errors = 0;
+    let mut current: Option<SideRecord> = None;
+    let mut skipping = false;
+
+    for (nr, line) in reader.lines().map_while(Result::ok).enumerate() {
+        let nr = nr + 1;
+        if let Some(key) = line.strip_prefix('#') {
+            if let Some(rec) = current.take() {
+                error!(
+                    "SYSERR: {} line {}: #{} has no End",
+                    file, rec.line, rec.key
+                );
+                errors += 1;
+            }
+            current = Some(SideRecord {
+                key: key.trim().to_string(),
+                line: nr,
+                fields: vec![],
+            });
+            skipping = false;
+        } else if line.trim() == "$" {
+            break;
+        } else if line.trim() == "End" {
+            if let Some(rec) = current.take() {
+                match make(&rec) {
+                    Ok(item) => items.push(item),
+                    Err(err) => {
+                        error!(
+                            "SYSERR: {} line {}: #{}: {}, skipped",
+                            file, rec.line, rec.key, err
+                        );
+                        errors += 1;
+                    }
+                }
+            }
+            skipping = false;
+        } else if let Some(rec) = current.as_mut() {
+            if let Some((tag, value)) = split_field(&line) {
+                rec.fields.push((tag.to_string(), value.to_string()));
+            } else if !line.trim().is_empty() {
+                error!(
+                    "SYSERR: {} line {}: #{}: expected 'Tag: value', skipped",
+                    file, nr, rec.key
+                );
+                errors += 1;
+                current = None;
+                skipping = true;
+            }
+        } else if !skipping && !line.trim().is_empty() && !line.starts_with('*') {
+            error!("SYSERR: {} line {}: expected '#'", file, nr);
+            errors += 1;
+            skipping = true;
+        }
+    }
+    if let Some(rec) = current {
+        error!(
+            "SYSERR: {} line {}: #{} has no End",
+            file, rec.line, rec.key
+        );
+        errors += 1;
+    }
+    (items, errors)
+}
+
+pub fn write_record<W: Write>(
+    w: &mut W,
+    key: impl Display,
+    fields: &[(&str, String)],
+) -> io::Result<()> {
+    writeln!(w, "#{}", key)?;
+    for (tag, value) in fields {
+        writeln!(w, "{}: {}", tag, value)?;
+    }
+    writeln!(w, "End")
+}
+
+/* Open a side file to read; that there is none yet is no error */
+pub fn open_side_file(path: &str) -> Option<BufReader<File>> {
+    match File::open(path) {
+        Ok(fl) => Some(BufReader::new(fl)),
+        Err(err) => {
+            if err.kind() != ErrorKind::NotFound {
+                error!("SYSERR: Unable to open '{}': {}", path, err);
+            }
+            None
+        }
+    }
+}
+
+/* Write a side file anew: the records, then the '$' */
+pub fn save_side_file(
+    path: &str,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> bool {
+    let fl = match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+    {
+        Err(err) => {
+            error!("SYSERR: Unable to open '{}' for writing: {}", path, err);
+            return false;
+        }
+        Ok(fl) => fl,
+    };
+    let mut writer = BufWriter::new(fl);
+    let res = write(&mut writer)
+        .and_then(|_| writeln!(writer, "$"))
+        .and_then(|_| writer.flush());
+    if let Err(err) = res {
+        error!("SYSERR: Unable to write '{}': {}", path, err);
+        return false;
+    }
+    true
+}
+
+/* Add a record at the end of a side file, which then has no '$' */
+pub fn append_record(path: &str, key: impl Display, fields: &[(&str, String)]) -> bool {
+    let mut buf = vec![];
+    let res = write_record(&mut buf, key, fields).and_then(|_| {
+        OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?
+            .write_all(&buf)
+    });
+    if let Err(err) = res {
+        error!("SYSERR: Unable to write '{}': {}", path, err);
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod sidefile_tests {
+    use super::*;
+
+    fn keep(rec: &SideRecord) -> Result<SideRecord, String> {
+        Ok(rec.clone())
+    }
+
+    #[test]
+    fn test_read_records() {
+        let text = "* a comment\n\n#ravens\nName: The Ravens\nMember: 4 1\nMember: 9 0\n\
+                    Prompt:  %h> \nEnd\n#7\nEnd\n$\n#after\nEnd\n";
+        let (recs, errors) = read_records(text.as_bytes(), "test", keep);
+        assert_eq!(errors, 0);
+        assert_eq!(recs.len(), 2);
+        assert_eq!(recs[0].key, "ravens");
+        assert_eq!(recs[0].line, 3);
+        assert_eq!(recs[0].get("Name"), Some("The Ravens"));
+        assert_eq!(recs[0].all("Member").collect::<Vec<_>>(), ["4 1", "9 0"]);
+        assert_eq!(recs[0].get("Prompt"), Some(" %h> "));
+        assert_eq!(
+            recs[0].number::<i32>("Name"),
+            Err("bad Name 'The Ravens'".into())
+        );
+        assert_eq!(recs[1].key, "7");
+        assert_eq!(recs[1].number::<i32>("Gold"), Err("no Gold".into()));
+    }
+
+    #[test]
+    fn test_bad_records_are_skipped() {
+        let text = "stray line\nmore\n#1\nno colon\nGold: 1\nEnd\n#2\nGold: x\nEnd\n\
+                    #3\nGold: 3\n#4\nGold: 4\nEnd\n#5\nGold: 5\n";
+        let (golds, errors) =
+            read_records(text.as_bytes(), "test", |rec| rec.number::<i32>("Gold"));
+        assert_eq!(golds, vec![4]);
+        assert_eq!(errors, 5);
+    }
+
+    #[test]
+    fn test_records_roundtrip() {
+        let mut buf = vec![];
+        write_record(
+            &mut buf,
+            12,
+            &[("Text", "hi: there ".into()), ("To", "3".into())],
+        )
+        .unwrap();
+        write_record(&mut buf, "x", &[]).unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "#12\nText: hi: there \nTo: 3\nEnd\n#x\nEnd\n"
+        );
+        let (recs, errors) = read_records(buf.as_slice(), "test", keep);
+        assert_eq!(errors, 0);
+        assert_eq!(recs[0].get("Text"), Some("hi: there "));
+        assert_eq!(recs[0].number::<i64>("To"), Ok(3));
+        assert_eq!(recs[1].key, "x");
+    }
+}
@@ -11,26 +11,92 @@
 
 /* functions to perform assignments */
 
+use std::fs;
+
 use log::error;
 
+use crate::bank::bank;
 use crate::boards::gen_board;
-use crate::castle::assign_kings_castle;
+use crate::castle::{
+    castle_guard, cleaning, dick_n_david, james, jerry, king_welmar, peter, tim, tom,
+    training_master,
+};
 use crate::config::DTS_ARE_DUMPS;
-use crate::db::DB;
+use crate::db::{DB, SPEC_LIST_FILE};
 use crate::mail::postmaster;
 use crate::objsave::{cryogenicist, receptionist};
+use crate::scripts::AttachType;
+use crate::shops::shop_keeper;
 use crate::spec_procs::{
-    bank, cityguard, dump, fido, guild_guard, janitor, magic_user, mayor, pet_shops, snake, thief,
+    cityguard, dump, fido, guild_guard, janitor, magic_user, mayor, pet_shops, snake, thief,
 };
 use crate::spec_procs::{guild, puff};
+use crate::specscript::{assign_spec_scripts, load_spec_scripts};
 use crate::structs::{
-    MobVnum, ObjVnum, RoomFlags, RoomRnum, RoomVnum, Special, NOBODY, NOTHING, NOWHERE,
+    IdxType, MobRnum, MobVnum, ObjVnum, RoomFlags, RoomRnum, RoomVnum, Special, NOBODY, NOTHING,
+    NOWHERE,
 };
 
-fn assignmob(db: &mut DB, mob: MobVnum, fname: Special) {
+/* Every spec proc compiled into the server, by the name used in SPEC_LIST_FILE. */
+const SPEC_PROCS: [(&str, Special); 27] = [
+    ("bank", bank),
+    ("castle_guard", castle_guard),
+    ("cityguard", cityguard),
+    ("cleaning", cleaning),
+    ("cryogenicist", cryogenicist),
+    ("dick_n_david", dick_n_david),
+    ("dump", dump),
+    ("fido", fido),
+    ("gen_board", gen_board),
+    ("guild", guild),
+    ("guild_guard", guild_guard),
+    ("james", james),
+    ("janitor", janitor),
+    ("jerry", jerry),
+    ("king_welmar", king_welmar),
+    ("magic_user", magic_user),
+    ("mayor", mayor),
+    ("peter", peter),
+    ("pet_shops", pet_shops),
+    ("postmaster", postmaster),
+    ("puff", puff),
+    ("receptionist", receptionist),
+    ("snake", snake),
+    ("thief", thief),
+    ("tim", tim),
+    ("tom", tom),
+    ("training_master", training_master),
+];
+
+pub fn find_spec_proc(name: &str) -> Option<Special> {
+    SPEC_PROCS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, func)| func)
+}
+
+fn is_shop_keeper(func: Option<Special>) -> bool {
+    func.is_some_and(|func| std::ptr::fn_addr_eq(func, shop_keeper as Special))
+}
+
+/*
+ * Once the shopkeepers are assigned, a shopkeeper's own spec proc is
+ * called by shop_keeper(), and lives in its shop.
+ */
+fn set_mob_spec(db: &mut DB, rnum: MobRnum, func: Option<Special>) {
+    if is_shop_keeper(db.mob_index[rnum as usize].func) {
+        for shop in db.shop_index.iter_mut().filter(|s| s.keeper == rnum) {
+            shop.func = func;
+        }
+    } else {
+        db.mob_index[rnum as usize].func = func;
+    }
+}
+
+pub fn assignmob(db: &mut DB, mob: MobVnum, fname: Special) {
     let rnum = db.real_mobile(mob);
     if rnum != NOBODY {
-        db.mob_index[rnum as usize].func = Some(fname);
+        set_mob_spec(db, rnum, Some(fname));
     } else if !db.mini_mud {
         error!(
             "SYSERR: Attempt to assign spec to non-existant mob #{}",
@@ -69,210 +135,98 @@ pub fn assignroom(db: &mut DB, room: RoomVnum, fname: Special) {
 *  Assignments                                                        *
 ******************************************************************** */
 
-/* assign special procedures to mobiles */
-pub fn assign_mobiles(db: &mut DB) {
-    assign_kings_castle(db);
-
-    assignmob(db, 1, puff);
-    //
-    /* Immortal Zone */
-    assignmob(db, 1200, receptionist);
-    assignmob(db, 1201, postmaster);
-    assignmob(db, 1202, janitor);
-    //
-    /* Midgaard */
-    assignmob(db, 3005, receptionist);
-    assignmob(db, 3010, postmaster);
-    assignmob(db, 3020, guild);
-    assignmob(db, 3021, guild);
-    assignmob(db, 3022, guild);
-    assignmob(db, 3023, guild);
-    assignmob(db, 3024, guild_guard);
-    assignmob(db, 3025, guild_guard);
-    assignmob(db, 3026, guild_guard);
-    assignmob(db, 3027, guild_guard);
-    assignmob(db, 3059, cityguard);
-    assignmob(db, 3060, cityguard);
-    assignmob(db, 3061, janitor);
-    assignmob(db, 3062, fido);
-    assignmob(db, 3066, fido);
-    assignmob(db, 3067, cityguard);
-    assignmob(db, 3068, janitor);
-    assignmob(db, 3095, cryogenicist);
-    assignmob(db, 3105, mayor);
-
-    /* MORIA */
-    assignmob(db, 4000, snake);
-    assignmob(db, 4001, snake);
-    assignmob(db, 4053, snake);
-    assignmob(db, 4100, magic_user);
-    assignmob(db, 4102, snake);
-    assignmob(db, 4103, thief);
-
-    /* Redferne's */
-    assignmob(db, 7900, cityguard);
-
-    /* PYRAMID */
-    assignmob(db, 5300, snake);
-    assignmob(db, 5301, snake);
-    assignmob(db, 5304, thief);
-    assignmob(db, 5305, thief);
-    assignmob(db, 5309, magic_user); /* should breath fire */
-    assignmob(db, 5311, magic_user);
-    assignmob(db, 5313, magic_user); /* should be a cleric */
-    assignmob(db, 5314, magic_user); /* should be a cleric */
-    assignmob(db, 5315, magic_user); /* should be a cleric */
-    assignmob(db, 5316, magic_user); /* should be a cleric */
-    assignmob(db, 5317, magic_user);
-
-    /* High Tower Of Sorcery */
-    assignmob(db, 2501, magic_user); /* should likely be cleric */
-    assignmob(db, 2504, magic_user);
-    assignmob(db, 2507, magic_user);
-    assignmob(db, 2508, magic_user);
-    assignmob(db, 2510, magic_user);
-    assignmob(db, 2511, thief);
-    assignmob(db, 2514, magic_user);
-    assignmob(db, 2515, magic_user);
-    assignmob(db, 2516, magic_user);
-    assignmob(db, 2517, magic_user);
-    assignmob(db, 2518, magic_user);
-    assignmob(db, 2520, magic_user);
-    assignmob(db, 2521, magic_user);
-    assignmob(db, 2522, magic_user);
-    assignmob(db, 2523, magic_user);
-    assignmob(db, 2524, magic_user);
-    assignmob(db, 2525, magic_user);
-    assignmob(db, 2526, magic_user);
-    assignmob(db, 2527, magic_user);
-    assignmob(db, 2528, magic_user);
-    assignmob(db, 2529, magic_user);
-    assignmob(db, 2530, magic_user);
-    assignmob(db, 2531, magic_user);
-    assignmob(db, 2532, magic_user);
-    assignmob(db, 2533, magic_user);
-    assignmob(db, 2534, magic_user);
-    assignmob(db, 2536, magic_user);
-    assignmob(db, 2537, magic_user);
-    assignmob(db, 2538, magic_user);
-    assignmob(db, 2540, magic_user);
-    assignmob(db, 2541, magic_user);
-    assignmob(db, 2548, magic_user);
-    assignmob(db, 2549, magic_user);
-    assignmob(db, 2552, magic_user);
-    assignmob(db, 2553, magic_user);
-    assignmob(db, 2554, magic_user);
-    assignmob(db, 2556, magic_user);
-    assignmob(db, 2557, magic_user);
-    assignmob(db, 2559, magic_user);
-    assignmob(db, 2560, magic_user);
-    assignmob(db, 2562, magic_user);
-    assignmob(db, 2564, magic_user);
-
-    /* SEWERS */
-    assignmob(db, 7006, snake);
-    assignmob(db, 7009, magic_user);
-    assignmob(db, 7200, magic_user);
-    assignmob(db, 7201, magic_user);
-    assignmob(db, 7202, magic_user);
-
-    /* FOREST */
-    assignmob(db, 6112, magic_user);
-    assignmob(db, 6113, snake);
-    assignmob(db, 6114, magic_user);
-    assignmob(db, 6115, magic_user);
-    assignmob(db, 6116, magic_user); /* should be a cleric */
-    assignmob(db, 6117, magic_user);
-
-    /* ARACHNOS */
-    assignmob(db, 6302, magic_user);
-    assignmob(db, 6309, magic_user);
-    assignmob(db, 6312, magic_user);
-    assignmob(db, 6314, magic_user);
-    assignmob(db, 6315, magic_user);
-
-    /* Desert */
-    assignmob(db, 5004, magic_user);
-    assignmob(db, 5005, guild_guard); /* brass dragon */
-    assignmob(db, 5010, magic_user);
-    assignmob(db, 5014, magic_user);
-
-    /* Drow City */
-    assignmob(db, 5103, magic_user);
-    assignmob(db, 5104, magic_user);
-    assignmob(db, 5107, magic_user);
-    assignmob(db, 5108, magic_user);
-
-    /* Old Thalos */
-    assignmob(db, 5200, magic_user);
-    assignmob(db, 5201, magic_user);
-    assignmob(db, 5209, magic_user);
-
-    /* New Thalos */
-    /* 5481 - Cleric (or Mage... but he IS a high priest... *shrug*) */
-    assignmob(db, 5404, receptionist);
-    assignmob(db, 5421, magic_user);
-    assignmob(db, 5422, magic_user);
-    assignmob(db, 5423, magic_user);
-    assignmob(db, 5424, magic_user);
-    assignmob(db, 5425, magic_user);
-    assignmob(db, 5426, magic_user);
-    assignmob(db, 5427, magic_user);
-    assignmob(db, 5428, magic_user);
-    assignmob(db, 5434, cityguard);
-    assignmob(db, 5440, magic_user);
-    assignmob(db, 5455, magic_user);
-    assignmob(db, 5461, cityguard);
-    assignmob(db, 5462, cityguard);
-    assignmob(db, 5463, cityguard);
-    assignmob(db, 5482, cityguard);
-    /*
-    5400 - Guildmaster (Mage)
-    5401 - Guildmaster (Cleric)
-    5402 - Guildmaster (Warrior)
-    5403 - Guildmaster (Thief)
-    5456 - Guildguard (Mage)
-    5457 - Guildguard (Cleric)
-    5458 - Guildguard (Warrior)
-    5459 - Guildguard (Thief)
-    */
-
-    /* ROME */
-    assignmob(db, 12009, magic_user);
-    assignmob(db, 12018, cityguard);
-    assignmob(db, 12020, magic_user);
-    assignmob(db, 12021, cityguard);
-    assignmob(db, 12025, magic_user);
-    assignmob(db, 12030, magic_user);
-    assignmob(db, 12031, magic_user);
-    assignmob(db, 12032, magic_user);
+pub struct SpecAssignment {
+    pub attach_type: AttachType,
+    pub vnum: IdxType,
+    pub name: String,
+    pub lineno: usize,
+}
 
-    /* King Welmar's Castle (not covered in castle.c) */
-    assignmob(db, 15015, thief); /* Ergan... have a better idea? */
-    assignmob(db, 15032, magic_user); /* Pit Fiend, have something better?  Use it */
+/*
+ * Parse SPEC_LIST_FILE: lines of '<M | O | R> <vnum> <spec proc>', ended
+ * by '$'.  Lines which cannot be parsed are reported and skipped.
+ */
+pub fn parse_spec_list(text: &str, filename: &str) -> Vec<SpecAssignment> {
+    let mut list = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('*') {
+            continue;
+        }
+        if line.starts_with('$') {
+            break;
+        }
+        let mut words = line.split_whitespace();
+        let attach_type = match words.next() {
+            Some("M") | Some("m") => Some(AttachType::Mob),
+            Some("O") | Some("o") => Some(AttachType::Obj),
+            Some("R") | Some("r") => Some(AttachType::Room),
+            _ => None,
+        };
+        let vnum = words.next().and_then(|w| w.parse::<IdxType>().ok());
+        match (attach_type, vnum, words.next()) {
+            (Some(attach_type), Some(vnum), Some(name)) => list.push(SpecAssignment {
+                attach_type,
+                vnum,
+                name: name.to_string(),
+                lineno: i + 1,
+            }),
+            _ => error!(
+                "SYSERR: Format error in {}, line {}: expecting '<M|O|R> <vnum> <spec proc>'",
+                filename,
+                i + 1
+            ),
+        }
+    }
+    list
+}
 
-    /* DWARVEN KINGDOM */
-    assignmob(db, 6500, cityguard);
-    assignmob(db, 6502, magic_user);
-    assignmob(db, 6509, magic_user);
-    assignmob(db, 6516, magic_user);
+/* Take away every spec proc, but leave the shopkeepers theirs. */
+fn clear_spec_procs(db: &mut DB) {
+    for rnum in 0..db.mob_index.len() {
+        set_mob_spec(db, rnum as MobRnum, None);
+    }
+    for obj in db.obj_index.iter_mut() {
+        obj.func = None;
+    }
+    for room in db.world.iter_mut() {
+        room.func = None;
+    }
 }
 
-/* assign special procedures to objects */
-pub fn assign_objects(db: &mut DB) {
-    assignobj(db, 3096, gen_board); /* social board */
-    assignobj(db, 3097, gen_board); /* freeze board */
-    assignobj(db, 3098, gen_board); /* immortal board */
-    assignobj(db, 3099, gen_board); /* mortal board */
+/*
+ * Assign the spec procs of mobiles, objects and rooms, from SPEC_LIST_FILE
+ * and the spec scripts.  Called at boot, before the shopkeepers are
+ * assigned, and again by 'reload spec'.
+ */
+pub fn assign_spec_procs(db: &mut DB) {
+    clear_spec_procs(db);
+    load_spec_scripts(db);
 
-    assignobj(db, 3034, bank); /* atm */
-    assignobj(db, 3036, bank); /* cashcard */
-}
+    match fs::read_to_string(SPEC_LIST_FILE) {
+        Ok(text) => {
+            for spec in parse_spec_list(&text, SPEC_LIST_FILE) {
+                if let Some(func) = find_spec_proc(&spec.name) {
+                    match spec.attach_type {
+                        AttachType::Mob => assignmob(db, spec.vnum, func),
+                        AttachType::Obj => assignobj(db, spec.vnum, func),
+                        AttachType::Room => assignroom(db, spec.vnum, func),
+                    }
+                } else if !db
+                    .spec_scripts
+                    .attach(spec.attach_type, spec.vnum, &spec.name)
+                {
+                    error!(
+                        "SYSERR: Unknown spec proc '{}' in {}, line {}",
+                        spec.name, SPEC_LIST_FILE, spec.lineno
+                    );
+                }
+            }
+        }
+        Err(err) => error!("SYSERR: Can't read {}: {}", SPEC_LIST_FILE, err),
+    }
 
-/* assign special procedures to rooms */
-pub fn assign_rooms(db: &mut DB) {
-    assignroom(db, 3030, dump);
-    assignroom(db, 3031, pet_shops);
+    assign_spec_scripts(db);
 
     if DTS_ARE_DUMPS {
         let l = db.world.len();
@@ -283,3 +237,41 @@ pub fn assign_rooms(db: &mut DB) {
         }
     }
 }
+
+#[cfg(test)]
+mod spec_assign_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec_list() {
+        let text = "* comment\n\nM 3060 cityguard  the guard\nO 3034 bank\nr 3030 dump\n\
+                    X 1 puff\nM abc puff\nM 1\n$\nM 2 puff\n";
+        let list = parse_spec_list(text, "spec.lst");
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].attach_type, AttachType::Mob);
+        assert_eq!(list[0].vnum, 3060);
+        assert_eq!(list[0].name, "cityguard");
+        assert_eq!(list[0].lineno, 3);
+        assert_eq!(list[1].attach_type, AttachType::Obj);
+        assert_eq!(list[2].attach_type, AttachType::Room);
+        assert_eq!(list[2].name, "dump");
+    }
+
+    #[test]
+    fn test_find_spec_proc() {
+        assert!(find_spec_proc("king_welmar").is_some());
+        assert!(find_spec_proc("magic_user").is_some());
+        assert!(find_spec_proc("shop_keeper").is_none());
+        assert!(find_spec_proc("nosuch").is_none());
+    }
+
+    #[test]
+    fn test_spec_list_names() {
+        let text = include_str!("../lib/world/spec.lst");
+        let list = parse_spec_list(text, "spec.lst");
+        assert!(list.len() > 100);
+        for spec in list {
+            assert!(find_spec_proc(&spec.name).is_some(), "{}", spec.name);
+        }
+    }
+}
@@ -53,8 +53,11 @@ pub fn sort_spells(db: &mut DB) {
         db.spell_sort_info[a] = a as i32;
     }
 
-    db.spell_sort_info
-        .sort_by_key(|s| db.spell_info[*s as usize].name);
+    db.spell_sort_info.sort_by(|a, b| {
+        db.spell_info[*a as usize]
+            .name
+            .cmp(&db.spell_info[*b as usize].name)
+    });
 }
 
 fn how_good(percent: i8) -> &'static str {
@@ -648,6 +651,7 @@ pub fn snake(
         chid,
         ch.fighting_id(),
         None,
+        None,
         SPELL_POISON,
         ch.get_level(),
         CAST_SPELL,
@@ -741,6 +745,7 @@ pub fn magic_user(
             chid,
             vict_id,
             None,
+            None,
             SPELL_POISON,
         );
     }
@@ -755,6 +760,7 @@ pub fn magic_user(
             chid,
             vict_id,
             None,
+            None,
             SPELL_BLINDNESS,
         );
     }
@@ -770,6 +776,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_ENERGY_DRAIN,
             );
         } else if ch.is_good() {
@@ -782,6 +789,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_DISPEL_EVIL,
             );
         }
@@ -802,6 +810,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_MAGIC_MISSILE,
             );
         }
@@ -815,6 +824,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_CHILL_TOUCH,
             );
         }
@@ -828,6 +838,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_BURNING_HANDS,
             );
         }
@@ -841,6 +852,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_SHOCKING_GRASP,
             );
         }
@@ -854,6 +866,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_LIGHTNING_BOLT,
             );
         }
@@ -867,6 +880,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_COLOR_SPRAY,
             );
         }
@@ -880,6 +894,7 @@ pub fn magic_user(
                 chid,
                 vict_id,
                 None,
+                None,
                 SPELL_FIREBALL,
             );
         }
@@ -1341,120 +1356,3 @@ pub fn pet_shops(
     /* All commands except list and buy */
     false
 }
-
-/* ********************************************************************
-*  Special procedures for objects                                     *
-******************************************************************** */
-#[allow(clippy::too_many_arguments)]
-pub fn bank(
-    game: &mut Game,
-    chars: &mut Depot<CharData>,
-    db: &mut DB,
-    _texts: &mut Depot<TextData>,
-    _objs: &mut Depot<ObjData>,
-    chid: DepotId,
-    _me: MeRef,
-    cmd: usize,
-    argument: &str,
-) -> bool {
-    let ch = chars.get(chid);
-
-    if cmd_is(cmd, "balance") {
-        if ch.get_bank_gold() > 0 {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                format!("Your current balance is {} coins.\r\n", ch.get_bank_gold()).as_str(),
-            );
-        } else {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You currently have no money deposited.\r\n",
-            );
-        }
-        true
-    } else if cmd_is(cmd, "deposit") {
-        let amount = argument.trim_start().parse::<i32>();
-        let amount = amount.unwrap_or(-1);
-        if amount <= 0 {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "How much do you want to deposit?\r\n",
-            );
-            return true;
-        }
-        if ch.get_gold() < amount {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You don't have that many coins!\r\n",
-            );
-            return true;
-        }
-        let ch = chars.get_mut(chid);
-        ch.set_gold(ch.get_gold() - amount);
-        ch.set_bank_gold(ch.get_bank_gold() + amount);
-        send_to_char(
-            &mut game.descriptors,
-            ch,
-            format!("You deposit {} coins.\r\n", amount).as_str(),
-        );
-        let ch = chars.get(chid);
-        act(
-            &mut game.descriptors,
-            chars,
-            db,
-            "$n makes a bank transaction.",
-            true,
-            Some(ch),
-            None,
-            None,
-            TO_ROOM,
-        );
-        true
-    } else if cmd_is(cmd, "withdraw") {
-        let amount = argument.trim_start().parse::<i32>();
-        let amount = amount.unwrap_or(-1);
-        if amount <= 0 {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "How much do you want to withdraw?\r\n",
-            );
-            return true;
-        }
-        if ch.get_bank_gold() < amount {
-            send_to_char(
-                &mut game.descriptors,
-                ch,
-                "You don't have that many coins deposited!\r\n",
-            );
-            return true;
-        }
-        let ch = chars.get_mut(chid);
-        ch.set_gold(ch.get_gold() + amount);
-        ch.set_bank_gold(ch.get_bank_gold() - amount);
-        let ch = chars.get(chid);
-        send_to_char(
-            &mut game.descriptors,
-            ch,
-            format!("You withdraw {} coins.\r\n", amount).as_str(),
-        );
-        act(
-            &mut game.descriptors,
-            chars,
-            db,
-            "$n makes a bank transaction.",
-            true,
-            Some(ch),
-            None,
-            None,
-            TO_ROOM,
-        );
-        true
-    } else {
-        false
-    }
-}
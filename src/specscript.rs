@@ -0,0 +1,846 @@
+/* ************************************************************************
+*   File: specscript.rs                                 Part of CircleMUD *
+*  Usage: Special procedures written as Rhai scripts                      *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * A spec proc does not have to be compiled into the server: every
+ * lib/spec/<name>.rhai file is a spec proc called <name>.  The top of the
+ * file may say what it is attached to, lib/world/spec.lst may name it like
+ * any other spec proc, and the file defines the function special(), called
+ * like any other Special routine:
+ *
+ *   attach_mob(3060);
+ *
+ *   fn special() {
+ *       if cmd() != "" || me().fighting >= 0 { return false; }
+ *       for p in people() {
+ *           if p.is_npc == false && p.alignment < -500 {
+ *               act_room("$n screams 'Evil-doer!  Die!'");
+ *               hit(p.id);
+ *               return true;
+ *           }
+ *       }
+ *       false
+ *   }
+ *
+ * Mobs still need the SPEC flag in their prototype to get called every
+ * mobile pulse.  Returning true means the command was taken care of, as
+ * for a Rust spec proc.
+ *
+ * The script only gets a copy of what is around it:
+ *
+ *   me()           the mob, object or room the spec proc belongs to
+ *   actor()        who typed the command (for mobs, on a pulse, me())
+ *   cmd(), arg()   the command typed, "" on a pulse, and its argument
+ *   people()       everyone in the room
+ *   contents()     the objects on the floor
+ *   inventory()    what the mob is carrying
+ *   exits()        the directions leading out of the room
+ *   rand(a, b)     a random number from a to b
+ *
+ * Characters have id, name, vnum, level, hit, max_hit, mana, gold,
+ * alignment, is_npc, awake, can_see and fighting (the id of who they
+ * fight, or -1); objects have id, name, keywords, vnum and cost.
+ *
+ * What the script wants done is only done once it has returned, in order:
+ *
+ *   act_room(msg)  act_vict(id, msg)  act_notvict(id, msg)
+ *   send_to_char(id, msg)  hit(id)  cast_spell(id, spell)
+ *   do_move(dir)  command(line)  get_obj(id)  drop_obj(id)  log(msg)
+ *
+ * Only mobs can hit, cast, move, run commands and handle objects.  Every
+ * call of special() may run SPEC_MAX_OPERATIONS operations at most; a
+ * script which fails or runs out is logged and treated as returning
+ * false.  'reload spec' reads the files again.
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use log::{error, info};
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+use crate::act_movement::perform_move;
+use crate::constants::DIRS;
+use crate::db::{DB, SPEC_PREFIX};
+use crate::depot::{Depot, DepotId};
+use crate::handler::{obj_from_char, obj_to_char};
+use crate::interpreter::{command_interpreter, search_block, CMD_INFO};
+use crate::scripts::AttachType;
+use crate::spec_assign::{assignmob, assignobj, assignroom};
+use crate::spell_parser::{cast_spell, find_skill_num};
+use crate::spells::TYPE_UNDEFINED;
+use crate::structs::{
+    IdxType, MeRef, MobVnum, ObjVnum, RoomRnum, RoomVnum, NOBODY, NOTHING, NOWHERE,
+};
+use crate::util::{can_see, rand_number};
+use crate::{act, send_to_char, send_to_room, CharData, Game, ObjData, TextData, VictimRef};
+use crate::{TO_NOTVICT, TO_ROOM, TO_VICT};
+
+/* Operations a single call of special() may run. */
+const SPEC_MAX_OPERATIONS: u64 = 20000;
+/* Actions a single call of special() may ask for. */
+const SPEC_MAX_ACTIONS: usize = 32;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Target {
+    Char(DepotId),
+    Obj(DepotId),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum SpecAction {
+    ActRoom(String),
+    ActVict(i64, String),
+    ActNotVict(i64, String),
+    Send(i64, String),
+    Hit(i64),
+    Cast(i64, String),
+    Move(String),
+    Command(String),
+    GetObj(i64),
+    DropObj(i64),
+}
+
+/* What a script can see during one call, and what it asked for. */
+#[derive(Default)]
+struct SpecCall {
+    me: Map,
+    actor: Map,
+    cmd: String,
+    arg: String,
+    people: Array,
+    contents: Array,
+    inventory: Array,
+    exits: Array,
+    /* what the ids handed to the script stand for */
+    targets: Vec<Target>,
+    actions: Vec<SpecAction>,
+    room: RoomRnum,
+    /* filled while a script file is loaded */
+    attach: Vec<(AttachType, i64)>,
+}
+
+impl SpecCall {
+    fn push(&mut self, action: SpecAction) -> Result<(), Box<EvalAltResult>> {
+        if self.actions.len() >= SPEC_MAX_ACTIONS {
+            return Err("too many actions".into());
+        }
+        self.actions.push(action);
+        Ok(())
+    }
+}
+
+pub struct SpecScripts {
+    engine: Engine,
+    call: Rc<RefCell<SpecCall>>,
+    scripts: HashMap<Rc<str>, Rc<AST>>,
+    mob: HashMap<i64, Rc<str>>,
+    obj: HashMap<i64, Rc<str>>,
+    room: HashMap<i64, Rc<str>>,
+}
+
+impl Default for SpecScripts {
+    fn default() -> Self {
+        SpecScripts::new()
+    }
+}
+
+impl SpecScripts {
+    pub fn new() -> SpecScripts {
+        let call = Rc::new(RefCell::new(SpecCall::default()));
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(SPEC_MAX_OPERATIONS)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(4096)
+            .set_max_array_size(1024)
+            .set_max_map_size(256)
+            .disable_symbol("eval")
+            .on_print(|s| info!("spec script: {}", s))
+            .on_debug(|s, _, pos| info!("spec script {}: {}", pos, s));
+
+        macro_rules! query {
+            ($name:literal, $field:ident) => {
+                let c = call.clone();
+                engine.register_fn($name, move || c.borrow().$field.clone());
+            };
+        }
+        query!("me", me);
+        query!("actor", actor);
+        query!("cmd", cmd);
+        query!("arg", arg);
+        query!("people", people);
+        query!("contents", contents);
+        query!("inventory", inventory);
+        query!("exits", exits);
+        engine.register_fn("rand", |from: i64, to: i64| {
+            rand_number(from.max(0) as u32, to.max(0) as u32) as i64
+        });
+        engine.register_fn("log", |msg: &str| info!("spec script: {}", msg));
+
+        macro_rules! action {
+            ($name:literal, |$($arg:ident: $ty:ty),*| $action:expr) => {
+                let c = call.clone();
+                engine.register_fn($name, move |$($arg: $ty),*| c.borrow_mut().push($action));
+            };
+        }
+        action!("act_room", |msg: &str| SpecAction::ActRoom(msg.to_string()));
+        action!("act_vict", |id: i64, msg: &str| SpecAction::ActVict(
+            id,
+            msg.to_string()
+        ));
+        action!("act_notvict", |id: i64, msg: &str| SpecAction::ActNotVict(
+            id,
+            msg.to_string()
+        ));
+        action!("send_to_char", |id: i64, msg: &str| SpecAction::Send(
+            id,
+            msg.to_string()
+        ));
+        action!("hit", |id: i64| SpecAction::Hit(id));
+        action!("cast_spell", |id: i64, spell: &str| SpecAction::Cast(
+            id,
+            spell.to_string()
+        ));
+        action!("do_move", |dir: &str| SpecAction::Move(dir.to_string()));
+        action!("command", |line: &str| SpecAction::Command(
+            line.to_string()
+        ));
+        action!("get_obj", |id: i64| SpecAction::GetObj(id));
+        action!("drop_obj", |id: i64| SpecAction::DropObj(id));
+
+        for (name, attach_type) in [
+            ("attach_mob", AttachType::Mob),
+            ("attach_obj", AttachType::Obj),
+            ("attach_room", AttachType::Room),
+        ] {
+            let c = call.clone();
+            engine.register_fn(name, move |vnum: i64| {
+                c.borrow_mut().attach.push((attach_type, vnum));
+            });
+        }
+
+        SpecScripts {
+            engine,
+            call,
+            scripts: HashMap::new(),
+            mob: HashMap::new(),
+            obj: HashMap::new(),
+            room: HashMap::new(),
+        }
+    }
+
+    /* Compile one script, and run its top level to see what it is attached to. */
+    fn compile(&mut self, name: &str, source: &str) -> Result<(), String> {
+        let ast = self.engine.compile(source).map_err(|e| e.to_string())?;
+        if !ast.iter_functions().any(|f| f.name == "special") {
+            return Err("no special() function".to_string());
+        }
+        self.call.borrow_mut().attach.clear();
+        let run = self.engine.run_ast(&ast);
+        let attach = std::mem::take(&mut self.call.borrow_mut().attach);
+        run.map_err(|e| e.to_string())?;
+
+        let name: Rc<str> = Rc::from(name);
+        for (attach_type, vnum) in attach {
+            let table = match attach_type {
+                AttachType::Mob => &mut self.mob,
+                AttachType::Obj => &mut self.obj,
+                AttachType::Room => &mut self.room,
+            };
+            table.insert(vnum, name.clone());
+        }
+        self.scripts.insert(name, Rc::new(ast));
+        Ok(())
+    }
+
+    /* Forget every script, and read all of dir/<name>.rhai. */
+    fn load(&mut self, dir: &Path) {
+        self.scripts.clear();
+        self.mob.clear();
+        self.obj.clear();
+        self.room.clear();
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            info!("   No {} directory, no spec scripts loaded.", dir.display());
+            return;
+        };
+        let mut files: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        files.sort();
+        for path in files {
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let result = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|source| self.compile(name, &source));
+            if let Err(err) = result {
+                error!("SYSERR: spec script {}: {}", path.display(), err);
+            }
+        }
+        info!("   {} spec scripts.", self.scripts.len());
+    }
+
+    /* Attach the script called 'name' to one more vnum, if there is such a script. */
+    pub fn attach(&mut self, attach_type: AttachType, vnum: IdxType, name: &str) -> bool {
+        let Some((name, _)) = self.scripts.get_key_value(name) else {
+            return false;
+        };
+        let table = match attach_type {
+            AttachType::Mob => &mut self.mob,
+            AttachType::Obj => &mut self.obj,
+            AttachType::Room => &mut self.room,
+        };
+        table.insert(vnum as i64, name.clone());
+        true
+    }
+
+    /* Call special() of a script; returns its value and the actions asked for. */
+    fn run(&self, name: &str) -> Result<(bool, Vec<SpecAction>), String> {
+        let ast = self
+            .scripts
+            .get(name)
+            .ok_or_else(|| "no such script".to_string())?;
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            ast,
+            "special",
+            (),
+        );
+        let actions = std::mem::take(&mut self.call.borrow_mut().actions);
+        let value = result.map_err(|e| e.to_string())?;
+        Ok((value.as_bool().unwrap_or(false), actions))
+    }
+}
+
+fn char_map(
+    game: &Game,
+    chars: &Depot<CharData>,
+    db: &DB,
+    viewer: Option<DepotId>,
+    ch: &CharData,
+    id: i64,
+    fighting: i64,
+) -> Map {
+    let mut map = Map::new();
+    let vnum = if ch.is_npc() && ch.get_mob_rnum() != NOBODY {
+        db.get_mob_vnum(ch) as i64
+    } else {
+        -1
+    };
+    let sees = viewer.is_none_or(|v| can_see(&game.descriptors, chars, db, chars.get(v), ch));
+    map.insert("id".into(), id.into());
+    map.insert("name".into(), ch.get_name().to_string().into());
+    map.insert("vnum".into(), vnum.into());
+    map.insert("level".into(), (ch.get_level() as i64).into());
+    map.insert("hit".into(), (ch.get_hit() as i64).into());
+    map.insert("max_hit".into(), (ch.get_max_hit() as i64).into());
+    map.insert("mana".into(), (ch.get_mana() as i64).into());
+    map.insert("gold".into(), (ch.get_gold() as i64).into());
+    map.insert("alignment".into(), (ch.get_alignment() as i64).into());
+    map.insert("is_npc".into(), ch.is_npc().into());
+    map.insert("awake".into(), ch.awake().into());
+    map.insert("can_see".into(), sees.into());
+    map.insert("fighting".into(), fighting.into());
+    map
+}
+
+fn obj_map(db: &DB, obj: &ObjData, id: i64) -> Map {
+    let mut map = Map::new();
+    let vnum = if obj.get_obj_rnum() != NOTHING {
+        db.get_obj_vnum(obj) as i64
+    } else {
+        -1
+    };
+    map.insert("id".into(), id.into());
+    map.insert("name".into(), obj.short_description.to_string().into());
+    map.insert("keywords".into(), obj.name.to_string().into());
+    map.insert("vnum".into(), vnum.into());
+    map.insert("cost".into(), (obj.get_obj_cost() as i64).into());
+    map
+}
+
+/* Fill in what the script of 'me' can see, with chid the one calling. */
+#[allow(clippy::too_many_arguments)]
+fn snapshot(
+    game: &Game,
+    db: &DB,
+    chars: &Depot<CharData>,
+    objs: &Depot<ObjData>,
+    chid: DepotId,
+    me: &MeRef,
+    cmd: usize,
+    argument: &str,
+) -> SpecCall {
+    let mut call = SpecCall {
+        cmd: if cmd == 0 {
+            String::new()
+        } else {
+            CMD_INFO[cmd].command.to_string()
+        },
+        arg: argument.trim().to_string(),
+        ..Default::default()
+    };
+    let room = chars.get(chid).in_room();
+    call.room = room;
+    let viewer = match *me {
+        MeRef::Char(id) => Some(id),
+        _ => None,
+    };
+
+    let peoples = &db.world[room as usize].peoples;
+    call.targets
+        .extend(peoples.iter().map(|&id| Target::Char(id)));
+    for (i, &id) in peoples.iter().enumerate() {
+        let ch = chars.get(id);
+        let fighting = ch
+            .fighting_id()
+            .and_then(|f| peoples.iter().position(|&p| p == f))
+            .map_or(-1, |p| p as i64);
+        let map = char_map(game, chars, db, viewer, ch, i as i64, fighting);
+        if Some(id) == viewer {
+            call.me = map.clone();
+        }
+        if id == chid {
+            call.actor = map.clone();
+        }
+        call.people.push(map.into());
+    }
+
+    let add_objs = |list: &[DepotId], call: &mut SpecCall| -> Array {
+        let mut array = Array::new();
+        for &oid in list {
+            let id = call.targets.len() as i64;
+            call.targets.push(Target::Obj(oid));
+            array.push(obj_map(db, objs.get(oid), id).into());
+        }
+        array
+    };
+    call.contents = add_objs(&db.world[room as usize].contents, &mut call);
+    match me {
+        &MeRef::Char(id) => {
+            call.inventory = add_objs(&chars.get(id).carrying, &mut call);
+        }
+        &MeRef::Obj(oid) => {
+            let id = call.targets.len() as i64;
+            call.targets.push(Target::Obj(oid));
+            call.me = obj_map(db, objs.get(oid), id);
+        }
+        MeRef::None => {
+            call.me.insert(
+                "name".into(),
+                db.world[room as usize].name.to_string().into(),
+            );
+            call.me
+                .insert("vnum".into(), (db.get_room_vnum(room) as i64).into());
+        }
+    }
+
+    for (dir, exit) in db.world[room as usize].dir_option.iter().enumerate() {
+        if exit.as_ref().is_some_and(|exit| exit.to_room != NOWHERE) {
+            call.exits.push(DIRS[dir].into());
+        }
+    }
+    call
+}
+
+fn target_char(call: &SpecCall, chars: &Depot<CharData>, id: i64) -> Option<DepotId> {
+    match call.targets.get(usize::try_from(id).ok()?) {
+        Some(&Target::Char(chid))
+            if chars.contains(chid) && chars.get(chid).in_room() != NOWHERE =>
+        {
+            Some(chid)
+        }
+        _ => None,
+    }
+}
+
+fn target_obj(call: &SpecCall, objs: &Depot<ObjData>, id: i64) -> Option<DepotId> {
+    match call.targets.get(usize::try_from(id).ok()?) {
+        Some(&Target::Obj(oid)) if objs.contains(oid) => Some(oid),
+        _ => None,
+    }
+}
+
+/* Carry out what the script asked for, as 'me'. */
+#[allow(clippy::too_many_arguments)]
+fn perform_actions(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    name: &str,
+    me: MeRef,
+    call: &SpecCall,
+    actions: Vec<SpecAction>,
+) {
+    let mob = match me {
+        MeRef::Char(id) => Some(id),
+        _ => None,
+    };
+    for action in actions {
+        if mob.is_some_and(|id| !chars.contains(id) || chars.get(id).in_room() == NOWHERE) {
+            return;
+        }
+        let to_vict = matches!(action, SpecAction::ActVict(..));
+        match action {
+            SpecAction::ActRoom(msg) => match mob {
+                Some(mob) => act(
+                    &mut game.descriptors,
+                    chars,
+                    db,
+                    &msg,
+                    false,
+                    Some(chars.get(mob)),
+                    None,
+                    None,
+                    TO_ROOM,
+                ),
+                None => send_to_room(
+                    &mut game.descriptors,
+                    chars,
+                    db,
+                    call.room,
+                    &format!("{}\r\n", msg),
+                ),
+            },
+            SpecAction::ActVict(id, msg) | SpecAction::ActNotVict(id, msg) => {
+                let Some(vict_id) = target_char(call, chars, id) else {
+                    continue;
+                };
+                let vict = chars.get(vict_id);
+                if let Some(mob) = mob {
+                    act(
+                        &mut game.descriptors,
+                        chars,
+                        db,
+                        &msg,
+                        false,
+                        Some(chars.get(mob)),
+                        None,
+                        Some(VictimRef::Char(vict)),
+                        if to_vict { TO_VICT } else { TO_NOTVICT },
+                    );
+                } else if to_vict {
+                    send_to_char(&mut game.descriptors, vict, &format!("{}\r\n", msg));
+                } else {
+                    for id in db.world[vict.in_room() as usize].peoples.clone() {
+                        if id != vict_id {
+                            send_to_char(
+                                &mut game.descriptors,
+                                chars.get(id),
+                                &format!("{}\r\n", msg),
+                            );
+                        }
+                    }
+                }
+            }
+            SpecAction::Send(id, msg) => {
+                if let Some(vict) = target_char(call, chars, id) {
+                    send_to_char(
+                        &mut game.descriptors,
+                        chars.get(vict),
+                        &format!("{}\r\n", msg),
+                    );
+                }
+            }
+            _ => match mob {
+                Some(mob) => {
+                    perform_mob_action(game, chars, db, texts, objs, name, mob, call, action)
+                }
+                None => error!("SYSERR: spec script {}: only mobs can {:?}", name, action),
+            },
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn perform_mob_action(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    name: &str,
+    mob: DepotId,
+    call: &SpecCall,
+    action: SpecAction,
+) {
+    match action {
+        SpecAction::Hit(id) => {
+            if let Some(vict) = target_char(call, chars, id) {
+                if chars.get(vict).in_room() == chars.get(mob).in_room() {
+                    game.hit(chars, db, texts, objs, mob, vict, TYPE_UNDEFINED);
+                }
+            }
+        }
+        SpecAction::Cast(id, spell) => {
+            let Some(spellnum) = find_skill_num(db, &spell) else {
+                error!("SYSERR: spec script {}: no spell '{}'", name, spell);
+                return;
+            };
+            if let Some(vict) = target_char(call, chars, id) {
+                cast_spell(
+                    game,
+                    chars,
+                    db,
+                    texts,
+                    objs,
+                    mob,
+                    Some(vict),
+                    None,
+                    None,
+                    spellnum,
+                );
+            }
+        }
+        SpecAction::Move(dir) => match search_block(&dir, &DIRS, false) {
+            Some(dir) => {
+                perform_move(game, db, chars, texts, objs, mob, dir as i32, false);
+            }
+            None => error!("SYSERR: spec script {}: no direction '{}'", name, dir),
+        },
+        SpecAction::Command(line) => {
+            command_interpreter(game, db, chars, texts, objs, mob, &line);
+        }
+        SpecAction::GetObj(id) => {
+            let Some(oid) = target_obj(call, objs, id) else {
+                return;
+            };
+            if objs.get(oid).in_room() != chars.get(mob).in_room() {
+                return;
+            }
+            db.obj_from_room(objs.get(oid));
+            obj_to_char(objs.get_mut(oid), chars.get_mut(mob));
+            let ch = chars.get(mob);
+            let obj = objs.get(oid);
+            act(
+                &mut game.descriptors,
+                chars,
+                db,
+                "$n gets $p.",
+                false,
+                Some(ch),
+                Some(obj),
+                None,
+                TO_ROOM,
+            );
+        }
+        SpecAction::DropObj(id) => {
+            let Some(oid) = target_obj(call, objs, id) else {
+                return;
+            };
+            if objs.get(oid).carried_by != Some(mob) {
+                return;
+            }
+            obj_from_char(chars, objs.get_mut(oid));
+            let room = chars.get(mob).in_room();
+            db.obj_to_room(objs.get_mut(oid), room);
+            let ch = chars.get(mob);
+            let obj = objs.get(oid);
+            act(
+                &mut game.descriptors,
+                chars,
+                db,
+                "$n drops $p.",
+                false,
+                Some(ch),
+                Some(obj),
+                None,
+                TO_ROOM,
+            );
+        }
+        _ => {}
+    }
+}
+
+/* The Special routine of every mob, object and room given a spec script. */
+#[allow(clippy::too_many_arguments)]
+pub fn spec_script(
+    game: &mut Game,
+    chars: &mut Depot<CharData>,
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    me: MeRef,
+    cmd: usize,
+    argument: &str,
+) -> bool {
+    let name = match me {
+        MeRef::Char(id) => db
+            .spec_scripts
+            .mob
+            .get(&(db.get_mob_vnum(chars.get(id)) as i64)),
+        MeRef::Obj(oid) => db
+            .spec_scripts
+            .obj
+            .get(&(db.get_obj_vnum(objs.get(oid)) as i64)),
+        MeRef::None => db
+            .spec_scripts
+            .room
+            .get(&(db.get_room_vnum(chars.get(chid).in_room()) as i64)),
+    };
+    let Some(name) = name.cloned() else {
+        return false;
+    };
+
+    let call = snapshot(game, db, chars, objs, chid, &me, cmd, argument);
+    let targets = call.targets.clone();
+    let room = call.room;
+    *db.spec_scripts.call.borrow_mut() = call;
+    let result = db.spec_scripts.run(&name);
+    let call = SpecCall {
+        targets,
+        room,
+        ..Default::default()
+    };
+    match result {
+        Ok((handled, actions)) => {
+            perform_actions(game, chars, db, texts, objs, &name, me, &call, actions);
+            handled
+        }
+        Err(err) => {
+            error!("SYSERR: spec script {}: {}", name, err);
+            false
+        }
+    }
+}
+
+/* (Re)read the spec scripts, which takes away all their attachments. */
+pub fn load_spec_scripts(db: &mut DB) {
+    db.spec_scripts.load(Path::new(SPEC_PREFIX));
+}
+
+/* Give spec_script() to everything a spec script is attached to. */
+pub fn assign_spec_scripts(db: &mut DB) {
+    let scripts = &db.spec_scripts;
+    let mobs: Vec<i64> = scripts.mob.keys().copied().collect();
+    let objects: Vec<i64> = scripts.obj.keys().copied().collect();
+    let rooms: Vec<i64> = scripts.room.keys().copied().collect();
+    for vnum in mobs {
+        assignmob(db, vnum as MobVnum, spec_script);
+    }
+    for vnum in objects {
+        assignobj(db, vnum as ObjVnum, spec_script);
+    }
+    for vnum in rooms {
+        assignroom(db, vnum as RoomVnum, spec_script);
+    }
+}
+#[cfg(test)]
+mod specscript_tests {
+    use super::*;
+
+    const GUARD: &str = r#"
+        attach_mob(3060);
+        attach_mob(3067);
+        attach_room(3001);
+
+        fn special() {
+            if cmd() != "" {
+                return false;
+            }
+            for p in people() {
+                if p.alignment < -500 {
+                    act_room("$n screams 'Evil-doer!'");
+                    hit(p.id);
+                    return true;
+                }
+            }
+            false
+        }
+    "#;
+
+    fn evil_guard() -> SpecScripts {
+        let mut scripts = SpecScripts::new();
+        scripts.compile("guard", GUARD).unwrap();
+        let mut evil = Map::new();
+        evil.insert("id".into(), 3.into());
+        evil.insert("alignment".into(), (-800_i64).into());
+        scripts.call.borrow_mut().people = vec![evil.into()];
+        scripts
+    }
+
+    #[test]
+    fn test_compile_attaches() {
+        let scripts = evil_guard();
+        assert_eq!(scripts.mob.get(&3060).map(|n| &**n), Some("guard"));
+        assert_eq!(scripts.mob.get(&3067).map(|n| &**n), Some("guard"));
+        assert_eq!(scripts.room.get(&3001).map(|n| &**n), Some("guard"));
+        assert!(scripts.obj.is_empty());
+        assert!(scripts.call.borrow().attach.is_empty());
+    }
+
+    #[test]
+    fn test_compile_lib_scripts() {
+        let mut scripts = SpecScripts::new();
+        scripts
+            .compile("altar", include_str!("../lib/spec/altar.rhai"))
+            .unwrap();
+        assert_eq!(scripts.room.get(&3054).map(|n| &**n), Some("altar"));
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let mut scripts = SpecScripts::new();
+        assert!(scripts.compile("bad", "fn special( {").is_err());
+        assert!(scripts.compile("nospecial", "attach_mob(1);").is_err());
+        assert!(scripts.mob.is_empty());
+        assert!(scripts
+            .compile("noeval", "eval(\"1\"); fn special() { true }")
+            .is_err());
+    }
+
+    #[test]
+    fn test_run_actions() {
+        let scripts = evil_guard();
+        let (handled, actions) = scripts.run("guard").unwrap();
+        assert!(handled);
+        assert_eq!(
+            actions,
+            vec![
+                SpecAction::ActRoom("$n screams 'Evil-doer!'".to_string()),
+                SpecAction::Hit(3),
+            ]
+        );
+
+        scripts.call.borrow_mut().cmd = "north".to_string();
+        assert_eq!(scripts.run("guard").unwrap(), (false, vec![]));
+        assert!(scripts.run("nosuch").is_err());
+    }
+
+    #[test]
+    fn test_run_limits() {
+        let mut scripts = SpecScripts::new();
+        scripts
+            .compile("forever", "fn special() { loop { } }")
+            .unwrap();
+        scripts
+            .compile("chatty", "fn special() { loop { log(\"x\"); hit(0); } }")
+            .unwrap();
+        assert!(scripts.run("forever").is_err());
+        assert!(scripts.run("chatty").is_err());
+        /* nothing is left over for the next call */
+        assert!(scripts.call.borrow().actions.is_empty());
+    }
+}
@@ -13,50 +13,45 @@ use std::cmp::{max, min};
 
 use crate::depot::{Depot, DepotId, HasId};
 use crate::fight::skill_message;
-use crate::{act, perform_act, send_to_char, ObjData, TextData, VictimRef};
+use crate::{act, perform_act, send_to_char, ActText, ObjData, TextData, VictimRef};
 use log::error;
 
-use crate::config::OK;
-use crate::db::DB;
+use crate::class::{CLASS_ABBREVS, PC_CLASS_TYPES};
+use crate::constants::{DIRS, POSITION_TYPES};
+use crate::db::{DB, SPELL_FILE};
 use crate::handler::{
     generic_find, get_char_vis, get_obj_in_list_vis, get_obj_in_list_vis2, get_obj_vis, isname,
     FindFlags,
 };
-use crate::interpreter::{any_one_arg, is_abbrev, one_argument};
+use crate::interpreter::{any_one_arg, is_abbrev, one_argument, search_block};
 use crate::magic::{
     mag_affects, mag_alter_objs, mag_areas, mag_creations, mag_damage, mag_groups, mag_masses,
-    mag_points, mag_summons, mag_unaffects,
+    mag_points, mag_rooms, mag_summons, mag_unaffects,
 };
+use crate::sidefile::{read_records, SideRecord};
 use crate::spells::{
     spell_charm, spell_create_water, spell_detect_poison, spell_enchant_weapon, spell_identify,
-    spell_locate_object, spell_recall, spell_summon, spell_teleport, SpellInfoType, CAST_POTION,
-    CAST_SCROLL, CAST_SPELL, CAST_STAFF, CAST_WAND, DEFAULT_STAFF_LVL, DEFAULT_WAND_LVL,
-    MAG_AFFECTS, MAG_ALTER_OBJS, MAG_AREAS, MAG_CREATIONS, MAG_DAMAGE, MAG_GROUPS, MAG_MANUAL,
-    MAG_MASSES, MAG_POINTS, MAG_SUMMONS, MAG_UNAFFECTS, MAX_SPELLS, SAVING_BREATH, SAVING_ROD,
-    SAVING_SPELL, SKILL_BACKSTAB, SKILL_BASH, SKILL_HIDE, SKILL_KICK, SKILL_PICK_LOCK,
-    SKILL_RESCUE, SKILL_SNEAK, SKILL_STEAL, SKILL_TRACK, SPELL_ACID_BREATH, SPELL_ANIMATE_DEAD,
-    SPELL_ARMOR, SPELL_BLESS, SPELL_BLINDNESS, SPELL_BURNING_HANDS, SPELL_CALL_LIGHTNING,
-    SPELL_CHARM, SPELL_CHILL_TOUCH, SPELL_CLONE, SPELL_COLOR_SPRAY, SPELL_CONTROL_WEATHER,
-    SPELL_CREATE_FOOD, SPELL_CREATE_WATER, SPELL_CURE_BLIND, SPELL_CURE_CRITIC, SPELL_CURE_LIGHT,
-    SPELL_CURSE, SPELL_DETECT_ALIGN, SPELL_DETECT_INVIS, SPELL_DETECT_MAGIC, SPELL_DETECT_POISON,
-    SPELL_DISPEL_EVIL, SPELL_DISPEL_GOOD, SPELL_EARTHQUAKE, SPELL_ENCHANT_WEAPON,
-    SPELL_ENERGY_DRAIN, SPELL_FIREBALL, SPELL_FIRE_BREATH, SPELL_FROST_BREATH, SPELL_GAS_BREATH,
-    SPELL_GROUP_ARMOR, SPELL_GROUP_HEAL, SPELL_HARM, SPELL_HEAL, SPELL_IDENTIFY, SPELL_INFRAVISION,
-    SPELL_INVISIBLE, SPELL_LIGHTNING_BOLT, SPELL_LIGHTNING_BREATH, SPELL_LOCATE_OBJECT,
-    SPELL_MAGIC_MISSILE, SPELL_POISON, SPELL_PROT_FROM_EVIL, SPELL_REMOVE_CURSE,
-    SPELL_REMOVE_POISON, SPELL_SANCTUARY, SPELL_SENSE_LIFE, SPELL_SHOCKING_GRASP, SPELL_SLEEP,
-    SPELL_STRENGTH, SPELL_SUMMON, SPELL_TELEPORT, SPELL_WATERWALK, SPELL_WORD_OF_RECALL,
-    TAR_CHAR_ROOM, TAR_CHAR_WORLD, TAR_FIGHT_SELF, TAR_FIGHT_VICT, TAR_IGNORE, TAR_NOT_SELF,
-    TAR_OBJ_EQUIP, TAR_OBJ_INV, TAR_OBJ_ROOM, TAR_OBJ_WORLD, TAR_SELF_ONLY, TOP_SPELL_DEFINE,
-    TYPE_UNDEFINED,
+    spell_locate_object, spell_recall, spell_summon, spell_teleport, SpellDice, SpellInfoType,
+    CAST_POTION, CAST_SCROLL, CAST_SPELL, CAST_STAFF, CAST_WAND, DEFAULT_STAFF_LVL,
+    DEFAULT_WAND_LVL, MAG_AFFECTS, MAG_ALTER_OBJS, MAG_AREAS, MAG_CREATIONS, MAG_DAMAGE,
+    MAG_GROUPS, MAG_MANUAL, MAG_MASSES, MAG_POINTS, MAG_ROOMS, MAG_SUMMONS, MAG_UNAFFECTS,
+    MAX_SPELLS, SAVING_BREATH, SAVING_ROD, SAVING_SPELL, SPELL_CHARM, SPELL_CREATE_WATER,
+    SPELL_DETECT_POISON, SPELL_ENCHANT_WEAPON, SPELL_IDENTIFY, SPELL_LOCATE_OBJECT, SPELL_SUMMON,
+    SPELL_TELEPORT, SPELL_WORD_OF_RECALL, TAR_CHAR_ROOM, TAR_CHAR_WORLD, TAR_DIR, TAR_FIGHT_SELF,
+    TAR_FIGHT_VICT, TAR_IGNORE, TAR_NOT_SELF, TAR_OBJ_EQUIP, TAR_OBJ_INV, TAR_OBJ_ROOM,
+    TAR_OBJ_WORLD, TAR_SELF_ONLY, TOP_SPELL_DEFINE, TYPE_UNDEFINED,
 };
 use crate::structs::NUM_CLASSES;
 use crate::structs::{
-    AffectFlags, CharData, Class, ItemType, Position, RoomFlags, LVL_IMMORT, LVL_IMPL, NUM_WEARS,
-    PULSE_VIOLENCE,
+    AffectFlags, CharData, ItemType, Position, RoomAffectFlags, RoomFlags, LVL_IMMORT, LVL_IMPL,
+    NOWHERE, NUM_WEARS, PULSE_VIOLENCE,
 };
 use crate::util::{has_spell_routine, rand_number};
 use crate::{is_set, Game, TO_CHAR, TO_ROOM, TO_VICT};
+use log::info;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::rc::Rc;
 
 /*
  * This arrangement is pretty stupid, but the number of skills is limited by
@@ -390,7 +385,7 @@ fn say_spell(
                 &mut game.descriptors,
                 chars,
                 db,
-                &buf1,
+                ActText::Plain(&buf1),
                 Some(ch),
                 toobj,
                 toch,
@@ -401,7 +396,7 @@ fn say_spell(
                 &mut game.descriptors,
                 chars,
                 db,
-                &buf2,
+                ActText::Plain(&buf2),
                 Some(ch),
                 toobj,
                 toch,
@@ -444,9 +439,9 @@ fn say_spell(
  * a valid spell/skill number.  A typical for() loop would not need to use
  * this because you can guarantee > 0 and <= TOP_SPELL_DEFINE.
  */
-pub fn skill_name(db: &DB, num: i32) -> &'static str {
+pub fn skill_name(db: &DB, num: i32) -> &str {
     if num > 0 && num <= TOP_SPELL_DEFINE as i32 {
-        db.spell_info[num as usize].name
+        &db.spell_info[num as usize].name
     } else if num == -1 {
         "UNUSED"
     } else {
@@ -457,12 +452,12 @@ pub fn skill_name(db: &DB, num: i32) -> &'static str {
 pub fn find_skill_num(db: &DB, name: &str) -> Option<i32> {
     let mut ok;
     for skindex in 1..(TOP_SPELL_DEFINE + 1) {
-        if is_abbrev(name, db.spell_info[skindex].name) {
+        if is_abbrev(name, &db.spell_info[skindex].name) {
             return Some(skindex as i32);
         }
 
         ok = true;
-        let tempbuf = db.spell_info[skindex].name;
+        let tempbuf = &db.spell_info[skindex].name;
         let mut first = String::new();
         let mut first2 = String::new();
         let mut temp = any_one_arg(tempbuf, &mut first);
@@ -501,6 +496,7 @@ pub fn call_magic(
     caster_id: DepotId,
     cvict_id: Option<DepotId>,
     ovict: Option<DepotId>,
+    tdir: Option<usize>,
     spellnum: i32,
     level: u8,
     casttype: i32,
@@ -630,6 +626,10 @@ pub fn call_magic(
         );
     }
 
+    if is_set!(sinfo_routines, MAG_ROOMS) {
+        mag_rooms(game, chars, db, level, caster_id, tdir, spellnum);
+    }
+
     if is_set!(sinfo_routines, MAG_CREATIONS) {
         mag_creations(game, chars, db, objs, level, caster_id, spellnum);
     }
@@ -863,7 +863,8 @@ pub fn mag_objectmagic(
                         let obj = objs.get(oid);
                         let spellnum = obj.get_obj_val(i);
                         call_magic(
-                            game, chars, db, texts, objs, chid, None, None, spellnum, k, CAST_STAFF,
+                            game, chars, db, texts, objs, chid, None, None, None, spellnum, k,
+                            CAST_STAFF,
                         );
                     }
                 } else {
@@ -880,6 +881,7 @@ pub fn mag_objectmagic(
                                 chid,
                                 Some(tch_id),
                                 None,
+                                None,
                                 spellnum,
                                 k,
                                 CAST_STAFF,
@@ -1064,6 +1066,7 @@ pub fn mag_objectmagic(
                     chid,
                     tch_id,
                     tobj_id,
+                    None,
                     obj.get_obj_val(3),
                     obj.get_obj_val(0) as u8,
                     CAST_WAND,
@@ -1078,6 +1081,7 @@ pub fn mag_objectmagic(
                     chid,
                     tch_id,
                     tobj_id,
+                    None,
                     obj.get_obj_val(3),
                     DEFAULT_WAND_LVL,
                     CAST_WAND,
@@ -1157,6 +1161,7 @@ pub fn mag_objectmagic(
                     chid,
                     tch_id,
                     tobj_id,
+                    None,
                     spellnum,
                     level,
                     CAST_SCROLL,
@@ -1220,6 +1225,7 @@ pub fn mag_objectmagic(
                     chid,
                     Some(chid),
                     None,
+                    None,
                     spellnum,
                     level,
                     CAST_POTION,
@@ -1242,7 +1248,7 @@ pub fn mag_objectmagic(
 
 /*
  * cast_spell is used generically to cast any spoken spell, assuming we
- * already have the target char/obj and spell number.  It checks all
+ * already have the target char/obj/exit and spell number.  It checks all
  * restrictions, etc., prints the words, etc.
  *
  * Entry point for NPC casts.  Recommended entry point for spells cast
@@ -1258,6 +1264,7 @@ pub fn cast_spell(
     chid: DepotId,
     tch_id: Option<DepotId>,
     tobj_id: Option<DepotId>,
+    tdir: Option<usize>,
     spellnum: i32,
 ) -> i32 {
     let ch = chars.get(chid);
@@ -1268,7 +1275,7 @@ pub fn cast_spell(
         );
         return 0;
     }
-    let sinfo = db.spell_info[spellnum as usize];
+    let sinfo = db.spell_info[spellnum as usize].clone();
     if ch.get_pos() < sinfo.min_position {
         match ch.get_pos() {
             Position::Sleeping => {
@@ -1339,7 +1346,15 @@ pub fn cast_spell(
         );
         return 0;
     }
-    send_to_char(&mut game.descriptors, ch, OK);
+    if db.room_affected(ch.in_room(), RoomAffectFlags::SILENCE) && ch.get_level() < LVL_IMMORT {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Your lips move, but no sound comes out!\r\n",
+        );
+        return 0;
+    }
+    send_to_char(&mut game.descriptors, ch, db.msg(ch, "ok"));
     say_spell(game, chars, db, objs, chid, spellnum, tch_id, tobj_id);
     let ch = chars.get(chid);
     call_magic(
@@ -1351,6 +1366,7 @@ pub fn cast_spell(
         chid,
         tch_id,
         tobj_id,
+        tdir,
         spellnum,
         ch.get_level(),
         CAST_SPELL,
@@ -1402,7 +1418,7 @@ pub fn do_cast(
         send_to_char(&mut game.descriptors, ch, "Cast what?!?\r\n");
         return;
     }
-    let sinfo = db.spell_info[spellnum as usize];
+    let sinfo = db.spell_info[spellnum as usize].clone();
     if ch.get_level() < sinfo.min_level[ch.get_class() as usize] as u8 {
         send_to_char(&mut game.descriptors, ch, "You do not know that spell!\r\n");
         return;
@@ -1425,8 +1441,28 @@ pub fn do_cast(
     let mut target = false;
     let mut tch = None;
     let mut tobj = None;
+    let mut tdir = None;
     if is_set!(sinfo.targets, TAR_IGNORE) {
         target = true;
+    } else if is_set!(sinfo.targets, TAR_DIR) {
+        if t.is_empty() {
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                "In which direction should the spell be cast?\r\n",
+            );
+            return;
+        }
+        let Some(dir) = search_block(t, &DIRS, false) else {
+            send_to_char(&mut game.descriptors, ch, "That's not a direction.\r\n");
+            return;
+        };
+        if db.exit(ch, dir).is_none_or(|exit| exit.to_room == NOWHERE) {
+            send_to_char(&mut game.descriptors, ch, "There is no exit that way.\r\n");
+            return;
+        }
+        tdir = Some(dir);
+        target = true;
     } else if !t.is_empty() {
         if !target && is_set!(sinfo.targets, TAR_CHAR_ROOM) && {
             tch = get_char_vis(
@@ -1557,6 +1593,14 @@ pub fn do_cast(
         );
         return;
     }
+    if db.room_affected(ch.in_room(), RoomAffectFlags::SILENCE) && ch.get_level() < LVL_IMMORT {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Your lips move, but no sound comes out!\r\n",
+        );
+        return;
+    }
     let mana = mag_manacost(ch, &sinfo);
     if mana > 0 && ch.get_mana() < mana && ch.get_level() < LVL_IMMORT {
         send_to_char(
@@ -1597,7 +1641,7 @@ pub fn do_cast(
         let tch_id = tch.map(|c| c.id());
         let tobj_id = tobj.map(|o| o.id());
         if cast_spell(
-            game, chars, db, texts, objs, chid, tch_id, tobj_id, spellnum,
+            game, chars, db, texts, objs, chid, tch_id, tobj_id, tdir, spellnum,
         ) != 0
         {
             let ch = chars.get_mut(chid);
@@ -1609,936 +1653,342 @@ pub fn do_cast(
     }
 }
 
-pub fn spell_level(db: &mut DB, spell: i32, chclass: Class, level: i32) {
-    let mut bad = false;
-
-    if spell < 0 || spell > TOP_SPELL_DEFINE as i32 {
-        error!(
-            "SYSERR: attempting assign to illegal spellnum {}/{}",
-            spell, TOP_SPELL_DEFINE
-        );
-        return;
-    }
+/* TAR_x, in bit order, as written in the spell file */
+const SPELL_TARGETS: [&str; 12] = [
+    "ignore",
+    "char_room",
+    "char_world",
+    "fight_self",
+    "fight_vict",
+    "self_only",
+    "not_self",
+    "obj_inv",
+    "obj_room",
+    "obj_world",
+    "obj_equip",
+    "dir",
+];
 
-    if chclass == Class::Undefined {
-        error!(
-            "SYSERR: assigning '{}' to illegal class {}/{}.",
-            skill_name(db, spell),
-            chclass as i8,
-            NUM_CLASSES - 1
-        );
-        bad = true;
-    }
+/* MAG_x, in bit order, as written in the spell file */
+const SPELL_ROUTINES: [&str; 12] = [
+    "damage",
+    "affects",
+    "unaffects",
+    "points",
+    "alter_objs",
+    "groups",
+    "masses",
+    "areas",
+    "summons",
+    "creations",
+    "manual",
+    "rooms",
+];
 
-    if level < 1 || level > LVL_IMPL as i32 {
-        error!(
-            "SYSERR: assigning '{}' to illegal level {}/{}.",
-            skill_name(db, spell),
-            level,
-            LVL_IMPL
-        );
-        bad = true;
+fn parse_spell_flags(value: &str, names: &[&str]) -> Result<i32, String> {
+    let mut flags = 0;
+    for word in value.split_whitespace() {
+        match names.iter().position(|n| n.eq_ignore_ascii_case(word)) {
+            Some(bit) => flags |= 1 << bit,
+            None => return Err(format!("unknown flag '{}'", word)),
+        }
     }
+    Ok(flags)
+}
 
-    if !bad {
-        db.spell_info[spell as usize].min_level[chclass as usize] = level;
-    }
+/* A class by full name or abbreviation, e.g. "Magic User" or "Mu". */
+fn parse_spell_class(name: &str) -> Result<usize, String> {
+    PC_CLASS_TYPES
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(name))
+        .or_else(|| {
+            CLASS_ABBREVS
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| format!("unknown class '{}'", name))
 }
 
-/* Assign the spells on boot up */
-#[allow(clippy::too_many_arguments)]
-fn spello(
-    db: &mut DB,
-    spl: i32,
-    name: &'static str,
-    max_mana: i32,
-    min_mana: i32,
-    mana_change: i32,
-    minpos: Position,
-    targets: i32,
-    violent: bool,
-    routines: i32,
-    wearoff: &'static str,
-) {
-    let spl = spl as usize;
-    for i in 0..NUM_CLASSES {
-        db.spell_info[spl].min_level[i] = LVL_IMMORT as i32;
-    }
+/* Splits "Magic User 12" into the class and what follows it. */
+fn split_spell_class(value: &str) -> Result<(Option<usize>, &str), String> {
+    let (class, last) = match value.rsplit_once(char::is_whitespace) {
+        Some((class, last)) => (Some(parse_spell_class(class.trim())?), last),
+        None => (None, value),
+    };
+    Ok((class, last))
+}
 
-    db.spell_info[spl].mana_max = max_mana;
-    db.spell_info[spl].mana_min = min_mana;
-    db.spell_info[spl].mana_change = mana_change;
-    db.spell_info[spl].min_position = minpos;
-    db.spell_info[spl].targets = targets;
-    db.spell_info[spl].violent = violent;
-    db.spell_info[spl].routines = routines;
-    db.spell_info[spl].name = name;
-    db.spell_info[spl].wear_off_msg = if wearoff.is_empty() {
-        None
-    } else {
-        Some(wearoff)
+/* NdS, then any number of "+bonus" and "+level". */
+fn parse_spell_dice(value: &str) -> Result<SpellDice, String> {
+    let bad = || format!("bad dice '{}'", value);
+    let mut terms = value.split('+');
+    let (number, size) = terms
+        .next()
+        .and_then(|d| d.split_once('d'))
+        .ok_or_else(bad)?;
+    let mut dice = SpellDice {
+        number: number.parse().map_err(|_| bad())?,
+        size: size.parse().map_err(|_| bad())?,
+        ..Default::default()
     };
+    for term in terms {
+        if term.eq_ignore_ascii_case("level") {
+            dice.per_level = true;
+        } else {
+            dice.bonus += term.parse::<i32>().map_err(|_| bad())?;
+        }
+    }
+    Ok(dice)
 }
 
-// fn unused_spell(chars: &mut Depot<CharData>, db: &mut DB, spl: usize) {
-//     for i in 0..NUM_CLASSES as usize {
-//         db.spell_info[spl].min_level[i] = (LVL_IMPL + 1) as i32;
-//         db.spell_info[spl].mana_max = 0;
-//         db.spell_info[spl].mana_min = 0;
-//         db.spell_info[spl].mana_change = 0;
-//         db.spell_info[spl].min_position = 0;
-//         db.spell_info[spl].targets = 0;
-//         db.spell_info[spl].violent = false;
-//         db.spell_info[spl].routines = 0;
-//         db.spell_info[spl].name = UNUSED_SPELLNAME;
-//     }
-// }
+fn parse_spell_field(sinfo: &mut SpellInfoType, tag: &str, value: &str) -> Result<(), String> {
+    let number = |v: &str| v.parse::<i32>().map_err(|_| format!("bad number '{}'", v));
+    match tag {
+        "Name" => {
+            if value.is_empty() {
+                return Err("empty name".into());
+            }
+            sinfo.name = Rc::from(value);
+        }
+        "Mana" => {
+            let mana = value
+                .split_whitespace()
+                .map(number)
+                .collect::<Result<Vec<_>, _>>()?;
+            if mana.len() != 3 {
+                return Err("Mana needs maximum, minimum and change".into());
+            }
+            sinfo.mana_max = mana[0];
+            sinfo.mana_min = mana[1];
+            sinfo.mana_change = mana[2];
+        }
+        "Position" => {
+            let pos = POSITION_TYPES
+                .iter()
+                .take_while(|p| **p != "\n")
+                .position(|p| p.eq_ignore_ascii_case(value))
+                .ok_or_else(|| format!("unknown position '{}'", value))?;
+            sinfo.min_position = Position::from(pos as u8);
+        }
+        "Targets" => sinfo.targets = parse_spell_flags(value, &SPELL_TARGETS)?,
+        "Violent" => sinfo.violent = value.eq_ignore_ascii_case("yes"),
+        "Routines" => sinfo.routines = parse_spell_flags(value, &SPELL_ROUTINES)?,
+        "WearOff" => sinfo.wear_off_msg = Some(Rc::from(value)),
+        "Damage" => {
+            let (class, dice) = split_spell_class(value)?;
+            let dice = parse_spell_dice(dice)?;
+            match class {
+                Some(class) => sinfo.class_damage[class] = Some(dice),
+                None => sinfo.damage = dice,
+            }
+        }
+        "Level" => {
+            let (class, level) = split_spell_class(value)?;
+            let class = class.ok_or("Level needs a class")?;
+            let level = number(level)?;
+            if level < 1 || level > LVL_IMPL as i32 {
+                return Err(format!("illegal level {}/{}", level, LVL_IMPL));
+            }
+            sinfo.min_level[class] = level;
+        }
+        _ => return Err(format!("unknown field '{}'", tag)),
+    }
+    Ok(())
+}
 
-fn skillo(db: &mut DB, skill: i32, name: &'static str) {
-    spello(db, skill, name, 0, 0, 0, Position::Dead, 0, false, 0, "");
+fn parse_spell(rec: &SideRecord) -> Result<(usize, SpellInfoType), String> {
+    let num = rec
+        .key
+        .parse::<usize>()
+        .ok()
+        .filter(|&num| num > 0 && num <= TOP_SPELL_DEFINE)
+        .ok_or("illegal spellnum")?;
+    let mut sinfo = SpellInfoType {
+        /* immortals can use every spell and skill */
+        min_level: [LVL_IMMORT as i32; NUM_CLASSES],
+        ..Default::default()
+    };
+    for (tag, value) in &rec.fields {
+        parse_spell_field(&mut sinfo, tag, value.trim())?;
+    }
+    if &*sinfo.name == UNUSED_SPELLNAME {
+        return Err("no name".into());
+    }
+    Ok((num, sinfo))
 }
 
 /*
- * Arguments for spello calls:
- *
- * spellnum, maxmana, minmana, manachng, minpos, targets, violent?, routines.
- *
- * spellnum:  Number of the spell.  Usually the symbolic name as defined in
- * spells.h (such as SPELL_HEAL).
- *
- * spellname: The name of the spell.
- *
- * maxmana :  The maximum mana this spell will take (i.e., the mana it
- * will take when the player first gets the spell).
- *
- * minmana :  The minimum mana this spell will take, no matter how high
- * level the caster is.
- *
- * manachng:  The change in mana for the spell from level to level.  This
- * number should be positive, but represents the reduction in mana cost as
- * the caster's level increases.
- *
- * minpos  :  Minimum position the caster must be in for the spell to work
- * (usually fighting or standing). targets :  A "list" of the valid targets
- * for the spell, joined with bitwise OR ('|').
- *
- * violent :  true or false, depending on if this is considered a violent
- * spell and should not be cast in PEACEFUL rooms or on yourself.  Should be
- * set on any spell that inflicts damage, is considered aggressive (i.e.
- * charm, curse), or is otherwise nasty.
- *
- * routines:  A list of magic routines which are associated with this spell
- * if the spell uses spell templates.  Also joined with bitwise OR ('|').
- *
- * See the CircleMUD documentation for a more detailed description of these
- * fields.
+ * Reads the spell definitions, a side file with one record per spell.
+ * The whole file is checked, and every error logged; if there was any,
+ * the number of errors is returned instead of the spells, so that no
+ * half-read table is ever used.
  */
+pub fn parse_spells<R: BufRead>(reader: R) -> Result<Vec<(usize, SpellInfoType)>, usize> {
+    match read_records(reader, SPELL_FILE, parse_spell) {
+        (spells, 0) => Ok(spells),
+        (_, errors) => Err(errors),
+    }
+}
 
 /*
- * NOTE: SPELL LEVELS ARE NO LONGER ASSIGNED HERE AS OF Circle 3.0 bpl9.
- * In order to make this cleaner, as well as to make adding new classes
- * much easier, spell levels are now assigned in class.c.  You only need
- * a spello() call to define a new spell; to decide who gets to use a spell
- * or skill, look in class.c.  -JE 5 Feb 1996
+ * Assign the spells on boot up, and again on 'reload spells'.  Names,
+ * mana, targets, class levels and damage dice all come from SPELL_FILE,
+ * so they can be changed without recompiling.  The table is left alone
+ * if the file cannot be read or has any error.
  */
+pub fn mag_assign_spells(db: &mut DB) -> bool {
+    let fl = match File::open(SPELL_FILE) {
+        Ok(fl) => fl,
+        Err(err) => {
+            error!("SYSERR: Cannot read spell file {}: {}", SPELL_FILE, err);
+            return false;
+        }
+    };
+    assign_spells(db, BufReader::new(fl))
+}
 
-pub fn mag_assign_spells(db: &mut DB) {
-    /* TODO Do not change the loop below. */
-    // for i in 0..(TOP_SPELL_DEFINE as usize + 1) {
-    //     unused_spell(i);
-    // }
-    /* Do not change the loop above. */
-
-    spello(
-        db,
-        SPELL_ANIMATE_DEAD,
-        "animate dead",
-        35,
-        10,
-        3,
-        Position::Standing,
-        TAR_OBJ_ROOM,
-        false,
-        MAG_SUMMONS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_ARMOR,
-        "armor",
-        30,
-        15,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_AFFECTS,
-        "You feel less protected.",
-    );
-
-    spello(
-        db,
-        SPELL_BLESS,
-        "bless",
-        35,
-        5,
-        3,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_OBJ_INV,
-        false,
-        MAG_AFFECTS | MAG_ALTER_OBJS,
-        "You feel less righteous.",
-    );
-
-    spello(
-        db,
-        SPELL_BLINDNESS,
-        "blindness",
-        35,
-        25,
-        1,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_NOT_SELF,
-        false,
-        MAG_AFFECTS,
-        "You feel a cloak of blindness dissolve.",
-    );
-
-    spello(
-        db,
-        SPELL_BURNING_HANDS,
-        "burning hands",
-        30,
-        10,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CALL_LIGHTNING,
-        "call lightning",
-        40,
-        25,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CHARM,
-        "charm person",
-        75,
-        50,
-        2,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_NOT_SELF,
-        true,
-        MAG_MANUAL,
-        "You feel more self-confident.",
-    );
-
-    spello(
-        db,
-        SPELL_CHILL_TOUCH,
-        "chill touch",
-        30,
-        10,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE | MAG_AFFECTS,
-        "You feel your strength return.",
-    );
-
-    spello(
-        db,
-        SPELL_CLONE,
-        "clone",
-        80,
-        65,
-        5,
-        Position::Standing,
-        TAR_SELF_ONLY,
-        false,
-        MAG_SUMMONS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_COLOR_SPRAY,
-        "color spray",
-        30,
-        15,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CONTROL_WEATHER,
-        "control weather",
-        75,
-        25,
-        5,
-        Position::Standing,
-        TAR_IGNORE,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CREATE_FOOD,
-        "create food",
-        30,
-        5,
-        4,
-        Position::Standing,
-        TAR_IGNORE,
-        false,
-        MAG_CREATIONS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CREATE_WATER,
-        "create water",
-        30,
-        5,
-        4,
-        Position::Standing,
-        TAR_OBJ_INV | TAR_OBJ_EQUIP,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CURE_BLIND,
-        "cure blind",
-        30,
-        5,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_UNAFFECTS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CURE_CRITIC,
-        "cure critic",
-        30,
-        10,
-        2,
-        Position::Fighting,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_POINTS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CURE_LIGHT,
-        "cure light",
-        30,
-        10,
-        2,
-        Position::Fighting,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_POINTS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_CURSE,
-        "curse",
-        80,
-        50,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_OBJ_INV,
-        true,
-        MAG_AFFECTS | MAG_ALTER_OBJS,
-        "You feel more optimistic.",
-    );
-
-    spello(
-        db,
-        SPELL_DETECT_ALIGN,
-        "detect alignment",
-        20,
-        10,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_SELF_ONLY,
-        false,
-        MAG_AFFECTS,
-        "You feel less aware.",
-    );
-
-    spello(
-        db,
-        SPELL_DETECT_INVIS,
-        "detect invisibility",
-        20,
-        10,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_SELF_ONLY,
-        false,
-        MAG_AFFECTS,
-        "Your eyes stop tingling.",
-    );
-
-    spello(
-        db,
-        SPELL_DETECT_MAGIC,
-        "detect magic",
-        20,
-        10,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_SELF_ONLY,
-        false,
-        MAG_AFFECTS,
-        "The detect magic wears off.",
-    );
-
-    spello(
-        db,
-        SPELL_DETECT_POISON,
-        "detect poison",
-        15,
-        5,
-        1,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_OBJ_INV | TAR_OBJ_ROOM,
-        false,
-        MAG_MANUAL,
-        "The detect poison wears off.",
-    );
-
-    spello(
-        db,
-        SPELL_DISPEL_EVIL,
-        "dispel evil",
-        40,
-        25,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_DISPEL_GOOD,
-        "dispel good",
-        40,
-        25,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_EARTHQUAKE,
-        "earthquake",
-        40,
-        25,
-        3,
-        Position::Fighting,
-        TAR_IGNORE,
-        true,
-        MAG_AREAS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_ENCHANT_WEAPON,
-        "enchant weapon",
-        150,
-        100,
-        10,
-        Position::Standing,
-        TAR_OBJ_INV,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_ENERGY_DRAIN,
-        "energy drain",
-        40,
-        25,
-        1,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE | MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_GROUP_ARMOR,
-        "group armor",
-        50,
-        30,
-        2,
-        Position::Standing,
-        TAR_IGNORE,
-        false,
-        MAG_GROUPS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_FIREBALL,
-        "fireball",
-        40,
-        30,
-        2,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_GROUP_HEAL,
-        "group heal",
-        80,
-        60,
-        5,
-        Position::Standing,
-        TAR_IGNORE,
-        false,
-        MAG_GROUPS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_HARM,
-        "harm",
-        75,
-        45,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_HEAL,
-        "heal",
-        60,
-        40,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_POINTS | MAG_UNAFFECTS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_INFRAVISION,
-        "infravision",
-        25,
-        10,
-        1,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_SELF_ONLY,
-        false,
-        MAG_AFFECTS,
-        "Your night vision seems to fade.",
-    );
-
-    spello(
-        db,
-        SPELL_INVISIBLE,
-        "invisibility",
-        35,
-        25,
-        1,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_OBJ_INV | TAR_OBJ_ROOM,
-        false,
-        MAG_AFFECTS | MAG_ALTER_OBJS,
-        "You feel yourself exposed.",
-    );
-
-    spello(
-        db,
-        SPELL_LIGHTNING_BOLT,
-        "lightning bolt",
-        30,
-        15,
-        1,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_LOCATE_OBJECT,
-        "locate object",
-        25,
-        20,
-        1,
-        Position::Standing,
-        TAR_OBJ_WORLD,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_MAGIC_MISSILE,
-        "magic missile",
-        25,
-        10,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_POISON,
-        "poison",
-        50,
-        20,
-        3,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_NOT_SELF | TAR_OBJ_INV,
-        true,
-        MAG_AFFECTS | MAG_ALTER_OBJS,
-        "You feel less sick.",
-    );
-
-    spello(
-        db,
-        SPELL_PROT_FROM_EVIL,
-        "protection from evil",
-        40,
-        10,
-        3,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_SELF_ONLY,
-        false,
-        MAG_AFFECTS,
-        "You feel less protected.",
-    );
-
-    spello(
-        db,
-        SPELL_REMOVE_CURSE,
-        "remove curse",
-        45,
-        25,
-        5,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_OBJ_INV | TAR_OBJ_EQUIP,
-        false,
-        MAG_UNAFFECTS | MAG_ALTER_OBJS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_REMOVE_POISON,
-        "remove poison",
-        40,
-        8,
-        4,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_OBJ_INV | TAR_OBJ_ROOM,
-        false,
-        MAG_UNAFFECTS | MAG_ALTER_OBJS,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_SANCTUARY,
-        "sanctuary",
-        110,
-        85,
-        5,
-        Position::Standing,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_AFFECTS,
-        "The white aura around your body fades.",
-    );
-
-    spello(
-        db,
-        SPELL_SENSE_LIFE,
-        "sense life",
-        20,
-        10,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM | TAR_SELF_ONLY,
-        false,
-        MAG_AFFECTS,
-        "You feel less aware of your surroundings.",
-    );
-
-    spello(
-        db,
-        SPELL_SHOCKING_GRASP,
-        "shocking grasp",
-        30,
-        15,
-        3,
-        Position::Fighting,
-        TAR_CHAR_ROOM | TAR_FIGHT_VICT,
-        true,
-        MAG_DAMAGE,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_SLEEP,
-        "sleep",
-        40,
-        25,
-        5,
-        Position::Standing,
-        TAR_CHAR_ROOM,
-        true,
-        MAG_AFFECTS,
-        "You feel less tired.",
-    );
-
-    spello(
-        db,
-        SPELL_STRENGTH,
-        "strength",
-        35,
-        30,
-        1,
-        Position::Standing,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_AFFECTS,
-        "You feel weaker.",
-    );
-
-    spello(
-        db,
-        SPELL_SUMMON,
-        "summon",
-        75,
-        50,
-        3,
-        Position::Standing,
-        TAR_CHAR_WORLD | TAR_NOT_SELF,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_TELEPORT,
-        "teleport",
-        75,
-        50,
-        3,
-        Position::Standing,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_WATERWALK,
-        "waterwalk",
-        40,
-        20,
-        2,
-        Position::Standing,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_AFFECTS,
-        "Your feet seem less buoyant.",
-    );
-
-    spello(
-        db,
-        SPELL_WORD_OF_RECALL,
-        "word of recall",
-        20,
-        10,
-        2,
-        Position::Fighting,
-        TAR_CHAR_ROOM,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    /* NON-castable spells should appear below here. */
-
-    spello(
-        db,
-        SPELL_IDENTIFY,
-        "identify",
-        0,
-        0,
-        0,
-        Position::Dead,
-        TAR_CHAR_ROOM | TAR_OBJ_INV | TAR_OBJ_ROOM,
-        false,
-        MAG_MANUAL,
-        "",
-    );
-
-    /*
-     * These spells are currently not used, not implemented, and not castable.
-     * Values for the 'breath' spells are filled in assuming a dragon's breath.
-     */
-
-    spello(
-        db,
-        SPELL_FIRE_BREATH,
-        "fire breath",
-        0,
-        0,
-        0,
-        Position::Sitting,
-        TAR_IGNORE,
-        true,
-        0,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_GAS_BREATH,
-        "gas breath",
-        0,
-        0,
-        0,
-        Position::Sitting,
-        TAR_IGNORE,
-        true,
-        0,
-        "",
-    );
-
-    spello(
-        db,
-        SPELL_FROST_BREATH,
-        "frost breath",
-        0,
-        0,
-        0,
-        Position::Sitting,
-        TAR_IGNORE,
-        true,
-        0,
-        "",
-    );
+/* Replace the spell table with the spells read, if they were all good */
+pub(crate) fn assign_spells(db: &mut DB, reader: impl BufRead) -> bool {
+    let spells = match parse_spells(reader) {
+        Ok(spells) => spells,
+        Err(errors) => {
+            error!(
+                "SYSERR: {} error(s) in spell file {}, spells left unchanged",
+                errors, SPELL_FILE
+            );
+            return false;
+        }
+    };
+    db.spell_info = std::array::from_fn(|_| SpellInfoType::default());
+    info!("   {} spells and skills.", spells.len());
+    for (num, sinfo) in spells {
+        db.spell_info[num] = sinfo;
+    }
+    true
+}
 
-    spello(
-        db,
-        SPELL_ACID_BREATH,
-        "acid breath",
-        0,
-        0,
-        0,
-        Position::Sitting,
-        TAR_IGNORE,
-        true,
-        0,
-        "",
-    );
+#[cfg(test)]
+mod spell_parser_tests {
+    use super::*;
+    use crate::spells::{SKILL_BACKSTAB, SPELL_FIREBALL};
+    use crate::structs::Class;
+
+    #[test]
+    fn test_spell_records_parse() {
+        let text = "\
+* A comment.
+#32
+Name: magic missile
+Mana: 25 10 3
+Position: fighting
+Targets: char_room fight_vict
+Violent: yes
+Routines: damage
+Damage: 1d6+1
+Damage: Magic User 1d8+1
+Level: Magic User 1
+End
+#23
+Name: earthquake
+Routines: areas
+Damage: 2d8+level
+Level: Cl 12
+End
+$
+";
+        let spells = parse_spells(text.as_bytes()).unwrap();
+        assert_eq!(spells.len(), 2);
+        let (num, mm) = &spells[0];
+        assert_eq!(*num, 32);
+        assert_eq!(&*mm.name, "magic missile");
+        assert_eq!((mm.mana_max, mm.mana_min, mm.mana_change), (25, 10, 3));
+        assert_eq!(mm.min_position, Position::Fighting);
+        assert_eq!(mm.targets, TAR_CHAR_ROOM | TAR_FIGHT_VICT);
+        assert!(mm.violent);
+        assert_eq!(mm.routines, MAG_DAMAGE);
+        assert_eq!(mm.wear_off_msg, None);
+        assert_eq!(
+            mm.damage,
+            SpellDice {
+                number: 1,
+                size: 6,
+                bonus: 1,
+                per_level: false
+            }
+        );
+        assert_eq!(mm.class_damage[Class::MagicUser as usize].unwrap().size, 8);
+        assert_eq!(mm.class_damage[Class::Cleric as usize], None);
+        assert_eq!(mm.min_level[Class::MagicUser as usize], 1);
+        assert_eq!(mm.min_level[Class::Cleric as usize], LVL_IMMORT as i32);
+        let (_, eq) = &spells[1];
+        assert!(eq.damage.per_level);
+        assert_eq!(eq.min_level[Class::Cleric as usize], 12);
+    }
 
-    spello(
-        db,
-        SPELL_LIGHTNING_BREATH,
-        "lightning breath",
-        0,
-        0,
-        0,
-        Position::Sitting,
-        TAR_IGNORE,
-        true,
-        0,
-        "",
-    );
+    #[test]
+    fn test_bad_spell_records_fail_the_file() {
+        let text = "\
+#1
+Name: armor
+Position: upside down
+End
+#2
+Name: teleport
+Level: Bard 3
+End
+#3
+Name: bless
+Damage: lots
+End
+#400
+Name: too high
+End
+#5
+Mana: 30 10 3
+End
+#6
+Name: call lightning
+End
+$
+";
+        /* every bad record is counted, not just the first one */
+        assert_eq!(parse_spells(text.as_bytes()).err(), Some(5));
+        let good = "#6\nName: call lightning\nEnd\n$\n";
+        assert_eq!(parse_spells(good.as_bytes()).unwrap().len(), 1);
+    }
 
-    /*
-     * Declaration of skills - this actually doesn't do anything except
-     * set it up so that immortals can use these skills by default.  The
-     * min level to use the skill for other classes is set up in class.c.
-     */
+    #[test]
+    fn test_reload_keeps_spells_on_error() {
+        let mut texts = Depot::default();
+        let mut db = DB::new(&mut texts);
+        let good = "#6\nName: call lightning\nLevel: Cl 15\nEnd\n$\n";
+        assert!(assign_spells(&mut db, good.as_bytes()));
+        assert_eq!(&*db.spell_info[6].name, "call lightning");
+
+        /* one good record and one bad one: nothing is taken */
+        let bad = "#6\nName: lightning\nEnd\n#7\nName: chill\nMana: 1 2\nEnd\n$\n";
+        assert!(!assign_spells(&mut db, bad.as_bytes()));
+        assert_eq!(&*db.spell_info[6].name, "call lightning");
+        assert_eq!(db.spell_info[6].min_level[Class::Cleric as usize], 15);
+    }
 
-    skillo(db, SKILL_BACKSTAB, "backstab");
-    skillo(db, SKILL_BASH, "bash");
-    skillo(db, SKILL_HIDE, "hide");
-    skillo(db, SKILL_KICK, "kick");
-    skillo(db, SKILL_PICK_LOCK, "pick lock");
-    skillo(db, SKILL_RESCUE, "rescue");
-    skillo(db, SKILL_SNEAK, "sneak");
-    skillo(db, SKILL_STEAL, "steal");
-    skillo(db, SKILL_TRACK, "track");
+    #[test]
+    fn test_shipped_spell_file_parses() {
+        let spells = parse_spells(include_str!("../lib/misc/spells").as_bytes()).unwrap();
+        assert_eq!(spells.len(), 69);
+        let fireball = &spells
+            .iter()
+            .find(|(num, _)| *num == SPELL_FIREBALL as usize)
+            .unwrap()
+            .1;
+        assert_eq!(&*fireball.name, "fireball");
+        assert_eq!(fireball.mana_max, 40);
+        assert_eq!(fireball.min_level[Class::MagicUser as usize], 15);
+        let backstab = &spells
+            .iter()
+            .find(|(num, _)| *num == SKILL_BACKSTAB as usize)
+            .unwrap()
+            .1;
+        assert_eq!(backstab.min_level[Class::Thief as usize], 3);
+    }
 }
@@ -11,6 +11,7 @@
 use crate::depot::{Depot, DepotId};
 use crate::{act, send_to_char, CharData, ObjData, TextData, VictimRef, DB};
 use std::cmp::{max, min};
+use std::rc::Rc;
 
 use crate::act_informative::look_at_room;
 use crate::act_item::{name_from_drinkcon, name_to_drinkcon, weight_change_object};
@@ -26,8 +27,8 @@ use crate::structs::{
     MOB_NOSUMMON, MOB_SPEC, NOWHERE, NUM_CLASSES, PLR_KILLER,
 };
 use crate::util::{
-    add_follower, age, circle_follow, pers, rand_number, sprintbit, sprinttype, stop_follower,
-    DisplayMode,
+    add_follower, age, circle_follow, dice, pers, rand_number, sprintbit, sprinttype,
+    stop_follower, DisplayMode,
 };
 use crate::{Game, TO_CHAR, TO_ROOM, TO_VICT};
 
@@ -52,6 +53,7 @@ pub const MAG_AREAS: i32 = 1 << 7;
 pub const MAG_SUMMONS: i32 = 1 << 8;
 pub const MAG_CREATIONS: i32 = 1 << 9;
 pub const MAG_MANUAL: i32 = 1 << 10;
+pub const MAG_ROOMS: i32 = 1 << 11;
 //
 //
 pub const TYPE_UNDEFINED: i32 = -1;
@@ -63,12 +65,12 @@ pub const SPELL_TELEPORT: i32 = 2; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_BLESS: i32 = 3; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_BLINDNESS: i32 = 4; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_BURNING_HANDS: i32 = 5; /* Reserved Skill[] DO NOT CHANGE */
-pub const SPELL_CALL_LIGHTNING: i32 = 6; /* Reserved Skill[] DO NOT CHANGE */
+// pub const SPELL_CALL_LIGHTNING: i32 = 6; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_CHARM: i32 = 7; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_CHILL_TOUCH: i32 = 8; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_CLONE: i32 = 9; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_COLOR_SPRAY: i32 = 10; /* Reserved Skill[] DO NOT CHANGE */
-pub const SPELL_CONTROL_WEATHER: i32 = 11; /* Reserved Skill[] DO NOT CHANGE */
+// pub const SPELL_CONTROL_WEATHER: i32 = 11; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_CREATE_FOOD: i32 = 12; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_CREATE_WATER: i32 = 13; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_CURE_BLIND: i32 = 14; /* Reserved Skill[] DO NOT CHANGE */
@@ -109,6 +111,11 @@ pub const SPELL_GROUP_HEAL: i32 = 48; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_GROUP_RECALL: i32 = 49; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_INFRAVISION: i32 = 50; /* Reserved Skill[] DO NOT CHANGE */
 pub const SPELL_WATERWALK: i32 = 51; /* Reserved Skill[] DO NOT CHANGE */
+pub const SPELL_DARKNESS: i32 = 52;
+pub const SPELL_FOG: i32 = 53;
+pub const SPELL_SILENCE: i32 = 54;
+pub const SPELL_WALL_OF_FORCE: i32 = 55;
+pub const SPELL_CONSECRATE: i32 = 56;
 /* Insert new spells here, up to MAX_SPELLS */
 pub const MAX_SPELLS: i32 = 130;
 
@@ -134,11 +141,11 @@ pub const SKILL_TRACK: i32 = 140; /* Reserved Skill[] DO NOT CHANGE */
  */
 
 pub const SPELL_IDENTIFY: i32 = 201;
-pub const SPELL_FIRE_BREATH: i32 = 202;
-pub const SPELL_GAS_BREATH: i32 = 203;
-pub const SPELL_FROST_BREATH: i32 = 204;
-pub const SPELL_ACID_BREATH: i32 = 205;
-pub const SPELL_LIGHTNING_BREATH: i32 = 206;
+// pub const SPELL_FIRE_BREATH: i32 = 202;
+// pub const SPELL_GAS_BREATH: i32 = 203;
+// pub const SPELL_FROST_BREATH: i32 = 204;
+// pub const SPELL_ACID_BREATH: i32 = 205;
+// pub const SPELL_LIGHTNING_BREATH: i32 = 206;
 
 pub const TOP_SPELL_DEFINE: usize = 299;
 // /* NEW NPC/OBJECT SPELLS can be inserted here up to 299 */
@@ -180,7 +187,24 @@ pub const TAR_OBJ_INV: i32 = 1 << 7;
 pub const TAR_OBJ_ROOM: i32 = 1 << 8;
 pub const TAR_OBJ_WORLD: i32 = 1 << 9;
 pub const TAR_OBJ_EQUIP: i32 = 1 << 10;
+pub const TAR_DIR: i32 = 1 << 11; /* An exit of the caster's room */
 
+/* Damage dice for mag_damage(): NdS + bonus, plus the caster's level. */
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct SpellDice {
+    pub number: i32,
+    pub size: i32,
+    pub bonus: i32,
+    pub per_level: bool,
+}
+
+impl SpellDice {
+    pub fn roll(&self, level: u8) -> i32 {
+        dice(self.number, self.size) + self.bonus + if self.per_level { level as i32 } else { 0 }
+    }
+}
+
+#[derive(Clone)]
 pub struct SpellInfoType {
     pub min_position: Position,
     /* Position for caster	 */
@@ -195,10 +219,11 @@ pub struct SpellInfoType {
     pub violent: bool,
     pub targets: i32,
     /* See below for use with TAR_XXX  */
-    pub name: &'static str,
-    /* Input size not limited. Originates from string constants. */
-    pub wear_off_msg: Option<&'static str>,
-    /* Input size not limited. Originates from string constants. */
+    pub name: Rc<str>,
+    pub wear_off_msg: Option<Rc<str>>,
+    pub damage: SpellDice,
+    /* Damage for players of one class, overriding the above */
+    pub class_damage: [Option<SpellDice>; NUM_CLASSES],
 }
 
 impl Default for SpellInfoType {
@@ -212,20 +237,14 @@ impl Default for SpellInfoType {
             routines: 0,
             violent: false,
             targets: 0,
-            name: UNUSED_SPELLNAME,
+            name: Rc::from(UNUSED_SPELLNAME),
             wear_off_msg: None,
+            damage: SpellDice::default(),
+            class_damage: [None; NUM_CLASSES],
         }
     }
 }
 
-impl Copy for SpellInfoType {}
-
-impl Clone for SpellInfoType {
-    fn clone(&self) -> Self {
-        *self
-    }
-}
-
 // /* Possible Targets:
 //
 //    bit 0 : IGNORE TARGET
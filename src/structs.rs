@@ -13,6 +13,8 @@ use std::rc::Rc;
 
 use crate::depot::{Depot, DepotId, HasId};
 use crate::interpreter::AliasData;
+use crate::quest::{QuestEntry, QuestVnum};
+use crate::screen::{ColorDepth, NUM_MSG_CATEGORIES};
 use crate::{Game, TextData, DB};
 
 pub enum MeRef {
@@ -39,7 +41,9 @@ pub const PASSES_PER_SEC: u128 = 1000000 / OPT_USEC;
 pub const PULSE_ZONE: u128 = 10 * PASSES_PER_SEC;
 pub const PULSE_MOBILE: u128 = 10 * PASSES_PER_SEC;
 pub const PULSE_VIOLENCE: u128 = 2 * PASSES_PER_SEC;
+pub const PULSE_SCRIPT: u128 = 13 * PASSES_PER_SEC;
 pub const PULSE_AUTOSAVE: u128 = 60 * PASSES_PER_SEC;
+pub const PULSE_AUCTION: u128 = 60 * PASSES_PER_SEC;
 pub const PULSE_IDLEPWD: u128 = 15 * PASSES_PER_SEC;
 // pub const PULSE_SANITY: u128 = 30 * PASSES_PER_SEC;
 pub const PULSE_USAGE: u128 = 5 * 60 * PASSES_PER_SEC; /* 5 mins */
@@ -68,6 +72,59 @@ bitflags! {
         const ATRIUM = 1 << 13;     // (R) The door to a house
         // const OLC = 1 << 14;     // (R) Modifyable/!compress
         const BFS_MARK = 1 << 15;   // (R) breath-first srch mrk
+        const CLANHALL = 1 << 16;   // (R) Room is a clan hall
+        const AUCTION = 1 << 17;    // Auction house business is done here
+    }
+}
+
+bitflags! {
+    /// Conditions of an alternate room description: room_data.alt_descriptions
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct DescConds: i32 {
+        const DAY = 1 << 0;         // Sun is up
+        const NIGHT = 1 << 1;       // Sun is down
+        const DAWN = 1 << 2;        // Sun is rising
+        const DUSK = 1 << 3;        // Sun is setting
+        const CLEAR = 1 << 4;       // Cloudless sky
+        const CLOUDY = 1 << 5;      // Cloudy sky
+        const RAIN = 1 << 6;        // Raining, storms included
+        const SNOW = 1 << 7;        // Snowing, blizzards included
+        const STORM = 1 << 8;       // Lightning or blizzard
+        const WINTER = 1 << 9;
+        const SPRING = 1 << 10;
+        const SUMMER = 1 << 11;
+        const AUTUMN = 1 << 12;
+    }
+}
+
+impl DescConds {
+    /* one condition of each group must hold, groups not used always hold */
+    pub const GROUPS: [DescConds; 3] = [
+        DescConds::DAY
+            .union(DescConds::NIGHT)
+            .union(DescConds::DAWN)
+            .union(DescConds::DUSK),
+        DescConds::CLEAR
+            .union(DescConds::CLOUDY)
+            .union(DescConds::RAIN)
+            .union(DescConds::SNOW)
+            .union(DescConds::STORM),
+        DescConds::WINTER
+            .union(DescConds::SPRING)
+            .union(DescConds::SUMMER)
+            .union(DescConds::AUTUMN),
+    ];
+}
+
+bitflags! {
+    /// Room affects: used in room_affected_type.bitvector
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct RoomAffectFlags: i32 {
+        const DARKNESS = 1 << 0;    // No light can pierce it
+        const FOG = 1 << 1;         // Hides what is in the room
+        const SILENCE = 1 << 2;     // No spell can be cast
+        const WALL = 1 << 3;        // An exit is blocked
+        const CONSECRATED = 1 << 4; // Faster regeneration
     }
 }
 
@@ -142,6 +199,38 @@ pub enum SkyCondition {
     Lightning = 3,
 }
 
+/* Zone climates: how a zone's weather behaves over the seasons */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Climate {
+    Temperate = 0,
+    Desert = 1,
+    Arctic = 2,
+    Underground = 3,
+}
+
+impl Climate {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Climate::Temperate),
+            1 => Some(Climate::Desert),
+            2 => Some(Climate::Arctic),
+            3 => Some(Climate::Underground),
+            _ => None,
+        }
+    }
+}
+
+/* Seasons, derived from time_info.month */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Season {
+    Winter = 0,
+    Spring = 1,
+    Summer = 2,
+    Autumn = 3,
+}
+
 /* Rent codes */
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(i32)]
@@ -392,6 +481,10 @@ pub enum ConState {
     /* Delete confirmation 2		*/
     ConDisconnect,
     /* In-game link loss (leave character)	*/
+    ConQlang,
+    /* Language of a new character?		*/
+    ConLanguage,
+    /* Changing language (main menu)	*/
 }
 
 /* Mobile flags: used by char_data.char_specials.act */
@@ -442,12 +535,17 @@ bitflags! {
         const NOGRATZ = 1 << 20;    /* Can't hear grats channel */
         const ROOMFLAGS = 1 << 21;  /* Can see room flags (ROOM_x) */
         const DISPAUTO = 1 << 22;   /* Show prompt HP, MP, MV when < 30%. */
+        const NOCLAN = 1 << 23;     /* Can't hear clan channel */
+        const SCREENREADER = 1 << 24; /* Plain output for screen readers */
+        const PRIVACY = 1 << 25;    /* Friends aren't told when one comes or goes */
     }
 }
 
 /* Variables for the output buffering system */
 // pub const MAX_SOCK_BUF: i32 = 12 * 1024; /* Size of kernel's sock buf   */
 pub const MAX_PROMPT_LENGTH: usize = 96; /* Max length of prompt        */
+pub const MIN_SCREEN_WIDTH: u16 = 40; /* Narrowest screen width set   */
+pub const MAX_SCREEN_WIDTH: u16 = 250; /* Widest screen width set      */
 // pub const GARBAGE_SPACE: i32 = 32; /* Space for **OVERFLOW** etc  */
 // pub const SMALL_BUFSIZE: i32 = 1024; /* Static output buffer size   */
 /* Max amount of output that can be buffered */
@@ -609,6 +707,28 @@ pub struct PlayerSpecialData {
     pub aliases: Vec<AliasData>, /* Character's aliases			*/
     pub last_tell: i64,
     /* idnum of last tell from		*/
+    pub quests: Vec<QuestEntry>,
+    /* Quests under way			*/
+    pub quests_done: Vec<QuestVnum>,
+    /* Quests already completed		*/
+    pub prompt: Rc<str>,
+    /* Prompt template, "" for the default	*/
+    pub screen_width: u16,
+    /* Columns of the screen, 0 for the default */
+    pub color_depth: Option<ColorDepth>,
+    /* Color markup depth, None to detect it */
+    pub color_theme: usize,
+    /* Theme the message colors come from	*/
+    pub colors: [Option<Rc<str>>; NUM_MSG_CATEGORIES],
+    /* Message colors changed from the theme */
+    pub language: Rc<str>,
+    /* Code of the message language, "" for the default */
+    pub channels_off: Vec<Rc<str>>,
+    /* Channels turned off that have no toggle of their own */
+    pub ignored: Vec<i64>,
+    /* idnums of the players not listened to */
+    pub friends: Vec<i64>,
+    /* idnums of the players on the friends list */
     // void *last_olc_targ;		/* olc control				*/
     // int last_olc_mode;		/* olc control				*/
 }
@@ -670,7 +790,8 @@ pub struct PlayerSpecialDataSaved {
     pub(crate) spare5: u8,
     pub spells_to_learn: i32,
     /* How many can you learn yet this level*/
-    pub(crate) spare7: i32,
+    pub quest_points: i32,
+    /* Quest points earned from quest masters */
     pub(crate) spare8: i32,
     pub(crate) spare9: i32,
     pub(crate) spare10: i32,
@@ -909,6 +1030,19 @@ pub struct AffectedType {
     /* Tells which bits to set (AFF_XXX) */
 }
 
+/* An affect on a room, cast by spells such as darkness or silence */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RoomAffectedType {
+    pub _type: i16,
+    /* The type of spell that caused this      */
+    pub duration: i16,
+    /* For how long its effects will last      */
+    pub modifier: i16,
+    /* Blocked exit (WALL) or regen bonus in % */
+    pub bitvector: RoomAffectFlags,
+    /* What it does (RAFF_XXX)                 */
+}
+
 /* ====================== File Element for Objects ======================= */
 /*                 BEWARE: Changing it will ruin rent files		   */
 #[repr(C, packed)]
@@ -1105,10 +1239,14 @@ pub struct RoomData {
     /* Shown when entered                 */
     pub ex_descriptions: Vec<ExtraDescrData>,
     /* for examine/look       */
+    pub alt_descriptions: Vec<AltDescrData>,
+    /* shown instead of description when they match */
     pub dir_option: [Option<RoomDirectionData>; NUM_OF_DIRS],
     /* Directions */
     pub room_flags: RoomFlags,
     /* DEATH,DARK ... etc */
+    pub affected: Vec<RoomAffectedType>,
+    /* Spells cast on the room            */
     pub light: u8,
     /* Number of lightsources in room     */
     pub func: Option<Special>,
@@ -1130,6 +1268,15 @@ pub struct ExtraDescrData {
     /* Next in list                     */
 }
 
+/* Alternate room description, for some time of day, weather or season */
+#[derive(Clone)]
+pub struct AltDescrData {
+    pub conditions: DescConds,
+    /* when to show it                  */
+    pub description: String,
+    /* What to see                      */
+}
+
 pub struct MsgType {
     pub attacker_msg: Rc<str>,
     /* message to attacker */
@@ -1198,6 +1345,7 @@ pub struct ConAppType {
     //pub shock: i16,
 }
 
+#[derive(Clone, Copy)]
 pub struct WeatherData {
     pub pressure: i32,
     /* How is the pressure ( Mb ) */
@@ -1205,8 +1353,6 @@ pub struct WeatherData {
     /* How fast and what way does it change. */
     pub sky: SkyCondition,
     /* How is the sky. */
-    pub sunlight: SunState,
-    /* And how much sun. */
 }
 
 /*
@@ -0,0 +1,334 @@
+/* ************************************************************************
+*   File: tells.rs                                      Part of CircleMUD *
+*  Usage: Ignore lists, and tells kept for players who are not playing    *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+
+use chrono::{TimeZone, Utc};
+use log::info;
+
+use crate::clan::member_name;
+use crate::db::{DB, TELL_FILE};
+use crate::depot::{Depot, DepotId};
+use crate::interpreter::one_argument;
+use crate::screen::{MsgCategory, MARKUP_NRM};
+use crate::sidefile::{open_side_file, read_records, save_side_file, write_record, SideRecord};
+use crate::structs::{CharData, LVL_IMMORT};
+use crate::util::time_now;
+use crate::{send_to_char, Game, ObjData, TextData};
+
+pub const MAX_IGNORED: usize = 20;
+pub const MAX_OFFLINE_TELLS: usize = 10; /* waiting for one player */
+pub const MAX_TELLS_SENT: usize = 20; /* waiting, sent by one player */
+
+/* Why a tell couldn't be kept */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TellRefusal {
+    RecipientFull,
+    SenderFull,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineTell {
+    pub to: i64,
+    pub from: i64,
+    pub when: u64,
+    pub text: Rc<str>,
+}
+
+/*
+ * Whether 'to' doesn't want to hear from 'ch'.  Only players can be
+ * ignored, and never the gods.
+ */
+pub fn is_ignoring(to: &CharData, ch: &CharData) -> bool {
+    !ch.is_npc()
+        && ch.get_level() < LVL_IMMORT
+        && to.player_specials.ignored.contains(&ch.get_idnum())
+}
+
+/* Read the tells waiting for their players at boot time */
+pub fn tells_boot(db: &mut DB) {
+    let Some(reader) = open_side_file(TELL_FILE) else {
+        info!("   Tell file '{}' does not exist.", TELL_FILE);
+        return;
+    };
+    let mut tells = parse_tells(reader);
+    /* the players may have been deleted since */
+    tells.retain(|t| db.get_name_by_id(t.to).is_some());
+    info!("   {} tells waiting.", tells.len());
+    db.offline_tells = tells;
+}
+
+fn parse_tell(rec: &SideRecord) -> Result<OfflineTell, String> {
+    Ok(OfflineTell {
+        to: rec.key.parse().map_err(|_| "bad recipient")?,
+        from: rec.number("From")?,
+        when: rec.number("Time")?,
+        text: Rc::from(rec.get("Text").ok_or("no Text")?),
+    })
+}
+
+/*
+ * The tell file is a side file with one record per tell, keyed by the
+ * idnum of the player it waits for:
+ *
+ *   #<to idnum>
+ *   From: <idnum>
+ *   Time: <time>
+ *   Text: <text>
+ *   End
+ */
+pub(crate) fn parse_tells(reader: impl BufRead) -> Vec<OfflineTell> {
+    read_records(reader, TELL_FILE, parse_tell).0
+}
+
+pub(crate) fn write_tells(w: &mut impl Write, tells: &[OfflineTell]) -> io::Result<()> {
+    for t in tells {
+        write_record(
+            w,
+            t.to,
+            &[
+                ("From", t.from.to_string()),
+                ("Time", t.when.to_string()),
+                ("Text", t.text.to_string()),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/* Save the waiting tells after every change */
+pub fn tells_save(db: &DB) {
+    save_side_file(TELL_FILE, |w| write_tells(w, &db.offline_tells));
+}
+
+/*
+ * Keep a tell for a player who isn't playing, unless too many already wait
+ * for them, or one player has left too many around.
+ */
+pub(crate) fn tell_keep(
+    tells: &mut Vec<OfflineTell>,
+    from: i64,
+    to: i64,
+    text: &str,
+) -> Result<(), TellRefusal> {
+    if tells.iter().filter(|t| t.to == to).count() >= MAX_OFFLINE_TELLS {
+        return Err(TellRefusal::RecipientFull);
+    }
+    if tells.iter().filter(|t| t.from == from).count() >= MAX_TELLS_SENT {
+        return Err(TellRefusal::SenderFull);
+    }
+    tells.push(OfflineTell {
+        to,
+        from,
+        when: time_now(),
+        text: Rc::from(text),
+    });
+    Ok(())
+}
+
+pub fn tell_store(db: &mut DB, from: i64, to: i64, text: &str) -> Result<(), TellRefusal> {
+    tell_keep(&mut db.offline_tells, from, to, text)?;
+    tells_save(db);
+    Ok(())
+}
+
+/* Give a player who just came in the tells sent while away */
+pub fn tells_login(game: &mut Game, chars: &mut Depot<CharData>, db: &mut DB, chid: DepotId) {
+    let ch = chars.get(chid);
+    let idnum = ch.get_idnum();
+    if db.offline_tells.iter().all(|t| t.to != idnum) {
+        return;
+    }
+    let (mine, others) = db.offline_tells.drain(..).partition(|t| t.to == idnum);
+    db.offline_tells = others;
+    tells_save(db);
+
+    let mine: Vec<OfflineTell> = mine
+        .into_iter()
+        .filter(|t| !ch.player_specials.ignored.contains(&t.from))
+        .collect();
+    let Some(last) = mine.last() else {
+        return;
+    };
+    let last_from = last.from;
+    let mut buf = format!("{}While you were away:\r\n", MsgCategory::Tell.tag());
+    for t in &mine {
+        let when = Utc
+            .timestamp_opt(t.when as i64, 0)
+            .single()
+            .map_or_else(String::new, |t| t.format("%b %e %H:%M").to_string());
+        buf.push_str(&format!(
+            "[{}] {} told you, '{}'\r\n",
+            when,
+            member_name(db, t.from),
+            t.text
+        ));
+    }
+    buf.push_str(MARKUP_NRM);
+    send_to_char(&mut game.descriptors, ch, &buf);
+    chars.get_mut(chid).set_last_tell(last_from);
+}
+
+fn ignore_list(game: &mut Game, db: &DB, ch: &CharData) {
+    let ignored = &ch.player_specials.ignored;
+    if ignored.is_empty() {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "You are not ignoring anyone.\r\n",
+        );
+        return;
+    }
+    let names: Vec<String> = ignored.iter().map(|&id| member_name(db, id)).collect();
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!("You are ignoring: {}.\r\n", names.join(", ")),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_ignore(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    _texts: &mut Depot<TextData>,
+    _objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    if ch.is_npc() {
+        return;
+    }
+    let mut arg = String::new();
+    one_argument(argument, &mut arg);
+    if arg.is_empty() {
+        ignore_list(game, db, ch);
+        return;
+    }
+    let idnum = db.get_id_by_name(&arg.to_lowercase());
+    if idnum < 0 {
+        send_to_char(&mut game.descriptors, ch, "There is no such player.\r\n");
+        return;
+    }
+    let name = member_name(db, idnum);
+    if idnum == ch.get_idnum() {
+        send_to_char(&mut game.descriptors, ch, "You can't ignore yourself.\r\n");
+        return;
+    }
+    let ignored = &ch.player_specials.ignored;
+    if ignored.contains(&idnum) {
+        let ch = chars.get_mut(chid);
+        ch.player_specials.ignored.retain(|&id| id != idnum);
+        let ch = chars.get(chid);
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!("You listen to {} again.\r\n", name),
+        );
+        return;
+    }
+    if ignored.len() >= MAX_IGNORED {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            &format!("You can't ignore more than {} players.\r\n", MAX_IGNORED),
+        );
+        return;
+    }
+    /* the gods are heard, whether they are online or not */
+    if db
+        .get_level_by_id(idnum)
+        .is_some_and(|level| level >= LVL_IMMORT)
+    {
+        send_to_char(&mut game.descriptors, ch, "You can't ignore the gods.\r\n");
+        return;
+    }
+    let ch = chars.get_mut(chid);
+    ch.player_specials.ignored.push(idnum);
+    let ch = chars.get(chid);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        &format!("You are now ignoring {}.\r\n", name),
+    );
+}
+
+#[cfg(test)]
+mod tells_tests {
+    use super::*;
+    use crate::db::TestWorld;
+
+    #[test]
+    fn test_tell_caps() {
+        let mut tells = vec![];
+        for i in 0..MAX_OFFLINE_TELLS {
+            assert_eq!(tell_keep(&mut tells, 100 + i as i64, 14, "hi"), Ok(()));
+        }
+        assert_eq!(
+            tell_keep(&mut tells, 12, 14, "hi"),
+            Err(TellRefusal::RecipientFull)
+        );
+        /* one sender can't fill the tell file for everybody */
+        for to in 0..(MAX_TELLS_SENT as i64) {
+            assert_eq!(tell_keep(&mut tells, 12, 200 + to, "hi"), Ok(()));
+        }
+        assert_eq!(
+            tell_keep(&mut tells, 12, 300, "hi"),
+            Err(TellRefusal::SenderFull)
+        );
+        assert_eq!(tell_keep(&mut tells, 13, 300, "hi"), Ok(()));
+    }
+
+    #[test]
+    fn test_tells_wait_for_login() {
+        let mut w = TestWorld::boot("tells");
+        let alice = w.add_player("alice", 1, 3001);
+        tell_store(&mut w.db, 2, 1, "Meet me at the bakery.  ").unwrap();
+        tell_store(&mut w.db, 3, 1, "Buy my junk!").unwrap();
+        tell_store(&mut w.db, 2, 4, "And you too.").unwrap();
+        assert_eq!(
+            parse_tells(open_side_file(TELL_FILE).unwrap()),
+            w.db.offline_tells
+        );
+
+        /* the tells are handed over once, but not from the ignored */
+        w.chars.get_mut(alice).player_specials.ignored = vec![3];
+        tells_login(&mut w.game, &mut w.chars, &mut w.db, alice);
+        assert_eq!(w.chars.get(alice).get_last_tell(), 2);
+        let left = parse_tells(open_side_file(TELL_FILE).unwrap());
+        assert_eq!(left.len(), 1);
+        assert_eq!((left[0].to, &*left[0].text), (4, "And you too."));
+        assert_eq!(left, w.db.offline_tells);
+    }
+
+    #[test]
+    fn test_ignore() {
+        let mut w = TestWorld::boot("ignore");
+        let alice = w.add_player("alice", 1, 3001);
+        w.add_player("bob", 2, 3001);
+        w.add_offline_player("carol", 3, 10);
+        w.add_offline_player("zeus", 4, LVL_IMMORT);
+        w.command(alice, "ignore bob");
+        w.command(alice, "ignore carol");
+        /* the gods can't be ignored, even while they are away */
+        w.command(alice, "ignore zeus");
+        w.command(alice, "ignore alice");
+        assert_eq!(w.chars.get(alice).player_specials.ignored, vec![2, 3]);
+
+        w.command(alice, "ignore bob");
+        assert_eq!(w.chars.get(alice).player_specials.ignored, vec![3]);
+    }
+}
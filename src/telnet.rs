@@ -2,11 +2,11 @@
  * Definitions for the TELNET protocol.
  */
 pub const IAC: u8 = 0xff; /* interpret as command: */
-// pub const DONT: u8 = 254; /* you are not to use option */
-// pub const DO: u8 = 253; /* please, you use option */
+pub const DONT: u8 = 254; /* you are not to use option */
+pub const DO: u8 = 253; /* please, you use option */
 pub const WONT: u8 = 0xfc; /* I won't use option */
 pub const WILL: u8 = 0xfb; /* I will use option */
-// pub const SB: u8 = 250; /* interpret as subnegotiation */
+pub const SB: u8 = 250; /* interpret as subnegotiation */
 // pub const GA: u8 = 249; /* you may reverse the line */
 // pub const EL: u8 = 248; /* erase the current line */
 // pub const EC: u8 = 247; /* erase the current character */
@@ -16,7 +16,7 @@ pub const WILL: u8 = 0xfb; /* I will use option */
 // pub const BREAK: u8 = 243; /* break */
 // pub const DM: u8 = 242; /* data mark--for connect. cleaning */
 // pub const NOP: u8 = 241; /* nop */
-// pub const SE: u8 = 240; /* end sub negotiation */
+pub const SE: u8 = 240; /* end sub negotiation */
 // pub const EOR: u8 = 239; /* end of record (transparent mode) */
 // pub const ABORT: u8 = 238; /* Abort process */
 // pub const SUSP: u8 = 237; /* Suspend process */
@@ -63,7 +63,7 @@ pub const TELOPT_ECHO: u8 = 0x01; /* echo */
 // pub const TELOPT_SUPDUP: u8 = 21; /* supdup protocol */
 // pub const TELOPT_SUPDUPOUTPUT: u8 = 22; /* supdup output */
 // pub const TELOPT_SNDLOC: u8 = 23; /* send location */
-// pub const TELOPT_TTYPE: u8 = 24; /* terminal type */
+pub const TELOPT_TTYPE: u8 = 24; /* terminal type */
 // pub const TELOPT_EOR: u8 = 25; /* end or record */
 // pub const TELOPT_TUID: u8 = 26; /* TACACS user identification */
 // pub const TELOPT_OUTMRK: u8 = 27; /* output marking */
@@ -80,3 +80,106 @@ pub const TELOPT_ECHO: u8 = 0x01; /* echo */
 // pub const TELOPT_ENCRYPT: u8 = 38; /* Encryption option */
 // pub const TELOPT_NEW_ENVIRON: u8 = 39; /* New - Environment variables */
 // pub const TELOPT_EXOPL: u8 = 255; /* extended-options-list */
+/* sub-option qualifiers */
+pub const TELQUAL_IS: u8 = 0; /* option is... */
+pub const TELQUAL_SEND: u8 = 1; /* send option */
+
+const MAX_TELNET_PENDING: usize = 256;
+
+/* What the client told us while negotiating. */
+#[derive(Debug, PartialEq, Eq)]
+pub enum TelnetEvent {
+    WillTtype,
+    Ttype(String),
+}
+
+/*
+ * Take the telnet commands out of what was just read.  A command split
+ * across two reads is kept in pending until the rest of it arrives.
+ */
+pub fn strip_telnet(pending: &mut Vec<u8>, data: &[u8], events: &mut Vec<TelnetEvent>) -> Vec<u8> {
+    let mut buf = std::mem::take(pending);
+    buf.extend_from_slice(data);
+    let mut out = Vec::with_capacity(buf.len());
+    let mut i = 0;
+
+    while i < buf.len() {
+        if buf[i] != IAC {
+            out.push(buf[i]);
+            i += 1;
+            continue;
+        }
+        let Some(&cmd) = buf.get(i + 1) else {
+            break;
+        };
+        match cmd {
+            /* an escaped 255 is not valid text anyway */
+            IAC => i += 2,
+            WILL | WONT | DO | DONT => {
+                let Some(&opt) = buf.get(i + 2) else {
+                    break;
+                };
+                if cmd == WILL && opt == TELOPT_TTYPE {
+                    events.push(TelnetEvent::WillTtype);
+                }
+                i += 3;
+            }
+            SB => {
+                let Some(end) = buf[i..].windows(2).position(|w| w == [IAC, SE]) else {
+                    break;
+                };
+                let sub = &buf[i + 2..i + end];
+                if sub.len() > 1 && sub[0] == TELOPT_TTYPE && sub[1] == TELQUAL_IS {
+                    events.push(TelnetEvent::Ttype(
+                        String::from_utf8_lossy(&sub[2..]).to_string(),
+                    ));
+                }
+                i += end + 2;
+            }
+            _ => i += 2,
+        }
+    }
+    /* a sub-negotiation that never ends is dropped rather than kept forever */
+    if i < buf.len() && buf.len() - i <= MAX_TELNET_PENDING {
+        pending.extend_from_slice(&buf[i..]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod telnet_tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_are_stripped() {
+        let mut pending = vec![];
+        let mut events = vec![];
+        let out = strip_telnet(
+            &mut pending,
+            &[
+                b'h',
+                IAC,
+                DO,
+                TELOPT_ECHO,
+                b'i',
+                IAC,
+                WILL,
+                TELOPT_TTYPE,
+                IAC,
+            ],
+            &mut events,
+        );
+        assert_eq!(out, b"hi");
+        assert_eq!(pending, [IAC]);
+        assert_eq!(events, [TelnetEvent::WillTtype]);
+
+        events.clear();
+        let mut data = vec![SB, TELOPT_TTYPE, TELQUAL_IS];
+        data.extend_from_slice(b"MTTS 13");
+        data.extend_from_slice(&[IAC, SE, b'\n']);
+        let out = strip_telnet(&mut pending, &data, &mut events);
+        assert_eq!(out, b"\n");
+        assert!(pending.is_empty());
+        assert_eq!(events, [TelnetEvent::Ttype("MTTS 13".to_string())]);
+    }
+}
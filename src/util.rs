@@ -29,21 +29,27 @@ use rand::Rng;
 
 use crate::class::CLASS_ABBREVS;
 use crate::constants::STR_APP;
-use crate::db::{DB, LIB_PLRALIAS, LIB_PLROBJS, SUF_ALIAS, SUF_OBJS};
+use crate::db::{
+    DB, LIB_PLRALIAS, LIB_PLRBANK, LIB_PLROBJS, LIB_PLRPREFS, LIB_PLRQUEST, SUF_ALIAS, SUF_BANK,
+    SUF_OBJS, SUF_PREFS, SUF_QUEST,
+};
 use crate::handler::{affect_from_char, affected_by_spell, fname};
 use crate::screen::{C_NRM, KGRN, KNRM, KNUL};
 use crate::spells::SPELL_CHARM;
 use crate::structs::ConState::ConPlaying;
 use crate::structs::{
-    AffectFlags, CharData, Class, ConState, FollowType, ItemType, MobVnum, ObjData, Position,
-    PrefFlags, RoomData, RoomDirectionData, RoomFlags, SectorType, Sex, Special, SunState,
-    LVL_IMMORT, MOB_ISNPC, NOWHERE, PLR_WRITING,
+    AffectFlags, CharData, Class, Climate, ConState, DescConds, FollowType, ItemType, MobVnum,
+    ObjData, Position, PrefFlags, RoomAffectFlags, RoomData, RoomDirectionData, RoomFlags,
+    SectorType, Sex, Special, SunState, LVL_IMMORT, MOB_ISNPC, NOWHERE, PLR_WRITING,
 };
 use crate::structs::{
-    ExitFlags, ExtraFlags, MobRnum, ObjVnum, RoomRnum, RoomVnum, TimeInfoData, WearFlags, NOBODY,
-    NOTHING,
+    ExitFlags, ExtraFlags, MobRnum, ObjVnum, RoomRnum, RoomVnum, Season, SkyCondition,
+    TimeInfoData, WearFlags, ZoneRnum, NOBODY, NOTHING,
+};
+use crate::weather::{season, snows};
+use crate::{
+    _clrlevel, clr, DescriptorData, Game, CCGRN, CCNRM, PAGE_WIDTH, TO_CHAR, TO_NOTVICT, TO_VICT,
 };
-use crate::{_clrlevel, clr, DescriptorData, Game, CCGRN, CCNRM, TO_CHAR, TO_NOTVICT, TO_VICT};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
@@ -61,6 +67,9 @@ pub enum FileType {
     Crash = 0,
     //   Etext = 1,
     Alias = 2,
+    Quest = 3,
+    Bank = 4,
+    Prefs = 5,
 }
 
 /* breadth-first searching : those are used as direction */
@@ -183,6 +192,12 @@ impl CharData {
     pub fn set_last_tell(&mut self, val: i64) {
         self.player_specials.last_tell = val;
     }
+    pub fn get_screen_width(&self) -> i32 {
+        match self.player_specials.screen_width {
+            0 => PAGE_WIDTH,
+            width => width as i32,
+        }
+    }
     pub fn get_invis_lev(&self) -> i16 {
         check_player_special!(self, self.player_specials.saved.invis_level)
     }
@@ -455,6 +470,12 @@ impl CharData {
     pub fn incr_practices(&mut self, val: i32) {
         self.player_specials.saved.spells_to_learn += val;
     }
+    pub fn get_quest_points(&self) -> i32 {
+        self.player_specials.saved.quest_points
+    }
+    pub fn set_quest_points(&mut self, val: i32) {
+        self.player_specials.saved.quest_points = val;
+    }
     pub fn get_bad_pws(&self) -> u8 {
         self.player_specials.saved.bad_pws
     }
@@ -908,6 +929,14 @@ impl DB {
         !self.room_flagged(ch.in_room(), RoomFlags::INDOORS)
     }
 
+    /* Outside and with a sky above: caves have no weather. */
+    pub fn under_sky(&self, ch: &CharData) -> bool {
+        self.outside(ch)
+            && self.valid_room_rnum(ch.in_room())
+            && self.zone_table[self.world[ch.in_room() as usize].zone as usize].climate
+                != Climate::Underground
+    }
+
     pub fn can_go(&self, ch: &CharData, door: usize) -> bool {
         self.exit(ch, door).is_some()
             && self.exit(ch, door).as_ref().unwrap().to_room != NOWHERE
@@ -1157,6 +1186,32 @@ impl DB {
     pub fn room_flagged(&self, loc: RoomRnum, flag: RoomFlags) -> bool {
         self.room_flags(loc).intersects(flag)
     }
+    pub fn room_affected(&self, loc: RoomRnum, flag: RoomAffectFlags) -> bool {
+        self.valid_room_rnum(loc)
+            && self.world[loc as usize]
+                .affected
+                .iter()
+                .any(|af| af.bitvector.intersects(flag))
+    }
+    pub fn exit_walled(&self, loc: RoomRnum, door: usize) -> bool {
+        self.valid_room_rnum(loc)
+            && self.world[loc as usize].affected.iter().any(|af| {
+                af.bitvector.intersects(RoomAffectFlags::WALL) && af.modifier as usize == door
+            })
+    }
+    /* extra regeneration in a consecrated room, in percent */
+    pub fn room_regen_bonus(&self, loc: RoomRnum) -> i32 {
+        if !self.valid_room_rnum(loc) {
+            return 0;
+        }
+        self.world[loc as usize]
+            .affected
+            .iter()
+            .filter(|af| af.bitvector.intersects(RoomAffectFlags::CONSECRATED))
+            .map(|af| af.modifier as i32)
+            .max()
+            .unwrap_or(0)
+    }
     pub fn set_room_flags_bit(&mut self, loc: RoomRnum, flags: RoomFlags) {
         self.world[loc as usize].room_flags.insert(flags);
     }
@@ -1771,6 +1826,18 @@ pub fn get_filename(filename: &mut String, mode: FileType, orig_name: &str) -> b
         FileType::Alias => {
             prefix = LIB_PLRALIAS;
             suffix = SUF_ALIAS;
+        }
+        FileType::Quest => {
+            prefix = LIB_PLRQUEST;
+            suffix = SUF_QUEST;
+        }
+        FileType::Bank => {
+            prefix = LIB_PLRBANK;
+            suffix = SUF_BANK;
+        }
+        FileType::Prefs => {
+            prefix = LIB_PLRPREFS;
+            suffix = SUF_PREFS;
         } // FileType::Etext => {
           //     prefix = LIB_PLRTEXT;
           //     suffix = SUF_TEXT;
@@ -1840,6 +1907,7 @@ pub fn num_pc_in_room(room: &RoomData) -> i32 {
  *
  * Inside and City rooms are always lit.
  * Outside rooms are dark at sunset and night.
+ * Magical darkness beats any light.
  */
 impl DB {
     pub fn is_light(&self, room: RoomRnum) -> bool {
@@ -1855,6 +1923,11 @@ impl DB {
             return false;
         }
 
+        /* magical darkness puts out every light */
+        if self.room_affected(room, RoomAffectFlags::DARKNESS) {
+            return true;
+        }
+
         if self.world[room as usize].light != 0 {
             return false;
         }
@@ -1867,12 +1940,71 @@ impl DB {
             return false;
         }
 
-        if self.weather_info.sunlight == SunState::Set
-            || self.weather_info.sunlight == SunState::Dark
-        {
+        if self.sunlight == SunState::Set || self.sunlight == SunState::Dark {
             return true;
         }
 
         false
     }
+
+    /* The time of day, sky and season in a zone, as description conditions. */
+    pub fn desc_conds_now(&self, zone: ZoneRnum) -> DescConds {
+        let mut now = match self.sunlight {
+            SunState::Light => DescConds::DAY,
+            SunState::Dark => DescConds::NIGHT,
+            SunState::Rise => DescConds::DAWN,
+            SunState::Set => DescConds::DUSK,
+        };
+        let season = season(self.time_info.month);
+        let zone = &self.zone_table[zone as usize];
+        if zone.climate != Climate::Underground {
+            let wet = if snows(zone.climate, season) {
+                DescConds::SNOW
+            } else {
+                DescConds::RAIN
+            };
+            now |= match zone.weather.sky {
+                SkyCondition::Cloudless => DescConds::CLEAR,
+                SkyCondition::Cloudy => DescConds::CLOUDY,
+                SkyCondition::Raining => wet,
+                SkyCondition::Lightning => wet | DescConds::STORM,
+            };
+        }
+        now | match season {
+            Season::Winter => DescConds::WINTER,
+            Season::Spring => DescConds::SPRING,
+            Season::Summer => DescConds::SUMMER,
+            Season::Autumn => DescConds::AUTUMN,
+        }
+    }
+
+    /*
+     * The description of a room right now: the alternate description
+     * matching the most kinds of conditions, the first one on a tie, or
+     * the usual description if none matches.
+     */
+    pub fn room_description(&self, room: RoomRnum) -> &str {
+        let rm = &self.world[room as usize];
+        if rm.alt_descriptions.is_empty() {
+            return &rm.description;
+        }
+        let now = self.desc_conds_now(rm.zone);
+        let mut best: Option<(usize, &str)> = None;
+        for alt in &rm.alt_descriptions {
+            let mut score = 0;
+            let mut matches = true;
+            for group in DescConds::GROUPS {
+                let wanted = alt.conditions & group;
+                if wanted.is_empty() {
+                    continue;
+                }
+                matches &= wanted.intersects(now);
+                score += 1;
+            }
+            if matches && best.is_none_or(|(best, _)| score > best) {
+                best = Some((score, &alt.description));
+            }
+        }
+        best.map_or(&rm.description, |(_, desc)| desc)
+    }
 }
@@ -10,7 +10,7 @@
 ************************************************************************ */
 
 use crate::depot::Depot;
-use crate::structs::{SkyCondition, SunState};
+use crate::structs::{Climate, Season, SkyCondition, SunState, WeatherData, ZoneRnum};
 use crate::util::dice;
 use crate::{CharData, Game, DB};
 use std::cmp::{max, min};
@@ -29,19 +29,19 @@ impl Game {
         if mode != 0 {
             match db.time_info.hours {
                 5 => {
-                    db.weather_info.sunlight = SunState::Rise;
+                    db.sunlight = SunState::Rise;
                     self.send_to_outdoor(chars, db, "The sun rises in the east.\r\n");
                 }
                 6 => {
-                    db.weather_info.sunlight = SunState::Light;
+                    db.sunlight = SunState::Light;
                     self.send_to_outdoor(chars, db, "The day has begun.\r\n");
                 }
                 21 => {
-                    db.weather_info.sunlight = SunState::Set;
+                    db.sunlight = SunState::Set;
                     self.send_to_outdoor(chars, db, "The sun slowly disappears in the west.\r\n");
                 }
                 22 => {
-                    db.weather_info.sunlight = SunState::Dark;
+                    db.sunlight = SunState::Dark;
                     self.send_to_outdoor(chars, db, "The night has begun.\r\n");
                 }
                 _ => {}
@@ -66,106 +66,205 @@ impl Game {
     }
 
     fn weather_change(&mut self, chars: &Depot<CharData>, db: &mut DB) {
-        let diff = if (db.time_info.month >= 9) && (db.time_info.month <= 16) {
-            if db.weather_info.pressure > 985 {
-                -2
-            } else {
-                2
+        let season = season(db.time_info.month);
+
+        for zone in 0..db.zone_table.len() {
+            let climate = db.zone_table[zone].climate;
+            if climate == Climate::Underground {
+                continue;
+            }
+            if let Some(messg) =
+                change_zone_weather(&mut db.zone_table[zone].weather, climate, season)
+            {
+                self.send_to_zone_outdoor(chars, db, zone as ZoneRnum, messg);
             }
-        } else if db.weather_info.pressure > 1015 {
-            -2
-        } else {
-            2
-        };
+        }
+    }
+}
 
-        db.weather_info.change += dice(1, 4) * diff + dice(2, 6) - dice(2, 6);
+/* Months 0 to 16, see MONTH_NAME; the year starts and ends in winter. */
+pub fn season(month: i32) -> Season {
+    match month {
+        4..=8 => Season::Spring,
+        9..=11 => Season::Summer,
+        12..=14 => Season::Autumn,
+        _ => Season::Winter,
+    }
+}
 
-        db.weather_info.change = min(db.weather_info.change, 12);
-        db.weather_info.change = max(db.weather_info.change, -12);
+/* The pressure a zone's weather drifts toward. */
+fn pressure_target(climate: Climate, season: Season) -> i32 {
+    let base = match climate {
+        Climate::Desert => 1025,
+        Climate::Arctic => 995,
+        Climate::Temperate | Climate::Underground => 1005,
+    };
+    base + match season {
+        Season::Winter => -15,
+        Season::Spring => 0,
+        Season::Summer => 10,
+        Season::Autumn => -10,
+    }
+}
 
-        db.weather_info.pressure += db.weather_info.change;
+/* Does rain fall as snow here? */
+pub fn snows(climate: Climate, season: Season) -> bool {
+    match climate {
+        Climate::Arctic => true,
+        Climate::Temperate => season == Season::Winter,
+        Climate::Desert | Climate::Underground => false,
+    }
+}
+
+/* The weather a zone starts with at boot. */
+pub fn init_weather(climate: Climate, month: i32) -> WeatherData {
+    let mut weather = WeatherData {
+        pressure: 960,
+        change: 0,
+        sky: SkyCondition::Cloudless,
+    };
+    if climate == Climate::Underground {
+        return weather;
+    }
 
-        db.weather_info.pressure = min(db.weather_info.pressure, 1040);
-        db.weather_info.pressure = max(db.weather_info.pressure, 960);
+    weather.pressure = pressure_target(climate, season(month)) - 45 + dice(1, 80);
+    weather.pressure = weather.pressure.clamp(960, 1040);
+    weather.sky = match weather.pressure {
+        ..=980 => SkyCondition::Lightning,
+        981..=1000 => SkyCondition::Raining,
+        1001..=1020 => SkyCondition::Cloudy,
+        _ => SkyCondition::Cloudless,
+    };
+    weather
+}
 
-        let mut change = 0;
+/*
+ * Move the weather of one zone along and tell what the people outside
+ * see, if anything changed.
+ */
+fn change_zone_weather(
+    weather: &mut WeatherData,
+    climate: Climate,
+    season: Season,
+) -> Option<&'static str> {
+    let diff = if weather.pressure > pressure_target(climate, season) {
+        -2
+    } else {
+        2
+    };
 
-        #[allow(clippy::if_same_then_else)]
-        match db.weather_info.sky {
-            SkyCondition::Cloudless => {
-                if db.weather_info.pressure < 990 {
-                    change = 1;
-                } else if db.weather_info.pressure < 1010 && dice(1, 4) == 1 {
-                    change = 1;
-                }
+    weather.change += dice(1, 4) * diff + dice(2, 6) - dice(2, 6);
+
+    weather.change = min(weather.change, 12);
+    weather.change = max(weather.change, -12);
+
+    weather.pressure += weather.change;
+
+    weather.pressure = min(weather.pressure, 1040);
+    weather.pressure = max(weather.pressure, 960);
+
+    let mut change = 0;
+
+    #[allow(clippy::if_same_then_else)]
+    match weather.sky {
+        SkyCondition::Cloudless => {
+            if weather.pressure < 990 {
+                change = 1;
+            } else if weather.pressure < 1010 && dice(1, 4) == 1 {
+                change = 1;
             }
-            SkyCondition::Cloudy => {
-                if db.weather_info.pressure < 970 {
+        }
+        SkyCondition::Cloudy => {
+            if weather.pressure < 970 {
+                change = 2;
+            } else if weather.pressure < 990 {
+                if dice(1, 4) == 1 {
                     change = 2;
-                } else if db.weather_info.pressure < 990 {
-                    if dice(1, 4) == 1 {
-                        change = 2;
-                    } else {
-                        change = 0;
-                    }
-                } else if db.weather_info.pressure > 1030 && dice(1, 4) == 1 {
-                    change = 3;
+                } else {
+                    change = 0;
                 }
+            } else if weather.pressure > 1030 && dice(1, 4) == 1 {
+                change = 3;
             }
-            SkyCondition::Raining => {
-                if db.weather_info.pressure < 970 {
-                    if dice(1, 4) == 1 {
-                        change = 4;
-                    } else {
-                        change = 0;
-                    }
-                } else if db.weather_info.pressure > 1030 {
-                    change = 5;
-                } else if db.weather_info.pressure > 1010 && dice(1, 4) == 1 {
-                    change = 5;
-                }
-            }
-            SkyCondition::Lightning => {
-                if db.weather_info.pressure > 1010 {
-                    change = 6;
-                } else if db.weather_info.pressure > 990 && dice(1, 4) == 1 {
-                    change = 6;
+        }
+        SkyCondition::Raining => {
+            if weather.pressure < 970 {
+                if dice(1, 4) == 1 {
+                    change = 4;
+                } else {
+                    change = 0;
                 }
+            } else if weather.pressure > 1030 {
+                change = 5;
+            } else if weather.pressure > 1010 && dice(1, 4) == 1 {
+                change = 5;
             }
         }
-
-        match change {
-            0 => {}
-            1 => {
-                self.send_to_outdoor(chars, db, "The sky starts to get cloudy.\r\n");
-                db.weather_info.sky = SkyCondition::Cloudy;
+        SkyCondition::Lightning => {
+            if weather.pressure > 1010 {
+                change = 6;
+            } else if weather.pressure > 990 && dice(1, 4) == 1 {
+                change = 6;
             }
+        }
+    }
 
-            2 => {
-                self.send_to_outdoor(chars, db, "It starts to rain.\r\n");
-                db.weather_info.sky = SkyCondition::Raining;
-            }
+    let snow = snows(climate, season);
+    let (sky, messg) = match change {
+        1 => (SkyCondition::Cloudy, "The sky starts to get cloudy.\r\n"),
+        2 if snow => (SkyCondition::Raining, "It starts to snow.\r\n"),
+        2 => (SkyCondition::Raining, "It starts to rain.\r\n"),
+        3 => (SkyCondition::Cloudless, "The clouds disappear.\r\n"),
+        4 if snow => (
+            SkyCondition::Lightning,
+            "A blizzard starts to howl around you.\r\n",
+        ),
+        4 => (
+            SkyCondition::Lightning,
+            "Lightning starts to show in the sky.\r\n",
+        ),
+        5 if snow => (SkyCondition::Cloudy, "The snow stops.\r\n"),
+        5 => (SkyCondition::Cloudy, "The rain stops.\r\n"),
+        6 if snow => (SkyCondition::Raining, "The blizzard dies down.\r\n"),
+        6 => (SkyCondition::Raining, "The lightning stops.\r\n"),
+        _ => return None,
+    };
+    weather.sky = sky;
+    Some(messg)
+}
 
-            3 => {
-                self.send_to_outdoor(chars, db, "The clouds disappear.\r\n");
-                db.weather_info.sky = SkyCondition::Cloudless;
-            }
+#[cfg(test)]
+mod weather_tests {
+    use super::*;
 
-            4 => {
-                self.send_to_outdoor(chars, db, "Lightning starts to show in the sky.\r\n");
-                db.weather_info.sky = SkyCondition::Lightning;
-            }
+    #[test]
+    fn test_seasons_follow_the_months() {
+        assert_eq!(season(0), Season::Winter);
+        assert_eq!(season(5), Season::Spring);
+        assert_eq!(season(10), Season::Summer);
+        assert_eq!(season(13), Season::Autumn);
+        assert_eq!(season(16), Season::Winter);
+    }
 
-            5 => {
-                self.send_to_outdoor(chars, db, "The rain stops.\r\n");
-                db.weather_info.sky = SkyCondition::Cloudy;
-            }
+    #[test]
+    fn test_snow_depends_on_climate_and_season() {
+        assert!(snows(Climate::Arctic, Season::Summer));
+        assert!(snows(Climate::Temperate, Season::Winter));
+        assert!(!snows(Climate::Temperate, Season::Spring));
+        assert!(!snows(Climate::Desert, Season::Winter));
+        assert!(!snows(Climate::Underground, Season::Winter));
+    }
 
-            6 => {
-                self.send_to_outdoor(chars, db, "The lightning stops.\r\n");
-                db.weather_info.sky = SkyCondition::Raining;
+    #[test]
+    fn test_pressure_stays_in_bounds() {
+        for _ in 0..50 {
+            let mut weather = init_weather(Climate::Desert, 10);
+            for _ in 0..200 {
+                change_zone_weather(&mut weather, Climate::Desert, Season::Summer);
+                assert!((960..=1040).contains(&weather.pressure));
             }
-            _ => {}
         }
+        let cave = init_weather(Climate::Underground, 0);
+        assert_eq!(cave.sky, SkyCondition::Cloudless);
     }
 }
@@ -0,0 +1,915 @@
+/* ************************************************************************
+*   File: worldcheck.rs                                 Part of CircleMUD *
+*  Usage: World consistency checks run by --check                         *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * The loaders in db.rs only refuse files they cannot parse.  Everything
+ * here runs on a world that booted fine and looks for things a builder
+ * most likely got wrong: exits and keys pointing at nothing, rooms no
+ * mortal can walk to, shopkeepers that are never loaded, prototypes
+ * with values the game code cannot make sense of, triggers attached
+ * to nothing and room descriptions the weather never shows.
+ *
+ * Errors are things which are broken in the game; warnings are things
+ * which are legal but usually unintended.  A report with any error makes
+ * --check exit with a failure status, so it can gate world changes.
+ */
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::constants::{CLIMATE_TYPES, DIRS, DRINKS, REV_DIR};
+use crate::db::{ZoneData, DB};
+use crate::fight::ATTACK_HIT_TEXT;
+use crate::scripts::AttachType;
+use crate::spells::{MAX_SPELLS, TOP_SPELL_DEFINE};
+use crate::structs::{
+    Climate, DescConds, ExitFlags, ItemType, ObjData, Position, ZoneVnum, LVL_IMPL, MAX_SKILLS,
+    NOBODY, NOTHING, NOWHERE,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn name(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+pub struct LintIssue {
+    pub severity: Severity,
+    /* short, stable identifier of the check, e.g. "one-way-exit" */
+    pub check: &'static str,
+    pub zone: Option<ZoneVnum>,
+    /* what the issue is about, e.g. "room #3001" */
+    pub what: String,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    fn add(
+        &mut self,
+        severity: Severity,
+        check: &'static str,
+        zone: Option<ZoneVnum>,
+        what: String,
+        message: String,
+    ) {
+        self.issues.push(LintIssue {
+            severity,
+            check,
+            zone,
+            what,
+            message,
+        });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) != 0
+    }
+
+    pub fn write_human(&self, out: &mut dyn Write) -> io::Result<()> {
+        for issue in &self.issues {
+            writeln!(
+                out,
+                "{:<7} [{}] {}: {}",
+                issue.severity.name().to_uppercase(),
+                issue.check,
+                issue.what,
+                issue.message
+            )?;
+        }
+        writeln!(
+            out,
+            "World check: {} error(s), {} warning(s).",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+
+    pub fn write_json(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{{")?;
+        writeln!(out, "  \"errors\": {},", self.count(Severity::Error))?;
+        writeln!(out, "  \"warnings\": {},", self.count(Severity::Warning))?;
+        write!(out, "  \"issues\": [")?;
+        for (n, issue) in self.issues.iter().enumerate() {
+            let zone = issue
+                .zone
+                .map_or_else(|| "null".to_string(), |z| z.to_string());
+            write!(
+                out,
+                "{}\n    {{\"severity\": {}, \"check\": {}, \"zone\": {}, \"what\": {}, \"message\": {}}}",
+                if n == 0 { "" } else { "," },
+                json_string(issue.severity.name()),
+                json_string(issue.check),
+                zone,
+                json_string(&issue.what),
+                json_string(&issue.message)
+            )?;
+        }
+        if !self.issues.is_empty() {
+            writeln!(out)?;
+            write!(out, "  ")?;
+        }
+        writeln!(out, "]")?;
+        writeln!(out, "}}")
+    }
+}
+
+/* Quote and escape a string for JSON output. */
+pub fn json_string(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len() + 2);
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(buf, "\\u{:04x}", c as u32);
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
+
+/* Run every check on a booted world. */
+pub fn lint_world(db: &DB) -> LintReport {
+    let mut report = LintReport::default();
+
+    check_zones(db, &mut report);
+    check_exits(db, &mut report);
+    check_doors(db, &mut report);
+    check_reachability(db, &mut report);
+    check_shops(db, &mut report);
+    check_mobiles(db, &mut report);
+    check_objects(db, &mut report);
+    check_triggers(db, &mut report);
+    check_alt_descriptions(db, &mut report);
+
+    report
+}
+
+fn room_zone(db: &DB, rnum: usize) -> Option<ZoneVnum> {
+    db.zone_table
+        .get(db.world[rnum].zone as usize)
+        .map(|z| z.number)
+}
+
+fn vnum_zone(db: &DB, vnum: i16) -> Option<ZoneVnum> {
+    db.zone_table.get(db.zone_of_vnum(vnum)).map(|z| z.number)
+}
+
+fn valid_room(db: &DB, rnum: i32) -> bool {
+    rnum >= 0 && (rnum as usize) < db.world.len()
+}
+
+/* Zone ranges, and zone commands placing things outside of their zone. */
+fn check_zones(db: &DB, report: &mut LintReport) {
+    /*
+     * Taken by their bottom room, a zone overlaps the one reaching highest
+     * among those before it, whatever their order in the zone table.
+     */
+    let mut by_bot: Vec<&ZoneData> = db.zone_table.iter().collect();
+    by_bot.sort_by_key(|z| z.bot);
+    let mut highest: Option<&ZoneData> = None;
+    for zone in by_bot {
+        if let Some(prev) = highest.filter(|prev| zone.bot <= prev.top) {
+            report.add(
+                Severity::Error,
+                "zone-range",
+                Some(zone.number),
+                format!("zone #{}", zone.number),
+                format!(
+                    "rooms #{}-#{} overlap zone #{} (#{}-#{})",
+                    zone.bot, zone.top, prev.number, prev.bot, prev.top
+                ),
+            );
+        }
+        if highest.is_none_or(|prev| zone.top > prev.top) {
+            highest = Some(zone);
+        }
+    }
+
+    for zone in &db.zone_table {
+        let what = format!("zone #{}", zone.number);
+        if zone.bot > zone.top {
+            report.add(
+                Severity::Error,
+                "zone-range",
+                Some(zone.number),
+                what.clone(),
+                format!("bottom room #{} is above top room #{}", zone.bot, zone.top),
+            );
+        }
+
+        for cmd in &zone.cmd {
+            if cmd.command == 'S' {
+                break;
+            }
+            let room = match cmd.command {
+                'M' | 'O' => cmd.arg3,
+                'D' | 'R' => cmd.arg1,
+                _ => continue,
+            };
+            if !valid_room(db, room) {
+                continue;
+            }
+            let vnum = db.world[room as usize].number;
+            if vnum < zone.bot || vnum > zone.top {
+                report.add(
+                    Severity::Warning,
+                    "zone-range",
+                    Some(zone.number),
+                    what.clone(),
+                    format!(
+                        "'{}' command on line {} uses room #{}, outside of #{}-#{}",
+                        cmd.command, cmd.line, vnum, zone.bot, zone.top
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/* Exits to nonexistent rooms and exits with no way back. */
+fn check_exits(db: &DB, report: &mut LintReport) {
+    for &(rnum, dir, to_vnum) in &db.dangling_exits {
+        let room = &db.world[rnum as usize];
+        report.add(
+            Severity::Error,
+            "exit-target",
+            room_zone(db, rnum as usize),
+            format!("room #{}", room.number),
+            format!("exit {} leads to nonexistent room #{}", DIRS[dir], to_vnum),
+        );
+    }
+
+    for (rnum, room) in db.world.iter().enumerate() {
+        for (dir, exit) in room.dir_option.iter().enumerate() {
+            let Some(exit) = exit else { continue };
+            if !valid_room(db, exit.to_room as i32) || exit.to_room as usize == rnum {
+                continue;
+            }
+            let target = &db.world[exit.to_room as usize];
+            let back = target.dir_option[REV_DIR[dir] as usize]
+                .as_ref()
+                .is_some_and(|back| back.to_room as usize == rnum);
+            if !back {
+                report.add(
+                    Severity::Warning,
+                    "one-way-exit",
+                    room_zone(db, rnum),
+                    format!("room #{}", room.number),
+                    format!(
+                        "exit {} to room #{} has no {} exit back",
+                        DIRS[dir], target.number, DIRS[REV_DIR[dir] as usize]
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/* Keys that do not exist, and doors locked by a zone without any key. */
+fn check_doors(db: &DB, report: &mut LintReport) {
+    for (rnum, room) in db.world.iter().enumerate() {
+        for (dir, exit) in room.dir_option.iter().enumerate() {
+            let Some(exit) = exit else { continue };
+            /* the door can still be picked or bashed, so it isn't an error */
+            if exit.key > 0 && db.real_object(exit.key) == NOTHING {
+                report.add(
+                    Severity::Warning,
+                    "door-key",
+                    room_zone(db, rnum),
+                    format!("room #{}", room.number),
+                    format!(
+                        "exit {} uses key #{}, which does not exist",
+                        DIRS[dir], exit.key
+                    ),
+                );
+            }
+        }
+    }
+
+    for zone in &db.zone_table {
+        for cmd in &zone.cmd {
+            if cmd.command == 'S' {
+                break;
+            }
+            if cmd.command != 'D' || !valid_room(db, cmd.arg1) {
+                continue;
+            }
+            let room = &db.world[cmd.arg1 as usize];
+            let Some(exit) = room
+                .dir_option
+                .get(cmd.arg2 as usize)
+                .and_then(|e| e.as_ref())
+            else {
+                continue;
+            };
+            let what = format!("room #{}", room.number);
+            if !exit.exit_info.contains(ExitFlags::ISDOOR) {
+                report.add(
+                    Severity::Error,
+                    "door-key",
+                    Some(zone.number),
+                    what,
+                    format!(
+                        "zone #{} line {} sets a door on exit {}, which is not a door",
+                        zone.number, cmd.line, DIRS[cmd.arg2 as usize]
+                    ),
+                );
+            } else if cmd.arg3 == 2 && exit.key <= 0 {
+                report.add(
+                    Severity::Warning,
+                    "door-key",
+                    Some(zone.number),
+                    what,
+                    format!(
+                        "door {} is locked by zone #{} line {} but has no key",
+                        DIRS[cmd.arg2 as usize], zone.number, cmd.line
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/* Rooms a mortal cannot walk to from the mortal start room. */
+fn check_reachability(db: &DB, report: &mut LintReport) {
+    if !valid_room(db, db.r_mortal_start_room as i32) {
+        return;
+    }
+    let mut seen = vec![false; db.world.len()];
+    let mut queue = VecDeque::new();
+    seen[db.r_mortal_start_room as usize] = true;
+    queue.push_back(db.r_mortal_start_room as usize);
+    while let Some(rnum) = queue.pop_front() {
+        for exit in db.world[rnum].dir_option.iter().flatten() {
+            if valid_room(db, exit.to_room as i32) && !seen[exit.to_room as usize] {
+                seen[exit.to_room as usize] = true;
+                queue.push_back(exit.to_room as usize);
+            }
+        }
+    }
+
+    for (rnum, room) in db.world.iter().enumerate() {
+        if !seen[rnum] {
+            report.add(
+                Severity::Warning,
+                "unreachable-room",
+                room_zone(db, rnum),
+                format!("room #{}", room.number),
+                format!(
+                    "cannot be reached on foot from the mortal start room #{}",
+                    db.world[db.r_mortal_start_room as usize].number
+                ),
+            );
+        }
+    }
+}
+
+/* Shopkeepers which are missing or never loaded, shop rooms that are gone. */
+fn check_shops(db: &DB, report: &mut LintReport) {
+    for shop in &db.shop_index {
+        let zone = vnum_zone(db, shop.vnum);
+        let what = format!("shop #{}", shop.vnum);
+        if shop.keeper == NOBODY || shop.keeper as usize >= db.mob_index.len() {
+            report.add(
+                Severity::Error,
+                "shop-keeper",
+                zone,
+                what.clone(),
+                "has no existing shopkeeper".to_string(),
+            );
+        } else {
+            let loaded = db.zone_table.iter().any(|z| {
+                z.cmd
+                    .iter()
+                    .take_while(|c| c.command != 'S')
+                    .any(|c| (c.command == 'M' || c.command == 'Z') && c.arg1 == shop.keeper as i32)
+            });
+            if !loaded {
+                report.add(
+                    Severity::Warning,
+                    "shop-keeper",
+                    zone,
+                    what.clone(),
+                    format!(
+                        "shopkeeper mob #{} is never loaded by any zone",
+                        db.mob_index[shop.keeper as usize].vnum
+                    ),
+                );
+            }
+        }
+        for &room in shop.in_room.iter().filter(|&&r| r != NOWHERE) {
+            if db.real_room(room) == NOWHERE {
+                report.add(
+                    Severity::Error,
+                    "shop-room",
+                    zone,
+                    what.clone(),
+                    format!("operates in room #{}, which does not exist", room),
+                );
+            }
+        }
+    }
+}
+
+/* Mobile prototypes whose stats cannot work in the game. */
+fn check_mobiles(db: &DB, report: &mut LintReport) {
+    for (nr, mob) in db.mob_protos.iter().enumerate() {
+        let vnum = db.mob_index[nr].vnum;
+        let mut problems = vec![];
+
+        /* Hit points are rolled from the hit/mana/move fields as xdy+z. */
+        let (hp_dice, hp_size, hp_add) = (mob.get_hit(), mob.get_mana(), mob.get_move());
+        if hp_dice < 0 || hp_size < 0 || hp_dice as i32 * hp_size as i32 + (hp_add as i32) <= 0 {
+            problems.push(format!(
+                "hit points {}d{}+{} can never be positive",
+                hp_dice, hp_size, hp_add
+            ));
+        }
+        if mob.get_alignment() < -1000 || mob.get_alignment() > 1000 {
+            problems.push(format!(
+                "alignment {} is not within -1000-1000",
+                mob.get_alignment()
+            ));
+        }
+        if mob.get_gold() < 0 {
+            problems.push(format!("gold {} is negative", mob.get_gold()));
+        }
+        if mob.get_exp() < 0 {
+            problems.push(format!("experience {} is negative", mob.get_exp()));
+        }
+        if mob.get_default_pos() == Position::Dead {
+            problems.push("default position is dead".to_string());
+        }
+        if mob.mob_specials.attack_type as usize >= ATTACK_HIT_TEXT.len() {
+            problems.push(format!(
+                "attack type {} is not within 0-{}",
+                mob.mob_specials.attack_type,
+                ATTACK_HIT_TEXT.len() - 1
+            ));
+        }
+        let abils = mob.real_abils;
+        for (name, value) in [
+            ("Str", abils.str),
+            ("Int", abils.intel),
+            ("Wis", abils.wis),
+            ("Dex", abils.dex),
+            ("Con", abils.con),
+            ("Cha", abils.cha),
+        ] {
+            if !(3..=25).contains(&value) {
+                problems.push(format!("{} {} is not within 3-25", name, value));
+            }
+        }
+
+        for message in problems {
+            report.add(
+                Severity::Error,
+                "mob-stats",
+                vnum_zone(db, vnum),
+                format!("mob #{} ({})", vnum, mob.player.short_descr),
+                message,
+            );
+        }
+    }
+}
+
+fn spell_in_range(spell: i32) -> bool {
+    /* -1 is no spell, skills cannot be cast from items. */
+    spell == -1
+        || (spell >= 0
+            && spell <= TOP_SPELL_DEFINE as i32
+            && !(spell > MAX_SPELLS && spell <= MAX_SKILLS as i32))
+}
+
+fn object_problems(obj: &ObjData) -> Vec<String> {
+    let mut problems = vec![];
+    let val = |n| obj.get_obj_val(n);
+
+    if obj.get_obj_weight() < 0 {
+        problems.push(format!("weight {} is negative", obj.get_obj_weight()));
+    }
+    if obj.get_obj_cost() < 0 {
+        problems.push(format!("cost {} is negative", obj.get_obj_cost()));
+    }
+    if obj.get_obj_rent() < 0 {
+        problems.push(format!("rent {} is negative", obj.get_obj_rent()));
+    }
+
+    match obj.get_obj_type() {
+        ItemType::Weapon => {
+            if val(1) <= 0 || val(2) <= 0 {
+                problems.push(format!("damage {}d{} is not positive", val(1), val(2)));
+            }
+            if val(3) < 0 || val(3) as usize >= ATTACK_HIT_TEXT.len() {
+                problems.push(format!(
+                    "attack type {} is not within 0-{}",
+                    val(3),
+                    ATTACK_HIT_TEXT.len() - 1
+                ));
+            }
+        }
+        ItemType::Drinkcon | ItemType::Fountain => {
+            if val(1) > val(0) {
+                problems.push(format!("contains {} more than maximum {}", val(1), val(0)));
+            }
+            if val(2) < 0 || val(2) as usize >= DRINKS.len() - 1 {
+                problems.push(format!(
+                    "liquid type {} is not within 0-{}",
+                    val(2),
+                    DRINKS.len() - 2
+                ));
+            }
+        }
+        ItemType::Container if val(0) < 0 => {
+            problems.push(format!("capacity {} is negative", val(0)));
+        }
+        ItemType::Money if val(0) <= 0 => {
+            problems.push(format!("amount {} is not positive", val(0)));
+        }
+        ItemType::Scroll | ItemType::Potion | ItemType::Wand | ItemType::Staff => {
+            if val(0) < 0 || val(0) > LVL_IMPL as i32 {
+                problems.push(format!(
+                    "spell level {} is not within 0-{}",
+                    val(0),
+                    LVL_IMPL
+                ));
+            }
+            let spells: &[usize] = match obj.get_obj_type() {
+                ItemType::Scroll | ItemType::Potion => &[1, 2, 3],
+                _ => &[3],
+            };
+            for &n in spells {
+                if !spell_in_range(val(n)) {
+                    problems.push(format!("spell #{} is out of range", val(n)));
+                }
+            }
+            if matches!(obj.get_obj_type(), ItemType::Wand | ItemType::Staff) && val(2) > val(1) {
+                problems.push(format!(
+                    "has {} charges, more than maximum {}",
+                    val(2),
+                    val(1)
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    problems
+}
+
+/* Object prototypes with values outside of what the game code handles. */
+fn check_objects(db: &DB, report: &mut LintReport) {
+    for (nr, obj) in db.obj_proto.iter().enumerate() {
+        let vnum = db.obj_index[nr].vnum;
+        for message in object_problems(obj) {
+            report.add(
+                Severity::Error,
+                "obj-values",
+                vnum_zone(db, vnum),
+                format!("obj #{} ({})", vnum, obj.short_description),
+                message,
+            );
+        }
+    }
+}
+
+/* Triggers attached to mobs, objects or rooms which do not exist. */
+fn check_triggers(db: &DB, report: &mut LintReport) {
+    for trig in &db.scripts.triggers {
+        for &vnum in &trig.attach {
+            let (exists, kind) = match trig.attach_type {
+                AttachType::Mob => (db.real_mobile(vnum) != NOBODY, "mob"),
+                AttachType::Obj => (db.real_object(vnum) != NOTHING, "obj"),
+                AttachType::Room => (db.real_room(vnum) != NOWHERE, "room"),
+            };
+            if !exists {
+                report.add(
+                    Severity::Error,
+                    "trigger-attach",
+                    vnum_zone(db, trig.vnum),
+                    format!("trigger #{} ({})", trig.vnum, trig.name),
+                    format!("is attached to {} #{}, which does not exist", kind, vnum),
+                );
+            }
+        }
+    }
+}
+
+/* Alternate room descriptions the weather of their zone never shows. */
+fn check_alt_descriptions(db: &DB, report: &mut LintReport) {
+    for (rnum, room) in db.world.iter().enumerate() {
+        let climate = db.zone_table[room.zone as usize].climate;
+        let never = match climate {
+            Climate::Underground => DescConds::GROUPS[1],
+            Climate::Desert => DescConds::SNOW,
+            Climate::Arctic => DescConds::RAIN,
+            Climate::Temperate => DescConds::empty(),
+        };
+        for (nr, alt) in room.alt_descriptions.iter().enumerate() {
+            let sky = alt.conditions & DescConds::GROUPS[1];
+            if !sky.is_empty() && (sky & !never).is_empty() {
+                report.add(
+                    Severity::Warning,
+                    "alt-desc",
+                    room_zone(db, rnum),
+                    format!("room #{}", room.number),
+                    format!(
+                        "alternate description {} needs weather a {} zone never has",
+                        nr + 1,
+                        CLIMATE_TYPES[climate as usize]
+                    ),
+                );
+            } else if room.alt_descriptions[..nr]
+                .iter()
+                .any(|prev| prev.conditions == alt.conditions)
+            {
+                report.add(
+                    Severity::Warning,
+                    "alt-desc",
+                    room_zone(db, rnum),
+                    format!("room #{}", room.number),
+                    format!(
+                        "alternate description {} has the same conditions as an earlier one",
+                        nr + 1
+                    ),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod worldcheck_tests {
+    use super::*;
+    use crate::db::TestWorld;
+
+    /*
+     * A world of a few rooms with one of each mistake, booted in place of
+     * the mini mud.  Zone #10 holds zone #20 and reaches the bottom of
+     * zone #30, which only a check of every zone before #30 can see.
+     */
+    const FIXTURE: &[(&str, &str)] = &[
+        (
+            "world/zon/index.mini",
+            "fix10.zon\nfix20.zon\nfix30.zon\n$\n",
+        ),
+        ("world/zon/fix10.zon", "#10\nOuter~\n1000 3000 10 2\nS\n$\n"),
+        ("world/zon/fix20.zon", "#20\nInner~\n1100 1199 10 2\nS\n$\n"),
+        (
+            "world/zon/fix30.zon",
+            "#30\nFixture~\n3000 3099 10 2\n\
+             M 0 3090 1 3001\n\
+             M 0 3091 1 1150\n\
+             D 0 3004 1 2\n\
+             D 0 3002 2 1\n\
+             S\n$\n",
+        ),
+        ("world/wld/index.mini", "fix.wld\n$\n"),
+        (
+            "world/wld/fix.wld",
+            "#1150\nA closet~\nNo way in.\n~\n0 0 0\nS\n\
+             #3001\nThe start~\nStart here.\n~\n0 0 0\n\
+             D0\n~\n~\n0 -1 3002\nD1\n~\n~\n0 -1 3003\n\
+             D2\n~\n~\n0 -1 3099\nD3\n~\ndoor~\n1 3098 3004\nS\n\
+             #3002\nNorth~\nNorth.\n~\n0 0 0\nD2\n~\n~\n0 -1 3001\nS\n\
+             #3003\nEast~\nNo way back.\n~\n0 0 0\nS\n\
+             #3004\nWest~\nWest.\n~\n0 0 0\nD1\n~\ndoor~\n1 -1 3001\nS\n\
+             #3005\nAlone~\nNo way in.\n~\n0 0 0\nS\n$\n",
+        ),
+        ("world/mob/index.mini", "fix.mob\n$\n"),
+        (
+            "world/mob/fix.mob",
+            "#3090\nkeeper~\nthe keeper~\nThe keeper stands here.\n~\nA keeper.\n~\n\
+             b 0 0 S\n1 20 10 1d1+1 1d4+0\n0 0\n8 8 0\n\
+             #3091\nwreck~\nthe wreck~\nA wreck is here.\n~\nA wreck.\n~\n\
+             b 0 2000 E\n1 20 10 0d0+0 1d4+0\n-5 0\n8 8 0\nBareHandAttack: 99\nE\n\
+             #3092\nclerk~\nthe clerk~\nThe clerk stands here.\n~\nA clerk.\n~\n\
+             b 0 0 S\n1 20 10 1d1+1 1d4+0\n0 0\n8 8 0\n$\n",
+        ),
+        ("world/obj/index.mini", "fix.obj\n$\n"),
+        (
+            "world/obj/fix.obj",
+            "#3090\nstick~\na stick~\nA stick lies here.~\n~\n13 0 a\n0 0 0 0\n1 0 0\n\
+             #3091\nsword~\na blunt sword~\nA sword lies here.~\n~\n5 0 a\n0 0 0 99\n-1 0 0\n\
+             #3092\ncup~\na cup~\nA cup lies here.~\n~\n17 0 a\n5 10 99 0\n1 0 0\n$\n",
+        ),
+        ("world/shp/index.mini", "fix.shp\n$\n"),
+        (
+            "world/shp/fix.shp",
+            "CircleMUD v3.0 Shop File~\n\
+             #3000~\n-1\n1.1\n0.9\n-1\n%s No.~\n%s No.~\n%s No.~\n%s No.~\n%s No.~\n\
+             %s %d coins.~\n%s %d coins.~\n0\n0\n3090\n0\n3001\n-1\n0\n28\n0\n0\n\
+             #3001~\n-1\n1.1\n0.9\n-1\n%s No.~\n%s No.~\n%s No.~\n%s No.~\n%s No.~\n\
+             %s %d coins.~\n%s %d coins.~\n0\n0\n3092\n0\n3077\n-1\n0\n28\n0\n0\n\
+             #3002~\n-1\n1.1\n0.9\n-1\n%s No.~\n%s No.~\n%s No.~\n%s No.~\n%s No.~\n\
+             %s %d coins.~\n%s %d coins.~\n0\n0\n3099\n0\n3001\n-1\n0\n28\n0\n0\n\
+             $~\n",
+        ),
+        ("world/trg/index.mini", "$\n"),
+    ];
+
+    /* What a check finds in the fixture world, one line per issue */
+    fn findings(name: &str, check: fn(&DB, &mut LintReport)) -> Vec<String> {
+        let w = TestWorld::boot_with(name, FIXTURE);
+        let mut report = LintReport::default();
+        check(&w.db, &mut report);
+        report
+            .issues
+            .iter()
+            .map(|i| {
+                format!(
+                    "{} [{}] {}: {}",
+                    i.severity.name(),
+                    i.check,
+                    i.what,
+                    i.message
+                )
+            })
+            .collect()
+    }
+
+    fn sample_report() -> LintReport {
+        let mut report = LintReport::default();
+        report.add(
+            Severity::Warning,
+            "one-way-exit",
+            Some(30),
+            "room #3001".to_string(),
+            "exit north to room #3002 has no south exit back".to_string(),
+        );
+        report.add(
+            Severity::Error,
+            "obj-values",
+            None,
+            "obj #1 (a \"quoted\" thing)".to_string(),
+            "weight -1 is negative".to_string(),
+        );
+        report
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a \"b\"\\c"), "\"a \\\"b\\\"\\\\c\"");
+        assert_eq!(json_string("x\ny\u{1}"), "\"x\\ny\\u0001\"");
+    }
+
+    #[test]
+    fn test_report_counts() {
+        let report = sample_report();
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert_eq!(report.count(Severity::Error), 1);
+        assert!(report.has_errors());
+        assert!(!LintReport::default().has_errors());
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = vec![];
+        sample_report().write_json(&mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+        assert!(json.contains("\"errors\": 1,"));
+        assert!(json.contains("\"warnings\": 1,"));
+        assert!(json.contains("\"check\": \"one-way-exit\", \"zone\": 30"));
+        assert!(json.contains("\"zone\": null, \"what\": \"obj #1 (a \\\"quoted\\\" thing)\""));
+
+        let mut out = vec![];
+        LintReport::default().write_json(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\n  \"errors\": 0,\n  \"warnings\": 0,\n  \"issues\": []\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_human() {
+        let mut out = vec![];
+        sample_report().write_human(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("WARNING [one-way-exit] room #3001: exit north"));
+        assert!(text.contains("ERROR   [obj-values] obj #1"));
+        assert!(text.ends_with("World check: 1 error(s), 1 warning(s).\n"));
+    }
+
+    #[test]
+    fn test_check_zones() {
+        assert_eq!(
+            findings("wc_zones", check_zones),
+            vec![
+                "error [zone-range] zone #20: rooms #1100-#1199 overlap zone #10 (#1000-#3000)",
+                "error [zone-range] zone #30: rooms #3000-#3099 overlap zone #10 (#1000-#3000)",
+                "warning [zone-range] zone #30: 'M' command on line 5 uses room #1150, outside of #3000-#3099",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_exits() {
+        assert_eq!(
+            findings("wc_exits", check_exits),
+            vec![
+                "error [exit-target] room #3001: exit south leads to nonexistent room #3099",
+                "warning [one-way-exit] room #3001: exit east to room #3003 has no west exit back",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_doors() {
+        assert_eq!(
+            findings("wc_doors", check_doors),
+            vec![
+                "warning [door-key] room #3001: exit west uses key #3098, which does not exist",
+                "warning [door-key] room #3004: door east is locked by zone #30 line 6 but has no key",
+                "error [door-key] room #3002: zone #30 line 7 sets a door on exit south, which is not a door",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_reachability() {
+        assert_eq!(
+            findings("wc_reach", check_reachability),
+            vec![
+                "warning [unreachable-room] room #1150: cannot be reached on foot from the mortal start room #3001",
+                "warning [unreachable-room] room #3005: cannot be reached on foot from the mortal start room #3001",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_shops() {
+        assert_eq!(
+            findings("wc_shops", check_shops),
+            vec![
+                "warning [shop-keeper] shop #3001: shopkeeper mob #3092 is never loaded by any zone",
+                "error [shop-room] shop #3001: operates in room #3077, which does not exist",
+                "error [shop-keeper] shop #3002: has no existing shopkeeper",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_mobiles() {
+        assert_eq!(
+            findings("wc_mobs", check_mobiles),
+            vec![
+                "error [mob-stats] mob #3091 (the wreck): hit points 0d0+0 can never be positive",
+                "error [mob-stats] mob #3091 (the wreck): alignment 2000 is not within -1000-1000",
+                "error [mob-stats] mob #3091 (the wreck): gold -5 is negative",
+                "error [mob-stats] mob #3091 (the wreck): attack type 99 is not within 0-14",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_objects() {
+        assert_eq!(
+            findings("wc_objs", check_objects),
+            vec![
+                "error [obj-values] obj #3091 (a blunt sword): weight -1 is negative",
+                "error [obj-values] obj #3091 (a blunt sword): damage 0d0 is not positive",
+                "error [obj-values] obj #3091 (a blunt sword): attack type 99 is not within 0-14",
+                "error [obj-values] obj #3092 (a cup): contains 10 more than maximum 5",
+                "error [obj-values] obj #3092 (a cup): liquid type 99 is not within 0-15",
+            ]
+        );
+    }
+}
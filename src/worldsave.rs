@@ -0,0 +1,892 @@
+/* ************************************************************************
+*   File: worldsave.rs                                  Part of CircleMUD *
+*  Usage: Writing rooms, mobiles, objects, zones and shops back to disk   *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * Every writer in this file produces exactly the text format read by the
+ * matching loader in db.rs / shops.rs, so that a world which has been
+ * booted, saved and booted again ends up with identical prototypes:
+ *
+ *   save_rooms    <-> parse_room
+ *   save_mobiles  <-> parse_mobile
+ *   save_objects  <-> parse_object
+ *   save_zone     <-> load_zones
+ *   save_shops    <-> boot_the_shops
+ *   save_triggers <-> parse_triggers
+ *
+ * Files are split by zone, one <zone vnum>.<ext> file per zone, and an
+ * 'index' file is written for every directory.
+ */
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use log::warn;
+
+use crate::constants::{DESC_CONDS, ITEM_TYPES};
+use crate::db::DB;
+use crate::depot::Depot;
+use crate::scripts::{TrigData, ATTACH_TYPES, TRIG_EVENTS};
+use crate::shops::ShopData;
+use crate::structs::{
+    ApplyType, CharData, Climate, ExitFlags, ObjData, RoomData, RoomFlags, MOB_ISNPC, NOBODY,
+    NOTHING, NOWHERE, NUM_OF_DIRS,
+};
+use crate::TextData;
+
+pub const WLD_SUFFIX: &str = "wld";
+pub const MOB_SUFFIX: &str = "mob";
+pub const OBJ_SUFFIX: &str = "obj";
+pub const ZON_SUFFIX: &str = "zon";
+pub const SHP_SUFFIX: &str = "shp";
+pub const TRG_SUFFIX: &str = "trg";
+
+/* Written at the top of every shop file, boot_the_shops() looks for it. */
+const SHOP_FILE_HEADER: &str = "CircleMUD v3.0 Shop File~";
+
+/*
+ * The reverse of asciiflag_conv(): bits 0-25 become 'a'-'z' and bits
+ * 26-51 become 'A'-'Z'.  An empty bitvector is written as "0".
+ */
+pub fn sprintascii(bits: i64) -> String {
+    let mut out = String::new();
+
+    for i in 0..52 {
+        if bits & (1 << i) != 0 {
+            out.push(if i < 26 {
+                (b'a' + i as u8) as char
+            } else {
+                (b'A' + (i - 26) as u8) as char
+            });
+        }
+    }
+
+    if out.is_empty() {
+        out.push('0');
+    }
+    out
+}
+
+impl DB {
+    /* Which zone a mobile, object or shop vnum is written to. */
+    pub fn zone_of_vnum(&self, vnum: i16) -> usize {
+        if let Some(zone) = self
+            .zone_table
+            .iter()
+            .position(|z| z.bot <= vnum && vnum <= z.top)
+        {
+            return zone;
+        }
+        /* Not inside any zone: file it with the closest zone below it. */
+        self.zone_table
+            .iter()
+            .rposition(|z| z.bot <= vnum)
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn room_vnum(&self, rnum: i32) -> i32 {
+        if rnum < 0 || rnum as usize >= self.world.len() {
+            NOWHERE as i32
+        } else {
+            self.world[rnum as usize].number as i32
+        }
+    }
+
+    pub(crate) fn mob_vnum(&self, rnum: i32) -> i32 {
+        if rnum < 0 || rnum as usize >= self.mob_index.len() {
+            NOBODY as i32
+        } else {
+            self.mob_index[rnum as usize].vnum as i32
+        }
+    }
+
+    pub(crate) fn obj_vnum(&self, rnum: i32) -> i32 {
+        if rnum < 0 || rnum as usize >= self.obj_index.len() {
+            NOTHING as i32
+        } else {
+            self.obj_index[rnum as usize].vnum as i32
+        }
+    }
+}
+
+/* Write a room in the format read by parse_room(). */
+fn save_room(db: &DB, out: &mut impl Write, room: &RoomData) -> io::Result<()> {
+    writeln!(out, "#{}", room.number)?;
+    writeln!(out, "{}~", room.name)?;
+    writeln!(out, "{}~", room.description)?;
+    writeln!(
+        out,
+        "{} {} {}",
+        db.zone_table[room.zone as usize].number,
+        /* clan halls are flagged from the clan file at boot */
+        sprintascii((room.room_flags - RoomFlags::CLANHALL).bits()),
+        room.sector_type as i32
+    )?;
+
+    for dir in 0..NUM_OF_DIRS {
+        let Some(exit) = &room.dir_option[dir] else {
+            continue;
+        };
+        /* CLOSED and LOCKED are reset state, only the door kind is saved. */
+        let door_flag = if !exit.exit_info.contains(ExitFlags::ISDOOR) {
+            0
+        } else if exit.exit_info.contains(ExitFlags::PICKPROOF) {
+            2
+        } else {
+            1
+        };
+        writeln!(out, "D{}", dir)?;
+        writeln!(out, "{}~", exit.general_description)?;
+        writeln!(out, "{}~", exit.keyword)?;
+        writeln!(
+            out,
+            "{} {} {}",
+            door_flag,
+            exit.key,
+            db.room_vnum(exit.to_room as i32)
+        )?;
+    }
+
+    for ex in &room.ex_descriptions {
+        writeln!(out, "E")?;
+        writeln!(out, "{}~", ex.keyword)?;
+        writeln!(out, "{}~", ex.description)?;
+    }
+
+    for alt in &room.alt_descriptions {
+        let conds: Vec<&str> = DESC_CONDS
+            .iter()
+            .enumerate()
+            .filter(|&(bit, _)| alt.conditions.bits() & (1 << bit) != 0)
+            .map(|(_, &name)| name)
+            .collect();
+        writeln!(out, "A")?;
+        writeln!(out, "{}~", conds.join(" "))?;
+        writeln!(out, "{}~", alt.description)?;
+    }
+    writeln!(out, "S")
+}
+
+pub fn save_rooms(db: &DB, out: &mut impl Write, zone: usize) -> io::Result<usize> {
+    let mut count = 0;
+    for room in db.world.iter().filter(|r| r.zone as usize == zone) {
+        save_room(db, out, room)?;
+        count += 1;
+    }
+    writeln!(out, "$~")?;
+    Ok(count)
+}
+
+/* Does this prototype need the E-spec section of an enhanced mobile? */
+fn mob_is_enhanced(mob: &CharData) -> bool {
+    let abils = &mob.real_abils;
+    mob.mob_specials.attack_type != 0
+        || abils.str_add != 0
+        || [
+            abils.str,
+            abils.intel,
+            abils.wis,
+            abils.dex,
+            abils.con,
+            abils.cha,
+        ]
+        .iter()
+        .any(|&a| a != 11)
+}
+
+/* Write a mobile in the format read by parse_mobile(). */
+fn save_mobile(
+    out: &mut impl Write,
+    texts: &Depot<TextData>,
+    vnum: i16,
+    mob: &CharData,
+) -> io::Result<()> {
+    let enhanced = mob_is_enhanced(mob);
+
+    writeln!(out, "#{}", vnum)?;
+    writeln!(out, "{}~", mob.player.name)?;
+    writeln!(out, "{}~", mob.player.short_descr)?;
+    writeln!(out, "{}~", mob.player.long_descr)?;
+    writeln!(out, "{}~", texts.get(mob.player.description).text)?;
+    writeln!(
+        out,
+        "{} {} {} {}",
+        /* MOB_ISNPC is reserved and set again by the loader. */
+        sprintascii(mob.mob_flags() & !MOB_ISNPC),
+        sprintascii(mob.aff_flags().bits()),
+        mob.get_alignment(),
+        if enhanced { 'E' } else { 'S' }
+    )?;
+    writeln!(
+        out,
+        "{} {} {} {}d{}+{} {}d{}+{}",
+        mob.get_level(),
+        20 - mob.get_hitroll() as i32,
+        mob.get_ac() / 10,
+        mob.get_hit(),
+        mob.get_mana(),
+        mob.get_move(),
+        mob.mob_specials.damnodice,
+        mob.mob_specials.damsizedice,
+        mob.get_damroll()
+    )?;
+    writeln!(out, "{} {}", mob.get_gold(), mob.get_exp())?;
+    writeln!(
+        out,
+        "{} {} {}",
+        mob.get_pos() as u8,
+        mob.get_default_pos() as u8,
+        mob.get_sex() as u8
+    )?;
+
+    if enhanced {
+        let abils = &mob.real_abils;
+        if mob.mob_specials.attack_type != 0 {
+            writeln!(out, "BareHandAttack: {}", mob.mob_specials.attack_type)?;
+        }
+        for (keyword, value) in [
+            ("Str", abils.str),
+            ("Int", abils.intel),
+            ("Wis", abils.wis),
+            ("Dex", abils.dex),
+            ("Con", abils.con),
+            ("Cha", abils.cha),
+        ] {
+            if value != 11 {
+                writeln!(out, "{}: {}", keyword, value)?;
+            }
+        }
+        if abils.str_add != 0 {
+            writeln!(out, "StrAdd: {}", abils.str_add)?;
+        }
+        writeln!(out, "E")?;
+    }
+    Ok(())
+}
+
+pub fn save_mobiles(
+    db: &DB,
+    texts: &Depot<TextData>,
+    out: &mut impl Write,
+    zone: usize,
+) -> io::Result<usize> {
+    let mut count = 0;
+    for (rnum, mob) in db.mob_protos.iter().enumerate() {
+        let vnum = db.mob_index[rnum].vnum;
+        if db.zone_of_vnum(vnum) != zone {
+            continue;
+        }
+        save_mobile(out, texts, vnum, mob)?;
+        count += 1;
+    }
+    writeln!(out, "$~")?;
+    Ok(count)
+}
+
+/* Write an object in the format read by parse_object(). */
+fn save_object(
+    out: &mut impl Write,
+    texts: &Depot<TextData>,
+    vnum: i16,
+    obj: &ObjData,
+) -> io::Result<()> {
+    writeln!(out, "#{}", vnum)?;
+    writeln!(out, "{}~", obj.name)?;
+    writeln!(out, "{}~", obj.short_description)?;
+    writeln!(out, "{}~", obj.description)?;
+    writeln!(out, "{}~", texts.get(obj.action_description).text)?;
+    writeln!(
+        out,
+        "{} {} {}",
+        obj.get_obj_type() as u8,
+        sprintascii(obj.get_obj_extra().bits() as i64),
+        sprintascii(obj.get_obj_wear().bits() as i64)
+    )?;
+    writeln!(
+        out,
+        "{} {} {} {}",
+        obj.get_obj_val(0),
+        obj.get_obj_val(1),
+        obj.get_obj_val(2),
+        obj.get_obj_val(3)
+    )?;
+    writeln!(
+        out,
+        "{} {} {}",
+        obj.get_obj_weight(),
+        obj.get_obj_cost(),
+        obj.get_obj_rent()
+    )?;
+
+    /*
+     * Affects go before the extra descriptions: parse_object() refuses 'A'
+     * fields once MAX_OBJ_AFFECT extra descriptions have been read.
+     */
+    for af in obj.affected.iter() {
+        let location = af.location;
+        if location == ApplyType::None {
+            continue;
+        }
+        let modifier = af.modifier;
+        writeln!(out, "A")?;
+        writeln!(out, "{} {}", location as i8, modifier)?;
+    }
+    for ex in &obj.ex_descriptions {
+        writeln!(out, "E")?;
+        writeln!(out, "{}~", ex.keyword)?;
+        writeln!(out, "{}~", ex.description)?;
+    }
+    Ok(())
+}
+
+pub fn save_objects(
+    db: &DB,
+    texts: &Depot<TextData>,
+    out: &mut impl Write,
+    zone: usize,
+) -> io::Result<usize> {
+    let mut count = 0;
+    for (rnum, obj) in db.obj_proto.iter().enumerate() {
+        let vnum = db.obj_index[rnum].vnum;
+        if db.zone_of_vnum(vnum) != zone {
+            continue;
+        }
+        save_object(out, texts, vnum, obj)?;
+        count += 1;
+    }
+    writeln!(out, "$~")?;
+    Ok(count)
+}
+
+/*
+ * Write a zone in the format read by load_zones().  The reset table holds
+ * real numbers once renum_zone_table() has run, so every argument is
+ * turned back into a virtual number.  Commands disabled at boot ('*') have
+ * already lost their original vnums, so only a comment is left where they
+ * were, for the builder to put them right.
+ */
+pub fn save_zone(db: &DB, out: &mut impl Write, zone: usize) -> io::Result<()> {
+    let z = &db.zone_table[zone];
+
+    writeln!(out, "#{}", z.number)?;
+    writeln!(out, "{}~", z.name)?;
+    write!(out, "{} {} {} {}", z.bot, z.top, z.lifespan, z.reset_mode)?;
+    if z.climate != Climate::Temperate {
+        write!(out, " {}", z.climate as i32)?;
+    }
+    writeln!(out)?;
+
+    for zcmd in &z.cmd {
+        let if_flag = zcmd.if_flag as i32;
+        match zcmd.command {
+            'M' => writeln!(
+                out,
+                "M {} {} {} {}\t{}",
+                if_flag,
+                db.mob_vnum(zcmd.arg1),
+                zcmd.arg2,
+                db.room_vnum(zcmd.arg3),
+                db.mob_protos[zcmd.arg1 as usize].player.short_descr
+            )?,
+            'O' => writeln!(
+                out,
+                "O {} {} {} {}\t{}",
+                if_flag,
+                db.obj_vnum(zcmd.arg1),
+                zcmd.arg2,
+                db.room_vnum(zcmd.arg3),
+                db.obj_proto[zcmd.arg1 as usize].short_description
+            )?,
+            'G' => writeln!(
+                out,
+                "G {} {} {}\t{}",
+                if_flag,
+                db.obj_vnum(zcmd.arg1),
+                zcmd.arg2,
+                db.obj_proto[zcmd.arg1 as usize].short_description
+            )?,
+            'E' => writeln!(
+                out,
+                "E {} {} {} {}\t{}",
+                if_flag,
+                db.obj_vnum(zcmd.arg1),
+                zcmd.arg2,
+                zcmd.arg3,
+                db.obj_proto[zcmd.arg1 as usize].short_description
+            )?,
+            'P' => writeln!(
+                out,
+                "P {} {} {} {}\t{}",
+                if_flag,
+                db.obj_vnum(zcmd.arg1),
+                zcmd.arg2,
+                db.obj_vnum(zcmd.arg3),
+                db.obj_proto[zcmd.arg1 as usize].short_description
+            )?,
+            'D' => writeln!(
+                out,
+                "D {} {} {} {}",
+                if_flag,
+                db.room_vnum(zcmd.arg1),
+                zcmd.arg2,
+                zcmd.arg3
+            )?,
+            'R' => writeln!(
+                out,
+                "R {} {} {}",
+                if_flag,
+                db.room_vnum(zcmd.arg1),
+                db.obj_vnum(zcmd.arg2)
+            )?,
+            'Z' => writeln!(
+                out,
+                "Z {} {} {}\t{}",
+                if_flag,
+                db.mob_vnum(zcmd.arg1),
+                zcmd.arg2,
+                db.mob_protos[zcmd.arg1 as usize].player.short_descr
+            )?,
+            '%' | 'L' => writeln!(out, "{} {} {}", zcmd.command, if_flag, zcmd.arg1)?,
+            'H' | 'W' => writeln!(
+                out,
+                "{} {} {} {}",
+                zcmd.command, if_flag, zcmd.arg1, zcmd.arg2
+            )?,
+            '*' => {
+                warn!(
+                    "Zone #{}: reset command on line {} was disabled at boot and is not exported.",
+                    z.number, zcmd.line
+                );
+                writeln!(out, "* line {}: command disabled at boot", zcmd.line)?
+            }
+            'S' => break,
+            _ => {}
+        }
+    }
+    writeln!(out, "S")?;
+    writeln!(out, "$")
+}
+
+/* Write a shop in the format read by boot_the_shops() (v3.0 format). */
+fn save_shop(db: &DB, out: &mut impl Write, shop: &ShopData) -> io::Result<()> {
+    writeln!(out, "#{}~", shop.vnum)?;
+
+    /* producing holds obj rnums, ended by NOTHING */
+    for &prod in shop.producing.iter().filter(|&&p| p != NOTHING) {
+        writeln!(out, "{}", db.obj_vnum(prod as i32))?;
+    }
+    writeln!(out, "-1")?;
+
+    writeln!(out, "{}", shop.profit_buy)?;
+    writeln!(out, "{}", shop.profit_sell)?;
+
+    for t in shop.type_.iter().filter(|t| t.type_ != NOTHING as i32) {
+        writeln!(out, "{}", ITEM_TYPES[t.type_ as usize])?;
+    }
+    writeln!(out, "-1")?;
+
+    for msg in [
+        &shop.no_such_item1,
+        &shop.no_such_item2,
+        &shop.do_not_buy,
+        &shop.missing_cash1,
+        &shop.missing_cash2,
+        &shop.message_buy,
+        &shop.message_sell,
+    ] {
+        writeln!(out, "{}~", msg)?;
+    }
+
+    writeln!(out, "{}", shop.temper1)?;
+    writeln!(out, "{}", shop.bitvector)?;
+    writeln!(out, "{}", db.mob_vnum(shop.keeper as i32))?;
+    writeln!(out, "{}", shop.with_who)?;
+
+    /* in_room holds room vnums, ended by NOWHERE */
+    for &room in shop.in_room.iter().filter(|&&r| r != NOWHERE) {
+        writeln!(out, "{}", room)?;
+    }
+    writeln!(out, "-1")?;
+
+    writeln!(out, "{}", shop.open1)?;
+    writeln!(out, "{}", shop.close1)?;
+    writeln!(out, "{}", shop.open2)?;
+    writeln!(out, "{}", shop.close2)
+}
+
+pub fn save_shops(db: &DB, out: &mut impl Write, zone: usize) -> io::Result<usize> {
+    let mut count = 0;
+    writeln!(out, "{}", SHOP_FILE_HEADER)?;
+    for shop in db
+        .shop_index
+        .iter()
+        .filter(|s| db.zone_of_vnum(s.vnum) == zone)
+    {
+        save_shop(db, out, shop)?;
+        count += 1;
+    }
+    writeln!(out, "$~")?;
+    Ok(count)
+}
+
+fn save_trigger(out: &mut impl Write, trig: &TrigData) -> io::Result<()> {
+    writeln!(out, "#{}", trig.vnum)?;
+    writeln!(out, "{}~", trig.name)?;
+    write!(
+        out,
+        "{} {} {}",
+        ATTACH_TYPES[trig.attach_type as usize], TRIG_EVENTS[trig.event as usize], trig.narg
+    )?;
+    for vnum in &trig.attach {
+        write!(out, " {}", vnum)?;
+    }
+    writeln!(out)?;
+    writeln!(out, "{}~", trig.arglist)?;
+    for line in trig.script.iter() {
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out, "~")
+}
+
+pub fn save_triggers(db: &DB, out: &mut impl Write, zone: usize) -> io::Result<usize> {
+    let mut count = 0;
+    for trig in db
+        .scripts
+        .triggers
+        .iter()
+        .filter(|t| db.zone_of_vnum(t.vnum) == zone)
+    {
+        save_trigger(out, trig)?;
+        count += 1;
+    }
+    writeln!(out, "$~")?;
+    Ok(count)
+}
+
+/*
+ * Write one '<zone vnum>.<suffix>' file per zone into 'dir', plus the
+ * 'index' listing them.  A file for which 'save' reports zero records is
+ * left out, since index_boot() refuses empty world files.
+ */
+fn save_world_files<F>(db: &DB, dir: &Path, suffix: &str, mut save: F) -> io::Result<()>
+where
+    F: FnMut(&mut BufWriter<File>, usize) -> io::Result<Option<usize>>,
+{
+    fs::create_dir_all(dir)?;
+    let mut index = BufWriter::new(File::create(dir.join("index"))?);
+
+    for zone in 0..db.zone_table.len() {
+        let name = format!("{}.{}", db.zone_table[zone].number, suffix);
+        let path = dir.join(&name);
+        let mut out = BufWriter::new(File::create(&path)?);
+        let count = save(&mut out, zone)?;
+        out.flush()?;
+        drop(out);
+        if count == Some(0) {
+            fs::remove_file(&path)?;
+        } else {
+            writeln!(index, "{}", name)?;
+        }
+    }
+    writeln!(index, "$")?;
+    index.flush()
+}
+
+/*
+ * Export the whole world below 'dir', using the same layout as lib/world:
+ * dir/wld, dir/mob, dir/obj, dir/zon, dir/shp and dir/trg.
+ */
+pub fn save_world(db: &DB, texts: &Depot<TextData>, dir: &Path) -> io::Result<()> {
+    save_world_files(db, &dir.join(WLD_SUFFIX), WLD_SUFFIX, |out, zone| {
+        save_rooms(db, out, zone).map(Some)
+    })?;
+    save_world_files(db, &dir.join(MOB_SUFFIX), MOB_SUFFIX, |out, zone| {
+        save_mobiles(db, texts, out, zone).map(Some)
+    })?;
+    save_world_files(db, &dir.join(OBJ_SUFFIX), OBJ_SUFFIX, |out, zone| {
+        save_objects(db, texts, out, zone).map(Some)
+    })?;
+    /* Every zone gets a zone file, even one without reset commands. */
+    save_world_files(db, &dir.join(ZON_SUFFIX), ZON_SUFFIX, |out, zone| {
+        save_zone(db, out, zone).map(|_| None)
+    })?;
+    save_world_files(db, &dir.join(SHP_SUFFIX), SHP_SUFFIX, |out, zone| {
+        save_shops(db, out, zone).map(Some)
+    })?;
+    save_world_files(db, &dir.join(TRG_SUFFIX), TRG_SUFFIX, |out, zone| {
+        save_triggers(db, out, zone).map(Some)
+    })
+}
+
+#[cfg(test)]
+mod worldsave_tests {
+    use super::*;
+    use crate::db::{boot_world, TestWorld, BOOT_DIR_LOCK};
+    use crate::Game;
+    use std::env;
+
+    /* boot_world() reads relative paths below the current directory. */
+    fn boot_from(lib: &Path, texts: &mut Depot<TextData>) -> DB {
+        env::set_current_dir(lib).expect("Cannot change to library directory");
+        let mut game = Game::new();
+        let mut chars = Depot::default();
+        let mut db = DB::new(texts);
+        db.scheck = true;
+        boot_world(&mut game, &mut db, &mut chars, texts);
+        db
+    }
+
+    fn assert_rooms_eq(a: &DB, b: &DB) {
+        assert_eq!(a.world.len(), b.world.len());
+        for (ra, rb) in a.world.iter().zip(b.world.iter()) {
+            let what = format!("room #{}", ra.number);
+            assert_eq!(ra.number, rb.number, "{}", what);
+            assert_eq!(ra.zone, rb.zone, "{}", what);
+            assert_eq!(ra.sector_type, rb.sector_type, "{}", what);
+            assert_eq!(ra.name, rb.name, "{}", what);
+            assert_eq!(ra.description, rb.description, "{}", what);
+            assert_eq!(ra.room_flags, rb.room_flags, "{}", what);
+            assert_eq!(
+                ra.ex_descriptions.len(),
+                rb.ex_descriptions.len(),
+                "{}",
+                what
+            );
+            for (ea, eb) in ra.ex_descriptions.iter().zip(rb.ex_descriptions.iter()) {
+                assert_eq!(ea.keyword, eb.keyword, "{}", what);
+                assert_eq!(ea.description, eb.description, "{}", what);
+            }
+            assert_eq!(
+                ra.alt_descriptions.len(),
+                rb.alt_descriptions.len(),
+                "{}",
+                what
+            );
+            for (aa, ab) in ra.alt_descriptions.iter().zip(rb.alt_descriptions.iter()) {
+                assert_eq!(aa.conditions, ab.conditions, "{}", what);
+                assert_eq!(aa.description, ab.description, "{}", what);
+            }
+            for dir in 0..NUM_OF_DIRS {
+                match (&ra.dir_option[dir], &rb.dir_option[dir]) {
+                    (None, None) => {}
+                    (Some(da), Some(db)) => {
+                        assert_eq!(da.general_description, db.general_description, "{}", what);
+                        assert_eq!(da.keyword, db.keyword, "{}", what);
+                        assert_eq!(da.exit_info, db.exit_info, "{}", what);
+                        assert_eq!(da.key, db.key, "{}", what);
+                        assert_eq!(da.to_room, db.to_room, "{}", what);
+                    }
+                    _ => panic!("{}: exit {} differs", what, dir),
+                }
+            }
+        }
+    }
+
+    fn assert_mobiles_eq(a: &DB, ta: &Depot<TextData>, b: &DB, tb: &Depot<TextData>) {
+        assert_eq!(a.mob_protos.len(), b.mob_protos.len());
+        for (nr, (ma, mb)) in a.mob_protos.iter().zip(b.mob_protos.iter()).enumerate() {
+            let what = format!("mob #{}", a.mob_index[nr].vnum);
+            assert_eq!(a.mob_index[nr].vnum, b.mob_index[nr].vnum, "{}", what);
+            assert_eq!(ma.player.name, mb.player.name, "{}", what);
+            assert_eq!(ma.player.short_descr, mb.player.short_descr, "{}", what);
+            assert_eq!(ma.player.long_descr, mb.player.long_descr, "{}", what);
+            assert_eq!(
+                ta.get(ma.player.description).text,
+                tb.get(mb.player.description).text,
+                "{}",
+                what
+            );
+            assert_eq!(ma.mob_flags(), mb.mob_flags(), "{}", what);
+            assert_eq!(ma.aff_flags(), mb.aff_flags(), "{}", what);
+            assert_eq!(ma.get_alignment(), mb.get_alignment(), "{}", what);
+            assert_eq!(ma.get_level(), mb.get_level(), "{}", what);
+            assert_eq!(ma.points, mb.points, "{}", what);
+            assert_eq!(ma.real_abils, mb.real_abils, "{}", what);
+            assert_eq!(ma.get_pos(), mb.get_pos(), "{}", what);
+            assert_eq!(ma.get_default_pos(), mb.get_default_pos(), "{}", what);
+            assert_eq!(ma.get_sex(), mb.get_sex(), "{}", what);
+            assert_eq!(
+                ma.mob_specials.attack_type, mb.mob_specials.attack_type,
+                "{}",
+                what
+            );
+            assert_eq!(
+                ma.mob_specials.damnodice, mb.mob_specials.damnodice,
+                "{}",
+                what
+            );
+            assert_eq!(
+                ma.mob_specials.damsizedice, mb.mob_specials.damsizedice,
+                "{}",
+                what
+            );
+        }
+    }
+
+    fn assert_objects_eq(a: &DB, ta: &Depot<TextData>, b: &DB, tb: &Depot<TextData>) {
+        assert_eq!(a.obj_proto.len(), b.obj_proto.len());
+        for (nr, (oa, ob)) in a.obj_proto.iter().zip(b.obj_proto.iter()).enumerate() {
+            let what = format!("obj #{}", a.obj_index[nr].vnum);
+            assert_eq!(a.obj_index[nr].vnum, b.obj_index[nr].vnum, "{}", what);
+            assert_eq!(oa.name, ob.name, "{}", what);
+            assert_eq!(oa.short_description, ob.short_description, "{}", what);
+            assert_eq!(oa.description, ob.description, "{}", what);
+            assert_eq!(
+                ta.get(oa.action_description).text,
+                tb.get(ob.action_description).text,
+                "{}",
+                what
+            );
+            assert_eq!(oa.get_obj_type(), ob.get_obj_type(), "{}", what);
+            assert_eq!(oa.get_obj_extra(), ob.get_obj_extra(), "{}", what);
+            assert_eq!(oa.get_obj_wear(), ob.get_obj_wear(), "{}", what);
+            assert_eq!(oa.obj_flags.value, ob.obj_flags.value, "{}", what);
+            assert_eq!(oa.get_obj_weight(), ob.get_obj_weight(), "{}", what);
+            assert_eq!(oa.get_obj_cost(), ob.get_obj_cost(), "{}", what);
+            assert_eq!(oa.get_obj_rent(), ob.get_obj_rent(), "{}", what);
+            for (aa, ab) in oa.affected.iter().zip(ob.affected.iter()) {
+                let (la, lb) = (aa.location, ab.location);
+                let (ma, mb) = (aa.modifier, ab.modifier);
+                assert_eq!(la, lb, "{}", what);
+                assert_eq!(ma, mb, "{}", what);
+            }
+            assert_eq!(
+                oa.ex_descriptions.len(),
+                ob.ex_descriptions.len(),
+                "{}",
+                what
+            );
+            for (ea, eb) in oa.ex_descriptions.iter().zip(ob.ex_descriptions.iter()) {
+                assert_eq!(ea.keyword, eb.keyword, "{}", what);
+                assert_eq!(ea.description, eb.description, "{}", what);
+            }
+        }
+    }
+
+    fn assert_zones_eq(a: &DB, b: &DB) {
+        assert_eq!(a.zone_table.len(), b.zone_table.len());
+        for (za, zb) in a.zone_table.iter().zip(b.zone_table.iter()) {
+            let what = format!("zone #{}", za.number);
+            assert_eq!(za.number, zb.number, "{}", what);
+            assert_eq!(za.name, zb.name, "{}", what);
+            assert_eq!(za.bot, zb.bot, "{}", what);
+            assert_eq!(za.top, zb.top, "{}", what);
+            assert_eq!(za.lifespan, zb.lifespan, "{}", what);
+            assert_eq!(za.reset_mode, zb.reset_mode, "{}", what);
+            assert_eq!(za.climate, zb.climate, "{}", what);
+            /* Commands disabled at boot are not written back. */
+            let ca: Vec<_> = za.cmd.iter().filter(|c| c.command != '*').collect();
+            let cb: Vec<_> = zb.cmd.iter().filter(|c| c.command != '*').collect();
+            assert_eq!(ca.len(), cb.len(), "{}", what);
+            for (ca, cb) in ca.iter().zip(cb.iter()) {
+                assert_eq!(ca.command, cb.command, "{}", what);
+                assert_eq!(ca.if_flag, cb.if_flag, "{}", what);
+                assert_eq!(ca.arg1, cb.arg1, "{}", what);
+                assert_eq!(ca.arg2, cb.arg2, "{}", what);
+                assert_eq!(ca.arg3, cb.arg3, "{}", what);
+            }
+        }
+    }
+
+    fn assert_shops_eq(a: &DB, b: &DB) {
+        assert_eq!(a.shop_index.len(), b.shop_index.len());
+        for (sa, sb) in a.shop_index.iter().zip(b.shop_index.iter()) {
+            let what = format!("shop #{}", sa.vnum);
+            assert_eq!(sa.vnum, sb.vnum, "{}", what);
+            assert_eq!(sa.producing, sb.producing, "{}", what);
+            assert_eq!(sa.profit_buy, sb.profit_buy, "{}", what);
+            assert_eq!(sa.profit_sell, sb.profit_sell, "{}", what);
+            let ta: Vec<_> = sa.type_.iter().map(|t| t.type_).collect();
+            let tb: Vec<_> = sb.type_.iter().map(|t| t.type_).collect();
+            assert_eq!(ta, tb, "{}", what);
+            assert_eq!(sa.no_such_item1, sb.no_such_item1, "{}", what);
+            assert_eq!(sa.no_such_item2, sb.no_such_item2, "{}", what);
+            assert_eq!(sa.do_not_buy, sb.do_not_buy, "{}", what);
+            assert_eq!(sa.missing_cash1, sb.missing_cash1, "{}", what);
+            assert_eq!(sa.missing_cash2, sb.missing_cash2, "{}", what);
+            assert_eq!(sa.message_buy, sb.message_buy, "{}", what);
+            assert_eq!(sa.message_sell, sb.message_sell, "{}", what);
+            assert_eq!(sa.temper1, sb.temper1, "{}", what);
+            assert_eq!(sa.bitvector, sb.bitvector, "{}", what);
+            assert_eq!(sa.keeper, sb.keeper, "{}", what);
+            assert_eq!(sa.with_who, sb.with_who, "{}", what);
+            assert_eq!(sa.in_room, sb.in_room, "{}", what);
+            assert_eq!(
+                (sa.open1, sa.close1, sa.open2, sa.close2),
+                (sb.open1, sb.close1, sb.open2, sb.close2),
+                "{}",
+                what
+            );
+        }
+    }
+
+    fn assert_triggers_eq(a: &DB, b: &DB) {
+        assert_eq!(a.scripts.triggers.len(), b.scripts.triggers.len());
+        for (ta, tb) in a.scripts.triggers.iter().zip(b.scripts.triggers.iter()) {
+            let what = format!("trigger #{}", ta.vnum);
+            assert_eq!(ta.vnum, tb.vnum, "{}", what);
+            assert_eq!(ta.name, tb.name, "{}", what);
+            assert_eq!(ta.attach_type, tb.attach_type, "{}", what);
+            assert_eq!(ta.event, tb.event, "{}", what);
+            assert_eq!(ta.narg, tb.narg, "{}", what);
+            assert_eq!(ta.attach, tb.attach, "{}", what);
+            assert_eq!(ta.arglist, tb.arglist, "{}", what);
+            assert_eq!(ta.script, tb.script, "{}", what);
+        }
+    }
+
+    #[test]
+    fn test_sprintascii() {
+        assert_eq!(sprintascii(0), "0");
+        assert_eq!(sprintascii(1 << 0 | 1 << 2 | 1 << 3), "acd");
+        assert_eq!(sprintascii(1 << 25 | 1 << 26), "zA");
+    }
+
+    #[test]
+    fn test_disabled_commands_leave_a_comment() {
+        let mut w = TestWorld::boot("worldsave");
+        let zone = w.db.real_zone(30).unwrap();
+        let zcmd = &mut w.db.zone_table[zone].cmd[0];
+        zcmd.command = '*';
+        zcmd.line = 7;
+        let mut out = vec![];
+        save_zone(&w.db, &mut out, zone).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text.lines().nth(3),
+            Some("* line 7: command disabled at boot")
+        );
+    }
+
+    #[test]
+    fn test_world_round_trip() {
+        let _lock = BOOT_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let lib = Path::new(env!("CARGO_MANIFEST_DIR")).join("lib");
+        let export = env::temp_dir().join(format!("mudr-worldsave-{}", std::process::id()));
+
+        let mut texts = Depot::default();
+        let original = boot_from(&lib, &mut texts);
+        save_world(&original, &texts, &export.join("world")).expect("Cannot export world");
+
+        let mut reloaded_texts = Depot::default();
+        let reloaded = boot_from(&export, &mut reloaded_texts);
+
+        assert_zones_eq(&original, &reloaded);
+        assert_rooms_eq(&original, &reloaded);
+        assert_mobiles_eq(&original, &texts, &reloaded, &reloaded_texts);
+        assert_objects_eq(&original, &texts, &reloaded, &reloaded_texts);
+        assert_shops_eq(&original, &reloaded);
+        assert_triggers_eq(&original, &reloaded);
+
+        env::set_current_dir(&lib).expect("Cannot change to library directory");
+        fs::remove_dir_all(&export).expect("Cannot remove exported world");
+    }
+}
@@ -0,0 +1,878 @@
+/* ************************************************************************
+*   File: zreload.rs                                    Part of CircleMUD *
+*  Usage: Reloading a zone's world files while the game is running        *
+*                                                                         *
+*  All rights reserved.  See license.doc for complete information.        *
+*                                                                         *
+*  Copyright (C) 1993, 94 by the Trustees of the Johns Hopkins University *
+*  CircleMUD is based on DikuMUD, Copyright (C) 1990, 1991.               *
+*  Rust port Copyright (C) 2023 - 2025 Laurent Pautet                     *
+************************************************************************ */
+
+/*
+ * zreload <zone> [refresh | keep]
+ *
+ * The zone's .zon, .wld, .mob, .obj and .shp files are parsed again, then
+ * its rooms and prototypes are swapped into the tables of the DB.  As the
+ * tables are sorted by vnum, every rnum held anywhere (exits, characters,
+ * objects, reset commands, shops, boards, scripts) is moved to its new
+ * place.
+ *
+ * This happens in two stages.  The files are first read aside: a format
+ * error there aborts the reload and leaves the running world untouched.
+ * Only then is the zone swapped in, which cannot fail.
+ *
+ * While the zone is rebuilt, the characters and objects in its rooms wait
+ * in the void and are put back in the room with the same vnum afterwards,
+ * or at the mortal start room if that room is gone.  With 'refresh' the
+ * mobiles and objects of the zone rooms are purged and the zone is reset;
+ * with 'keep' (the default) they stay as they are and only what is loaded
+ * later comes from the new prototypes.
+ */
+
+use std::cmp::max;
+use std::fs::{self, File};
+use std::mem;
+
+use log::error;
+
+use crate::act_informative::look_at_room;
+use crate::boards::renum_boards;
+use crate::clan::clan_flag_rooms;
+use crate::config::MORTAL_START_ROOM;
+use crate::db::{
+    renum_zone, DbBootMode, ZoneData, DB, MOB_PREFIX, OBJ_PREFIX, SHP_PREFIX, WLD_PREFIX,
+    ZON_PREFIX,
+};
+use crate::depot::{Depot, DepotId};
+use crate::house::house_flag_rooms;
+use crate::interpreter::two_arguments;
+use crate::shops::{boot_the_shops, shop_keeper, ShopData};
+use crate::spec_assign::assign_spec_procs;
+use crate::structs::{
+    AffectFlags, IdxType, IndexData, RoomAffectFlags, RoomAffectedType, RoomData, RoomFlags,
+    LVL_GRGOD, MOB_NOTDEADYET, NOBODY, NOTHING, NOWHERE, NUM_OF_DIRS, NUM_WEARS,
+};
+use crate::util::DisplayMode;
+use crate::{send_to_char, CharData, Game, ObjData, TextData};
+
+const INDEX_FILE: &str = "index";
+const MINDEX_FILE: &str = "index.mini";
+
+/* A zone as its files describe it, read aside from the running world. */
+struct ZoneFiles {
+    zone: ZoneData,
+    rooms: Vec<RoomData>,
+    mobs: Vec<(IndexData, CharData)>,
+    objs: Vec<(IndexData, ObjData)>,
+    shops: Vec<ShopData>,
+}
+
+/* What a reload did, for the message to the wizard. */
+struct ReloadReport {
+    rooms: usize,
+    mobs: usize,
+    objs: usize,
+    moved: usize,
+    purged: usize,
+}
+
+/**************************************************************************
+*  Reading the files                                                      *
+**************************************************************************/
+
+/* Is the file part of the world, i.e. listed in the index of its directory? */
+fn listed_in_index(prefix: &str, name: &str, mini_mud: bool) -> bool {
+    let index = format!(
+        "{}{}",
+        prefix,
+        if mini_mud { MINDEX_FILE } else { INDEX_FILE }
+    );
+    fs::read_to_string(index)
+        .map(|text| {
+            text.lines()
+                .map(str::trim)
+                .take_while(|line| !line.starts_with('$'))
+                .any(|line| line == name)
+        })
+        .unwrap_or(false)
+}
+
+/* Open one of the zone's files; None when the world doesn't use one. */
+fn open_zone_file(
+    db: &DB,
+    prefix: &str,
+    number: IdxType,
+    suffix: &str,
+    required: bool,
+) -> Result<Option<(File, String)>, String> {
+    let name = format!("{}.{}", number, suffix);
+    let path = format!("{}{}", prefix, name);
+    if !listed_in_index(prefix, &name, db.mini_mud) {
+        return if required {
+            Err(format!("{} is not listed in the {} index", path, suffix))
+        } else {
+            Ok(None)
+        };
+    }
+    match File::open(&path) {
+        Ok(file) => Ok(Some((file, path))),
+        Err(err) => Err(format!("{}: {}", path, err)),
+    }
+}
+
+/* Sort vnums and make sure each is only used once. */
+fn sorted_vnums(mut vnums: Vec<IdxType>, what: &str) -> Result<Vec<IdxType>, String> {
+    vnums.sort();
+    match vnums.windows(2).find(|w| w[0] == w[1]) {
+        Some(w) => Err(format!("{} #{} is defined twice", what, w[0])),
+        None => Ok(vnums),
+    }
+}
+
+/* Read the rooms and the zone; nothing in the running world is touched. */
+fn read_zone_files(
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    zone: usize,
+) -> Result<ZoneFiles, String> {
+    let number = db.zone_table[zone].number;
+    let (bot, top) = (db.zone_table[zone].bot, db.zone_table[zone].top);
+
+    /* the zone file, parsed into an empty zone table */
+    let (file, path) = open_zone_file(db, ZON_PREFIX, number, "zon", true)?.unwrap();
+    let zone_table = mem::take(&mut db.zone_table);
+    let parsed = db.load_zones(file, &path);
+    let new_zone = mem::replace(&mut db.zone_table, zone_table).pop();
+    let new_zone = parsed
+        .ok()
+        .and(new_zone)
+        .ok_or_else(|| format!("format error in {}", path))?;
+    if new_zone.number != number || new_zone.bot != bot || new_zone.top != top {
+        return Err(format!(
+            "{} changed the zone's number or room range, that needs a reboot",
+            path
+        ));
+    }
+
+    /* rooms, parsed into an empty world */
+    let (file, path) = open_zone_file(db, WLD_PREFIX, number, "wld", true)?.unwrap();
+    let world = mem::take(&mut db.world);
+    let parsed = db.discrete_load(texts, file, DbBootMode::World, &path);
+    let rooms = mem::replace(&mut db.world, world);
+    parsed.map_err(|_| format!("format error in {}", path))?;
+
+    let mut files = ZoneFiles {
+        zone: new_zone,
+        rooms,
+        mobs: vec![],
+        objs: vec![],
+        shops: vec![],
+    };
+    if let Err(err) = read_zone_protos(db, texts, zone, &mut files)
+        .and_then(|()| check_zone_files(db, zone, &mut files))
+    {
+        /* the texts of the new prototypes are shared with nothing yet */
+        for (_, mob) in &files.mobs {
+            texts.take(mob.player.description);
+        }
+        for (_, obj) in &files.objs {
+            texts.take(obj.action_description);
+        }
+        return Err(err);
+    }
+    Ok(files)
+}
+
+/*
+ * Read the mobiles and objects into empty tables.  What a broken file
+ * yielded before its error is kept in files, so that it can be freed.
+ */
+fn read_zone_protos(
+    db: &mut DB,
+    texts: &mut Depot<TextData>,
+    zone: usize,
+    files: &mut ZoneFiles,
+) -> Result<(), String> {
+    let number = db.zone_table[zone].number;
+    if let Some((file, path)) = open_zone_file(db, MOB_PREFIX, number, "mob", false)? {
+        let mob_index = mem::take(&mut db.mob_index);
+        let mob_protos = mem::take(&mut db.mob_protos);
+        let parsed = db.discrete_load(texts, file, DbBootMode::Mob, &path);
+        let new_index = mem::replace(&mut db.mob_index, mob_index);
+        let new_protos = mem::replace(&mut db.mob_protos, mob_protos);
+        files.mobs = new_index.into_iter().zip(new_protos).collect();
+        parsed.map_err(|_| format!("format error in {}", path))?;
+    }
+
+    if let Some((file, path)) = open_zone_file(db, OBJ_PREFIX, number, "obj", false)? {
+        let obj_index = mem::take(&mut db.obj_index);
+        let obj_proto = mem::take(&mut db.obj_proto);
+        let parsed = db.discrete_load(texts, file, DbBootMode::Object, &path);
+        let new_index = mem::replace(&mut db.obj_index, obj_index);
+        let new_proto = mem::replace(&mut db.obj_proto, obj_proto);
+        files.objs = new_index.into_iter().zip(new_proto).collect();
+        parsed.map_err(|_| format!("format error in {}", path))?;
+    }
+    Ok(())
+}
+
+/* Everything must belong to this zone, then the shops are read. */
+fn check_zone_files(db: &mut DB, zone: usize, files: &mut ZoneFiles) -> Result<(), String> {
+    let number = db.zone_table[zone].number;
+    let (bot, top) = (db.zone_table[zone].bot, db.zone_table[zone].top);
+    let in_zone = |vnum: IdxType| vnum >= bot && vnum <= top;
+
+    /* anything outside the zone would shadow another one */
+    if let Some(room) = files.rooms.iter().find(|r| r.zone as usize != zone) {
+        return Err(format!("room #{} is outside zone #{}", room.number, number));
+    }
+    if let Some((idx, _)) = files.mobs.iter().find(|(idx, _)| !in_zone(idx.vnum)) {
+        return Err(format!("mobile #{} is outside zone #{}", idx.vnum, number));
+    }
+    if let Some((idx, _)) = files.objs.iter().find(|(idx, _)| !in_zone(idx.vnum)) {
+        return Err(format!("object #{} is outside zone #{}", idx.vnum, number));
+    }
+    sorted_vnums(files.rooms.iter().map(|r| r.number).collect(), "room")?;
+    if in_zone(MORTAL_START_ROOM) && !files.rooms.iter().any(|r| r.number == MORTAL_START_ROOM) {
+        return Err(format!(
+            "the mortal start room #{} would be gone",
+            MORTAL_START_ROOM
+        ));
+    }
+
+    /*
+     * Shops keep the rnums of their keeper and products, so they are parsed
+     * against the indexes as they will be once the zone is swapped in.
+     */
+    let mob_vnums = sorted_vnums(
+        db.mob_index
+            .iter()
+            .map(|i| i.vnum)
+            .filter(|&v| !in_zone(v))
+            .chain(files.mobs.iter().map(|(i, _)| i.vnum))
+            .collect(),
+        "mobile",
+    )?;
+    let obj_vnums = sorted_vnums(
+        db.obj_index
+            .iter()
+            .map(|i| i.vnum)
+            .filter(|&v| !in_zone(v))
+            .chain(files.objs.iter().map(|(i, _)| i.vnum))
+            .collect(),
+        "object",
+    )?;
+    if !db.no_specials {
+        if let Some((file, path)) = open_zone_file(db, SHP_PREFIX, number, "shp", false)? {
+            let index = |vnums: Vec<IdxType>| {
+                vnums
+                    .into_iter()
+                    .map(|vnum| IndexData {
+                        vnum,
+                        number: 0,
+                        func: None,
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let mob_index = mem::replace(&mut db.mob_index, index(mob_vnums));
+            let obj_index = mem::replace(&mut db.obj_index, index(obj_vnums));
+            let shop_index = mem::take(&mut db.shop_index);
+            let parsed = boot_the_shops(db, file, &path, 0);
+            db.mob_index = mob_index;
+            db.obj_index = obj_index;
+            files.shops = mem::replace(&mut db.shop_index, shop_index);
+            parsed.map_err(|_| format!("format error in {}", path))?;
+        }
+    }
+    if let Some(shop) = files.shops.iter().find(|s| !in_zone(s.vnum)) {
+        return Err(format!("shop #{} is outside zone #{}", shop.vnum, number));
+    }
+    Ok(())
+}
+
+/**************************************************************************
+*  Swapping the zone in                                                   *
+**************************************************************************/
+
+/*
+ * Merge new entries into a table sorted by vnum, replacing the old entries
+ * that are not kept.  Returns the table, the map from old rnum to new rnum
+ * (found by vnum, -1 if the vnum is gone) and which entries are new.
+ */
+fn merge_table<T>(
+    old: Vec<T>,
+    new: Vec<T>,
+    keep: impl Fn(&T) -> bool,
+    vnum: impl Fn(&T) -> IdxType,
+) -> (Vec<T>, Vec<IdxType>, Vec<bool>) {
+    let old_vnums: Vec<IdxType> = old.iter().map(&vnum).collect();
+    let mut entries: Vec<(T, bool)> = old
+        .into_iter()
+        .filter(|e| keep(e))
+        .map(|e| (e, false))
+        .collect();
+    entries.extend(new.into_iter().map(|e| (e, true)));
+    entries.sort_by_key(|(e, _)| vnum(e));
+
+    let (table, fresh): (Vec<T>, Vec<bool>) = entries.into_iter().unzip();
+    let map = old_vnums
+        .iter()
+        .map(|v| {
+            table
+                .binary_search_by_key(v, &vnum)
+                .map_or(-1, |rnum| rnum as IdxType)
+        })
+        .collect();
+    (table, map, fresh)
+}
+
+/* Where an rnum went in a rebuilt table.  Negative rnums mean nothing. */
+fn renum(map: &[IdxType], rnum: IdxType) -> IdxType {
+    if rnum < 0 || rnum as usize >= map.len() {
+        rnum
+    } else {
+        map[rnum as usize]
+    }
+}
+
+/* Same for an argument of a reset command; false if its target is gone. */
+fn renum_arg(map: &[IdxType], arg: &mut i32) -> bool {
+    let old = *arg;
+    *arg = renum(map, old as IdxType) as i32;
+    old < 0 || *arg >= 0
+}
+
+#[allow(clippy::too_many_arguments)]
+fn swap_zone(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    zone: usize,
+    files: ZoneFiles,
+    refresh: bool,
+) -> ReloadReport {
+    let (bot, top) = (db.zone_table[zone].bot, db.zone_table[zone].top);
+    let in_zone = |vnum: IdxType| vnum >= bot && vnum <= top;
+    let mut report = ReloadReport {
+        rooms: files.rooms.len(),
+        mobs: files.mobs.len(),
+        objs: files.objs.len(),
+        moved: 0,
+        purged: 0,
+    };
+
+    /* Everything in the zone waits in the void while it is rebuilt. */
+    let mut waiting_chars: Vec<(DepotId, IdxType)> = vec![];
+    let mut waiting_objs: Vec<(DepotId, IdxType)> = vec![];
+    let mut purge: Vec<DepotId> = vec![];
+    let mut waiting_affects: Vec<(IdxType, Vec<RoomAffectedType>)> = vec![];
+    for rnum in 0..db.world.len() {
+        if db.world[rnum].zone as usize != zone {
+            continue;
+        }
+        let vnum = db.world[rnum].number;
+        if !db.world[rnum].affected.is_empty() {
+            waiting_affects.push((vnum, mem::take(&mut db.world[rnum].affected)));
+        }
+        let is_house = db.room_flagged(rnum as IdxType, RoomFlags::HOUSE);
+        for chid in db.world[rnum].peoples.clone() {
+            if let Some(vict_id) = chars.get(chid).fighting_id() {
+                db.stop_fighting(chars.get_mut(vict_id));
+            }
+            let ch = chars.get_mut(chid);
+            db.char_from_room(objs, ch);
+            ch.set_in_room(NOWHERE);
+            if refresh && ch.is_npc() && !ch.aff_flagged(AffectFlags::CHARM) {
+                purge.push(chid);
+            } else if !ch.is_npc() {
+                send_to_char(
+                    &mut game.descriptors,
+                    ch,
+                    "The world around you fades away for a moment...\r\n",
+                );
+            }
+            waiting_chars.push((chid, vnum));
+        }
+        for oid in db.world[rnum].contents.clone() {
+            if refresh && !is_house && objs.get(oid).get_obj_rnum() != NOTHING {
+                db.extract_obj(chars, objs, oid);
+                report.purged += 1;
+                continue;
+            }
+            let obj = objs.get_mut(oid);
+            db.obj_from_room(obj);
+            obj.set_in_room(NOWHERE);
+            waiting_objs.push((oid, vnum));
+        }
+    }
+
+    /* Rebuild the world, keeping the exits of the other zones in vnums. */
+    let old_room_vnums: Vec<IdxType> = db.world.iter().map(|r| r.number).collect();
+    let (world, room_map, fresh) = merge_table(
+        mem::take(&mut db.world),
+        files.rooms,
+        |r| r.zone as usize != zone,
+        |r| r.number,
+    );
+    db.world = world;
+    let dangling = mem::take(&mut db.dangling_exits);
+    for (rnum, &is_new) in fresh.iter().enumerate() {
+        for door in 0..NUM_OF_DIRS {
+            let Some(exit) = db.world[rnum].dir_option[door].as_ref() else {
+                continue;
+            };
+            if exit.to_room == NOWHERE {
+                continue;
+            }
+            let vnum = if is_new {
+                exit.to_room
+            } else {
+                old_room_vnums[exit.to_room as usize]
+            };
+            let to_room = db.real_room(vnum);
+            if to_room == NOWHERE {
+                db.dangling_exits.push((rnum as IdxType, door, vnum));
+            }
+            if let Some(exit) = db.world[rnum].dir_option[door].as_mut() {
+                exit.to_room = to_room;
+            }
+        }
+    }
+    /* exits that led nowhere may lead into the new rooms */
+    for (rnum, door, vnum) in dangling {
+        let rnum = renum(&room_map, rnum);
+        if rnum == NOWHERE || fresh[rnum as usize] {
+            continue;
+        }
+        let to_room = db.real_room(vnum);
+        if to_room == NOWHERE {
+            db.dangling_exits.push((rnum, door, vnum));
+        } else if let Some(exit) = db.world[rnum as usize].dir_option[door].as_mut() {
+            exit.to_room = to_room;
+        }
+    }
+
+    /* room spells outlast the reload, unless the wall's exit is gone */
+    for (vnum, mut affected) in waiting_affects {
+        let rnum = db.real_room(vnum);
+        if rnum == NOWHERE {
+            continue;
+        }
+        let room = &mut db.world[rnum as usize];
+        affected.retain(|af| {
+            !af.bitvector.intersects(RoomAffectFlags::WALL)
+                || room.dir_option[af.modifier as usize].is_some()
+        });
+        room.affected = affected;
+    }
+
+    /* Rebuild the prototype tables. */
+    let (mobs, mob_map, _) = merge_table(
+        mem::take(&mut db.mob_index)
+            .into_iter()
+            .zip(mem::take(&mut db.mob_protos))
+            .collect(),
+        files.mobs,
+        |(idx, _)| !in_zone(idx.vnum),
+        |(idx, _)| idx.vnum,
+    );
+    for (rnum, (idx, mut proto)) in mobs.into_iter().enumerate() {
+        proto.nr = rnum as IdxType;
+        db.mob_index.push(idx);
+        db.mob_protos.push(proto);
+    }
+    let (obj_table, obj_map, _) = merge_table(
+        mem::take(&mut db.obj_index)
+            .into_iter()
+            .zip(mem::take(&mut db.obj_proto))
+            .collect(),
+        files.objs,
+        |(idx, _)| !in_zone(idx.vnum),
+        |(idx, _)| idx.vnum,
+    );
+    for (rnum, (idx, mut proto)) in obj_table.into_iter().enumerate() {
+        proto.item_number = rnum as IdxType;
+        db.obj_index.push(idx);
+        db.obj_proto.push(proto);
+    }
+
+    /* The reset commands of the other zones, then the new ones. */
+    for idx in 0..db.zone_table.len() {
+        if idx == zone {
+            continue;
+        }
+        let number = db.zone_table[idx].number;
+        for cmd in db.zone_table[idx].cmd.iter_mut() {
+            let ok = match cmd.command {
+                'M' => renum_arg(&mob_map, &mut cmd.arg1) && renum_arg(&room_map, &mut cmd.arg3),
+                'Z' => renum_arg(&mob_map, &mut cmd.arg1),
+                'O' => renum_arg(&obj_map, &mut cmd.arg1) && renum_arg(&room_map, &mut cmd.arg3),
+                'G' | 'E' => renum_arg(&obj_map, &mut cmd.arg1),
+                'P' => renum_arg(&obj_map, &mut cmd.arg1) && renum_arg(&obj_map, &mut cmd.arg3),
+                'D' => renum_arg(&room_map, &mut cmd.arg1),
+                'R' => renum_arg(&room_map, &mut cmd.arg1) && renum_arg(&obj_map, &mut cmd.arg2),
+                _ => true,
+            };
+            if !ok {
+                error!(
+                    "SYSERR: zreload: '{}' cmd in zone #{}, line {} lost its target, cmd disabled",
+                    cmd.command, number, cmd.line
+                );
+                cmd.command = '*';
+            }
+        }
+    }
+    let mut new_zone = files.zone;
+    new_zone.age = db.zone_table[zone].age;
+    if new_zone.climate == db.zone_table[zone].climate {
+        new_zone.weather = db.zone_table[zone].weather;
+    }
+    db.zone_table[zone] = new_zone;
+    renum_zone(game, db, chars, zone);
+
+    /* Shops, boards, houses, clan halls, start rooms and scripts. */
+    if !db.no_specials {
+        let mut shops: Vec<ShopData> = mem::take(&mut db.shop_index)
+            .into_iter()
+            .filter(|s| !in_zone(s.vnum))
+            .collect();
+        for shop in shops.iter_mut() {
+            shop.keeper = renum(&mob_map, shop.keeper);
+            shop.producing = shop
+                .producing
+                .iter()
+                .map(|&o| renum(&obj_map, o))
+                .filter(|&o| o != NOTHING)
+                .collect();
+        }
+        shops.extend(files.shops);
+        shops.sort_by_key(|s| s.vnum);
+        db.shop_index = shops;
+        for shop in db.shop_index.iter() {
+            if shop.keeper != NOBODY && in_zone(db.mob_index[shop.keeper as usize].vnum) {
+                db.mob_index[shop.keeper as usize].func = Some(shop_keeper);
+            }
+        }
+        assign_spec_procs(db);
+    }
+    renum_boards(db);
+    house_flag_rooms(db);
+    clan_flag_rooms(db);
+    db.r_mortal_start_room = renum(&room_map, db.r_mortal_start_room);
+    db.r_immort_start_room = renum(&room_map, db.r_immort_start_room);
+    if db.r_immort_start_room == NOWHERE {
+        db.r_immort_start_room = db.r_mortal_start_room;
+    }
+    db.r_frozen_start_room = renum(&room_map, db.r_frozen_start_room);
+    if db.r_frozen_start_room == NOWHERE {
+        db.r_frozen_start_room = db.r_mortal_start_room;
+    }
+    db.scripts.renum_rooms(&room_map);
+
+    /* Characters and objects elsewhere just follow their rooms. */
+    let mut orphans = vec![];
+    for &chid in &db.character_list {
+        let ch = chars.get_mut(chid);
+        ch.set_in_room(renum(&room_map, ch.in_room()));
+        let was_in = ch.get_was_in();
+        ch.set_was_in(renum(&room_map, was_in));
+        if was_in != NOWHERE && ch.get_was_in() == NOWHERE {
+            ch.set_was_in(db.r_mortal_start_room);
+        }
+        if ch.is_npc() {
+            let nr = ch.get_mob_rnum();
+            ch.set_mob_rnum(renum(&mob_map, nr));
+            if nr != NOBODY && ch.get_mob_rnum() == NOBODY {
+                orphans.push(chid);
+            }
+        }
+    }
+    for &oid in &db.object_list {
+        let obj = objs.get_mut(oid);
+        obj.set_in_room(renum(&room_map, obj.in_room()));
+        obj.item_number = renum(&obj_map, obj.item_number);
+    }
+
+    /* Bring back what waited in the void. */
+    for (chid, vnum) in waiting_chars {
+        let mut room = db.real_room(vnum);
+        if room == NOWHERE {
+            room = db.r_mortal_start_room;
+            report.moved += 1;
+        }
+        db.char_to_room(chars, objs, chid, room);
+    }
+    for (oid, vnum) in waiting_objs {
+        let room = db.real_room(vnum);
+        if room == NOWHERE {
+            db.extract_obj(chars, objs, oid);
+            report.purged += 1;
+        } else {
+            db.obj_to_room(objs.get_mut(oid), room);
+        }
+    }
+
+    /* Mobiles whose prototype is gone can't stay, nor can purged ones. */
+    report.purged += purge.len();
+    for &chid in &purge {
+        let ch = chars.get(chid);
+        let mut items = ch.carrying.clone();
+        items.extend((0..NUM_WEARS).filter_map(|pos| ch.get_eq(pos)));
+        for oid in items {
+            db.extract_obj(chars, objs, oid);
+        }
+    }
+    for chid in orphans.into_iter().chain(purge) {
+        if !chars.get(chid).mob_flagged(MOB_NOTDEADYET) {
+            db.extract_char(chars, chid);
+        }
+    }
+    game.extract_pending_chars(chars, db, texts, objs);
+
+    /* The counts of the live instances, by prototype. */
+    for idx in db.mob_index.iter_mut() {
+        idx.number = 0;
+    }
+    for idx in db.obj_index.iter_mut() {
+        idx.number = 0;
+    }
+    for &chid in &db.character_list {
+        let ch = chars.get(chid);
+        if ch.is_npc() && ch.get_mob_rnum() != NOBODY {
+            db.mob_index[ch.get_mob_rnum() as usize].number += 1;
+        }
+    }
+    for &oid in &db.object_list {
+        let rnum = objs.get(oid).get_obj_rnum();
+        if rnum != NOTHING {
+            db.obj_index[rnum as usize].number += 1;
+        }
+    }
+
+    if refresh {
+        game.reset_zone(db, chars, objs, zone);
+    }
+
+    /* Show the players where they are now. */
+    for rnum in 0..db.world.len() {
+        if db.world[rnum].zone as usize != zone {
+            continue;
+        }
+        for &chid in &db.world[rnum].peoples {
+            let ch = chars.get(chid);
+            if ch.desc.is_some() {
+                look_at_room(&mut game.descriptors, db, chars, texts, objs, ch, false);
+            }
+        }
+    }
+
+    report
+}
+
+/**************************************************************************
+*  The command                                                            *
+**************************************************************************/
+
+#[allow(clippy::too_many_arguments)]
+pub fn do_zreload(
+    game: &mut Game,
+    db: &mut DB,
+    chars: &mut Depot<CharData>,
+    texts: &mut Depot<TextData>,
+    objs: &mut Depot<ObjData>,
+    chid: DepotId,
+    argument: &str,
+    _cmd: usize,
+    _subcmd: i32,
+) {
+    let ch = chars.get(chid);
+    let mut arg = String::new();
+    let mut mode = String::new();
+    two_arguments(argument, &mut arg, &mut mode);
+
+    let zone = if arg == "." {
+        Some(db.world[ch.in_room() as usize].zone as usize)
+    } else {
+        arg.parse::<IdxType>().ok().and_then(|v| db.real_zone(v))
+    };
+    let refresh = match mode.as_str() {
+        "" | "keep" => Some(false),
+        "refresh" => Some(true),
+        _ => None,
+    };
+    let (Some(zone), Some(refresh)) = (zone, refresh) else {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            "Usage: zreload < zone | '.' > [ refresh | keep ]\r\n",
+        );
+        return;
+    };
+
+    let files = match read_zone_files(db, texts, zone) {
+        Ok(files) => files,
+        Err(err) => {
+            error!("SYSERR: zreload: {}", err);
+            let ch = chars.get(chid);
+            send_to_char(
+                &mut game.descriptors,
+                ch,
+                format!("Zone not reloaded: {}.\r\n", err).as_str(),
+            );
+            return;
+        }
+    };
+    let report = swap_zone(game, db, chars, texts, objs, zone, files, refresh);
+
+    let ch = chars.get(chid);
+    send_to_char(
+        &mut game.descriptors,
+        ch,
+        format!(
+            "Reloaded zone #{}: {} rooms, {} mobiles, {} objects{}.\r\n",
+            db.zone_table[zone].number,
+            report.rooms,
+            report.mobs,
+            report.objs,
+            if refresh { ", then reset" } else { "" }
+        )
+        .as_str(),
+    );
+    if report.purged > 0 || report.moved > 0 {
+        send_to_char(
+            &mut game.descriptors,
+            ch,
+            format!(
+                "{} mobiles and objects purged, {} characters sent to the start room.\r\n",
+                report.purged, report.moved
+            )
+            .as_str(),
+        );
+    }
+    game.mudlog(
+        chars,
+        DisplayMode::Normal,
+        max(LVL_GRGOD as i32, ch.get_invis_lev() as i32),
+        true,
+        format!(
+            "(GC) {} reloaded zone {} ({}).",
+            ch.get_name(),
+            db.zone_table[zone].number,
+            if refresh { "refresh" } else { "keep" }
+        )
+        .as_str(),
+    );
+}
+
+#[cfg(test)]
+mod zreload_tests {
+    use super::*;
+    use crate::db::TestWorld;
+    use crate::structs::MobRnum;
+
+    fn edit(path: &str, from: &str, to: &str) {
+        let text = fs::read_to_string(path).unwrap();
+        assert!(text.contains(from), "{} not in {}", from, path);
+        fs::write(path, text.replacen(from, to, 1)).unwrap();
+    }
+
+    #[test]
+    fn test_reload_zone() {
+        let mut w = TestWorld::boot("zreload");
+        let zone = w.db.real_zone(30).unwrap();
+
+        /* a new room name, a new mobile description, the baker moved */
+        edit(
+            "world/wld/30.wld",
+            "The Temple Of Midgaard~",
+            "The Old Temple Of Midgaard~",
+        );
+        edit("world/mob/30.mob", "the baker~", "the sleepy baker~");
+        edit("world/zon/30.zon", "M 0 3001 1 3009", "M 0 3001 1 3001");
+        let files = read_zone_files(&mut w.db, &mut w.texts, zone).unwrap();
+        swap_zone(
+            &mut w.game,
+            &mut w.db,
+            &mut w.chars,
+            &mut w.texts,
+            &mut w.objs,
+            zone,
+            files,
+            true,
+        );
+
+        let temple = w.db.real_room(3001);
+        assert_eq!(
+            w.db.world[temple as usize].name,
+            "The Old Temple Of Midgaard"
+        );
+        let baker = w.db.real_mobile(3001);
+        assert_eq!(
+            w.db.mob_protos[baker as usize].player.short_descr.as_ref(),
+            "the sleepy baker"
+        );
+        let reset = w.db.zone_table[zone]
+            .cmd
+            .iter()
+            .find(|c| c.command == 'M' && c.arg1 == baker as i32)
+            .unwrap();
+        assert_eq!(reset.arg3, temple as i32);
+        assert!(w.db.world[temple as usize].peoples.iter().any(|&chid| w
+            .chars
+            .get(chid)
+            .get_mob_rnum()
+            == baker as MobRnum));
+        assert!(w.db.world[w.db.real_room(3009) as usize].peoples.is_empty());
+
+        /* a mobile file cut short leaves everything as it was */
+        let mob = fs::read_to_string("world/mob/30.mob").unwrap();
+        let cut = mob.find("#3002").unwrap() + 12;
+        fs::write("world/mob/30.mob", &mob[..cut]).unwrap();
+        edit(
+            "world/wld/30.wld",
+            "The Old Temple Of Midgaard~",
+            "The New Temple Of Midgaard~",
+        );
+        let text_count = w.texts.len();
+        let mob_count = w.db.mob_protos.len();
+        assert!(read_zone_files(&mut w.db, &mut w.texts, zone).is_err());
+        assert_eq!(w.texts.len(), text_count);
+        assert_eq!(w.db.mob_protos.len(), mob_count);
+        assert_eq!(
+            w.db.world[temple as usize].name,
+            "The Old Temple Of Midgaard"
+        );
+    }
+
+    #[test]
+    fn test_merge_table_maps_old_rnums() {
+        let old = vec![100, 3001, 3005, 3100];
+        let new = vec![3000, 3005, 3050];
+        let (table, map, fresh) = merge_table(old, new, |&v| !(3000..=3099).contains(&v), |&v| v);
+        assert_eq!(table, vec![100, 3000, 3005, 3050, 3100]);
+        assert_eq!(map, vec![0, -1, 2, 4]);
+        assert_eq!(fresh, vec![false, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_renum_arg_disables_lost_targets() {
+        let map = vec![0, -1, 1];
+        let mut arg = 2;
+        assert!(renum_arg(&map, &mut arg));
+        assert_eq!(arg, 1);
+        let mut arg = 1;
+        assert!(!renum_arg(&map, &mut arg));
+        let mut arg = -1;
+        assert!(renum_arg(&map, &mut arg));
+        assert_eq!(arg, -1);
+    }
+
+    #[test]
+    fn test_sorted_vnums_rejects_duplicates() {
+        assert_eq!(sorted_vnums(vec![3, 1, 2], "room"), Ok(vec![1, 2, 3]));
+        assert!(sorted_vnums(vec![3, 1, 3], "room").is_err());
+    }
+}
@@ -20,6 +20,9 @@ fn test_help_output() {
     assert!(stdout.contains("--quick"));
     assert!(stdout.contains("--restrict"));
     assert!(stdout.contains("--no-specials"));
+    assert!(stdout.contains("--check-json"));
+    assert!(stdout.contains("--export-world"));
+    assert!(stdout.contains("--export-map"));
 }
 
 #[test]